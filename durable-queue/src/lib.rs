@@ -0,0 +1,658 @@
+//! ack/가시성 타임아웃 방식의 제한 크기 큐 (메모리 기반, 선택적 디스크 spill)
+//!
+//! 꺼낸 항목은 `ack` 하기 전까지 in-flight로 남는다. 가시성 타임아웃이 지나도록 ack 되지 않으면
+//! 다시 받을 수 있는 상태로 돌아가고, `max_deliveries`만큼 전달되고도 ack 되지 않으면 dead letter가 된다.
+//! 항목 id는 호출자가 정하며(메시지 id 등) 큐 안에서 유일해야 한다.
+//!
+//! 가시성 판단은 `playground-clock`의 시계를 따르므로 `TokioClock`으로 일시정지 시간 테스트가 가능하다.
+
+mod spill;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use playground_clock::SharedClock;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use spill::Spill;
+
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    /// ready + spill + in-flight 합계 상한 - 가득 차면 `push`는 자리가 날 때까지 대기
+    pub capacity: usize,
+    /// 메모리에 둘 ready 항목 수 - 넘치면 `spill_dir`이 있을 때 디스크로 내려감
+    pub memory_items: usize,
+    pub spill_dir: Option<PathBuf>,
+    pub visibility_timeout: Duration,
+    /// 전달 횟수에 따라 가시성 타임아웃을 늘리는 방식 (기본은 매번 `visibility_timeout`)
+    pub backoff: Backoff,
+    /// 가시성 타임아웃을 매번 ±이 비율(0.0~1.0) 안에서 무작위로 늘이거나 줄임 - 한꺼번에 나간 항목들이 한꺼번에 만료되지 않도록
+    pub jitter: f64,
+    /// 이만큼 전달되고도 ack 되지 않으면 dead letter (None이면 무제한 재전달)
+    pub max_deliveries: Option<u32>,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            memory_items: 1024,
+            spill_dir: None,
+            visibility_timeout: Duration::from_secs(30),
+            backoff: Backoff::Fixed,
+            jitter: 0.0,
+            max_deliveries: None,
+        }
+    }
+}
+
+/// n번째 전달의 가시성 타임아웃
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Backoff {
+    /// 매번 `visibility_timeout`
+    #[default]
+    Fixed,
+    /// `visibility_timeout × factor^(n-1)`, `max`를 넘지 않음
+    Exponential { factor: f64, max: Duration },
+}
+
+impl Backoff {
+    /// `delivery`번째(1부터) 전달 뒤 ack를 기다릴 시간
+    pub fn timeout(&self, base: Duration, delivery: u32) -> Duration {
+        match *self {
+            Backoff::Fixed => base,
+            Backoff::Exponential { factor, max } => {
+                let exponent = delivery.saturating_sub(1).min(i32::MAX as u32) as i32;
+                let secs = base.as_secs_f64() * factor.powi(exponent);
+                // 큰 지수에서 inf/NaN이 되어도 max로
+                Duration::try_from_secs_f64(secs).map_or(max, |timeout| timeout.min(max))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum QueueError {
+    /// `close` 이후의 추가
+    Closed,
+    /// `try_push`에서 용량 초과
+    Full,
+    /// 같은 id가 이미 큐에 있음
+    Duplicate(u64),
+    /// spill 파일 입출력/직렬화 실패
+    Io(io::Error),
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::Closed => write!(f, "queue is closed"),
+            QueueError::Full => write!(f, "queue is full"),
+            QueueError::Duplicate(id) => write!(f, "item {} is already queued", id),
+            QueueError::Io(e) => write!(f, "queue spill error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+impl From<io::Error> for QueueError {
+    fn from(e: io::Error) -> Self {
+        QueueError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for QueueError {
+    fn from(e: serde_json::Error) -> Self {
+        QueueError::Io(e.into())
+    }
+}
+
+/// `nack` 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nacked {
+    /// 맨 앞으로 돌아가 바로 다시 받을 수 있음
+    Requeued,
+    /// 전달 한도를 다 써서 dead letter가 됨
+    DeadLetter,
+}
+
+/// 꺼낸 항목 - `id`로 ack/nack
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lease<T> {
+    pub id: u64,
+    pub item: T,
+    /// 몇 번째 전달인지 (1부터)
+    pub delivery: u32,
+}
+
+/// `list`가 보여주는 항목 하나
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listed {
+    pub id: u64,
+    /// 지금까지 전달한 횟수 (ready로 돌아온 항목은 이전 전달까지)
+    pub deliveries: u32,
+    /// in-flight면 가시성 타임아웃까지 남은 시간, ready면 None
+    pub visible_in: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    id: u64,
+    item: T,
+    deliveries: u32,
+}
+
+struct InFlight<T> {
+    item: T,
+    deliveries: u32,
+    /// 이 시각(unix ms)을 넘기면 다시 받을 수 있게 됨
+    visible_at: u64,
+}
+
+struct State<T> {
+    ready: VecDeque<Entry<T>>,
+    spill: Option<Spill>,
+    in_flight: HashMap<u64, InFlight<T>>,
+    /// ready + spill + in-flight의 모든 id
+    ids: HashSet<u64>,
+    dead: Vec<(u64, T)>,
+    closed: bool,
+}
+
+impl<T> State<T> {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn spilled(&self) -> usize {
+        self.spill.as_ref().map_or(0, Spill::len)
+    }
+}
+
+struct Inner<T> {
+    config: QueueConfig,
+    clock: SharedClock,
+    state: Mutex<State<T>>,
+    changed: Notify,
+    /// `jitter` 용
+    rng: Mutex<StdRng>,
+}
+
+pub struct DurableQueue<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for DurableQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> DurableQueue<T>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    pub fn new(config: QueueConfig, clock: SharedClock) -> io::Result<Self> {
+        let spill = config.spill_dir.as_deref().map(Spill::create).transpose()?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                config,
+                clock,
+                state: Mutex::new(State {
+                    ready: VecDeque::new(),
+                    spill,
+                    in_flight: HashMap::new(),
+                    ids: HashSet::new(),
+                    dead: Vec::new(),
+                    closed: false,
+                }),
+                changed: Notify::new(),
+                rng: Mutex::new(StdRng::from_entropy()),
+            }),
+        })
+    }
+
+    fn now(&self) -> u64 {
+        self.inner.clock.unix_millis()
+    }
+
+    fn visible_at(&self, delivery: u32) -> u64 {
+        let config = &self.inner.config;
+        let mut timeout = config.backoff.timeout(config.visibility_timeout, delivery);
+        if config.jitter > 0.0 {
+            let jitter = config.jitter.min(1.0);
+            timeout = timeout.mul_f64(self.inner.rng.lock().unwrap().gen_range(1.0 - jitter..=1.0 + jitter));
+        }
+        self.now() + timeout.as_millis() as u64
+    }
+
+    /// 자리가 날 때까지 기다렸다가 추가
+    pub async fn push(&self, id: u64, item: T) -> Result<(), QueueError> {
+        let mut item = Some(item);
+        loop {
+            let notified = self.inner.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            match self.push_now(id, &mut item) {
+                Err(QueueError::Full) => notified.await,
+                result => return result,
+            }
+        }
+    }
+
+    /// ack 되지 않은 항목(ready + spill + in-flight)이 하나도 남지 않을 때까지 대기
+    pub async fn drained(&self) {
+        loop {
+            let notified = self.inner.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.is_empty() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// 가득 차 있으면 `Full`
+    pub fn try_push(&self, id: u64, item: T) -> Result<(), QueueError> {
+        self.push_now(id, &mut Some(item))
+    }
+
+    fn push_now(&self, id: u64, item: &mut Option<T>) -> Result<(), QueueError> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.closed {
+            return Err(QueueError::Closed);
+        }
+        if state.ids.contains(&id) {
+            return Err(QueueError::Duplicate(id));
+        }
+        if state.len() >= self.inner.config.capacity {
+            return Err(QueueError::Full);
+        }
+
+        let entry = Entry {
+            id,
+            item: item.take().expect("item is pushed once"),
+            deliveries: 0,
+        };
+        // 순서를 지키기 위해 한 번 spill이 시작되면 비워질 때까지 뒤에 오는 항목도 디스크로
+        let spill_now = state.ready.len() >= self.inner.config.memory_items || state.spilled() > 0;
+        match state.spill.as_mut() {
+            Some(spill) if spill_now => spill.push(&serde_json::to_string(&entry)?)?,
+            _ => state.ready.push_back(entry),
+        }
+        state.ids.insert(id);
+        drop(state);
+        self.inner.changed.notify_waiters();
+        Ok(())
+    }
+
+    /// ready 항목을 하나 꺼내 in-flight로 - 없으면 들어오거나 가시성이 풀릴 때까지 대기
+    ///
+    /// 큐가 닫혔고 남은 항목(in-flight 포함)이 없으면 `None`.
+    pub async fn receive(&self) -> Result<Option<Lease<T>>, QueueError> {
+        loop {
+            let notified = self.inner.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(lease) = self.try_receive()? {
+                return Ok(Some(lease));
+            }
+
+            let next_visible = {
+                let state = self.inner.state.lock().unwrap();
+                if state.closed && state.len() == 0 {
+                    return Ok(None);
+                }
+                state
+                    .in_flight
+                    .values()
+                    .map(|in_flight| in_flight.visible_at)
+                    .min()
+            };
+            match next_visible {
+                Some(visible_at) => {
+                    let wait = Duration::from_millis(visible_at.saturating_sub(self.now()) + 1);
+                    let _ = tokio::time::timeout(wait, notified).await;
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// 가시성이 풀린 항목을 먼저 되돌린 뒤 ready 항목을 하나 꺼냄 (기다리지 않음)
+    pub fn try_receive(&self) -> Result<Option<Lease<T>>, QueueError> {
+        self.requeue_expired();
+        self.try_receive_ready()
+    }
+
+    /// 가시성 타임아웃은 보지 않고 ready 항목만 하나 꺼냄 - in-flight를 훑지 않으므로 항목마다 타이머를 걸어
+    /// 직접 `nack`하는 소비자용
+    pub fn try_receive_ready(&self) -> Result<Option<Lease<T>>, QueueError> {
+        let mut state = self.inner.state.lock().unwrap();
+        self.take_ready(&mut state)
+    }
+
+    /// ready 항목을 모두 꺼내고, 그중 가장 앞 id보다 뒤에 보낸 in-flight 항목도 함께 다시 전달 (id 순서)
+    ///
+    /// 앞 항목을 재전송할 때 뒤 항목도 그 뒤로 다시 보내 순서를 지키려는(go-back-N) 소비자용.
+    /// 다시 전달하는 in-flight 항목은 전달 횟수를 올리고 가시성 타임아웃을 다시 시작한다.
+    pub fn go_back_n(&self) -> Result<Vec<Lease<T>>, QueueError> {
+        let mut state = self.inner.state.lock().unwrap();
+        let mut later: Vec<u64> = state.in_flight.keys().copied().collect();
+        let mut leases = Vec::new();
+        while let Some(lease) = self.take_ready(&mut state)? {
+            leases.push(lease);
+        }
+        let Some(first) = leases.iter().map(|lease| lease.id).min() else {
+            return Ok(leases);
+        };
+
+        later.retain(|id| *id > first);
+        for id in later {
+            let in_flight = state.in_flight.get_mut(&id).expect("listed id is in flight");
+            in_flight.deliveries += 1;
+            in_flight.visible_at = self.visible_at(in_flight.deliveries);
+            leases.push(Lease {
+                id,
+                item: in_flight.item.clone(),
+                delivery: in_flight.deliveries,
+            });
+        }
+        leases.sort_unstable_by_key(|lease| lease.id);
+        Ok(leases)
+    }
+
+    fn take_ready(&self, state: &mut State<T>) -> Result<Option<Lease<T>>, QueueError> {
+        let entry = match state.ready.pop_front() {
+            Some(entry) => entry,
+            None => match state.spill.as_mut().map(Spill::pop).transpose()?.flatten() {
+                Some(line) => serde_json::from_str(&line)?,
+                None => return Ok(None),
+            },
+        };
+
+        let delivery = entry.deliveries + 1;
+        state.in_flight.insert(
+            entry.id,
+            InFlight {
+                item: entry.item.clone(),
+                deliveries: delivery,
+                visible_at: self.visible_at(delivery),
+            },
+        );
+        Ok(Some(Lease {
+            id: entry.id,
+            item: entry.item,
+            delivery,
+        }))
+    }
+
+    /// ready를 거치지 않고 바로 in-flight로 등록 (받는 즉시 보내는 생산자용)
+    ///
+    /// 이미 in-flight인 id면 전달 횟수를 올리고 가시성 타임아웃을 다시 시작한다. 몇 번째 전달인지 반환.
+    pub fn lease(&self, id: u64, item: T) -> Result<u32, QueueError> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.closed {
+            return Err(QueueError::Closed);
+        }
+        if let Some(in_flight) = state.in_flight.get_mut(&id) {
+            in_flight.item = item;
+            in_flight.deliveries += 1;
+            in_flight.visible_at = self.visible_at(in_flight.deliveries);
+            return Ok(in_flight.deliveries);
+        }
+        if state.ids.contains(&id) {
+            return Err(QueueError::Duplicate(id));
+        }
+        state.in_flight.insert(
+            id,
+            InFlight {
+                item,
+                deliveries: 1,
+                visible_at: self.visible_at(1),
+            },
+        );
+        state.ids.insert(id);
+        Ok(1)
+    }
+
+    /// 처리 완료 - in-flight였으면 true
+    pub fn ack(&self, id: u64) -> bool {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.in_flight.remove(&id).is_none() {
+            return false;
+        }
+        state.ids.remove(&id);
+        drop(state);
+        self.inner.changed.notify_waiters();
+        true
+    }
+
+    /// `id` 이하인 in-flight 항목을 모두 처리 완료 - ack 한 id를 오름차순으로 반환
+    pub fn ack_up_to(&self, id: u64) -> Vec<u64> {
+        let mut state = self.inner.state.lock().unwrap();
+        let mut acked: Vec<u64> = state.in_flight.keys().copied().filter(|acked| *acked <= id).collect();
+        if acked.is_empty() {
+            return acked;
+        }
+        acked.sort_unstable();
+        for acked in &acked {
+            state.in_flight.remove(acked);
+            state.ids.remove(acked);
+        }
+        drop(state);
+        self.inner.changed.notify_waiters();
+        acked
+    }
+
+    /// 처리 실패 - 가시성 타임아웃을 기다리지 않고 맨 앞으로 되돌림 (in-flight가 아니면 None)
+    ///
+    /// 전달 한도를 다 쓴 항목은 되돌리지 않고 dead letter로 옮김.
+    pub fn nack(&self, id: u64) -> Option<Nacked> {
+        let mut state = self.inner.state.lock().unwrap();
+        let in_flight = state.in_flight.remove(&id)?;
+        let spent = self
+            .inner
+            .config
+            .max_deliveries
+            .is_some_and(|max| in_flight.deliveries >= max);
+        let nacked = if spent {
+            state.ids.remove(&id);
+            state.dead.push((id, in_flight.item));
+            Nacked::DeadLetter
+        } else {
+            state.ready.push_front(Entry {
+                id,
+                item: in_flight.item,
+                deliveries: in_flight.deliveries,
+            });
+            Nacked::Requeued
+        };
+        drop(state);
+        self.inner.changed.notify_waiters();
+        Some(nacked)
+    }
+
+    /// in-flight 항목을 모두 가시성 타임아웃을 기다리지 않고 ready 맨 앞으로 (id 순서) - 되돌린 수를 반환
+    ///
+    /// 소비자가 바뀌어(재연결 등) 보낸 항목을 처음부터 다시 보내야 할 때. 전달 한도를 다 쓴 항목은 dead letter로.
+    pub fn requeue_in_flight(&self) -> usize {
+        let mut state = self.inner.state.lock().unwrap();
+        let mut ids: Vec<u64> = state.in_flight.keys().copied().collect();
+        ids.sort_unstable();
+        let mut requeued = 0;
+        for id in ids.into_iter().rev() {
+            let in_flight = state.in_flight.remove(&id).expect("id is in flight");
+            let spent = self
+                .inner
+                .config
+                .max_deliveries
+                .is_some_and(|max| in_flight.deliveries >= max);
+            if spent {
+                state.ids.remove(&id);
+                state.dead.push((id, in_flight.item));
+            } else {
+                state.ready.push_front(Entry {
+                    id,
+                    item: in_flight.item,
+                    deliveries: in_flight.deliveries,
+                });
+                requeued += 1;
+            }
+        }
+        drop(state);
+        self.inner.changed.notify_waiters();
+        requeued
+    }
+
+    /// 가시성 타임아웃이 지난 in-flight 항목을 ready 맨 앞으로 되돌림
+    ///
+    /// 전달 한도를 다 쓴 항목은 dead letter로 옮기고, 이번에 옮긴 것들을 반환한다.
+    pub fn requeue_expired(&self) -> Vec<(u64, T)> {
+        let now = self.now();
+        let mut state = self.inner.state.lock().unwrap();
+        let mut expired: Vec<_> = state
+            .in_flight
+            .iter()
+            .filter(|(_, in_flight)| now > in_flight.visible_at)
+            .map(|(id, _)| *id)
+            .collect();
+        if expired.is_empty() {
+            return Vec::new();
+        }
+        // 먼저 보낸 항목이 먼저 다시 나가도록 id 역순으로 앞에 끼워 넣음
+        expired.sort_unstable();
+
+        let mut exhausted = Vec::new();
+        for id in expired.into_iter().rev() {
+            let in_flight = state
+                .in_flight
+                .remove(&id)
+                .expect("expired id is in flight");
+            let spent = self
+                .inner
+                .config
+                .max_deliveries
+                .is_some_and(|max| in_flight.deliveries >= max);
+            if spent {
+                state.ids.remove(&id);
+                state.dead.push((id, in_flight.item.clone()));
+                exhausted.push((id, in_flight.item));
+            } else {
+                state.ready.push_front(Entry {
+                    id,
+                    item: in_flight.item,
+                    deliveries: in_flight.deliveries,
+                });
+            }
+        }
+        drop(state);
+        self.inner.changed.notify_waiters();
+        exhausted.reverse();
+        exhausted
+    }
+
+    /// 더 이상 추가하지 않음 - 소비자는 남은 항목을 마저 받은 뒤 `None`
+    pub fn close(&self) {
+        self.inner.state.lock().unwrap().closed = true;
+        self.inner.changed.notify_waiters();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.state.lock().unwrap().closed
+    }
+
+    /// ack 되지 않은 항목 수 (ready + spill + in-flight, dead letter 제외)
+    pub fn len(&self) -> usize {
+        self.inner.state.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// in-flight인 `id`의 가시성 타임아웃까지 남은 시간 (in-flight가 아니면 None) - 항목마다 타이머를 거는 소비자용
+    pub fn visible_in(&self, id: u64) -> Option<Duration> {
+        let now = self.now();
+        let state = self.inner.state.lock().unwrap();
+        let in_flight = state.in_flight.get(&id)?;
+        Some(Duration::from_millis(in_flight.visible_at.saturating_sub(now)))
+    }
+
+    /// in-flight인 `id`의 항목 사본
+    pub fn peek_in_flight(&self, id: u64) -> Option<T> {
+        let state = self.inner.state.lock().unwrap();
+        state.in_flight.get(&id).map(|in_flight| in_flight.item.clone())
+    }
+
+    /// `id`를 다시 전달하지 않고 빼냄 (ready든 in-flight든) - `dead_letter`면 dead letter로 옮김. 있었으면 true
+    pub fn remove(&self, id: u64, dead_letter: bool) -> bool {
+        let mut state = self.inner.state.lock().unwrap();
+        let item = match state.in_flight.remove(&id) {
+            Some(in_flight) => in_flight.item,
+            None => match state.ready.iter().position(|entry| entry.id == id) {
+                Some(index) => state.ready.remove(index).expect("index is in range").item,
+                None => return false,
+            },
+        };
+        state.ids.remove(&id);
+        if dead_letter {
+            state.dead.push((id, item));
+        }
+        drop(state);
+        self.inner.changed.notify_waiters();
+        true
+    }
+
+    /// 메모리에 있는 ready·in-flight 항목을 id 순서로 (디스크로 넘친 항목은 `spilled` 수로만)
+    pub fn list(&self) -> Vec<Listed> {
+        let now = self.now();
+        let state = self.inner.state.lock().unwrap();
+        let ready = state.ready.iter().map(|entry| Listed {
+            id: entry.id,
+            deliveries: entry.deliveries,
+            visible_in: None,
+        });
+        let in_flight = state.in_flight.iter().map(|(id, in_flight)| Listed {
+            id: *id,
+            deliveries: in_flight.deliveries,
+            visible_in: Some(Duration::from_millis(in_flight.visible_at.saturating_sub(now))),
+        });
+        let mut listed: Vec<Listed> = ready.chain(in_flight).collect();
+        listed.sort_unstable_by_key(|listed| listed.id);
+        listed
+    }
+
+    /// ack 되지 않은 항목(ready, spill, in-flight) 중에 `id`가 있는지
+    pub fn contains(&self, id: u64) -> bool {
+        self.inner.state.lock().unwrap().ids.contains(&id)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.inner.state.lock().unwrap().in_flight.len()
+    }
+
+    /// 디스크에 내려가 있는 항목 수
+    pub fn spilled(&self) -> usize {
+        self.inner.state.lock().unwrap().spilled()
+    }
+
+    pub fn dead_letters(&self) -> Vec<(u64, T)> {
+        self.inner.state.lock().unwrap().dead.clone()
+    }
+}
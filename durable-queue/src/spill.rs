@@ -0,0 +1,80 @@
+//! 메모리에 다 못 담은 ready 항목을 내려두는 JSONL 파일
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+
+pub struct Spill {
+    path: PathBuf,
+    writer: File,
+    reader: BufReader<File>,
+    /// 아직 읽지 않은 줄 수
+    pending: usize,
+}
+
+impl Spill {
+    /// `dir` 아래에 프로세스/큐마다 겹치지 않는 파일 생성
+    pub fn create(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let name = format!(
+            "queue-{}-{}.jsonl",
+            std::process::id(),
+            NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        let writer = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)?;
+        let reader = BufReader::new(File::open(&path)?);
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            pending: 0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending
+    }
+
+    pub fn push(&mut self, line: &str) -> io::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    /// 가장 먼저 내려둔 줄 - 다 읽으면 파일을 비워 크기가 계속 늘지 않게 함
+    pub fn pop(&mut self) -> io::Result<Option<String>> {
+        if self.pending == 0 {
+            return Ok(None);
+        }
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "spill file is shorter than expected",
+            ));
+        }
+        self.pending -= 1;
+        if self.pending == 0 {
+            self.writer.set_len(0)?;
+            self.writer.seek(SeekFrom::Start(0))?;
+            self.reader.seek(SeekFrom::Start(0))?;
+        }
+        Ok(Some(line.trim_end().to_string()))
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
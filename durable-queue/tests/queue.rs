@@ -0,0 +1,332 @@
+//! 가시성 타임아웃은 tokio 일시정지 시간으로 검증 - 수 초짜리 대기가 즉시 끝남
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use durable_queue::{Backoff, DurableQueue, Listed, Nacked, QueueConfig, QueueError};
+use playground_clock::TokioClock;
+use tokio::time::Instant;
+
+fn queue(config: QueueConfig) -> DurableQueue<String> {
+    DurableQueue::new(config, Arc::new(TokioClock::new())).unwrap()
+}
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test(start_paused = true)]
+async fn unacked_item_is_redelivered_after_visibility_timeout() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        ..QueueConfig::default()
+    });
+    let start = Instant::now();
+
+    queue.push(1, "a".to_string()).await.unwrap();
+    let first = queue.receive().await.unwrap().unwrap();
+    assert_eq!((first.id, first.delivery), (1, 1));
+    assert_eq!(queue.in_flight(), 1);
+
+    // ack 하지 않으면 타임아웃이 지난 뒤 다시 나옴
+    let second = queue.receive().await.unwrap().unwrap();
+    assert_eq!((second.id, second.delivery), (1, 2));
+    assert!(start.elapsed() > Duration::from_secs(2));
+    assert!(start.elapsed() < Duration::from_secs(3));
+
+    assert!(queue.ack(1));
+    assert!(!queue.ack(1));
+    assert!(queue.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn exhausted_item_becomes_dead_letter() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        max_deliveries: Some(2),
+        ..QueueConfig::default()
+    });
+
+    assert_eq!(queue.lease(7, "x".to_string()).unwrap(), 1);
+    tokio::time::advance(Duration::from_millis(2001)).await;
+    assert!(queue.requeue_expired().is_empty());
+    assert_eq!(queue.try_receive().unwrap().unwrap().delivery, 2);
+
+    tokio::time::advance(Duration::from_millis(2001)).await;
+    assert_eq!(queue.requeue_expired(), vec![(7, "x".to_string())]);
+    assert!(queue.try_receive().unwrap().is_none());
+    assert!(queue.is_empty());
+    assert_eq!(queue.dead_letters(), vec![(7, "x".to_string())]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn lease_refresh_counts_deliveries_and_restarts_timeout() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        ..QueueConfig::default()
+    });
+
+    assert_eq!(queue.lease(1, "a".to_string()).unwrap(), 1);
+    tokio::time::advance(Duration::from_millis(1500)).await;
+    assert_eq!(queue.lease(1, "a".to_string()).unwrap(), 2);
+    tokio::time::advance(Duration::from_millis(1500)).await;
+    // 갱신 시점부터 다시 2초이므로 아직 만료 전
+    assert!(queue.try_receive().unwrap().is_none());
+    assert_eq!(queue.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn exponential_backoff_doubles_timeout_per_delivery_up_to_max() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(1),
+        backoff: Backoff::Exponential { factor: 2.0, max: Duration::from_secs(3) },
+        ..QueueConfig::default()
+    });
+    let start = Instant::now();
+
+    queue.push(1, "a".to_string()).await.unwrap();
+    let mut redelivered_at = Vec::new();
+    for _ in 0..4 {
+        let lease = queue.receive().await.unwrap().unwrap();
+        redelivered_at.push((lease.delivery, start.elapsed().as_millis() / 1000));
+    }
+    // 1초, 2초, 3초(상한), 3초 간격
+    assert_eq!(redelivered_at, vec![(1, 0), (2, 1), (3, 3), (4, 6)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn jitter_spreads_expiry_of_items_leased_together() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        jitter: 0.5,
+        ..QueueConfig::default()
+    });
+    for id in 0..20 {
+        queue.lease(id, id.to_string()).unwrap();
+    }
+
+    // 1초~3초 사이에 흩어져 만료됨
+    tokio::time::advance(Duration::from_millis(999)).await;
+    assert!(queue.try_receive().unwrap().is_none());
+    let first = (0..20).filter_map(|id| queue.visible_in(id)).min().unwrap() + Duration::from_millis(1);
+    tokio::time::advance(first).await;
+    let expired = || std::iter::from_fn(|| queue.try_receive().unwrap()).filter(|lease| queue.ack(lease.id)).count();
+    let early = expired();
+    assert!((1..20).contains(&early), "{} of 20 expired together", early);
+    tokio::time::advance(Duration::from_secs(2)).await;
+    assert_eq!(expired(), 20 - early);
+}
+
+#[tokio::test(start_paused = true)]
+async fn push_waits_until_capacity_frees() {
+    let queue = queue(QueueConfig {
+        capacity: 1,
+        ..QueueConfig::default()
+    });
+    queue.push(1, "a".to_string()).await.unwrap();
+    assert!(matches!(queue.try_push(2, "b".to_string()), Err(QueueError::Full)));
+
+    let producer = {
+        let queue = queue.clone();
+        tokio::spawn(async move { queue.push(2, "b".to_string()).await })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!producer.is_finished());
+
+    let lease = queue.receive().await.unwrap().unwrap();
+    // in-flight도 용량을 차지하므로 ack 해야 자리가 남
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!producer.is_finished());
+    queue.ack(lease.id);
+    producer.await.unwrap().unwrap();
+    assert_eq!(queue.receive().await.unwrap().unwrap().item, "b");
+}
+
+#[tokio::test(start_paused = true)]
+async fn spilled_items_keep_fifo_order() {
+    let dir = fresh_dir("queue-spill");
+    let queue = queue(QueueConfig {
+        memory_items: 2,
+        spill_dir: Some(dir.clone()),
+        ..QueueConfig::default()
+    });
+
+    for id in 1..=5 {
+        queue.push(id, format!("m{}", id)).await.unwrap();
+    }
+    assert_eq!(queue.spilled(), 3);
+
+    // 메모리에 자리가 나도 spill이 비기 전에는 뒤에 붙음
+    let first = queue.receive().await.unwrap().unwrap();
+    queue.ack(first.id);
+    queue.push(6, "m6".to_string()).await.unwrap();
+
+    let mut ids = vec![first.id];
+    while let Some(lease) = queue.try_receive().unwrap() {
+        queue.ack(lease.id);
+        ids.push(lease.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(queue.spilled(), 0);
+
+    drop(queue);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn close_drains_remaining_items_then_ends() {
+    let queue = queue(QueueConfig::default());
+    queue.push(1, "a".to_string()).await.unwrap();
+    queue.close();
+
+    assert!(matches!(queue.push(2, "b".to_string()).await, Err(QueueError::Closed)));
+    let lease = queue.receive().await.unwrap().unwrap();
+    queue.ack(lease.id);
+    assert!(queue.receive().await.unwrap().is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn nack_and_duplicate_ids() {
+    let queue = queue(QueueConfig::default());
+    queue.push(1, "a".to_string()).await.unwrap();
+    queue.push(2, "b".to_string()).await.unwrap();
+    assert!(matches!(queue.try_push(1, "a".to_string()), Err(QueueError::Duplicate(1))));
+
+    let lease = queue.receive().await.unwrap().unwrap();
+    assert_eq!(queue.nack(lease.id), Some(Nacked::Requeued));
+    assert_eq!(queue.nack(lease.id), None);
+    // 되돌린 항목이 맨 앞, 전달 횟수는 이어짐
+    let again = queue.receive().await.unwrap().unwrap();
+    assert_eq!((again.id, again.delivery), (1, 2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn ack_up_to_acks_every_in_flight_item_at_or_below_id() {
+    let queue = queue(QueueConfig::default());
+    for id in [3, 1, 5, 2] {
+        queue.lease(id, id.to_string()).unwrap();
+    }
+    queue.push(4, "4".to_string()).await.unwrap();
+
+    assert_eq!(queue.ack_up_to(4), vec![1, 2, 3]);
+    assert_eq!(queue.ack_up_to(4), Vec::<u64>::new());
+    // ready 항목(아직 보내지 않은 4)과 범위 밖의 5는 남음
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.in_flight(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn requeue_in_flight_returns_everything_in_id_order() {
+    let queue = queue(QueueConfig {
+        max_deliveries: Some(2),
+        ..QueueConfig::default()
+    });
+    for id in [2, 1, 3] {
+        queue.lease(id, id.to_string()).unwrap();
+    }
+    // 3은 이미 두 번 전달됨
+    queue.lease(3, "3".to_string()).unwrap();
+
+    assert_eq!(queue.requeue_in_flight(), 2);
+    assert_eq!(queue.in_flight(), 0);
+    let replay: Vec<_> = std::iter::from_fn(|| queue.try_receive().unwrap()).map(|lease| (lease.id, lease.delivery)).collect();
+    assert_eq!(replay, vec![(1, 2), (2, 2)]);
+    assert_eq!(queue.dead_letters(), vec![(3, "3".to_string())]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn per_item_timers_read_visibility_and_remove_single_items() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        ..QueueConfig::default()
+    });
+    queue.lease(1, "one".to_string()).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    queue.lease(2, "two".to_string()).unwrap();
+
+    assert_eq!(queue.visible_in(1), Some(Duration::from_millis(1500)));
+    assert_eq!(queue.visible_in(2), Some(Duration::from_secs(2)));
+    assert_eq!(queue.visible_in(3), None);
+    assert_eq!(queue.peek_in_flight(2), Some("two".to_string()));
+
+    // 가시성이 풀려도 try_receive_ready는 in-flight를 되돌리지 않음 - 타이머 쪽에서 nack해야 나감
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert!(queue.try_receive_ready().unwrap().is_none());
+    queue.nack(2);
+    assert_eq!(queue.try_receive_ready().unwrap().map(|lease| (lease.id, lease.delivery)), Some((2, 2)));
+
+    // ready로 돌아온 1과 그 뒤의 in-flight(2)를 함께 다시 전달 - 2는 전달 횟수가 오르고 가시성 타임아웃이 다시 시작
+    queue.nack(1);
+    let go_back: Vec<_> = queue.go_back_n().unwrap().into_iter().map(|lease| (lease.id, lease.delivery)).collect();
+    assert_eq!(go_back, vec![(1, 2), (2, 3)]);
+    assert_eq!(queue.visible_in(2), Some(Duration::from_secs(2)));
+    assert!(queue.go_back_n().unwrap().is_empty());
+
+    assert!(queue.remove(1, false));
+    assert!(queue.remove(2, true));
+    assert!(!queue.remove(2, true));
+    assert!(queue.is_empty());
+    assert_eq!(queue.dead_letters(), vec![(2, "two".to_string())]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drained_waits_until_last_item_is_acked() {
+    let queue = queue(QueueConfig::default());
+    queue.lease(1, "one".to_string()).unwrap();
+    queue.lease(2, "two".to_string()).unwrap();
+
+    let acker = queue.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        acker.ack(1);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        acker.ack(2);
+    });
+    let start = tokio::time::Instant::now();
+    queue.drained().await;
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn nack_after_last_delivery_is_dead_letter() {
+    let queue = queue(QueueConfig {
+        max_deliveries: Some(2),
+        ..QueueConfig::default()
+    });
+    queue.lease(1, "a".to_string()).unwrap();
+    assert_eq!(queue.nack(1), Some(Nacked::Requeued));
+    assert_eq!(queue.try_receive().unwrap().unwrap().delivery, 2);
+    assert_eq!(queue.nack(1), Some(Nacked::DeadLetter));
+    assert!(queue.is_empty());
+    assert_eq!(queue.dead_letters(), vec![(1, "a".to_string())]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn list_shows_ready_and_in_flight_items_in_id_order() {
+    let queue = queue(QueueConfig {
+        visibility_timeout: Duration::from_secs(2),
+        ..QueueConfig::default()
+    });
+    for id in 1..=3 {
+        queue.push(id, id.to_string()).await.unwrap();
+    }
+    queue.receive().await.unwrap().unwrap();
+    queue.receive().await.unwrap().unwrap();
+    tokio::time::advance(Duration::from_millis(500)).await;
+    queue.nack(1);
+
+    assert_eq!(
+        queue.list(),
+        vec![
+            Listed { id: 1, deliveries: 1, visible_in: None },
+            Listed { id: 2, deliveries: 1, visible_in: Some(Duration::from_millis(1500)) },
+            Listed { id: 3, deliveries: 0, visible_in: None },
+        ]
+    );
+    assert!(queue.contains(3));
+    assert!(queue.ack(2));
+    assert!(!queue.contains(2));
+}
@@ -1,15 +1,93 @@
 import asyncio
+import contextlib
 import grpc
 import time
 import sys
 import os
+import uuid
 
 import streaming_pb2
 import streaming_pb2_grpc
 
+# OpenTelemetry (선택) - OTEL_EXPORTER_OTLP_ENDPOINT가 설정되면 traceparent를 gRPC 메타데이터로 전달
+try:
+    from opentelemetry import propagate, trace
+    from opentelemetry.exporter.otlp.proto.grpc.trace_exporter import OTLPSpanExporter
+    from opentelemetry.sdk.resources import Resource
+    from opentelemetry.sdk.trace import TracerProvider
+    from opentelemetry.sdk.trace.export import BatchSpanProcessor
+except ImportError:
+    trace = None
+
+
+def init_tracer(service_name):
+    if trace is None or not os.getenv('OTEL_EXPORTER_OTLP_ENDPOINT'):
+        return None
+    provider = TracerProvider(resource=Resource.create({'service.name': service_name}))
+    provider.add_span_processor(BatchSpanProcessor(OTLPSpanExporter()))
+    trace.set_tracer_provider(provider)
+    return trace.get_tracer(service_name)
+
+
+def start_span(tracer, name):
+    if tracer is None:
+        return contextlib.nullcontext()
+    return tracer.start_as_current_span(name)
+
+
+def trace_metadata():
+    """현재 span 컨텍스트를 gRPC 메타데이터로 변환 (트레이싱 비활성 시 빈 리스트)"""
+    if trace is None:
+        return []
+    carrier = {}
+    propagate.inject(carrier)
+    return list(carrier.items())
+
+
+def auth_metadata():
+    """GRPC_AUTH_TOKEN이 있으면 서버 --auth-token/--auth-hmac-secret용 bearer 토큰 메타데이터"""
+    token = os.getenv('GRPC_AUTH_TOKEN')
+    return [('authorization', f'Bearer {token}')] if token else []
+
+
+# 서버가 응답 스트림을 끝낼 때 붙이는 요약 trailer
+SUMMARY_TRAILERS = ('x-messages-sent', 'x-cancel-reason', 'x-duration-ms', 'x-messages-lagged')
+
+
+def stream_summary(trailing_metadata):
+    """trailer 중 서버 스트림 요약만 dict로 - cancel()로 끝난 호출처럼 trailer가 없으면 빈 dict"""
+    return {key: value for key, value in (trailing_metadata or ()) if key in SUMMARY_TRAILERS}
+
+
+def print_summary(summary):
+    if summary:
+        print(f"📋 [PYTHON CLIENT] Server summary: sent={summary.get('x-messages-sent')} "
+              f"duration={summary.get('x-duration-ms')}ms reason={summary.get('x-cancel-reason')!r}")
+
+
+def open_channel(address):
+    """GRPC_TLS_CA가 있으면 TLS 채널 (GRPC_TLS_CLIENT_CERT/KEY까지 있으면 mTLS), 없으면 평문"""
+    ca_path = os.getenv('GRPC_TLS_CA')
+    if not ca_path:
+        return grpc.aio.insecure_channel(address)
+
+    def read(path):
+        with open(path, 'rb') as f:
+            return f.read()
+
+    cert_path = os.getenv('GRPC_TLS_CLIENT_CERT')
+    key_path = os.getenv('GRPC_TLS_CLIENT_KEY')
+    credentials = grpc.ssl_channel_credentials(
+        root_certificates=read(ca_path),
+        private_key=read(key_path) if key_path else None,
+        certificate_chain=read(cert_path) if cert_path else None,
+    )
+    return grpc.aio.secure_channel(address, credentials)
+
+
 
 class StreamingClient:
-    def __init__(self, server_address=None):
+    def __init__(self, server_address=None, rpc='bidi', heartbeat_timeout=None, max_messages=None, topics=None):
         # 환경변수에서 서버 주소 가져오기 (프록시 테스트용)
         import os
         if server_address:
@@ -23,13 +101,62 @@ class StreamingClient:
                 self.server_address = '[::1]:50051'
                 print(f"🔗 [PYTHON CLIENT] Using default server address: [::1]:50051")
         self.call = None
+        # bidi: BidirectionalStream, server: ServerStream (요청 스트림 없음)
+        self.rpc = rpc
+        # 이 시간 동안 메시지도 heartbeat도 없으면 연결이 막힌 것으로 보고 재연결 (None이면 감지 안 함)
+        self.heartbeat_timeout = heartbeat_timeout
+        # 서버에 요청할 메시지 수 (x-max-messages, None이면 서버 기본값)
+        self.max_messages = max_messages
+        # ServerStream으로 구독할 토픽 (비어 있으면 토픽 없는 스트림 하나)
+        self.topics = topics or []
         self.message_count = 0
+        self.last_message_id = 0
+        # UNAUTHENTICATED는 재연결해도 같은 결과라 재시도하지 않음
+        self.auth_failed = False
+        # 서버 --max-streams 한도로 거절된 RESOURCE_EXHAUSTED - run_simple_test가 기다렸다가 재시도
+        self.rejected = None
+        # 마지막 스트림의 서버 요약 trailer (x-messages-sent, x-cancel-reason, x-duration-ms)
+        self.summary = {}
         self.auto_cancel_delay = None
         self.start_time = None
+        self.tracer = init_tracer('grpc-stream-cancel-client')
+        # 재연결해도 서버가 같은 메시지 생성기를 이어가도록 클라이언트마다 고정된 세션 id 사용
+        self.session_id = str(uuid.uuid4())
+        print(f"🪪 [PYTHON CLIENT] Session id: {self.session_id}")
 
         
+    def stream_metadata(self):
+        """세션 id와, 재연결이면 마지막으로 받은 메시지 id"""
+        metadata = trace_metadata() + auth_metadata() + [('x-session-id', self.session_id)]
+        if self.max_messages is not None:
+            metadata.append(('x-max-messages', str(self.max_messages)))
+        if self.last_message_id > 0:
+            metadata.append(('x-resume-from', str(self.last_message_id)))
+        return metadata
+
+    async def responses(self):
+        """응답 스트림 - heartbeat_timeout이 있으면 그 시간 동안 아무것도 오지 않을 때 cancel() 후 ConnectionError"""
+        if self.heartbeat_timeout is None:
+            async for response in self.call:
+                yield response
+            return
+        while True:
+            try:
+                response = await asyncio.wait_for(self.call.read(), self.heartbeat_timeout)
+            except asyncio.TimeoutError:
+                self.call.cancel()
+                raise ConnectionError(f"No message or heartbeat for {self.heartbeat_timeout}s - connection stalled")
+            if response is grpc.aio.EOF:
+                return
+            yield response
+
     async def bidirectional_stream(self):
-        async with grpc.aio.insecure_channel(self.server_address) as channel:
+        span_name = 'server_stream' if self.rpc == 'server' else 'bidirectional_stream'
+        with start_span(self.tracer, span_name):
+            await self._bidirectional_stream()
+
+    async def _bidirectional_stream(self):
+        async with open_channel(self.server_address) as channel:
             stub = streaming_pb2_grpc.StreamingServiceStub(channel)
             
             # 순수 gRPC 표준 request generator (데이터 전송 없음)
@@ -39,14 +166,28 @@ class StreamingClient:
                 yield
 
             try:
-                print("🔗 [PYTHON CLIENT] Establishing bidirectional stream...")
-                self.call = stub.BidirectionalStream(empty_request_generator())
+                if self.rpc == 'server':
+                    # 세션/재개 정보는 메타데이터로 함께 전달됨 (SubscribeRequest에는 토픽만)
+                    print(f"🔗 [PYTHON CLIENT] Establishing server stream... topics={self.topics or '-'}")
+                    self.call = stub.ServerStream(streaming_pb2.SubscribeRequest(topics=self.topics), metadata=self.stream_metadata())
+                else:
+                    print("🔗 [PYTHON CLIENT] Establishing bidirectional stream...")
+                    self.call = stub.BidirectionalStream(empty_request_generator(), metadata=self.stream_metadata())
                 self.start_time = time.time()
                 
-                async for response in self.call:
+                async for response in self.responses():
+                    if response.heartbeat:
+                        # 서버가 보낼 데이터가 없을 때 보내는 keepalive - 메시지로 세지 않음
+                        print("💓 [PYTHON CLIENT] Heartbeat")
+                        continue
                     self.message_count += 1
-                    # 이제 직접 DataMessage를 받음
-                    print(f"📨 [PYTHON CLIENT] Received message {response.id}: {response.payload}")
+                    if response.topic:
+                        # 토픽마다 id를 따로 세므로 x-resume-from으로 이어받을 수 없음
+                        print(f"📨 [PYTHON CLIENT] Received message {response.id} on '{response.topic}': {response.payload}")
+                    else:
+                        self.last_message_id = response.id
+                        # 이제 직접 DataMessage를 받음
+                        print(f"📨 [PYTHON CLIENT] Received message {response.id}: {response.payload}")
                     
                     # 자동 cancel 체크
                     if self.auto_cancel_delay is not None:
@@ -58,12 +199,16 @@ class StreamingClient:
                             break
                 
                 print(f"✅ [PYTHON CLIENT] Stream ended normally. Total: {self.message_count} messages")
+                self.summary = stream_summary(await self.call.trailing_metadata())
+                print_summary(self.summary)
                             
             except grpc.aio.AioRpcError as e:
                 print(f"⚠️  [PYTHON CLIENT] gRPC Error occurred:")
                 print(f"   Status Code: {e.code()}")
                 print(f"   Details: {e.details()}")
                 print(f"   Messages received before error: {self.message_count}")
+                self.summary = stream_summary(e.trailing_metadata())
+                print_summary(self.summary)
                 
                 if e.code() == grpc.StatusCode.CANCELLED:
                     print("🚫 [PYTHON CLIENT] Stream was CANCELLED")
@@ -72,17 +217,67 @@ class StreamingClient:
                     print("🔌 [PYTHON CLIENT] Server UNAVAILABLE - likely network issue")
                 elif e.code() == grpc.StatusCode.DEADLINE_EXCEEDED:
                     print("⏰ [PYTHON CLIENT] DEADLINE_EXCEEDED - timeout occurred")
+                elif e.code() == grpc.StatusCode.RESOURCE_EXHAUSTED:
+                    print("🚦 [PYTHON CLIENT] RESOURCE_EXHAUSTED - server is at its stream limit, retry later")
+                    self.rejected = e
+                elif e.code() == grpc.StatusCode.UNAUTHENTICATED:
+                    print("🔐 [PYTHON CLIENT] UNAUTHENTICATED - token rejected, not a cancellation (check GRPC_AUTH_TOKEN)")
+                    self.auth_failed = True
                 else:
                     print(f"❓ [PYTHON CLIENT] Other error: {e.code()}")
                     
             except asyncio.CancelledError:
                 print("🚫 [PYTHON CLIENT] AsyncIO CancelledError - likely from call.cancel()")
                 print(f"   Messages received before cancellation: {self.message_count}")
+
+            except ConnectionError as e:
+                # heartbeat 끊김 - run_simple_test가 재연결하도록 다시 던짐
+                print(f"🕳️ [PYTHON CLIENT] {e}")
+                raise
                     
             except Exception as e:
                 print(f"💥 [PYTHON CLIENT] Unexpected error: {e}")
 
 
+    async def upload(self, count, cancel_after=None):
+        """ClientStream으로 count개를 0.5초 간격으로 올리고 요약을 받음 - cancel_after초가 지나면 업로드 도중 cancel()"""
+        async with open_channel(self.server_address) as channel:
+            stub = streaming_pb2_grpc.StreamingServiceStub(channel)
+
+            async def messages():
+                for i in range(1, count + 1):
+                    yield streaming_pb2.DataMessage(id=i, timestamp=int(time.time()), payload=f"Upload {i} from client")
+                    print(f"📤 [PYTHON CLIENT] Uploaded message {i}/{count}")
+                    await asyncio.sleep(0.5)
+
+            call = stub.ClientStream(messages(), metadata=trace_metadata() + auth_metadata())
+            if cancel_after is not None:
+                asyncio.get_running_loop().call_later(cancel_after, call.cancel)
+            try:
+                summary = await call
+                print(f"✅ [PYTHON CLIENT] Upload summary: {summary.messages} messages, "
+                      f"{summary.bytes} bytes in {summary.duration_ms}ms")
+            except asyncio.CancelledError:
+                print("🚫 [PYTHON CLIENT] Upload cancelled mid-stream → server sees CANCELLED")
+            except grpc.aio.AioRpcError as e:
+                print(f"⚠️  [PYTHON CLIENT] Upload failed: {e.code()} {e.details()}")
+
+    async def slow_unary(self, wait, cancel_after=None):
+        """SlowUnary로 wait초 뒤의 응답을 요청 - cancel_after초가 지나면 응답 전에 cancel()"""
+        async with open_channel(self.server_address) as channel:
+            stub = streaming_pb2_grpc.StreamingServiceStub(channel)
+            request = streaming_pb2.DelayRequest(delay_ms=int(wait * 1000))
+            call = stub.SlowUnary(request, metadata=trace_metadata() + auth_metadata())
+            if cancel_after is not None:
+                asyncio.get_running_loop().call_later(cancel_after, call.cancel)
+            try:
+                message = await call
+                print(f"✅ [PYTHON CLIENT] Unary reply: {message.payload}")
+            except asyncio.CancelledError:
+                print("🚫 [PYTHON CLIENT] Unary call cancelled → server drops the handler")
+            except grpc.aio.AioRpcError as e:
+                print(f"⚠️  [PYTHON CLIENT] Unary call failed: {e.code()} {e.details()}")
+
     async def run_simple_test(self):
         """간단한 연결 테스트 - 재연결 포함"""
         print("🔗 [PYTHON CLIENT] Starting connection test with auto-reconnection...")
@@ -95,6 +290,14 @@ class StreamingClient:
             try:
                 print(f"🔗 [PYTHON CLIENT] Connection attempt {retry_count + 1}")
                 await self.bidirectional_stream()
+
+                if self.auth_failed:
+                    print("❌ [PYTHON CLIENT] Authentication failed - not reconnecting")
+                    break
+                if self.rejected is not None:
+                    # 아래 gRPC 오류 재시도 경로로 넘김
+                    error, self.rejected = self.rejected, None
+                    raise error
                 
                 # 스트림이 정상 종료되면 완료
                 print("✅ [PYTHON CLIENT] Stream completed by server")
@@ -112,8 +315,12 @@ class StreamingClient:
                 print(f"🔌 [PYTHON CLIENT] gRPC error (attempt {retry_count}/{max_retries}): {e.code()}")
                 print(f"📊 [PYTHON CLIENT] Messages received so far: {self.message_count}")
                 
+                if e.code() == grpc.StatusCode.RESOURCE_EXHAUSTED:
+                    # 다른 스트림이 끝나야 자리가 나므로 실패가 이어질수록 더 오래 기다림
+                    retry_delay = min(2 ** consecutive_failures, 16)
+                    print(f"🚦 [PYTHON CLIENT] Server stream limit reached, backing off {retry_delay}s...")
                 # 네트워크 오류인 경우 더 자주 재시도
-                if e.code() in [grpc.StatusCode.UNAVAILABLE, grpc.StatusCode.DEADLINE_EXCEEDED]:
+                elif e.code() in [grpc.StatusCode.UNAVAILABLE, grpc.StatusCode.DEADLINE_EXCEEDED]:
                     retry_delay = 1  # 네트워크 오류는 1초 후 재시도
                     print(f"🔄 [PYTHON CLIENT] Network error detected, retrying in {retry_delay}s...")
                 else:
@@ -155,7 +362,7 @@ class StreamingClient:
         self.auto_cancel_delay = delay
         await self.bidirectional_stream()
 
-    def run(self, mode="auto_cancel", cancel_delay=3.0):
+    def run(self, mode="auto_cancel", cancel_delay=3.0, upload_count=10, unary_wait=5.0):
         """클라이언트 실행 - 의도적 취소 vs 네트워크 단절 테스트"""
         print("🚀 [PYTHON CLIENT] Starting gRPC reconnection test client")
         print(f"🔗 [PYTHON CLIENT] Connecting to server at {self.server_address}")
@@ -169,8 +376,25 @@ class StreamingClient:
             print("   Expected: Receive all messages from server")
             asyncio.run(self.run_simple_test())
             
+        elif mode == "upload":
+            print(f"📤 [PYTHON CLIENT] UPLOAD MODE: ClientStream with {upload_count} messages")
+            asyncio.run(self.upload(upload_count))
+
+        elif mode == "upload_cancel":
+            print(f"📤 [PYTHON CLIENT] UPLOAD CANCEL MODE: cancel the upload after {cancel_delay} seconds")
+            asyncio.run(self.upload(upload_count, cancel_after=cancel_delay))
+
+        elif mode == "unary":
+            print(f"⏳ [PYTHON CLIENT] UNARY MODE: SlowUnary replying after {unary_wait} seconds")
+            asyncio.run(self.slow_unary(unary_wait))
+
+        elif mode == "unary_cancel":
+            print(f"⏳ [PYTHON CLIENT] UNARY CANCEL MODE: cancel SlowUnary after {cancel_delay} seconds")
+            asyncio.run(self.slow_unary(unary_wait, cancel_after=cancel_delay))
+
         else:
-            print(f"❌ [PYTHON CLIENT] Unknown mode: {mode}. Use 'auto_cancel' or 'simple'")
+            print(f"❌ [PYTHON CLIENT] Unknown mode: {mode}. Use 'auto_cancel', 'simple', 'upload', 'upload_cancel', "
+                  "'unary' or 'unary_cancel'")
             sys.exit(1)
 
 
@@ -178,12 +402,31 @@ if __name__ == "__main__":
     import argparse
     
     parser = argparse.ArgumentParser(description='gRPC Cancellation vs Disconnection Test Client')
-    parser.add_argument('--mode', choices=['auto_cancel', 'simple'], default='auto_cancel',
-                       help='Test mode: auto_cancel (automatic cancel after delay), simple (basic connection test)')
+    parser.add_argument('--mode', choices=['auto_cancel', 'simple', 'upload', 'upload_cancel', 'unary', 'unary_cancel'],
+                       default='auto_cancel',
+                       help='Test mode: auto_cancel (automatic cancel after delay), simple (basic connection test), '
+                            'upload (ClientStream), upload_cancel (cancel the upload after delay), '
+                            'unary (SlowUnary), unary_cancel (cancel SlowUnary after delay)')
     parser.add_argument('--delay', type=float, default=3.0,
                        help='Delay in seconds before auto-cancellation (default: 3.0)')
+    parser.add_argument('--rpc', choices=['bidi', 'server'], default='bidi',
+                       help='RPC shape: bidi (BidirectionalStream) or server (ServerStream, no request stream)')
+    parser.add_argument('--count', type=int, default=10,
+                       help='Messages to upload in upload modes (default: 10)')
+    parser.add_argument('--heartbeat-timeout', type=float, default=None,
+                       help='Reconnect when no message or heartbeat arrives for this many seconds '
+                            '(pair with the server --heartbeat-interval)')
+    parser.add_argument('--wait', type=float, default=5.0,
+                       help='Seconds the server waits before answering SlowUnary in unary modes (default: 5.0)')
+    parser.add_argument('--max-messages', type=int, default=None,
+                       help='Ask the server for this many messages (x-max-messages; default: server --max-messages)')
+    parser.add_argument('--topics', type=lambda value: [topic for topic in value.split(',') if topic], default=[],
+                       help='Comma-separated topics to subscribe to with --rpc server; each topic has its own '
+                            'generator and messages arrive tagged with their topic')
     
     args = parser.parse_args()
+    if args.topics and args.rpc != 'server':
+        parser.error('--topics requires --rpc server')
     
     print("=" * 60)
     print("🧪 gRPC INTENTIONAL CANCEL vs NETWORK DISCONNECTION")
@@ -194,5 +437,6 @@ if __name__ == "__main__":
     print("=" * 60)
     print()
     
-    client = StreamingClient()
-    client.run(args.mode, args.delay)
\ No newline at end of file
+    client = StreamingClient(rpc=args.rpc, heartbeat_timeout=args.heartbeat_timeout, max_messages=args.max_messages,
+                             topics=args.topics)
+    client.run(args.mode, args.delay, args.count, args.wait)
\ No newline at end of file
@@ -14,7 +14,7 @@ _sym_db = _symbol_database.Default()
 
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0fstreaming.proto\x12\tstreaming\"=\n\x0b\x44\x61taMessage\x12\n\n\x02id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07payload\x18\x03 \x01(\t2]\n\x10StreamingService\x12I\n\x13\x42idirectionalStream\x12\x16.streaming.DataMessage\x1a\x16.streaming.DataMessage(\x01\x30\x01\x62\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0fstreaming.proto\x12\tstreaming\"_\n\x0b\x44\x61taMessage\x12\n\n\x02id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07payload\x18\x03 \x01(\t\x12\x11\n\theartbeat\x18\x04 \x01(\x08\x12\r\n\x05topic\x18\x05 \x01(\t\"K\n\x10SubscribeRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x13\n\x0bresume_from\x18\x02 \x01(\x04\x12\x0e\n\x06topics\x18\x03 \x03(\t\" \n\x0c\x44\x65layRequest\x12\x10\n\x08\x64\x65lay_ms\x18\x01 \x01(\x04\"E\n\rUploadSummary\x12\x10\n\x08messages\x18\x01 \x01(\x04\x12\r\n\x05\x62ytes\x18\x02 \x01(\x04\x12\x13\n\x0b\x64uration_ms\x18\x03 \x01(\x04\x32\xa6\x02\n\x10StreamingService\x12I\n\x13\x42idirectionalStream\x12\x16.streaming.DataMessage\x1a\x16.streaming.DataMessage(\x01\x30\x01\x12\x45\n\x0cServerStream\x12\x1b.streaming.SubscribeRequest\x1a\x16.streaming.DataMessage0\x01\x12\x42\n\x0c\x43lientStream\x12\x16.streaming.DataMessage\x1a\x18.streaming.UploadSummary(\x01\x12<\n\tSlowUnary\x12\x17.streaming.DelayRequest\x1a\x16.streaming.DataMessageb\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
@@ -22,7 +22,13 @@ _builder.BuildTopDescriptorsAndMessages(DESCRIPTOR, 'streaming_pb2', _globals)
 if _descriptor._USE_C_DESCRIPTORS == False:
   DESCRIPTOR._options = None
   _globals['_DATAMESSAGE']._serialized_start=30
-  _globals['_DATAMESSAGE']._serialized_end=91
-  _globals['_STREAMINGSERVICE']._serialized_start=93
-  _globals['_STREAMINGSERVICE']._serialized_end=186
+  _globals['_DATAMESSAGE']._serialized_end=125
+  _globals['_SUBSCRIBEREQUEST']._serialized_start=127
+  _globals['_SUBSCRIBEREQUEST']._serialized_end=202
+  _globals['_DELAYREQUEST']._serialized_start=204
+  _globals['_DELAYREQUEST']._serialized_end=236
+  _globals['_UPLOADSUMMARY']._serialized_start=238
+  _globals['_UPLOADSUMMARY']._serialized_end=307
+  _globals['_STREAMINGSERVICE']._serialized_start=310
+  _globals['_STREAMINGSERVICE']._serialized_end=604
 # @@protoc_insertion_point(module_scope)
@@ -19,6 +19,21 @@ class StreamingServiceStub(object):
                 request_serializer=streaming__pb2.DataMessage.SerializeToString,
                 response_deserializer=streaming__pb2.DataMessage.FromString,
                 )
+        self.ServerStream = channel.unary_stream(
+                '/streaming.StreamingService/ServerStream',
+                request_serializer=streaming__pb2.SubscribeRequest.SerializeToString,
+                response_deserializer=streaming__pb2.DataMessage.FromString,
+                )
+        self.ClientStream = channel.stream_unary(
+                '/streaming.StreamingService/ClientStream',
+                request_serializer=streaming__pb2.DataMessage.SerializeToString,
+                response_deserializer=streaming__pb2.UploadSummary.FromString,
+                )
+        self.SlowUnary = channel.unary_unary(
+                '/streaming.StreamingService/SlowUnary',
+                request_serializer=streaming__pb2.DelayRequest.SerializeToString,
+                response_deserializer=streaming__pb2.DataMessage.FromString,
+                )
 
 
 class StreamingServiceServicer(object):
@@ -30,6 +45,27 @@ class StreamingServiceServicer(object):
         context.set_details('Method not implemented!')
         raise NotImplementedError('Method not implemented!')
 
+    def ServerStream(self, request, context):
+        """같은 생성기를 서버 스트리밍으로 - 요청 스트림이 없어 클라이언트 취소는 RST_STREAM으로만 전달됨
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def ClientStream(self, request_iterator, context):
+        """클라이언트 스트리밍 업로드 - 클라이언트가 스트림을 닫으면 받은 메시지 수와 바이트를 돌려줌
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def SlowUnary(self, request, context):
+        """unary 취소 실험 - 요청한 시간만큼 기다린 뒤 메시지 하나를 돌려줌 (취소되면 tonic이 핸들러 future를 버림)
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
 
 def add_StreamingServiceServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -38,6 +74,21 @@ def add_StreamingServiceServicer_to_server(servicer, server):
                     request_deserializer=streaming__pb2.DataMessage.FromString,
                     response_serializer=streaming__pb2.DataMessage.SerializeToString,
             ),
+            'ServerStream': grpc.unary_stream_rpc_method_handler(
+                    servicer.ServerStream,
+                    request_deserializer=streaming__pb2.SubscribeRequest.FromString,
+                    response_serializer=streaming__pb2.DataMessage.SerializeToString,
+            ),
+            'ClientStream': grpc.stream_unary_rpc_method_handler(
+                    servicer.ClientStream,
+                    request_deserializer=streaming__pb2.DataMessage.FromString,
+                    response_serializer=streaming__pb2.UploadSummary.SerializeToString,
+            ),
+            'SlowUnary': grpc.unary_unary_rpc_method_handler(
+                    servicer.SlowUnary,
+                    request_deserializer=streaming__pb2.DelayRequest.FromString,
+                    response_serializer=streaming__pb2.DataMessage.SerializeToString,
+            ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
             'streaming.StreamingService', rpc_method_handlers)
@@ -64,3 +115,54 @@ class StreamingService(object):
             streaming__pb2.DataMessage.FromString,
             options, channel_credentials,
             insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
+
+    @staticmethod
+    def ServerStream(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_stream(request, target, '/streaming.StreamingService/ServerStream',
+            streaming__pb2.SubscribeRequest.SerializeToString,
+            streaming__pb2.DataMessage.FromString,
+            options, channel_credentials,
+            insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
+
+    @staticmethod
+    def ClientStream(request_iterator,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.stream_unary(request_iterator, target, '/streaming.StreamingService/ClientStream',
+            streaming__pb2.DataMessage.SerializeToString,
+            streaming__pb2.UploadSummary.FromString,
+            options, channel_credentials,
+            insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
+
+    @staticmethod
+    def SlowUnary(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(request, target, '/streaming.StreamingService/SlowUnary',
+            streaming__pb2.DelayRequest.SerializeToString,
+            streaming__pb2.DataMessage.FromString,
+            options, channel_credentials,
+            insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
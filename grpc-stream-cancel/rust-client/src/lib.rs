@@ -0,0 +1,201 @@
+//! 취소 데모의 Rust 참조 클라이언트
+//!
+//! 서버에 양방향 스트림을 열어 메시지를 받다가 `cancel_after`가 지나면 끊음. 호출을 버려
+//! RST_STREAM(CANCEL)을 보내거나(`StopMode::Cancel`), HTTP/2 프레임 없이 TCP 연결만 닫아
+//! 네트워크 단절을 흉내냄(`StopMode::Abort`).
+
+use std::fmt;
+use std::net::Shutdown;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cancel_server::session::{RESUME_METADATA, SESSION_METADATA};
+use cancel_server::streaming::streaming_service_client::StreamingServiceClient;
+use cancel_server::streaming::DataMessage;
+use hyper_util::rt::TokioIo;
+use playground_auth::{metadata_value, AUTHORIZATION_METADATA};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic::{Request, Status};
+use tower::service_fn;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `cancel_after`가 지났을 때 스트림을 끊는 방법
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    /// 호출을 버림 - tonic이 RST_STREAM(CANCEL)을 보냄
+    Cancel,
+    /// 소켓을 닫음 - 서버는 RST_STREAM이나 GOAWAY 없이 연결이 끊긴 것만 봄
+    Abort,
+}
+
+impl FromStr for StopMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cancel" => Ok(StopMode::Cancel),
+            "abort" => Ok(StopMode::Abort),
+            other => Err(format!("unknown stop mode '{}' (expected cancel or abort)", other)),
+        }
+    }
+}
+
+impl fmt::Display for StopMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StopMode::Cancel => "cancel",
+            StopMode::Abort => "abort",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// `http://host:port`
+    pub addr: String,
+    /// 스트림을 연 뒤 이만큼 지나면 `stop_mode`로 끊음 - `None`이면 서버가 끝낼 때까지 받음
+    pub cancel_after: Option<Duration>,
+    pub stop_mode: StopMode,
+    /// `x-session-id` 메타데이터 (재연결 세션)
+    pub session_id: Option<String>,
+    /// `x-resume-from` 메타데이터 - 이 id까지 받았다고 알림
+    pub resume_from: Option<u64>,
+    /// 서버 인증용 bearer 토큰
+    pub auth_token: Option<String>,
+    /// 받은 메시지를 한 줄씩 출력
+    pub verbose: bool,
+}
+
+impl ClientConfig {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            cancel_after: None,
+            stop_mode: StopMode::Cancel,
+            session_id: None,
+            resume_from: None,
+            auth_token: None,
+            verbose: false,
+        }
+    }
+}
+
+/// 스트림이 끝난 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    /// 서버가 OK로 닫음
+    Completed,
+    /// `cancel_after`가 지나 호출을 버림
+    Cancelled,
+    /// `cancel_after`가 지나 TCP 연결을 닫음
+    Aborted,
+}
+
+/// 받은 메시지 기록
+#[derive(Debug, Default)]
+pub struct StreamReport {
+    /// 수신 순서대로 기록한 메시지 id (heartbeat 제외)
+    pub received: Vec<u64>,
+    pub heartbeats: u64,
+}
+
+/// 마지막으로 연 TCP 연결의 복제본 - 닫으면 그 위의 HTTP/2 연결이 프레임 없이 끊김
+#[derive(Clone, Default)]
+struct Connection(Arc<Mutex<Option<std::net::TcpStream>>>);
+
+impl Connection {
+    fn abort(&self) {
+        if let Some(socket) = self.0.lock().unwrap().take() {
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// 스트림이 끝나거나 `cancel_after`가 지날 때까지 받으며 `report`에 기록 - 서버 오류는 그 상태를 반환
+pub async fn run(config: &ClientConfig, report: &mut StreamReport) -> Result<Ending, Status> {
+    let connection = Connection::default();
+    let channel = connect(&config.addr, connection.clone()).await?;
+
+    // 요청 스트림으로는 아무것도 보내지 않지만 끊을 때까지 열어 둠 - 닫으면 서버는 half-close로 봄
+    let (requests, request_rx) = mpsc::channel::<DataMessage>(1);
+    let mut request = Request::new(ReceiverStream::new(request_rx));
+    if let Some(session) = &config.session_id {
+        request.metadata_mut().insert(SESSION_METADATA, metadata_value(session)?);
+    }
+    if let Some(last_id) = config.resume_from {
+        request.metadata_mut().insert(RESUME_METADATA, metadata_value(&last_id.to_string())?);
+    }
+    if let Some(token) = &config.auth_token {
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_METADATA, metadata_value(&format!("Bearer {}", token))?);
+    }
+    let mut stream = StreamingServiceClient::new(channel)
+        .bidirectional_stream(request)
+        .await?
+        .into_inner();
+
+    let deadline = config.cancel_after.map(|delay| Instant::now() + delay);
+    loop {
+        let message = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, stream.message()).await {
+                Ok(message) => message?,
+                Err(_) => break,
+            },
+            None => stream.message().await?,
+        };
+        let Some(message) = message else {
+            return Ok(Ending::Completed);
+        };
+        if message.heartbeat {
+            report.heartbeats += 1;
+            if config.verbose {
+                println!("💓 [RUST CLIENT] Heartbeat");
+            }
+            continue;
+        }
+        report.received.push(message.id);
+        if config.verbose {
+            println!("📨 [RUST CLIENT] Received message {}: {}", message.id, message.payload);
+        }
+    }
+
+    match config.stop_mode {
+        StopMode::Cancel => {
+            // 응답 스트림과 요청 sender를 모두 버려야 tonic이 RST_STREAM(CANCEL)을 보냄
+            drop(stream);
+            drop(requests);
+            Ok(Ending::Cancelled)
+        }
+        StopMode::Abort => {
+            connection.abort();
+            Ok(Ending::Aborted)
+        }
+    }
+}
+
+/// 연결한 소켓을 `connection`에 남겨 두는 connector로 채널을 엶
+async fn connect(addr: &str, connection: Connection) -> Result<Channel, Status> {
+    Endpoint::try_from(addr.to_string())
+        .map_err(|e| Status::invalid_argument(e.to_string()))?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .connect_with_connector(service_fn(move |uri: Uri| {
+            let connection = connection.clone();
+            async move {
+                // IPv6 호스트는 `[::1]`처럼 괄호가 붙어 있음
+                let host = uri.host().unwrap_or_default().trim_matches(['[', ']']);
+                let stream = TcpStream::connect((host, uri.port_u16().unwrap_or(80))).await?;
+                let socket = stream.into_std()?;
+                *connection.0.lock().unwrap() = Some(socket.try_clone()?);
+                Ok::<_, std::io::Error>(TokioIo::new(TcpStream::from_std(socket)?))
+            }
+        }))
+        .await
+        .map_err(|e| Status::unavailable(e.to_string()))
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use clap::Parser;
+use grpc_stream_cancel_client::{ClientConfig, Ending, StopMode, StreamReport};
+
+#[derive(Parser, Debug)]
+#[command(name = "grpc-stream-cancel-client")]
+#[command(about = "Reference client for the cancellation demo: consumes the bidi stream and cancels or aborts it")]
+struct Args {
+    #[arg(long, default_value = "http://[::1]:50051")]
+    addr: String,
+
+    /// Stop the stream this many seconds after opening it (default: read until the server closes it)
+    #[arg(long)]
+    cancel_after: Option<f64>,
+
+    /// How to stop: `cancel` drops the call (RST_STREAM), `abort` closes the TCP connection without any HTTP/2 frame
+    #[arg(long, default_value_t = StopMode::Cancel)]
+    mode: StopMode,
+
+    /// Session ID sent as x-session-id metadata
+    #[arg(long)]
+    session_id: Option<String>,
+
+    /// Last message ID already received, sent as x-resume-from metadata
+    #[arg(long)]
+    resume_from: Option<u64>,
+
+    /// Bearer token for a server started with --auth-token or --auth-hmac-secret
+    #[arg(long, env = "GRPC_AUTH_TOKEN", hide_env_values = true)]
+    auth_token: Option<String>,
+
+    /// Only print how the stream ended, not every message
+    #[arg(long)]
+    quiet: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    let config = ClientConfig {
+        addr: args.addr,
+        cancel_after: args.cancel_after.map(Duration::from_secs_f64),
+        stop_mode: args.mode,
+        session_id: args.session_id,
+        resume_from: args.resume_from,
+        auth_token: args.auth_token,
+        verbose: !args.quiet,
+    };
+
+    match config.cancel_after {
+        Some(delay) => println!("⏰ [RUST CLIENT] Connecting to {}, will {} after {:?}", config.addr, config.stop_mode, delay),
+        None => println!("🔗 [RUST CLIENT] Connecting to {}", config.addr),
+    }
+    let mut report = StreamReport::default();
+    let result = grpc_stream_cancel_client::run(&config, &mut report).await;
+
+    let total = report.received.len();
+    match result {
+        Ok(Ending::Completed) => println!("✅ [RUST CLIENT] Stream completed by server. Total: {} messages", total),
+        Ok(Ending::Cancelled) => println!("📤 [RUST CLIENT] Dropped the call → RST_STREAM(CANCEL). Total: {} messages", total),
+        Ok(Ending::Aborted) => println!("🔌 [RUST CLIENT] Closed the TCP connection without RST_STREAM. Total: {} messages", total),
+        Err(status) => {
+            println!("⚠️  [RUST CLIENT] Stream ended with {:?}: {}. Total: {} messages", status.code(), status.message(), total);
+            std::process::exit(1);
+        }
+    }
+}
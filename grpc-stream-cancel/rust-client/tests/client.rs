@@ -0,0 +1,86 @@
+//! 임시 포트에 띄운 취소 서버를 상대로 클라이언트를 실행 - tokio 시간을 멈춰 두어 메시지 간격만큼 기다리지 않음
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cancel_server::server::StreamingServer;
+use cancel_server::streaming::streaming_service_server::StreamingServiceServer;
+use grpc_stream_cancel_client::{ClientConfig, Ending, StopMode, StreamReport};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+async fn serve(max_messages: u64, stats: Arc<ServerStats>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = StreamingServer::new(1, max_messages, stats, Arc::new(TokioClock::new()));
+    tokio::spawn(
+        Server::builder()
+            .add_service(StreamingServiceServer::new(server))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    addr
+}
+
+/// 서버가 스트림을 정리하고 취소로 기록할 때까지 기다림
+async fn wait_for_cancellation(stats: &ServerStats) {
+    while stats.cancellations().values().sum::<u64>() == 0 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// 1부터 빠짐없이 받다가 끊겼는지
+fn assert_prefix(report: &StreamReport, max_messages: u64) {
+    let received = report.received.len() as u64;
+    assert!((1..max_messages).contains(&received), "received {:?}", report.received);
+    assert_eq!(report.received, (1..=received).collect::<Vec<_>>());
+}
+
+#[tokio::test(start_paused = true)]
+async fn reads_until_the_server_completes_the_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve(3, stats.clone()).await;
+
+    let mut report = StreamReport::default();
+    let ending = grpc_stream_cancel_client::run(&ClientConfig::new(format!("http://{}", addr)), &mut report)
+        .await
+        .unwrap();
+
+    assert_eq!(ending, Ending::Completed);
+    assert_eq!(report.received, vec![1, 2, 3]);
+    assert!(stats.cancellations().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancel_after_drops_the_call_mid_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve(100, stats.clone()).await;
+
+    let mut config = ClientConfig::new(format!("http://{}", addr));
+    config.cancel_after = Some(Duration::from_millis(2500));
+    let mut report = StreamReport::default();
+    let ending = grpc_stream_cancel_client::run(&config, &mut report).await.unwrap();
+
+    assert_eq!(ending, Ending::Cancelled);
+    assert_prefix(&report, 100);
+    wait_for_cancellation(&stats).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn abort_closes_the_connection_mid_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve(100, stats.clone()).await;
+
+    let mut config = ClientConfig::new(format!("http://{}", addr));
+    config.cancel_after = Some(Duration::from_millis(2500));
+    config.stop_mode = StopMode::Abort;
+    let mut report = StreamReport::default();
+    let ending = grpc_stream_cancel_client::run(&config, &mut report).await.unwrap();
+
+    assert_eq!(ending, Ending::Aborted);
+    assert_prefix(&report, 100);
+    wait_for_cancellation(&stats).await;
+}
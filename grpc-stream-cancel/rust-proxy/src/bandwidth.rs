@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// 방향별 대역폭 상한 (바이트/초) - 연결마다 방향별 `TokenBucket`을 따로 둠
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bandwidth {
+    /// client → server
+    pub up: Option<u64>,
+    /// server → client
+    pub down: Option<u64>,
+}
+
+/// 초당 `rate` 바이트씩 채워지는 토큰 버킷 - 최대 0.1초 분량(`burst`)까지 모아 둠
+///
+/// 보낸 만큼 토큰을 먼저 빼고(모자라면 빚), 빚을 갚을 때까지 기다릴 시간을 돌려줌.
+/// 그래서 긴 구간의 평균은 정확히 `rate`이고 한 번에 `burst`보다 많이 몰려 나가지 않음.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: u64,
+    burst: u64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    /// 가득 찬 버킷 (`rate`는 0보다 커야 함)
+    pub fn new(rate: u64, now: Instant) -> Self {
+        let burst = (rate / 10).max(1);
+        Self {
+            rate,
+            burst,
+            tokens: burst as f64,
+            refilled: now,
+        }
+    }
+
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// 한 번에 읽을 최대 바이트 수
+    pub fn burst(&self) -> usize {
+        self.burst as usize
+    }
+
+    /// `n` 바이트를 보내고 그만큼 채워질 때까지 기다릴 시간 (충분하면 0)
+    pub fn take(&mut self, n: usize, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.burst as f64) - n as f64;
+        self.refilled = now;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate as f64)
+        }
+    }
+}
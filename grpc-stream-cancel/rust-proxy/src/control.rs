@@ -0,0 +1,83 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::fault::{Direction, FaultWindow};
+use crate::scenario::Phase;
+
+/// `POST /faults`로 건 장애 - 해제하거나 `for`가 지날 때까지 모든 연결에서 스케줄보다 우선
+///
+/// 스케줄을 가리므로 `restore`를 걸면 스케줄에 장애가 있어도 그대로 전달하고, 한 방향에만 걸면 반대 방향은
+/// 장애 없이 전달함.
+#[derive(Default)]
+pub struct FaultControl {
+    forced: Mutex<Option<Forced>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Forced {
+    phase: Phase,
+    set_at: Instant,
+}
+
+/// `GET /faults` 응답
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ForcedView {
+    pub fault: &'static str,
+    pub direction: Direction,
+    /// None이면 해제할 때까지
+    pub remaining_ms: Option<u64>,
+}
+
+impl FaultControl {
+    /// 이전에 건 장애를 바꿈 - `for`는 지금부터 잼
+    pub fn set(&self, phase: Phase) {
+        *self.forced.lock().unwrap() = Some(Forced {
+            phase,
+            set_at: Instant::now(),
+        });
+    }
+
+    /// 해제하고 스케줄로 돌아감 - 건 장애가 있었는지 반환
+    pub fn clear(&self) -> bool {
+        self.forced.lock().unwrap().take().is_some()
+    }
+
+    /// 지금 걸린 장애 - 바깥 None은 스케줄을 따름, 안쪽 None은 `restore`
+    pub fn window(&self) -> Option<Option<FaultWindow>> {
+        let forced = self.current()?;
+        let phase = forced.phase;
+        Some(phase.fault.map(|fault| FaultWindow {
+            fault,
+            start: Duration::ZERO,
+            duration: phase.duration.unwrap_or(Duration::MAX),
+            repeat_every: None,
+            direction: phase.direction,
+        }))
+    }
+
+    pub fn view(&self) -> Option<ForcedView> {
+        let forced = self.current()?;
+        Some(ForcedView {
+            fault: forced.phase.fault.map_or("restore", |fault| fault.name()),
+            direction: forced.phase.direction,
+            remaining_ms: forced
+                .phase
+                .duration
+                .map(|duration| duration.saturating_sub(forced.set_at.elapsed()).as_millis() as u64),
+        })
+    }
+
+    /// 만료된 장애는 지우고 None
+    fn current(&self) -> Option<Forced> {
+        let mut forced = self.forced.lock().unwrap();
+        let expired = forced
+            .as_ref()
+            .and_then(|forced| forced.phase.duration.map(|duration| forced.set_at.elapsed() >= duration));
+        if expired == Some(true) {
+            *forced = None;
+        }
+        *forced
+    }
+}
@@ -0,0 +1,302 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::bandwidth::Bandwidth;
+
+/// 프록시가 주입할 수 있는 네트워크 장애 종류
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// 전달을 멈춤 (패킷이 오지 않는 상황) - 멈춘 동안의 바이트를 어떻게 할지는 `BlockMode`
+    Block(BlockMode),
+    /// 연결을 즉시 닫음 - 이 쪽 소켓은 SO_LINGER=0으로 닫아 RST를 보내고, 나머지 쪽은 FIN으로 닫음
+    Reset(Side),
+    /// 청크마다 `delay` ± `jitter`(균등 분포) 지연 추가 - 방향마다 따로 뽑음
+    Latency { delay: Duration, jitter: Duration },
+    /// 읽은 청크를 주어진 확률로 버림
+    ///
+    /// TCP 패킷 손실과 달리 커널이 다시 보내주지 않으므로 바이트 스트림에 구멍이 나고, 그 뒤의 HTTP/2 프레임
+    /// 경계가 어긋나 대개 상대가 PROTOCOL_ERROR/GOAWAY로 연결을 끊음. 연결 수준 오류와 재연결을 일으키는 용도.
+    Loss(f64),
+    /// 청크를 `rate` 확률로 골라 서로 다른 위치 `bytes`개(청크보다 길면 청크 전체)의 비트를 하나씩 뒤집어 전달
+    Corrupt { rate: f64, bytes: usize },
+    /// 초당 이 바이트 수까지만 전달
+    Throttle(u64),
+}
+
+impl Fault {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fault::Block(_) => "block",
+            Fault::Reset(_) => "reset",
+            Fault::Latency { .. } => "latency",
+            Fault::Loss(_) => "loss",
+            Fault::Corrupt { .. } => "corrupt",
+            Fault::Throttle(_) => "throttle",
+        }
+    }
+}
+
+/// 차단 중에 들어온 바이트 처리
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockMode {
+    /// 읽지 않음 - 커널 버퍼와 TCP 흐름 제어가 붙잡아 두었다가 풀리면 늦게 모두 전달
+    #[default]
+    Stall,
+    /// 읽어서 버림 - 풀려도 그동안의 바이트는 오지 않고, 바이트 스트림에 구멍이 나 HTTP/2 연결이 깨짐
+    Discard,
+    /// 차단이 시작되면 연결을 닫음 (양쪽에 FIN)
+    Close,
+}
+
+/// `Reset`이 RST를 보낼 소켓
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    #[default]
+    Both,
+    /// 클라이언트 쪽 소켓
+    Client,
+    /// 서버 쪽 소켓
+    Server,
+}
+
+impl Side {
+    pub fn client(&self) -> bool {
+        *self != Side::Server
+    }
+
+    pub fn server(&self) -> bool {
+        *self != Side::Client
+    }
+}
+
+/// 장애를 적용할 전달 방향 - `Reset`은 어느 방향이든 연결 전체를 닫음
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    #[default]
+    Both,
+    #[value(alias = "up")]
+    ClientToServer,
+    #[value(alias = "down")]
+    ServerToClient,
+}
+
+impl Direction {
+    /// 이 구간이 `pump` 방향(`ClientToServer` 또는 `ServerToClient`)에 적용되는지
+    pub fn covers(&self, pump: Direction) -> bool {
+        *self == Direction::Both || *self == pump
+    }
+}
+
+/// `start`부터 `duration` 동안 `direction` 방향에 `fault`를 적용 - `repeat_every`가 있으면 그 주기마다 다시 적용
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultWindow {
+    pub fault: Fault,
+    pub start: Duration,
+    pub duration: Duration,
+    pub repeat_every: Option<Duration>,
+    pub direction: Direction,
+}
+
+impl FaultWindow {
+    fn contains(&self, elapsed: Duration) -> bool {
+        let Some(since_start) = elapsed.checked_sub(self.start) else {
+            return false;
+        };
+        let offset = match self.repeat_every {
+            Some(period) if !period.is_zero() => {
+                Duration::from_nanos((since_start.as_nanos() % period.as_nanos()) as u64)
+            }
+            _ => since_start,
+        };
+        // `Duration::MAX`는 끝나지 않는 구간
+        offset < self.duration
+    }
+}
+
+/// `with_reset` 구간의 길이 - 장애를 다시 확인하는 주기(0.1초)보다 넉넉히 길어야 열린 연결을 모두 끊음
+pub const RESET_WINDOW: Duration = Duration::from_secs(1);
+
+/// 장애 타이머의 기준 시각
+#[derive(Debug, Clone, Copy)]
+pub enum ScheduleOrigin {
+    /// 연결마다 타이머를 새로 시작
+    PerConnection,
+    /// 모든 연결이 같은 기준 시각을 공유
+    Shared(Instant),
+}
+
+#[derive(Debug, Clone)]
+pub struct FaultSchedule {
+    pub origin: ScheduleOrigin,
+    pub windows: Vec<FaultWindow>,
+    /// 장애 구간과 별개로 항상 적용하는 방향별 대역폭 상한
+    pub bandwidth: Bandwidth,
+    /// `Loss`/`Corrupt` 주사위와 `Latency` jitter의 시드 - 연결마다 `seed + 연결 번호`로 RNG를 만듦 (None이면 매번 다름)
+    pub seed: Option<u64>,
+}
+
+impl FaultSchedule {
+    pub fn new(origin: ScheduleOrigin) -> Self {
+        Self {
+            origin,
+            windows: Vec::new(),
+            bandwidth: Bandwidth::default(),
+            seed: None,
+        }
+    }
+
+    pub fn with(mut self, fault: Fault, start: Duration, duration: Duration) -> Self {
+        self.windows.push(FaultWindow {
+            fault,
+            start,
+            duration,
+            repeat_every: None,
+            direction: Direction::Both,
+        });
+        self
+    }
+
+    /// `start`부터 `every`마다 `duration` 동안 `fault`를 적용
+    pub fn with_repeating(mut self, fault: Fault, start: Duration, duration: Duration, every: Duration) -> Self {
+        self.windows.push(FaultWindow {
+            fault,
+            start,
+            duration,
+            repeat_every: Some(every),
+            direction: Direction::Both,
+        });
+        self
+    }
+
+    pub fn with_window(mut self, window: FaultWindow) -> Self {
+        self.windows.push(window);
+        self
+    }
+
+    pub fn with_origin(mut self, origin: ScheduleOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// `at`에 열려 있는 연결을 모두 끊음 (`side` 쪽에는 RST) - 다른 구간보다 우선
+    ///
+    /// `RESET_WINDOW` 동안 유지하므로 그 사이에 새로 연결해도 바로 끊김.
+    pub fn with_reset(mut self, at: Duration, side: Side) -> Self {
+        self.windows.insert(
+            0,
+            FaultWindow {
+                fault: Fault::Reset(side),
+                start: at,
+                duration: RESET_WINDOW,
+                repeat_every: None,
+                direction: Direction::Both,
+            },
+        );
+        self
+    }
+
+    /// 처음부터 끝까지 `direction` 방향 청크를 `rate` 확률로 버림 - 먼저 넣은 구간이 겹치는 동안은 그 구간이 우선
+    pub fn with_loss(self, rate: f64, direction: Direction) -> Self {
+        self.with_window(FaultWindow {
+            fault: Fault::Loss(rate),
+            start: Duration::ZERO,
+            duration: Duration::MAX,
+            repeat_every: None,
+            direction,
+        })
+    }
+
+    /// 처음부터 끝까지 모든 청크에 지연을 더함 - 먼저 넣은 구간(차단 등)이 겹치는 동안은 그 구간이 우선
+    pub fn with_latency(self, delay: Duration, jitter: Duration) -> Self {
+        self.with(Fault::Latency { delay, jitter }, Duration::ZERO, Duration::MAX)
+    }
+
+    /// 지금(프록시 시작)부터 `at` 후 `duration`간 `direction` 방향을 `mode`로 차단 (`every`가 있으면 그 주기로 반복)
+    ///
+    /// 모든 연결이 같은 타임라인을 따르므로 차단 뒤 다시 연결해도 다시 차단되지 않음.
+    /// 기본값(5초 후 5초, 양방향, stall)이 기존 데모 동작.
+    pub fn block(mode: BlockMode, at: Duration, duration: Duration, every: Option<Duration>, direction: Direction) -> Self {
+        Self::new(ScheduleOrigin::Shared(Instant::now())).with_window(FaultWindow {
+            fault: Fault::Block(mode),
+            start: at,
+            duration,
+            repeat_every: every,
+            direction,
+        })
+    }
+
+    /// 처음부터 끝까지 청크를 `rate` 확률로 골라 `bytes`개 바이트를 손상시킴
+    pub fn corrupt(rate: f64, bytes: usize) -> Self {
+        Self::new(ScheduleOrigin::Shared(Instant::now())).with(Fault::Corrupt { rate, bytes }, Duration::ZERO, Duration::MAX)
+    }
+
+    /// 연결이 시작된 시각을 받아 타이머 기준 시각 결정
+    pub fn origin_for(&self, connected_at: Instant) -> Instant {
+        match self.origin {
+            ScheduleOrigin::PerConnection => connected_at,
+            ScheduleOrigin::Shared(origin) => origin,
+        }
+    }
+
+    /// 기준 시각으로부터 `elapsed` 시점에 활성화된 장애 구간 - 여러 구간이 겹치면 먼저 넣은 구간
+    pub fn active(&self, elapsed: Duration) -> Option<&FaultWindow> {
+        self.windows.iter().find(|window| window.contains(elapsed))
+    }
+
+    /// `active`와 같되 `pump` 방향에 걸린 구간만 봄 - 한 방향만 차단하는 동안에도 반대 방향에는 다른 구간이 적용됨
+    pub fn active_towards(&self, elapsed: Duration, pump: Direction) -> Option<&FaultWindow> {
+        self.all_towards(elapsed, pump).next()
+    }
+
+    /// `pump` 방향에 지금 걸린 구간 모두 (스케줄 순서) - 겹친 청크 단위 장애를 함께 적용할 때 씀
+    pub fn all_towards(&self, elapsed: Duration, pump: Direction) -> impl Iterator<Item = &FaultWindow> {
+        self.windows
+            .iter()
+            .filter(move |window| window.direction.covers(pump) && window.contains(elapsed))
+    }
+}
+
+/// `5` (초), `5s`, `500ms`
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.strip_suffix("ms") {
+        Some(number) => (number, Duration::from_millis(1)),
+        None => (value.strip_suffix('s').unwrap_or(value), Duration::from_secs(1)),
+    };
+    let count = number
+        .parse::<u32>()
+        .map_err(|e| format!("invalid duration '{}': {}", value, e))?;
+    Ok(unit * count)
+}
+
+/// `0.05` - 0 이상 1 이하의 확률
+pub fn parse_rate(value: &str) -> Result<f64, String> {
+    let rate = value
+        .parse::<f64>()
+        .map_err(|e| format!("invalid rate '{}': {}", value, e))?;
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("rate {} is not between 0 and 1", rate));
+    }
+    Ok(rate)
+}
+
+/// `delay - jitter ..= delay + jitter`에서 `rng`로 균등하게 뽑은 지연 (0 아래로는 내려가지 않음)
+pub fn jittered(delay: Duration, jitter: Duration, rng: &mut impl Rng) -> Duration {
+    if jitter.is_zero() {
+        return delay;
+    }
+    rng.gen_range(delay.saturating_sub(jitter)..=delay.saturating_add(jitter))
+}
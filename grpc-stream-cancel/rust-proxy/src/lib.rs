@@ -0,0 +1,6 @@
+pub mod bandwidth;
+pub mod control;
+pub mod fault;
+pub mod proxy;
+pub mod scenario;
+pub mod status;
@@ -1,123 +1,218 @@
+use clap::Parser;
+use playground_shutdown::{Phase, Shutdown, DEFAULT_DRAIN_TIMEOUT};
+use rust_proxy::bandwidth::Bandwidth;
+use rust_proxy::fault::{parse_duration, parse_rate, BlockMode, Direction, FaultSchedule, ScheduleOrigin, Side};
+use rust_proxy::scenario::Scenario;
+use rust_proxy::status::{self, ProxyStatus};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::time::sleep;
+use tokio::io;
+use tokio::net::TcpListener;
 
-struct NetworkProxy {
-    start_time: Instant,
-    is_blocked: bool,
+#[derive(Parser, Debug)]
+#[command(name = "rust-proxy")]
+#[command(about = "Forward TCP to the gRPC server and inject network faults on a schedule")]
+struct Args {
+    /// Address to accept clients on (the orchestrator assigns ephemeral ports through the env var)
+    #[arg(long, env = "PROXY_LISTEN_ADDRESS", default_value = "[::1]:8080")]
+    listen: String,
+
+    /// Server address every connection is forwarded to
+    #[arg(long, env = "PROXY_UPSTREAM_ADDRESS", default_value = "[::1]:50051")]
+    upstream: String,
+
+    /// Address of the HTTP /status endpoint
+    #[arg(long, env = "PROXY_STATUS_ADDRESS", default_value = "[::1]:8081")]
+    status: SocketAddr,
+
+    /// Start blocking this long after the proxy starts: seconds (5), or a duration (5s, 500ms)
+    #[arg(long, default_value = "5s", value_parser = parse_duration)]
+    block_at: Duration,
+
+    /// Keep each block up this long (0s never blocks)
+    #[arg(long, default_value = "5s", value_parser = parse_duration)]
+    block_for: Duration,
+
+    /// Which direction each block stalls: up (client→server), down (server→client) or both
+    #[arg(long, value_enum, default_value = "both")]
+    block_direction: Direction,
+
+    /// What a block does with bytes sent meanwhile: stall (hold them, deliver late), discard (read and drop), close (FIN both sides)
+    #[arg(long, value_enum, default_value = "stall")]
+    block_mode: BlockMode,
+
+    /// Block again this often after --block-at, measured from one block start to the next (blocks once when unset)
+    #[arg(long, value_parser = parse_duration)]
+    repeat_every: Option<Duration>,
+
+    /// Corrupt this share (0..1) of chunks from the start instead of blocking
+    #[arg(long, env = "PROXY_CORRUPT_RATE", value_parser = parse_rate, conflicts_with_all = ["block_at", "block_for", "repeat_every", "block_direction", "block_mode"])]
+    corrupt_rate: Option<f64>,
+
+    /// Flip one random bit in this many distinct bytes of each corrupted chunk
+    #[arg(long, env = "PROXY_CORRUPT_BYTES", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..), requires = "corrupt_rate")]
+    corrupt_bytes: u64,
+
+    /// Kill every open connection this long after the proxy starts, with a TCP RST instead of a FIN (wins over a block)
+    #[arg(long, value_parser = parse_duration, conflicts_with = "corrupt_rate")]
+    reset_at: Option<Duration>,
+
+    /// Which socket --reset-at sends the RST on; the other side is closed with a FIN
+    #[arg(long, value_enum, default_value = "both", requires = "reset_at")]
+    reset_side: Side,
+
+    /// Delay every chunk in each direction by this much, outside the block windows (--block-for 0s for latency only)
+    #[arg(long, value_parser = parse_duration, conflicts_with = "corrupt_rate")]
+    latency: Option<Duration>,
+
+    /// Spread each chunk's --latency uniformly by up to this much either way
+    #[arg(long, value_parser = parse_duration, requires = "latency")]
+    jitter: Option<Duration>,
+
+    /// Discard this share (0..1) of read chunks, outside the block windows; breaks HTTP/2 framing on purpose (combines with --latency)
+    #[arg(long, value_parser = parse_rate, conflicts_with = "corrupt_rate")]
+    drop_rate: Option<f64>,
+
+    /// Which direction --drop-rate applies to
+    #[arg(long, value_enum, default_value = "both", requires = "drop_rate")]
+    drop_direction: Direction,
+
+    /// Seed for the drop/corrupt dice and latency jitter (connection N uses seed + N) so a run can be repeated
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Cap client→server traffic at this many bytes/sec per connection (token bucket, on top of any fault)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    bandwidth_up: Option<u64>,
+
+    /// Cap server→client traffic at this many bytes/sec per connection
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    bandwidth_down: Option<u64>,
+
+    /// Restart the timeline (--block-at, --reset-at, ...) for every connection instead of sharing one from proxy start
+    #[arg(long)]
+    per_connection: bool,
+
+    /// TOML timeline of fault phases (block, latency, throttle, loss, corrupt, reset, restore) with durations and directions, instead of the block flags
+    #[arg(long, conflicts_with_all = ["block_at", "block_for", "repeat_every", "block_direction", "block_mode", "corrupt_rate", "reset_at", "latency", "drop_rate", "per_connection"])]
+    scenario: Option<PathBuf>,
 }
 
-impl NetworkProxy {
-    fn new() -> Self {
-        Self {
-            start_time: Instant::now(),
-            is_blocked: false,
-        }
-    }
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    let args = Args::parse();
+    let telemetry = playground_telemetry::init("rust-proxy");
+    let shutdown = Shutdown::install();
 
-    fn should_block(&mut self) -> bool {
-        let elapsed = self.start_time.elapsed().as_secs();
-        
-        // 5초 후 5초간 차단
-        if elapsed >= 5 && elapsed < 10 {
-            if !self.is_blocked {
-                println!("🚫 [PROXY] Network BLOCKED (5 seconds)");
-                self.is_blocked = true;
-            }
-            true
-        } else {
-            if self.is_blocked && elapsed >= 10 {
-                println!("✅ [PROXY] Network RESTORED");
-                self.is_blocked = false;
-            }
-            false
+    println!("🚀 [PROXY] Rust Network Proxy starting on {} → {}", args.listen, args.upstream);
+    // --scenario나 --corrupt-rate가 있으면 차단 대신 그 장애를 적용
+    let schedule = match (&args.scenario, args.corrupt_rate) {
+        (Some(path), _) => {
+            let scenario = Scenario::load(path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+            println!(
+                "🎬 [PROXY] Scenario {}: {} phase(s), {:?} timeline{}",
+                path.display(),
+                scenario.phases.len(),
+                scenario.origin,
+                if scenario.repeat { ", repeating" } else { "" }
+            );
+            scenario.schedule(Instant::now())
         }
-    }
-
-    async fn handle_client(&mut self, mut client: TcpStream) -> io::Result<()> {
-        // 서버에 연결
-        let mut server = TcpStream::connect("[::1]:50051").await?;
-        
-        let (mut client_read, mut client_write) = client.split();
-        let (mut server_read, mut server_write) = server.split();
-
-        // 양방향 데이터 전달
-        let proxy_clone = std::sync::Arc::new(std::sync::Mutex::new(self));
-        
-        let client_to_server = {
-            let proxy = proxy_clone.clone();
-            async move {
-                let mut buffer = [0; 4096];
-                loop {
-                    // 네트워크 차단 확인
-                    if proxy.lock().unwrap().should_block() {
-                        sleep(Duration::from_millis(100)).await;
-                        continue;
-                    }
-
-                    match client_read.read(&mut buffer).await {
-                        Ok(0) => break, // 연결 종료
-                        Ok(n) => {
-                            if server_write.write_all(&buffer[..n]).await.is_err() {
-                                break;
-                            }
-                        }
-                        Err(_) => break,
-                    }
+        (None, Some(rate)) => {
+            println!("🎯 [PROXY] Will corrupt {:.0}% of chunks, {} byte(s) each", rate * 100.0, args.corrupt_bytes);
+            FaultSchedule::corrupt(rate, args.corrupt_bytes as usize)
+        }
+        (None, None) => {
+            match args.repeat_every {
+                // 이전 차단이 끝나기 전에 다음 차단이 시작하면 풀리지 않음
+                Some(every) if every <= args.block_for => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("--repeat-every ({:?}) must be longer than --block-for ({:?})", every, args.block_for),
+                    ));
                 }
+                Some(every) => println!(
+                    "🎯 [PROXY] Will block network ({:?}, {:?}) for {:?} after {:?}, every {:?}",
+                    args.block_direction, args.block_mode, args.block_for, args.block_at, every
+                ),
+                None => println!(
+                    "🎯 [PROXY] Will block network ({:?}, {:?}) for {:?} after {:?}",
+                    args.block_direction, args.block_mode, args.block_for, args.block_at
+                ),
             }
-        };
-
-        let server_to_client = {
-            let proxy = proxy_clone.clone();
-            async move {
-                let mut buffer = [0; 4096];
-                loop {
-                    // 네트워크 차단 확인
-                    if proxy.lock().unwrap().should_block() {
-                        sleep(Duration::from_millis(100)).await;
-                        continue;
-                    }
-
-                    match server_read.read(&mut buffer).await {
-                        Ok(0) => break, // 연결 종료
-                        Ok(n) => {
-                            if client_write.write_all(&buffer[..n]).await.is_err() {
-                                break;
-                            }
-                        }
-                        Err(_) => break,
-                    }
+            let schedule = FaultSchedule::block(
+                args.block_mode,
+                args.block_at,
+                args.block_for,
+                args.repeat_every,
+                args.block_direction,
+            );
+            let schedule = match args.reset_at {
+                Some(at) => {
+                    println!("💥 [PROXY] Will reset each connection after {:?} (RST to {:?})", at, args.reset_side);
+                    schedule.with_reset(at, args.reset_side)
+                }
+                None => schedule,
+            };
+            let schedule = match args.latency {
+                Some(delay) => {
+                    let jitter = args.jitter.unwrap_or_default();
+                    println!("🐢 [PROXY] Will delay every chunk by {:?} ± {:?}", delay, jitter);
+                    // 차단 구간을 먼저 넣어 두었으므로 차단 중에는 차단이 우선
+                    schedule.with_latency(delay, jitter)
                 }
+                None => schedule,
+            };
+            match args.drop_rate {
+                Some(rate) => {
+                    println!("🕳️ [PROXY] Will drop {:.0}% of chunks ({:?})", rate * 100.0, args.drop_direction);
+                    schedule.with_loss(rate, args.drop_direction)
+                }
+                None => schedule,
             }
-        };
-
-        // 양방향 전달을 동시에 실행
-        tokio::select! {
-            _ = client_to_server => {},
-            _ = server_to_client => {},
         }
-
-        Ok(())
+    };
+    let bandwidth = Bandwidth {
+        up: args.bandwidth_up,
+        down: args.bandwidth_down,
+    };
+    if bandwidth != Bandwidth::default() {
+        let show = |rate: Option<u64>| rate.map_or_else(|| "unlimited".to_string(), |rate| format!("{} bytes/s", rate));
+        println!("🚰 [PROXY] Bandwidth up {}, down {}", show(bandwidth.up), show(bandwidth.down));
     }
-}
+    let schedule = schedule.with_bandwidth(bandwidth).with_seed(args.seed);
+    // 시나리오는 파일의 `origin`을 따름
+    let schedule = if args.per_connection {
+        println!("⏱️ [PROXY] Fault timeline restarts for every connection");
+        schedule.with_origin(ScheduleOrigin::PerConnection)
+    } else {
+        schedule
+    };
 
-#[tokio::main]
-async fn main() -> io::Result<()> {
-    println!("🚀 [PROXY] Rust Network Proxy starting on [::1]:8080");
-    println!("🎯 [PROXY] Will block network for 1 second after 5 seconds");
-    
-    let listener = TcpListener::bind("[::1]:8080").await?;
-    
-    loop {
-        let (client, _) = listener.accept().await?;
-        let mut proxy = NetworkProxy::new();
-        
-        tokio::spawn(async move {
-            if let Err(e) = proxy.handle_client(client).await {
-                eprintln!("❌ [PROXY] Error handling client: {}", e);
-            }
-        });
+    let listener = TcpListener::bind(&args.listen).await?;
+
+    let status = Arc::new(ProxyStatus::default());
+    println!("📊 [PROXY] Status endpoint on http://{}/status", args.status);
+    tokio::spawn(status::serve(args.status, status.clone(), shutdown.token()));
+
+    let proxy = rust_proxy::proxy::run(
+        listener,
+        args.upstream,
+        schedule,
+        status,
+        shutdown.token(),
+    );
+
+    // 신호를 받으면 수락을 멈추고 열린 연결이 끝나기를 기다린 뒤 span을 flush
+    if let Some(result) = shutdown.drain(proxy, DEFAULT_DRAIN_TIMEOUT).await {
+        result?;
     }
-}
\ No newline at end of file
+    shutdown.advance(Phase::Flush);
+    drop(telemetry);
+    shutdown.advance(Phase::Exit);
+
+    Ok(())
+}
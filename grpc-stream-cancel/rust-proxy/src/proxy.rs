@@ -0,0 +1,438 @@
+use crate::bandwidth::TokenBucket;
+use crate::fault::{jittered, BlockMode, Direction, Fault, FaultSchedule, FaultWindow, ScheduleOrigin, Side};
+use crate::status::ProxyStatus;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use socket2::SockRef;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{self, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, sleep_until, timeout};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{info_span, Instrument};
+
+/// 장애 상태를 다시 확인하는 주기 (읽기 대기 중에도 적용)
+const FAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 방향마다 지연 중으로 쌓아 둘 수 있는 청크 수 (최대 4MB) - 가득 차면 읽기를 멈춰 보낸 쪽에 배압을 전달
+const DELAY_QUEUE_CHUNKS: usize = 1024;
+
+/// 모든 연결이 공유하는 장애 상태 - 스케줄 하나와 제어 API(`ProxyStatus::control`)를 함께 봄
+///
+/// 연결마다 다른 값(타이머 기준 시각, 주사위, 차단 카운트)은 `Connection`에 둠. 잠금은 모두 짧게 잡고
+/// await 전에 놓으므로 std `Mutex`로 충분함.
+struct FaultState {
+    schedule: FaultSchedule,
+    status: Arc<ProxyStatus>,
+    /// 받은 연결 수 - 다음 연결의 번호
+    accepted: AtomicU64,
+    /// 공유 타임라인에서 마지막으로 알린 구간 - 연결 수만큼 같은 로그를 찍지 않도록
+    announced: Mutex<Option<FaultWindow>>,
+}
+
+impl FaultState {
+    fn new(schedule: FaultSchedule, status: Arc<ProxyStatus>) -> Self {
+        Self {
+            schedule,
+            status,
+            accepted: AtomicU64::new(0),
+            announced: Mutex::new(None),
+        }
+    }
+
+    /// 새 연결의 상태 - 시드가 있으면 연결 번호(0부터)를 더해 연결마다 다른 RNG를 만듦
+    fn connect(self: &Arc<Self>) -> Connection {
+        let number = self.accepted.fetch_add(1, Ordering::Relaxed);
+        let rng = self
+            .schedule
+            .seed
+            .map_or_else(StdRng::from_entropy, |seed| StdRng::seed_from_u64(seed.wrapping_add(number)));
+        Connection {
+            state: self.clone(),
+            origin: self.schedule.origin_for(Instant::now()),
+            rng: Mutex::new(rng),
+            active: Mutex::new(None),
+        }
+    }
+
+    /// `origin` 기준으로 방향과 관계없이 지금 걸린 구간
+    ///
+    /// 제어 API로 건 장애가 있으면 스케줄을 통째로 가림.
+    fn window(&self, origin: Instant) -> Option<FaultWindow> {
+        if let Some(forced) = self.status.control.window() {
+            return forced;
+        }
+        self.schedule.active(origin.elapsed()).copied()
+    }
+
+    /// `origin` 기준으로 지금 `pump` 방향에 걸린 장애 모두 (스케줄 순서) - 제어 API로 건 장애가 있으면 그것만
+    fn faults(&self, origin: Instant, pump: Direction) -> Vec<Fault> {
+        if let Some(forced) = self.status.control.window() {
+            return forced.filter(|window| window.direction.covers(pump)).map(|window| window.fault).into_iter().collect();
+        }
+        self.schedule.all_towards(origin.elapsed(), pump).map(|window| window.fault).collect()
+    }
+
+    /// 구간이 바뀌었음을 기록 - 공유 타임라인이면 프록시 전체에서 한 번만
+    fn announce(&self, window: Option<FaultWindow>) {
+        if matches!(self.schedule.origin, ScheduleOrigin::Shared(_)) {
+            let mut announced = self.announced.lock().unwrap();
+            if *announced == window {
+                return;
+            }
+            *announced = window;
+        }
+        match window {
+            Some(window) => {
+                let only = match window.direction {
+                    Direction::Both => "",
+                    Direction::ClientToServer => " client→server",
+                    Direction::ServerToClient => " server→client",
+                };
+                match window.fault {
+                    Fault::Block(mode) => println!(
+                        "🚫 [PROXY] Network BLOCKED{} ({} seconds, {:?})",
+                        only,
+                        window.duration.as_secs_f64(),
+                        mode
+                    ),
+                    Fault::Reset(side) => println!("💥 [PROXY] Connection RESET (RST to {:?})", side),
+                    Fault::Latency { delay, jitter } => {
+                        println!("🐢 [PROXY] Latency{} +{}ms ±{}ms", only, delay.as_millis(), jitter.as_millis())
+                    }
+                    Fault::Loss(rate) => println!("🕳️ [PROXY] Packet loss{} {:.0}%", only, rate * 100.0),
+                    Fault::Corrupt { rate, bytes } => println!(
+                        "🧪 [PROXY] Corrupting{} {:.0}% of chunks, {} byte(s) each",
+                        only,
+                        rate * 100.0,
+                        bytes
+                    ),
+                    Fault::Throttle(rate) => println!("🚰 [PROXY] Throttled{} to {} bytes/s", only, rate),
+                }
+                tracing::info!(fault = window.fault.name(), direction = ?window.direction, "fault started");
+            }
+            None => {
+                println!("✅ [PROXY] Network RESTORED");
+                tracing::info!("network restored");
+            }
+        }
+    }
+}
+
+/// 연결 하나의 장애 상태 - 두 방향 `pump`가 공유
+struct Connection {
+    state: Arc<FaultState>,
+    /// 타이머 기준 시각 (공유 타임라인이면 모든 연결이 같음)
+    origin: Instant,
+    /// `Loss`/`Corrupt` 주사위와 `Latency` jitter - 두 방향이 나눠 씀
+    rng: Mutex<StdRng>,
+    /// 마지막으로 본 구간 - `blocked_connections`를 연결 단위로 세기 위함
+    active: Mutex<Option<FaultWindow>>,
+}
+
+impl Connection {
+    /// 상태 변화를 기록하고 `direction` 방향에 지금 걸린 장애를 모두 반환 (앞선 구간이 먼저)
+    fn faults_towards(&self, direction: Direction) -> Vec<Fault> {
+        self.observe();
+        self.state.faults(self.origin, direction)
+    }
+
+    /// 방향과 관계없이 지금 구간이 바뀌었으면 차단 카운트를 고치고 알림
+    fn observe(&self) {
+        let window = self.state.window(self.origin);
+        let mut active = self.active.lock().unwrap();
+        if *active == window {
+            return;
+        }
+        let status = &self.state.status;
+        if is_block(*active) {
+            status.blocked_connections.fetch_sub(1, Ordering::Relaxed);
+        }
+        if is_block(window) {
+            status.blocked_connections.fetch_add(1, Ordering::Relaxed);
+        }
+        *active = window;
+        drop(active);
+        self.state.announce(window);
+    }
+
+    /// `rate` 확률로 true
+    fn roll(&self, rate: f64) -> bool {
+        self.rng.lock().unwrap().gen::<f64>() < rate
+    }
+
+    /// `delay ± jitter`에서 뽑은 지연 - 시드가 있으면 연결마다 같은 순서로 나옴
+    fn delay(&self, delay: Duration, jitter: Duration) -> Duration {
+        jittered(delay, jitter, &mut *self.rng.lock().unwrap())
+    }
+
+    /// `rate` 확률로 `chunk`를 손상시키고 손상시켰는지 반환
+    fn maybe_corrupt(&self, chunk: &mut [u8], rate: f64, bytes: usize) -> bool {
+        let mut rng = self.rng.lock().unwrap();
+        if rng.gen::<f64>() >= rate {
+            return false;
+        }
+        corrupt(chunk, bytes, &mut *rng);
+        true
+    }
+
+    async fn handle_client(&self, mut client: TcpStream, upstream: &str) -> io::Result<()> {
+        // 서버에 연결
+        let mut server = TcpStream::connect(upstream).await?;
+
+        let (client_read, client_write) = client.split();
+        let (server_read, server_write) = server.split();
+
+        // 양방향 전달을 동시에 실행
+        let reset = tokio::select! {
+            reset = pump(self, Direction::ClientToServer, client_read, server_write) => reset,
+            reset = pump(self, Direction::ServerToClient, server_read, client_write) => reset,
+        };
+
+        // SO_LINGER=0이면 drop할 때 FIN 대신 RST를 보냄
+        if let Some(side) = reset {
+            if side.client() {
+                SockRef::from(&client).set_linger(Some(Duration::ZERO))?;
+            }
+            if side.server() {
+                SockRef::from(&server).set_linger(Some(Duration::ZERO))?;
+            }
+            self.state.status.reset_connections.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    /// 장애 도중 끊긴 연결은 차단 카운트에서 제외
+    fn drop(&mut self) {
+        if is_block(*self.active.get_mut().unwrap()) {
+            self.state.status.blocked_connections.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn is_block(window: Option<FaultWindow>) -> bool {
+    window.is_some_and(|window| matches!(window.fault, Fault::Block(_)))
+}
+
+/// 한 방향(`direction`)으로 바이트를 전달하면서 그 방향에 걸린 현재 장애와 대역폭 상한을 적용
+///
+/// 읽는 쪽(`read_chunks`)이 청크마다 내보낼 시각을 붙여 큐에 넣고 쓰는 쪽이 그 시각에 내보냄. 지연 중에도
+/// 계속 읽으므로 `Latency`는 처리량을 깎지 않고 모든 바이트를 같은 만큼 늦춤.
+/// `Reset` 구간이 시작되어 멈췄으면 RST를 보낼 쪽을 반환.
+async fn pump<R, W>(
+    connection: &Connection,
+    direction: Direction,
+    reader: R,
+    mut writer: W,
+) -> Option<Side>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let status = &connection.state.status;
+    let bytes = match direction {
+        Direction::ClientToServer => &status.bytes_client_to_server,
+        _ => &status.bytes_server_to_client,
+    };
+    let (queue, mut delayed) = mpsc::channel::<(Instant, Vec<u8>)>(DELAY_QUEUE_CHUNKS);
+    // 읽은 순서대로 내보냄 - jitter로 앞 청크보다 이르게 뽑힌 청크도 앞 청크를 기다림 (바이트 순서 유지)
+    let writing = async move {
+        while let Some((release_at, chunk)) = delayed.recv().await {
+            sleep_until(release_at.into()).await;
+            if writer.write_all(&chunk).await.is_err() {
+                return;
+            }
+            bytes.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        }
+    };
+    let reading = read_chunks(connection, direction, reader, queue);
+    tokio::pin!(writing, reading);
+
+    tokio::select! {
+        stopped = &mut reading => match stopped {
+            // 큐에 남은 청크는 제 시각에 마저 보내고 닫음
+            Stopped::Eof => {
+                writing.await;
+                None
+            }
+            // 지연 중이던 청크도 버림
+            Stopped::Closed => None,
+            Stopped::Reset(side) => Some(side),
+        },
+        _ = &mut writing => None,
+    }
+}
+
+/// `read_chunks`가 멈춘 이유
+enum Stopped {
+    /// 읽던 쪽이 닫혔거나 쓰는 쪽이 끝남
+    Eof,
+    /// `Block(Close)` - 평범하게 drop해서 양쪽에 FIN
+    Closed,
+    /// `Reset` - RST를 보낼 쪽
+    Reset(Side),
+}
+
+/// `reader`에서 읽은 청크에 장애와 대역폭 상한을 적용해 내보낼 시각(읽은 시각 + 지연)과 함께 `queue`에 넣음
+async fn read_chunks<R>(
+    connection: &Connection,
+    direction: Direction,
+    mut reader: R,
+    queue: mpsc::Sender<(Instant, Vec<u8>)>,
+) -> Stopped
+where
+    R: AsyncRead + Unpin,
+{
+    let status = &connection.state.status;
+    let bandwidth = match direction {
+        Direction::ClientToServer => connection.state.schedule.bandwidth.up,
+        _ => connection.state.schedule.bandwidth.down,
+    };
+    let mut buffer = [0; 4096];
+    let mut link = bandwidth.map(|rate| TokenBucket::new(rate, Instant::now()));
+    // `Throttle` 구간의 버킷 - 구간이 바뀌어 속도가 달라지면 새로 만듦
+    let mut throttle: Option<TokenBucket> = None;
+    loop {
+        let faults = connection.faults_towards(direction);
+        // 차단/리셋은 앞선 구간일 때만 (차단 구간을 먼저 넣으므로 차단이 이김) - 나머지는 겹치면 모두 적용
+        match faults.first().copied() {
+            Some(Fault::Block(BlockMode::Stall)) => {
+                sleep(FAULT_POLL_INTERVAL).await;
+                continue;
+            }
+            Some(Fault::Block(BlockMode::Discard)) => {
+                // 계속 읽어 커널 버퍼를 비움 - 보낸 쪽은 전달된 줄 알지만 받는 쪽에는 영영 오지 않음
+                match timeout(FAULT_POLL_INTERVAL, reader.read(&mut buffer)).await {
+                    Err(_) => {}
+                    Ok(Ok(0)) | Ok(Err(_)) => return Stopped::Eof,
+                    Ok(Ok(n)) => {
+                        status.discarded_bytes.fetch_add(n as u64, Ordering::Relaxed);
+                    }
+                }
+                continue;
+            }
+            Some(Fault::Block(BlockMode::Close)) => return Stopped::Closed,
+            Some(Fault::Reset(side)) => return Stopped::Reset(side),
+            _ => {}
+        }
+
+        let throttled = faults.iter().find_map(|fault| match fault {
+            Fault::Throttle(rate) => Some(*rate),
+            _ => None,
+        });
+        match throttled {
+            Some(rate) if throttle.as_ref().map(TokenBucket::rate) != Some(rate) => {
+                throttle = Some(TokenBucket::new(rate, Instant::now()));
+            }
+            Some(_) => {}
+            None => throttle = None,
+        }
+        // 대역폭 제한 중에는 버킷 크기(0.1초 분량)씩 읽어 고르게 흘려보냄
+        let limit = [&link, &throttle]
+            .into_iter()
+            .flatten()
+            .map(TokenBucket::burst)
+            .fold(buffer.len(), usize::min);
+        // 읽기 대기 중에도 장애 시작을 놓치지 않도록 주기적으로 깨어남
+        let n = match timeout(FAULT_POLL_INTERVAL, reader.read(&mut buffer[..limit])).await {
+            Err(_) => continue,
+            Ok(Ok(0)) => return Stopped::Eof, // 연결 종료
+            Ok(Ok(n)) => n,
+            Ok(Err(_)) => return Stopped::Eof,
+        };
+        let read_at = Instant::now();
+
+        // 예: `--latency`와 `--drop-rate`가 겹치면 버리지 않은 청크만 늦게 보냄
+        let mut release_at = read_at;
+        let mut dropped = false;
+        for fault in &faults {
+            match *fault {
+                Fault::Latency { delay, jitter } => release_at += connection.delay(delay, jitter),
+                Fault::Loss(rate) if connection.roll(rate) => {
+                    dropped = true;
+                    break;
+                }
+                Fault::Corrupt { rate, bytes } if connection.maybe_corrupt(&mut buffer[..n], rate, bytes) => {
+                    status.corrupted_chunks.fetch_add(1, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+        }
+        if dropped {
+            status.dropped_chunks.fetch_add(1, Ordering::Relaxed);
+            continue;
+        }
+
+        // 보낸 만큼 토큰을 빼고 모자란 만큼 다음 읽기를 미룸 - 두 버킷이 모두 있으면 느린 쪽에 맞춤
+        let wait = [&mut link, &mut throttle]
+            .into_iter()
+            .flatten()
+            .map(|bucket| bucket.take(n, read_at))
+            .max()
+            .unwrap_or_default();
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+
+        // 큐가 가득 차면 여기서 기다려 읽기를 멈춤 (배압)
+        if queue.send((release_at, buffer[..n].to_vec())).await.is_err() {
+            return Stopped::Eof;
+        }
+    }
+}
+
+/// `chunk`에서 서로 다른 위치 `bytes`개를 골라 각각 무작위 비트 하나를 뒤집음
+pub fn corrupt(chunk: &mut [u8], bytes: usize, rng: &mut impl Rng) {
+    for index in rand::seq::index::sample(rng, chunk.len(), bytes.min(chunk.len())) {
+        chunk[index] ^= 1 << rng.gen_range(0..8);
+    }
+}
+
+/// 연결을 받아 `upstream`으로 전달하는 프록시 루프
+///
+/// `shutdown`이 취소되면 새 연결을 받지 않고 열려 있는 연결이 끝날 때까지 기다린 뒤 반환.
+pub async fn run(
+    listener: TcpListener,
+    upstream: String,
+    schedule: FaultSchedule,
+    status: Arc<ProxyStatus>,
+    shutdown: CancellationToken,
+) -> io::Result<()> {
+    let upstream: Arc<str> = upstream.into();
+    let state = Arc::new(FaultState::new(schedule, status.clone()));
+    let connections = TaskTracker::new();
+
+    loop {
+        let (client, peer) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = shutdown.cancelled() => break,
+        };
+        let connection = state.connect();
+
+        // 프록시는 바이트만 전달하므로 gRPC traceparent 헤더는 그대로 서버까지 전달됨
+        // 여기서는 연결 단위 span만 기록
+        let connection_span = info_span!("proxy_connection", peer = %peer);
+        let status = status.clone();
+        let upstream = upstream.clone();
+        connections.spawn(async move {
+            status.active_connections.fetch_add(1, Ordering::Relaxed);
+            status.total_connections.fetch_add(1, Ordering::Relaxed);
+            if let Err(e) = connection.handle_client(client, &upstream).await {
+                eprintln!("❌ [PROXY] Error handling client: {}", e);
+            }
+            status.active_connections.fetch_sub(1, Ordering::Relaxed);
+        }.instrument(connection_span));
+    }
+
+    println!("🛑 [PROXY] Stopped accepting, waiting for {} connection(s)", connections.len());
+    connections.close();
+    connections.wait().await;
+    Ok(())
+}
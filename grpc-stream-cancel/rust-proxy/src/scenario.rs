@@ -0,0 +1,247 @@
+//! `--scenario scenario.toml` - 장애 단계를 순서대로 적은 타임라인
+//!
+//! `[[phase]]`마다 `fault`(block, latency, throttle, loss, corrupt, reset, restore)와 길이 `for`를 적고,
+//! 단계는 앞 단계가 끝나면 이어서 시작함. 마지막 단계만 `for`를 생략해 끝까지 유지할 수 있음.
+//! `direction`(both, client_to_server, server_to_client)으로 한 방향에만 걸 수 있고, `restore`는 장애 없이 전달.
+//!
+//! ```toml
+//! origin = "connection"   # 연결마다 처음부터 (proxy: 프록시 시작 시각 기준으로 모든 연결이 공유)
+//! repeat = true           # 마지막 단계가 끝나면 처음부터 다시
+//!
+//! [[phase]]
+//! fault = "restore"
+//! for = "5s"
+//!
+//! [[phase]]
+//! fault = "latency"
+//! delay = "300ms"
+//! jitter = "50ms"
+//! direction = "server_to_client"
+//! for = "10s"
+//! ```
+
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::fault::{parse_duration, BlockMode, Direction, Fault, FaultSchedule, FaultWindow, ScheduleOrigin, Side};
+
+/// 타임라인의 기준 시각
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Origin {
+    /// 연결마다 처음부터
+    #[default]
+    Connection,
+    /// 프록시가 시작한 시각부터 - 나중에 연결하면 중간 단계부터 겪음
+    Proxy,
+}
+
+/// 검증을 마친 시나리오
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub origin: Origin,
+    pub repeat: bool,
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Phase {
+    /// None이면 `restore` (장애 없이 전달)
+    pub fault: Option<Fault>,
+    /// None이면 끝까지 (마지막 단계만)
+    pub duration: Option<Duration>,
+    pub direction: Direction,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioFile {
+    #[serde(default)]
+    origin: Origin,
+    #[serde(default)]
+    repeat: bool,
+    #[serde(default)]
+    phase: Vec<PhaseSpec>,
+}
+
+/// `[[phase]]` 하나 - `POST /faults` 본문도 같은 키를 씀
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhaseSpec {
+    fault: PhaseKind,
+    /// 초 단위 정수 또는 `500ms`, `5s`
+    #[serde(rename = "for")]
+    duration: Option<toml::Value>,
+    #[serde(default)]
+    direction: Direction,
+    /// latency
+    delay: Option<toml::Value>,
+    /// latency - `delay` ± 이 범위에서 청크마다 뽑음
+    jitter: Option<toml::Value>,
+    /// loss, corrupt (0..=1)
+    rate: Option<f64>,
+    /// corrupt - 손상시킬 청크마다 비트를 뒤집을 바이트 수 (기본 1)
+    bytes: Option<usize>,
+    /// throttle
+    bytes_per_sec: Option<u64>,
+    /// reset - RST를 보낼 소켓 (client, server, both)
+    side: Option<Side>,
+    /// block - 차단 중에 들어온 바이트 처리 (stall, discard, close)
+    mode: Option<BlockMode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PhaseKind {
+    Block,
+    Latency,
+    Throttle,
+    Loss,
+    Corrupt,
+    Reset,
+    Restore,
+}
+
+impl Scenario {
+    /// TOML 파싱과 검증 - 오류는 모두 `InvalidData`
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let file: ScenarioFile = toml::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        if file.phase.is_empty() {
+            return Err(invalid("scenario has no [[phase]]".to_string()));
+        }
+        let last = file.phase.len() - 1;
+        let phases = file
+            .phase
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                let phase = spec.into_phase().map_err(|e| invalid(format!("phase {}: {}", index + 1, e)))?;
+                if phase.duration.is_none() && (index < last || file.repeat) {
+                    return Err(invalid(format!(
+                        "phase {}: `for` is required {}",
+                        index + 1,
+                        if file.repeat { "when the scenario repeats" } else { "except on the last phase" }
+                    )));
+                }
+                Ok(phase)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let scenario = Self {
+            origin: file.origin,
+            repeat: file.repeat,
+            phases,
+        };
+        if scenario.repeat && scenario.length().is_zero() {
+            return Err(invalid("a repeating scenario needs phases longer than 0s".to_string()));
+        }
+        Ok(scenario)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// 한 바퀴의 길이 - 마지막 단계가 끝나지 않으면 `Duration::MAX`
+    pub fn length(&self) -> Duration {
+        self.phases
+            .iter()
+            .map(|phase| phase.duration.unwrap_or(Duration::MAX))
+            .fold(Duration::ZERO, Duration::saturating_add)
+    }
+
+    /// 장애 단계를 구간으로 바꿈 - `restore` 단계는 구간 없이 시간만 지나감
+    ///
+    /// `started`는 `origin = "proxy"`일 때 모든 연결이 공유하는 기준 시각.
+    pub fn schedule(&self, started: Instant) -> FaultSchedule {
+        let origin = match self.origin {
+            Origin::Connection => ScheduleOrigin::PerConnection,
+            Origin::Proxy => ScheduleOrigin::Shared(started),
+        };
+        let repeat_every = self.repeat.then(|| self.length());
+        let mut schedule = FaultSchedule::new(origin);
+        let mut start = Duration::ZERO;
+        for phase in &self.phases {
+            let duration = phase.duration.unwrap_or(Duration::MAX);
+            if let Some(fault) = phase.fault {
+                schedule = schedule.with_window(FaultWindow {
+                    fault,
+                    start,
+                    duration,
+                    repeat_every,
+                    direction: phase.direction,
+                });
+            }
+            start = start.saturating_add(duration);
+        }
+        schedule
+    }
+}
+
+impl PhaseSpec {
+    pub fn into_phase(self) -> Result<Phase, String> {
+        // 다른 종류의 값을 적었으면 오타일 가능성이 큼
+        let unused = [
+            ("delay", self.delay.is_some() && self.fault != PhaseKind::Latency),
+            ("jitter", self.jitter.is_some() && self.fault != PhaseKind::Latency),
+            ("rate", self.rate.is_some() && !matches!(self.fault, PhaseKind::Loss | PhaseKind::Corrupt)),
+            ("bytes", self.bytes.is_some() && self.fault != PhaseKind::Corrupt),
+            ("bytes_per_sec", self.bytes_per_sec.is_some() && self.fault != PhaseKind::Throttle),
+            ("side", self.side.is_some() && self.fault != PhaseKind::Reset),
+            ("mode", self.mode.is_some() && self.fault != PhaseKind::Block),
+        ];
+        if let Some((key, _)) = unused.iter().find(|(_, unused)| *unused) {
+            return Err(format!("`{}` does not apply to {:?}", key, self.fault));
+        }
+        let rate = || match self.rate {
+            Some(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+            Some(rate) => Err(format!("rate {} is not between 0 and 1", rate)),
+            None => Err("`rate` is required".to_string()),
+        };
+        let fault = match self.fault {
+            PhaseKind::Block => Some(Fault::Block(self.mode.unwrap_or_default())),
+            PhaseKind::Reset => Some(Fault::Reset(self.side.unwrap_or_default())),
+            PhaseKind::Restore => None,
+            PhaseKind::Latency => {
+                let delay = self.delay.as_ref().ok_or("`delay` is required")?;
+                let jitter = self.jitter.as_ref().map(|jitter| duration("jitter", jitter)).transpose()?;
+                Some(Fault::Latency {
+                    delay: duration("delay", delay)?,
+                    jitter: jitter.unwrap_or_default(),
+                })
+            }
+            PhaseKind::Throttle => match self.bytes_per_sec {
+                Some(0) | None => return Err("`bytes_per_sec` above 0 is required".to_string()),
+                Some(rate) => Some(Fault::Throttle(rate)),
+            },
+            PhaseKind::Loss => Some(Fault::Loss(rate()?)),
+            PhaseKind::Corrupt => match self.bytes {
+                Some(0) => return Err("`bytes` must be at least 1".to_string()),
+                bytes => Some(Fault::Corrupt {
+                    rate: rate()?,
+                    bytes: bytes.unwrap_or(1),
+                }),
+            },
+        };
+        Ok(Phase {
+            fault,
+            duration: self.duration.as_ref().map(|value| duration("for", value)).transpose()?,
+            direction: self.direction,
+        })
+    }
+}
+
+fn duration(key: &str, value: &toml::Value) -> Result<Duration, String> {
+    let text = match value {
+        toml::Value::Integer(seconds) => seconds.to_string(),
+        toml::Value::String(text) => text.clone(),
+        other => return Err(format!("{}: expected seconds or a string, got {}", key, other)),
+    };
+    parse_duration(&text).map_err(|e| format!("{}: {}", key, e))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
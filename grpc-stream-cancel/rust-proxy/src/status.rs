@@ -0,0 +1,104 @@
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use axum::{extract::State, routing::get, Json, Router};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::control::{FaultControl, ForcedView};
+use crate::scenario::PhaseSpec;
+
+/// 모든 연결이 공유하는 프록시 상태 (대시보드/스크립트 조회용)
+#[derive(Default)]
+pub struct ProxyStatus {
+    pub active_connections: AtomicU64,
+    pub total_connections: AtomicU64,
+    pub blocked_connections: AtomicU64,
+    pub bytes_client_to_server: AtomicU64,
+    pub bytes_server_to_client: AtomicU64,
+    /// `Fault::Corrupt`로 비트를 뒤집어 전달한 청크 수
+    pub corrupted_chunks: AtomicU64,
+    /// `Fault::Loss`로 버린 청크 수
+    pub dropped_chunks: AtomicU64,
+    /// `Fault::Reset`으로 끊은 연결 수
+    pub reset_connections: AtomicU64,
+    /// `BlockMode::Discard` 차단 중에 읽어서 버린 바이트 수
+    pub discarded_bytes: AtomicU64,
+    /// `POST /faults`로 건 장애
+    pub control: FaultControl,
+}
+
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub phase: &'static str,
+    pub active_connections: u64,
+    pub total_connections: u64,
+    pub blocked_connections: u64,
+    pub bytes_client_to_server: u64,
+    pub bytes_server_to_client: u64,
+    pub corrupted_chunks: u64,
+    pub dropped_chunks: u64,
+    pub reset_connections: u64,
+    pub discarded_bytes: u64,
+    /// `POST /faults`로 건 장애 (없으면 스케줄대로)
+    pub forced: Option<ForcedView>,
+}
+
+impl ProxyStatus {
+    pub fn report(&self) -> StatusReport {
+        let blocked_connections = self.blocked_connections.load(Ordering::Relaxed);
+        StatusReport {
+            phase: if blocked_connections > 0 { "blocked" } else { "forwarding" },
+            active_connections: self.active_connections.load(Ordering::Relaxed),
+            total_connections: self.total_connections.load(Ordering::Relaxed),
+            blocked_connections,
+            bytes_client_to_server: self.bytes_client_to_server.load(Ordering::Relaxed),
+            bytes_server_to_client: self.bytes_server_to_client.load(Ordering::Relaxed),
+            corrupted_chunks: self.corrupted_chunks.load(Ordering::Relaxed),
+            dropped_chunks: self.dropped_chunks.load(Ordering::Relaxed),
+            reset_connections: self.reset_connections.load(Ordering::Relaxed),
+            discarded_bytes: self.discarded_bytes.load(Ordering::Relaxed),
+            forced: self.control.view(),
+        }
+    }
+}
+
+async fn get_status(State(status): State<Arc<ProxyStatus>>) -> Json<StatusReport> {
+    Json(status.report())
+}
+
+async fn get_faults(State(status): State<Arc<ProxyStatus>>) -> Json<Option<ForcedView>> {
+    Json(status.control.view())
+}
+
+/// 시나리오 파일의 `[[phase]]`와 같은 키 - `{"fault": "block", "direction": "server_to_client", "for": "3s"}`
+async fn set_fault(
+    State(status): State<Arc<ProxyStatus>>,
+    Json(spec): Json<PhaseSpec>,
+) -> Result<Json<Option<ForcedView>>, (StatusCode, String)> {
+    let phase = spec.into_phase().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    status.control.set(phase);
+    println!("🎛️ [PROXY] Fault forced through the control API: {:?}", phase);
+    Ok(Json(status.control.view()))
+}
+
+async fn clear_fault(State(status): State<Arc<ProxyStatus>>) -> StatusCode {
+    if status.control.clear() {
+        println!("🎛️ [PROXY] Forced fault cleared, back to the schedule");
+    }
+    StatusCode::NO_CONTENT
+}
+
+/// `GET /status`와 장애 제어(`GET`/`POST`/`DELETE /faults`) HTTP 엔드포인트 실행 (`shutdown` 취소 시 종료)
+pub async fn serve(addr: SocketAddr, status: Arc<ProxyStatus>, shutdown: CancellationToken) -> std::io::Result<()> {
+    let app = Router::new()
+        .route("/status", get(get_status))
+        .route("/faults", get(get_faults).post(set_fault).delete(clear_fault))
+        .with_state(status);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await
+}
@@ -0,0 +1,42 @@
+//! `TokenBucket` - 시각을 직접 넘겨 실제로 기다리지 않고 확인
+
+use std::time::{Duration, Instant};
+
+use rust_proxy::bandwidth::TokenBucket;
+
+#[test]
+fn full_bucket_sends_one_burst_without_waiting() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(10_000, now);
+
+    assert_eq!(bucket.burst(), 1_000);
+    assert_eq!(bucket.take(1_000, now), Duration::ZERO);
+    // 빈 버킷에서 1000바이트는 0.1초 빚
+    assert_eq!(bucket.take(1_000, now), Duration::from_millis(100));
+}
+
+#[test]
+fn long_transfer_averages_the_rate() {
+    let started = Instant::now();
+    let mut now = started;
+    let mut bucket = TokenBucket::new(2_048, now);
+
+    // 청크마다 돌려준 시간만큼 기다렸다고 치고 20KB를 보냄
+    for _ in 0..100 {
+        now += bucket.take(bucket.burst(), now);
+    }
+    let sent = 100.0 * bucket.burst() as f64;
+    let expected = (sent - bucket.burst() as f64) / 2_048.0;
+    assert!((now.duration_since(started).as_secs_f64() - expected).abs() < 0.01);
+}
+
+#[test]
+fn idle_time_refills_only_up_to_one_burst() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(1_000, now);
+    bucket.take(100, now);
+
+    let later = now + Duration::from_secs(60);
+    assert_eq!(bucket.take(100, later), Duration::ZERO);
+    assert_eq!(bucket.take(100, later), Duration::from_millis(100));
+}
@@ -0,0 +1,51 @@
+//! `POST /faults`로 건 장애 - HTTP 없이 `FaultControl`만 확인
+
+use std::time::Duration;
+
+use rust_proxy::control::FaultControl;
+use rust_proxy::fault::{BlockMode, Direction, Fault};
+use rust_proxy::scenario::Phase;
+
+fn phase(fault: Option<Fault>, duration: Option<Duration>) -> Phase {
+    Phase {
+        fault,
+        duration,
+        direction: Direction::ServerToClient,
+    }
+}
+
+#[test]
+fn forced_fault_holds_until_cleared() {
+    let control = FaultControl::default();
+    assert_eq!(control.window(), None);
+
+    control.set(phase(Some(Fault::Block(BlockMode::Stall)), None));
+    let window = control.window().unwrap().unwrap();
+    assert_eq!(window.fault, Fault::Block(BlockMode::Stall));
+    assert_eq!(window.direction, Direction::ServerToClient);
+    let view = control.view().unwrap();
+    assert_eq!((view.fault, view.remaining_ms), ("block", None));
+
+    assert!(control.clear());
+    assert!(!control.clear());
+    assert_eq!(control.window(), None);
+}
+
+#[test]
+fn restore_overrides_the_schedule_with_no_fault() {
+    let control = FaultControl::default();
+    control.set(phase(None, Some(Duration::from_secs(60))));
+
+    // Some(None) - 스케줄을 가리고 그대로 전달
+    assert_eq!(control.window(), Some(None));
+    assert_eq!(control.view().unwrap().fault, "restore");
+}
+
+#[test]
+fn forced_fault_expires_after_its_duration() {
+    let control = FaultControl::default();
+    control.set(phase(Some(Fault::Throttle(1024)), Some(Duration::ZERO)));
+
+    assert_eq!(control.window(), None);
+    assert_eq!(control.view(), None);
+}
@@ -0,0 +1,44 @@
+//! `--corrupt-bytes` - 손상시킨 청크에서 정확히 그 수만큼의 바이트가 비트 하나씩 바뀜
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rust_proxy::proxy::corrupt;
+
+/// 원본과 달라진 바이트마다 뒤집힌 비트 수
+fn flipped_bits(original: &[u8], chunk: &[u8]) -> Vec<u32> {
+    original
+        .iter()
+        .zip(chunk)
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| (a ^ b).count_ones())
+        .collect()
+}
+
+#[test]
+fn flips_one_bit_in_each_of_the_requested_bytes() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let original: Vec<u8> = (0..=255).collect();
+    for bytes in [1, 3, 16] {
+        let mut chunk = original.clone();
+        corrupt(&mut chunk, bytes, &mut rng);
+        assert_eq!(flipped_bits(&original, &chunk), vec![1; bytes]);
+    }
+}
+
+#[test]
+fn more_bytes_than_the_chunk_corrupts_every_byte() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut chunk = [0u8; 4];
+    corrupt(&mut chunk, 10, &mut rng);
+    assert!(chunk.iter().all(|&byte| byte.count_ones() == 1));
+}
+
+#[test]
+fn same_seed_corrupts_the_same_positions() {
+    let run = |seed| {
+        let mut chunk = [0u8; 64];
+        corrupt(&mut chunk, 5, &mut StdRng::seed_from_u64(seed));
+        chunk
+    };
+    assert_eq!(run(42), run(42));
+}
@@ -0,0 +1,184 @@
+//! `FaultSchedule` 구간 계산 - 연결 기준 경과 시간만 보므로 실제 소켓 없이 확인
+
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rust_proxy::fault::{jittered, parse_rate, BlockMode, Direction, Fault, FaultSchedule, ScheduleOrigin, Side};
+
+const STALL: Fault = Fault::Block(BlockMode::Stall);
+
+/// 기존 데모와 같은 stall 차단
+fn stall(at: Duration, duration: Duration, every: Option<Duration>, direction: Direction) -> FaultSchedule {
+    FaultSchedule::block(BlockMode::Stall, at, duration, every, direction)
+}
+
+fn fault_at(schedule: &FaultSchedule, millis: u64) -> Option<Fault> {
+    schedule.active(Duration::from_millis(millis)).map(|window| window.fault)
+}
+
+#[test]
+fn block_without_repeat_happens_once() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both);
+
+    assert_eq!(fault_at(&schedule, 4_999), None);
+    assert_eq!(fault_at(&schedule, 5_000), Some(STALL));
+    assert_eq!(fault_at(&schedule, 9_999), Some(STALL));
+    assert_eq!(fault_at(&schedule, 10_000), None);
+    assert_eq!(fault_at(&schedule, 35_000), None);
+}
+
+#[test]
+fn repeating_block_restarts_every_period_after_the_first() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(2), Some(Duration::from_secs(30)), Direction::Both);
+
+    assert_eq!(fault_at(&schedule, 0), None);
+    assert_eq!(fault_at(&schedule, 6_000), Some(STALL));
+    assert_eq!(fault_at(&schedule, 7_000), None);
+    assert_eq!(fault_at(&schedule, 34_999), None);
+    assert_eq!(fault_at(&schedule, 35_000), Some(STALL));
+    assert_eq!(fault_at(&schedule, 66_999), Some(STALL));
+    assert_eq!(fault_at(&schedule, 67_000), None);
+}
+
+#[test]
+fn zero_length_block_never_applies() {
+    let schedule = stall(Duration::ZERO, Duration::ZERO, None, Direction::Both);
+
+    assert_eq!(fault_at(&schedule, 0), None);
+    assert_eq!(fault_at(&schedule, 5_000), None);
+}
+
+#[test]
+fn latency_applies_outside_the_block_window() {
+    let latency = Fault::Latency {
+        delay: Duration::from_millis(200),
+        jitter: Duration::from_millis(50),
+    };
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both)
+        .with_latency(Duration::from_millis(200), Duration::from_millis(50));
+
+    assert_eq!(fault_at(&schedule, 0), Some(latency));
+    assert_eq!(fault_at(&schedule, 5_000), Some(STALL));
+    assert_eq!(fault_at(&schedule, 10_000), Some(latency));
+    assert_eq!(fault_at(&schedule, 3_600_000), Some(latency));
+}
+
+#[test]
+fn jitter_stays_within_its_range_and_above_zero() {
+    let mut rng = StdRng::from_entropy();
+    let delay = Duration::from_millis(200);
+    assert_eq!(jittered(delay, Duration::ZERO, &mut rng), delay);
+    for _ in 0..1_000 {
+        let sample = jittered(delay, Duration::from_millis(50), &mut rng);
+        assert!((Duration::from_millis(150)..=Duration::from_millis(250)).contains(&sample));
+        assert!(jittered(Duration::from_millis(10), Duration::from_millis(50), &mut rng) <= Duration::from_millis(60));
+    }
+}
+
+#[test]
+fn jitter_repeats_with_the_same_seed() {
+    let samples = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..20).map(|_| jittered(Duration::from_millis(200), Duration::from_millis(50), &mut rng)).collect::<Vec<_>>()
+    };
+    assert_eq!(samples(7), samples(7));
+    assert_ne!(samples(7), samples(8));
+}
+
+#[test]
+fn drop_window_covers_only_its_direction_outside_blocks() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both)
+        .with_loss(0.1, Direction::ServerToClient);
+
+    let window = schedule.active(Duration::ZERO).unwrap();
+    assert_eq!(window.fault, Fault::Loss(0.1));
+    assert!(window.direction.covers(Direction::ServerToClient));
+    assert!(!window.direction.covers(Direction::ClientToServer));
+    assert_eq!(fault_at(&schedule, 5_000), Some(STALL));
+    assert_eq!(fault_at(&schedule, 10_000), Some(Fault::Loss(0.1)));
+}
+
+#[test]
+fn rates_outside_zero_to_one_are_rejected() {
+    assert_eq!(parse_rate("0.05"), Ok(0.05));
+    assert_eq!(parse_rate("1"), Ok(1.0));
+    assert!(parse_rate("1.5").unwrap_err().contains("not between 0 and 1"));
+    assert!(parse_rate("-0.1").is_err());
+    assert!(parse_rate("often").is_err());
+}
+
+#[test]
+fn reset_wins_over_every_other_window_for_a_moment() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both)
+        .with_reset(Duration::from_secs(8), Side::Client)
+        .with_latency(Duration::from_millis(200), Duration::ZERO);
+
+    assert!(matches!(fault_at(&schedule, 1_000), Some(Fault::Latency { .. })));
+    // 차단 중이어도 끊고, 1초 뒤에는 남은 차단으로 돌아감
+    assert_eq!(fault_at(&schedule, 8_000), Some(Fault::Reset(Side::Client)));
+    assert_eq!(fault_at(&schedule, 9_000), Some(STALL));
+    assert!(matches!(fault_at(&schedule, 10_000), Some(Fault::Latency { .. })));
+    assert!(Side::Client.client() && !Side::Client.server());
+    assert!(Side::Both.client() && Side::Both.server());
+}
+
+#[test]
+fn one_direction_block_leaves_the_other_direction_to_later_windows() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::ServerToClient)
+        .with_latency(Duration::from_millis(200), Duration::ZERO);
+    let towards = |millis, direction| {
+        schedule
+            .active_towards(Duration::from_millis(millis), direction)
+            .map(|window| window.fault)
+    };
+
+    // ACK(클라이언트 → 서버)는 지연만 되고 데이터(서버 → 클라이언트)는 멈춤
+    assert_eq!(towards(6_000, Direction::ServerToClient), Some(STALL));
+    assert!(matches!(towards(6_000, Direction::ClientToServer), Some(Fault::Latency { .. })));
+    assert!(matches!(towards(10_000, Direction::ServerToClient), Some(Fault::Latency { .. })));
+}
+
+#[test]
+fn overlapping_latency_and_loss_are_both_active() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both)
+        .with_latency(Duration::from_millis(200), Duration::ZERO)
+        .with_loss(0.1, Direction::ServerToClient);
+    let all = |millis, direction| {
+        schedule
+            .all_towards(Duration::from_millis(millis), direction)
+            .map(|window| window.fault.name())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(all(1_000, Direction::ServerToClient), vec!["latency", "loss"]);
+    assert_eq!(all(1_000, Direction::ClientToServer), vec!["latency"]);
+    // 차단이 앞선 구간이라 먼저 나옴
+    assert_eq!(all(6_000, Direction::ServerToClient), vec!["block", "latency", "loss"]);
+}
+
+#[test]
+fn block_mode_is_carried_by_the_window() {
+    let schedule = FaultSchedule::block(
+        BlockMode::Discard,
+        Duration::from_secs(1),
+        Duration::from_secs(1),
+        None,
+        Direction::ClientToServer,
+    );
+
+    assert_eq!(fault_at(&schedule, 1_000), Some(Fault::Block(BlockMode::Discard)));
+}
+
+#[test]
+fn connections_share_one_timeline_unless_asked_otherwise() {
+    let schedule = stall(Duration::from_secs(5), Duration::from_secs(5), None, Direction::Both);
+    let first = Instant::now();
+    let later = first + Duration::from_secs(7);
+
+    // 7초 뒤에 연결해도 프록시 시작 기준으로 이미 차단 중
+    assert_eq!(schedule.origin_for(first), schedule.origin_for(later));
+
+    let schedule = schedule.with_origin(ScheduleOrigin::PerConnection);
+    assert_eq!(schedule.origin_for(later), later);
+}
@@ -0,0 +1,47 @@
+//! `--latency` - 실제 소켓으로 프록시를 거쳐 지연이 청크마다 쌓이지 않고 한 번만 더해지는지 확인
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rust_proxy::fault::{FaultSchedule, ScheduleOrigin};
+use rust_proxy::proxy::run;
+use rust_proxy::status::ProxyStatus;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+const LATENCY: Duration = Duration::from_millis(200);
+
+#[tokio::test]
+async fn latency_delays_a_large_transfer_once_instead_of_per_chunk() {
+    let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let upstream_addr = upstream.local_addr().unwrap().to_string();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy_addr = listener.local_addr().unwrap();
+    let schedule = FaultSchedule::new(ScheduleOrigin::PerConnection).with_latency(LATENCY, Duration::ZERO);
+    let status = Arc::new(ProxyStatus::default());
+    let shutdown = CancellationToken::new();
+    let proxy = tokio::spawn(run(listener, upstream_addr, schedule, status.clone(), shutdown.clone()));
+
+    // 1MB는 4KB 청크로 256개 이상 - 청크마다 기다리면 50초가 넘게 걸림
+    let sent: Vec<u8> = (0..1 << 20).map(|i: u32| (i % 251) as u8).collect();
+    let receiving = tokio::spawn(async move {
+        let (mut server, _) = upstream.accept().await.unwrap();
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).await.unwrap();
+        received
+    });
+    let start = Instant::now();
+    let mut client = TcpStream::connect(proxy_addr).await.unwrap();
+    client.write_all(&sent).await.unwrap();
+    client.shutdown().await.unwrap();
+
+    let received = receiving.await.unwrap();
+    let elapsed = start.elapsed();
+    assert!(received == sent, "bytes arrived out of order or incomplete");
+    assert!(elapsed >= LATENCY, "{:?}", elapsed);
+    assert!(elapsed < LATENCY * 5, "{:?}", elapsed);
+
+    shutdown.cancel();
+    proxy.await.unwrap().unwrap();
+}
@@ -0,0 +1,82 @@
+//! `--scenario` 파일을 읽어 만든 장애 구간 - 실제 소켓 없이 경과 시간으로 확인
+
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+use rust_proxy::fault::{BlockMode, Direction, Fault, Side};
+use rust_proxy::scenario::{Origin, Scenario};
+
+const FLAKY: &str = include_str!("../scenarios/flaky.toml");
+
+fn window_at(scenario: &Scenario, millis: u64) -> Option<(Fault, Direction)> {
+    scenario
+        .schedule(Instant::now())
+        .active(Duration::from_millis(millis))
+        .map(|window| (window.fault, window.direction))
+}
+
+fn error(text: &str) -> String {
+    let err = Scenario::parse(text).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    err.to_string()
+}
+
+#[test]
+fn example_scenario_runs_its_phases_in_order_and_repeats() {
+    let scenario = Scenario::parse(FLAKY).unwrap();
+    assert_eq!(scenario.origin, Origin::Connection);
+    assert!(scenario.repeat);
+    assert_eq!(scenario.length(), Duration::from_secs(30));
+
+    assert_eq!(window_at(&scenario, 4_999), None);
+    let latency = Fault::Latency {
+        delay: Duration::from_millis(300),
+        jitter: Duration::from_millis(50),
+    };
+    assert_eq!(window_at(&scenario, 5_000), Some((latency, Direction::ServerToClient)));
+    assert_eq!(window_at(&scenario, 10_000), Some((Fault::Throttle(2048), Direction::Both)));
+    assert_eq!(window_at(&scenario, 20_000), Some((Fault::Block(BlockMode::Stall), Direction::Both)));
+    assert_eq!(window_at(&scenario, 22_000), Some((Fault::Reset(Side::Server), Direction::Both)));
+    assert_eq!(window_at(&scenario, 22_500), None);
+    // 두 번째 바퀴
+    assert_eq!(window_at(&scenario, 30_000), None);
+    assert_eq!(window_at(&scenario, 50_500), Some((Fault::Block(BlockMode::Stall), Direction::Both)));
+}
+
+#[test]
+fn last_phase_without_a_duration_lasts_forever() {
+    let scenario = Scenario::parse(
+        r#"
+        [[phase]]
+        fault = "restore"
+        for = 2
+
+        [[phase]]
+        fault = "loss"
+        rate = 0.5
+        direction = "client_to_server"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(window_at(&scenario, 1_999), None);
+    assert_eq!(window_at(&scenario, 2_000), Some((Fault::Loss(0.5), Direction::ClientToServer)));
+    assert_eq!(window_at(&scenario, 3_600_000), Some((Fault::Loss(0.5), Direction::ClientToServer)));
+}
+
+#[test]
+fn invalid_scenarios_are_rejected_with_the_phase_number() {
+    assert!(error("").contains("no [[phase]]"));
+    assert!(error("[[phase]]\nfault = \"latency\"\nfor = \"1s\"").contains("phase 1: `delay` is required"));
+    assert!(error("[[phase]]\nfault = \"block\"\n\n[[phase]]\nfault = \"restore\"").contains("phase 1: `for` is required"));
+    assert!(error("repeat = true\n[[phase]]\nfault = \"block\"").contains("when the scenario repeats"));
+    assert!(error("[[phase]]\nfault = \"block\"\ndelay = \"1s\"").contains("`delay` does not apply"));
+    assert!(error("[[phase]]\nfault = \"loss\"\nrate = 0.1\njitter = \"1s\"").contains("`jitter` does not apply"));
+    assert!(error("[[phase]]\nfault = \"throttle\"\nbytes_per_sec = 0").contains("bytes_per_sec"));
+    assert!(error("[[phase]]\nfault = \"corrupt\"\nrate = 2.0").contains("not between 0 and 1"));
+    assert!(error("[[phase]]\nfault = \"corrupt\"\nrate = 0.1\nbytes = 0").contains("`bytes` must be at least 1"));
+    assert!(error("[[phase]]\nfault = \"loss\"\nrate = 0.1\nbytes = 2").contains("`bytes` does not apply"));
+    assert!(error("[[phase]]\nfault = \"block\"\nside = \"client\"").contains("`side` does not apply"));
+    assert!(error("[[phase]]\nfault = \"reset\"\nmode = \"discard\"").contains("`mode` does not apply"));
+    assert!(error("[[phase]]\nfault = \"jitter\"").contains("unknown variant"));
+}
@@ -1,4 +1,10 @@
+use std::path::PathBuf;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("../proto/streaming.proto")?;
+    // 리플렉션 서비스용 descriptor set도 함께 생성
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("streaming_descriptor.bin"))
+        .compile_protos(&["../proto/streaming.proto", "../proto/admin.proto"], &["../proto"])?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,145 @@
+//! 실행 중인 스트림 조회와 서버 측 취소 (`AdminService`)
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use durable_queue::DurableQueue;
+use playground_bus::BusMessage;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+
+use crate::context::GrpcContext;
+use crate::generator::MessageGenerator;
+use crate::server::{StreamAbort, ADMIN_CANCEL_REASON};
+
+pub mod proto {
+    tonic::include_proto!("admin");
+}
+
+use proto::admin_service_server::{AdminService as AdminServiceTrait, AdminServiceServer};
+use proto::{CancelStreamRequest, CancelStreamResponse, ListStreamsRequest, ListStreamsResponse, StreamInfo};
+
+/// `CancelStream`에 사유가 없을 때 쓰는 값
+pub const DEFAULT_CANCEL_REASON: &str = "cancelled by admin";
+
+/// 열린 스트림 하나 - 조회와 취소에 필요한 핸들
+pub(crate) struct StreamHandle {
+    pub uuid: String,
+    pub peer: String,
+    pub session_id: String,
+    pub generator: MessageGenerator,
+    pub queue: DurableQueue<BusMessage>,
+    pub queue_capacity: usize,
+    pub opened_at: u64,
+    pub context: GrpcContext,
+    pub abort: StreamAbort,
+}
+
+/// 서버에 열려 있는 스트림 (stream id 순)
+#[derive(Default)]
+pub struct StreamRegistry {
+    streams: Mutex<BTreeMap<u64, StreamHandle>>,
+}
+
+impl StreamRegistry {
+    pub(crate) fn insert(&self, stream_id: u64, handle: StreamHandle) {
+        self.streams.lock().unwrap().insert(stream_id, handle);
+    }
+
+    pub(crate) fn remove(&self, stream_id: u64) {
+        self.streams.lock().unwrap().remove(&stream_id);
+    }
+
+    /// 열린 스트림마다 진행 상황과 큐 깊이
+    pub async fn list(&self) -> Vec<StreamInfo> {
+        // 생성기 진행 상황은 await가 필요하므로 잠금을 풀고 조회
+        let streams: Vec<_> = self
+            .streams
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&stream_id, handle)| {
+                let info = StreamInfo {
+                    stream_id,
+                    stream_uuid: handle.uuid.clone(),
+                    peer: handle.peer.clone(),
+                    session_id: handle.session_id.clone(),
+                    buffered: handle.queue.len() as u64,
+                    buffer_capacity: handle.queue_capacity as u64,
+                    opened_at: handle.opened_at,
+                    ..StreamInfo::default()
+                };
+                (info, handle.generator.clone())
+            })
+            .collect();
+
+        let mut infos = Vec::with_capacity(streams.len());
+        for (mut info, generator) in streams {
+            (info.generated, info.max_messages) = generator.get_progress().await;
+            infos.push(info);
+        }
+        infos
+    }
+
+    /// 스트림의 context를 `ADMIN_CANCEL_REASON: {reason}`으로 취소하고 클라이언트에 CANCELLED를 보냄 - 없으면 false
+    pub async fn cancel(&self, stream_id: u64, reason: &str) -> bool {
+        let Some((context, abort)) = self
+            .streams
+            .lock()
+            .unwrap()
+            .get(&stream_id)
+            .map(|handle| (handle.context.clone(), handle.abort.clone()))
+        else {
+            return false;
+        };
+        context.cancel(format!("{}: {}", ADMIN_CANCEL_REASON, reason)).await;
+        abort.abort(Status::cancelled(reason));
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct AdminService {
+    streams: Arc<StreamRegistry>,
+}
+
+impl AdminService {
+    pub fn new(streams: Arc<StreamRegistry>) -> AdminServiceServer<Self> {
+        AdminServiceServer::new(Self { streams })
+    }
+}
+
+#[tonic::async_trait]
+impl AdminServiceTrait for AdminService {
+    async fn list_streams(
+        &self,
+        _request: Request<ListStreamsRequest>,
+    ) -> Result<Response<ListStreamsResponse>, Status> {
+        Ok(Response::new(ListStreamsResponse {
+            streams: self.streams.list().await,
+        }))
+    }
+
+    async fn cancel_stream(
+        &self,
+        request: Request<CancelStreamRequest>,
+    ) -> Result<Response<CancelStreamResponse>, Status> {
+        let request = request.into_inner();
+        let reason = match request.reason.trim() {
+            "" => DEFAULT_CANCEL_REASON,
+            reason => reason,
+        };
+        if !self.streams.cancel(request.stream_id, reason).await {
+            return Err(Status::not_found(format!("No active stream {}", request.stream_id)));
+        }
+        warn!(stream_id = request.stream_id, reason, "Stream cancelled by admin");
+        Ok(Response::new(CancelStreamResponse {}))
+    }
+}
@@ -0,0 +1,87 @@
+use clap::{Parser, Subcommand};
+use grpc_stream_server::admin::proto::admin_service_client::AdminServiceClient;
+use grpc_stream_server::admin::proto::{CancelStreamRequest, ListStreamsRequest};
+use playground_auth::AUTHORIZATION_METADATA;
+use tonic::metadata::errors::InvalidMetadataValue;
+use tonic::Request;
+
+#[derive(Parser, Debug)]
+#[command(name = "stream-admin")]
+#[command(about = "List the cancel server's open streams or cancel one from the server side")]
+struct Args {
+    /// Server address (plaintext)
+    #[arg(long, env = "GRPC_SERVER_ADDRESS", default_value = "[::1]:50051")]
+    server: String,
+
+    /// Bearer token for a server started with --auth-token or --auth-hmac-secret
+    #[arg(long, env = "GRPC_AUTH_TOKEN")]
+    token: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show open streams with their progress and buffered messages
+    List,
+    /// Cancel a stream; its client receives CANCELLED with the reason
+    Cancel {
+        stream_id: u64,
+
+        /// Reason sent to the client and recorded by the server
+        #[arg(long, default_value = "")]
+        reason: String,
+    },
+}
+
+/// 토큰이 있으면 `authorization: Bearer` 메타데이터를 붙인 요청
+fn request<T>(message: T, token: Option<&str>) -> Result<Request<T>, InvalidMetadataValue> {
+    let mut request = Request::new(message);
+    if let Some(token) = token {
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_METADATA, format!("Bearer {}", token).parse()?);
+    }
+    Ok(request)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let url = if args.server.contains("://") {
+        args.server.clone()
+    } else {
+        format!("http://{}", args.server)
+    };
+    let mut client = AdminServiceClient::connect(url).await?;
+    let token = args.token.as_deref();
+
+    match args.command {
+        Command::List => {
+            let streams = client.list_streams(request(ListStreamsRequest {}, token)?).await?.into_inner().streams;
+            if streams.is_empty() {
+                println!("No open streams");
+            }
+            for stream in streams {
+                println!(
+                    "stream {}  uuid={}  peer={}  session={}  progress={}/{}  buffered={}/{}  opened_at={}",
+                    stream.stream_id,
+                    stream.stream_uuid,
+                    stream.peer,
+                    stream.session_id,
+                    stream.generated,
+                    stream.max_messages,
+                    stream.buffered,
+                    stream.buffer_capacity,
+                    stream.opened_at
+                );
+            }
+        }
+        Command::Cancel { stream_id, reason } => {
+            client.cancel_stream(request(CancelStreamRequest { stream_id, reason }, token)?).await?;
+            println!("Cancelled stream {}", stream_id);
+        }
+    }
+    Ok(())
+}
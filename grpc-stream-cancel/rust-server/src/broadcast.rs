@@ -0,0 +1,184 @@
+//! `--broadcast` - 생성기 하나가 `tokio::sync::broadcast` 채널로 연결된 모든 스트림에 같은 메시지를 보냄
+//!
+//! 스트림마다 생성기와 큐를 두는 기본 모델과 달리 느린 클라이언트가 생성기를 막지 않음: 채널 용량보다
+//! 뒤처진 수신자는 `Lagged`로 건너뛴 메시지 수를 받고 채널에 남은 가장 오래된 메시지부터 이어 받음.
+
+use playground_stats::{ActiveStreamGuard, ServerStats};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tonic::{Code, Status};
+use tracing::{info, info_span, warn, Instrument};
+
+use crate::generator::MessageGenerator;
+use crate::interval::MessageInterval;
+use crate::server::{MessageStream, DURATION_TRAILER, MESSAGES_SENT_TRAILER};
+use crate::streaming::DataMessage;
+
+/// 채널 용량을 넘게 뒤처져 이 스트림이 건너뛴 메시지 수
+pub const LAGGED_TRAILER: &str = "x-messages-lagged";
+
+/// 모든 스트림이 구독하는 생성기 태스크의 채널
+pub struct Broadcaster {
+    /// 생성기 태스크가 끝나면(메시지를 다 보냈거나 서버 종료) 더는 구독할 수 없음
+    sender: broadcast::WeakSender<DataMessage>,
+    stats: Arc<ServerStats>,
+}
+
+impl Broadcaster {
+    /// `generator`의 메시지를 `interval`마다 채널에 보내는 태스크 시작 - 다 보내거나 `shutdown`이 취소되면 끝남
+    ///
+    /// 구독자가 없을 때 만든 메시지는 누구에게도 가지 않음. `capacity`는 수신자마다 뒤처질 수 있는 메시지 수.
+    pub fn start(
+        generator: MessageGenerator,
+        interval: MessageInterval,
+        capacity: usize,
+        stats: Arc<ServerStats>,
+        shutdown: CancellationToken,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        let weak = sender.downgrade();
+        let generator_stats = stats.clone();
+        tokio::spawn(
+            async move {
+                let mut pacer = interval.pacer(None);
+                while let Some(message) = generator.generate_next().await {
+                    ServerStats::incr(&generator_stats.messages_generated);
+                    let message_id = message.id;
+                    match sender.send(message) {
+                        Ok(receivers) => info!(message_id, receivers, "Message broadcast"),
+                        Err(_) => info!(message_id, "Message broadcast with no subscribers"),
+                    }
+                    tokio::select! {
+                        _ = tokio::time::sleep(pacer.next_delay()) => {}
+                        _ = shutdown.cancelled() => {
+                            info!("Server shutting down - stopping broadcast");
+                            return;
+                        }
+                    }
+                }
+                info!("All messages broadcast - closing channel");
+            }
+            .instrument(info_span!("broadcast")),
+        );
+        Self { sender: weak, stats }
+    }
+
+    /// 이 시점 이후의 메시지를 받는 스트림 - 채널이 닫히면 요약 trailer를 단 OK 상태로 끝남
+    ///
+    /// `in_stream`이 CANCELLED 등의 오류로 끝나면 그 분류로 취소를 기록하고, 응답 스트림이 먼저 버려지면
+    /// `network_disconnection`으로 기록함.
+    pub fn subscribe<S>(&self, stream_id: u64, in_stream: S) -> MessageStream
+    where
+        S: Stream<Item = Result<DataMessage, Status>> + Send + Unpin + 'static,
+    {
+        let (receiver, subscribers) = match self.sender.upgrade() {
+            Some(sender) => (sender.subscribe(), sender.receiver_count()),
+            // 생성기가 이미 끝남 - 바로 Closed를 돌려주는 수신자
+            None => (broadcast::channel(1).1, 0),
+        };
+        let span = info_span!("broadcast_stream", stream_id);
+        span.in_scope(|| info!(subscribers, "Broadcast client subscribed"));
+        let subscriber = Subscriber {
+            receiver,
+            in_stream: Some(in_stream),
+            _guard: self.stats.stream_opened(),
+            stats: self.stats.clone(),
+            sent: 0,
+            lagged: 0,
+            opened: Instant::now(),
+            finished: false,
+            span,
+        };
+        Box::pin(futures_util::stream::unfold(Some(subscriber), |subscriber| async move {
+            let mut subscriber = subscriber?;
+            loop {
+                tokio::select! {
+                    received = subscriber.receiver.recv() => match received {
+                        Ok(message) => {
+                            subscriber.sent += 1;
+                            ServerStats::incr(&subscriber.stats.messages_sent);
+                            return Some((Ok(message), Some(subscriber)));
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            subscriber.lagged += skipped;
+                            subscriber.stats.lagged_messages.fetch_add(skipped, Ordering::Relaxed);
+                            warn!(parent: &subscriber.span, skipped, lagged = subscriber.lagged, "Broadcast receiver lagged - skipped messages");
+                        }
+                        Err(RecvError::Closed) => {
+                            subscriber.finished = true;
+                            ServerStats::incr(&subscriber.stats.completed_streams);
+                            info!(parent: &subscriber.span, sent = subscriber.sent, lagged = subscriber.lagged, "Broadcast ended - closing stream");
+                            return Some((Err(subscriber.summary(Status::new(Code::Ok, ""))), None));
+                        }
+                    },
+                    request = next_request(&mut subscriber.in_stream) => match request {
+                        // 클라이언트가 보내는 데이터는 쓰지 않음
+                        Some(Ok(_)) => {}
+                        Some(Err(status)) => {
+                            let kind = match status.code() {
+                                Code::Cancelled => "intentional_cancellation",
+                                Code::DeadlineExceeded => "deadline_exceeded",
+                                _ => "network_disconnection",
+                            };
+                            subscriber.finished = true;
+                            subscriber.stats.stream_cancelled(kind);
+                            warn!(parent: &subscriber.span, code = ?status.code(), kind, sent = subscriber.sent, lagged = subscriber.lagged, "Broadcast client cancelled");
+                            return None;
+                        }
+                        // 요청 스트림만 닫힘 - 응답은 계속 보냄
+                        None => subscriber.in_stream = None,
+                    },
+                }
+            }
+        }))
+    }
+}
+
+/// 요청 스트림의 다음 항목 - 이미 끝난 스트림은 영원히 기다림
+async fn next_request<S>(in_stream: &mut Option<S>) -> Option<Result<DataMessage, Status>>
+where
+    S: Stream<Item = Result<DataMessage, Status>> + Unpin,
+{
+    match in_stream {
+        Some(stream) => stream.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 브로드캐스트를 받는 스트림 하나의 수신자와 집계
+struct Subscriber<S> {
+    receiver: broadcast::Receiver<DataMessage>,
+    in_stream: Option<S>,
+    stats: Arc<ServerStats>,
+    _guard: ActiveStreamGuard,
+    sent: u64,
+    lagged: u64,
+    opened: Instant,
+    /// 완료나 취소를 이미 기록함 - 아니면 drop될 때 연결 끊김으로 기록
+    finished: bool,
+    span: tracing::Span,
+}
+
+impl<S> Subscriber<S> {
+    /// `status`에 보낸 수, 건너뛴 수, 걸린 시간 trailer를 붙임
+    fn summary(&self, status: Status) -> Status {
+        let mut metadata = status.metadata().clone();
+        metadata.insert(MESSAGES_SENT_TRAILER, self.sent.into());
+        metadata.insert(LAGGED_TRAILER, self.lagged.into());
+        metadata.insert(DURATION_TRAILER, (self.opened.elapsed().as_millis() as u64).into());
+        Status::with_metadata(status.code(), status.message(), metadata)
+    }
+}
+
+impl<S> Drop for Subscriber<S> {
+    fn drop(&mut self) {
+        if !self.finished {
+            warn!(parent: &self.span, sent = self.sent, lagged = self.lagged, "Broadcast client went away");
+            self.stats.stream_cancelled("network_disconnection");
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! `--config config.toml` - 새 스트림에 적용할 설정을 파일로 주고, 파일이 바뀌면 재시작 없이 다시 읽음
+//!
+//! 키는 CLI 옵션 이름과 같음 (`message_interval`, `max_messages`, `channel_capacity`, `inject_drop_every`,
+//! `inject_delay_every`, `inject_duplicate_every`). 파일에 있는 키가 CLI 값을 덮어쓰고, 키를 지우면 CLI 값으로 돌아감.
+//! 이미 열린 스트림은 시작할 때의 값을 그대로 씀.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::fault::{DelayEvery, FaultInjection};
+use crate::interval::MessageInterval;
+
+/// 편집기가 저장하면서 연달아 내는 이벤트를 한 번의 reload로 묶는 시간
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 새 스트림이 열릴 때 읽는 설정 - `StreamingServer::settings()`로 바꿀 수 있음
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSettings {
+    pub message_interval: MessageInterval,
+    /// `x-max-messages`가 없는 새 세션의 메시지 수
+    pub max_messages: u64,
+    pub queue_capacity: usize,
+    pub faults: FaultInjection,
+}
+
+/// 설정 파일 내용 - 없는 키는 CLI 값을 유지
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// 초 단위 정수 또는 `500ms`, `2s`, `1s..3s`
+    message_interval: Option<toml::Value>,
+    max_messages: Option<u64>,
+    channel_capacity: Option<usize>,
+    inject_drop_every: Option<u64>,
+    /// `N:ms`
+    inject_delay_every: Option<String>,
+    inject_duplicate_every: Option<u64>,
+}
+
+impl Config {
+    /// TOML 파싱 - 값 검증은 `apply`에서, 오류는 모두 `InvalidData`
+    pub fn parse(text: &str) -> io::Result<Self> {
+        toml::from_str(text).map_err(|e| invalid(e.to_string()))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// `base`(CLI 값) 위에 파일 값을 덮어씀
+    pub fn apply(&self, base: StreamSettings) -> io::Result<StreamSettings> {
+        let mut settings = base;
+        if let Some(interval) = &self.message_interval {
+            let text = match interval {
+                toml::Value::Integer(seconds) => seconds.to_string(),
+                toml::Value::String(text) => text.clone(),
+                other => return Err(invalid(format!("message_interval: expected seconds or a string, got {}", other))),
+            };
+            settings.message_interval = text.parse().map_err(|e| invalid(format!("message_interval: {}", e)))?;
+        }
+        if let Some(max_messages) = self.max_messages {
+            settings.max_messages = max_messages;
+        }
+        match self.channel_capacity {
+            Some(0) => return Err(invalid("channel_capacity: must be at least 1".to_string())),
+            Some(capacity) => settings.queue_capacity = capacity,
+            None => {}
+        }
+        match self.inject_drop_every {
+            Some(0) => return Err(invalid("inject_drop_every: must be at least 1".to_string())),
+            Some(every) => settings.faults.drop_every = Some(every),
+            None => {}
+        }
+        if let Some(delay) = &self.inject_delay_every {
+            let delay: DelayEvery = delay.parse().map_err(|e| invalid(format!("inject_delay_every: {}", e)))?;
+            settings.faults.delay_every = Some(delay);
+        }
+        match self.inject_duplicate_every {
+            Some(0) => return Err(invalid("inject_duplicate_every: must be at least 1".to_string())),
+            Some(every) => settings.faults.duplicate_every = Some(every),
+            None => {}
+        }
+        Ok(settings)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// `path`를 감시하다 바뀌면 다시 읽어 `base` 위에 덮어쓴 값을 `settings`로 보냄 - `shutdown`까지
+///
+/// 읽거나 파싱하지 못한 내용은 경고만 남기고 이전 설정을 유지함. 편집기가 파일을 새로 만들어 바꾸는 경우도
+/// 잡도록 상위 디렉터리를 감시함.
+pub fn watch(
+    path: PathBuf,
+    base: StreamSettings,
+    settings: watch::Sender<StreamSettings>,
+    shutdown: CancellationToken,
+) -> notify::Result<()> {
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let mut watcher = RecommendedWatcher::new(
+        move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.paths.iter().any(|changed| changed.file_name() == file_name.as_deref()) => {
+                let _ = events_tx.send(());
+            }
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Config watcher error"),
+        },
+        notify::Config::default(),
+    )?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    info!(path = %path.display(), "Watching config file for changes");
+
+    tokio::spawn(async move {
+        // 태스크가 끝날 때까지 감시 유지
+        let _watcher = watcher;
+        loop {
+            tokio::select! {
+                event = events.recv() => if event.is_none() { return },
+                _ = shutdown.cancelled() => return,
+            }
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while events.try_recv().is_ok() {}
+            if let Err(e) = apply_file(&path, base, &settings) {
+                warn!(path = %path.display(), error = %e, "Config reload failed - keeping previous settings");
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 파일을 읽어 `base` 위에 덮어쓴 값을 `settings`로 보냄 - 값이 바뀌었으면 true
+pub fn apply_file(path: &Path, base: StreamSettings, settings: &watch::Sender<StreamSettings>) -> io::Result<bool> {
+    let next = Config::load(path)?.apply(base)?;
+    let changed = settings.send_if_modified(|current| {
+        if *current == next {
+            return false;
+        }
+        *current = next;
+        true
+    });
+    if changed {
+        info!(
+            path = %path.display(),
+            message_interval = %next.message_interval,
+            max_messages = next.max_messages,
+            queue_capacity = next.queue_capacity,
+            inject_drop_every = next.faults.drop_every,
+            inject_delay_every = next.faults.delay_every.map(|delay| delay.to_string()),
+            inject_duplicate_every = next.faults.duplicate_every,
+            "Config applied - new streams use these settings"
+        );
+    }
+    Ok(changed)
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Java gRPC의 Context.cancel()과 유사한 기능 - Tokio CancellationToken 사용
+#[derive(Clone)]
+pub struct GrpcContext {
+    cancellation_token: CancellationToken,
+    cancellation_reason: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for GrpcContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrpcContext {
+    pub fn new() -> Self {
+        Self {
+            cancellation_token: CancellationToken::new(),
+            cancellation_reason: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Java의 Context.isCancelled()와 동일
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Java의 Context.cancel()과 동일
+    pub async fn cancel(&self, reason: String) {
+        {
+            let mut cancel_reason = self.cancellation_reason.lock().await;
+            *cancel_reason = Some(reason);
+        }
+        self.cancellation_token.cancel();
+    }
+
+    pub async fn get_cancellation_reason(&self) -> Option<String> {
+        self.cancellation_reason.lock().await.clone()
+    }
+
+    /// Java의 Context.cancelled() future와 유사
+    pub async fn cancelled(&self) {
+        self.cancellation_token.cancelled().await;
+    }
+}
+
+/// 클라이언트가 보낸 마감 시간 헤더 (`grpc-timeout: 30S`, `500m` 등)
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// `grpc-timeout` 값 파싱 - 최대 8자리 숫자 + 단위(H, M, S, m, u, n), 형식이 틀리면 None
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let unit = value.chars().last()?;
+    let digits = &value[..value.len() - unit.len_utf8()];
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u64 = digits.parse().ok()?;
+    Some(match unit {
+        'H' => Duration::from_secs(amount * 60 * 60),
+        'M' => Duration::from_secs(amount * 60),
+        'S' => Duration::from_secs(amount),
+        'm' => Duration::from_millis(amount),
+        'u' => Duration::from_micros(amount),
+        'n' => Duration::from_nanos(amount),
+        _ => return None,
+    })
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 송신 루프에 넣는 인위적 장애 - TCP 프록시 없이 애플리케이션 수준의 손실/지연/중복을 재현
+///
+/// 스트림마다 새로 생성한 메시지를 보낸 횟수(재연결 replay 제외)를 세어 N번째마다 적용.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultInjection {
+    /// N번째 송신마다 메시지를 큐에 넣지 않고 버림 (버스에는 ack - 재전송되지 않음)
+    pub drop_every: Option<u64>,
+    /// N번째 송신마다 큐에 넣기 전에 지연
+    pub delay_every: Option<DelayEvery>,
+    /// N번째 송신마다 같은 id의 메시지를 바로 한 번 더 큐에 넣음 - 클라이언트 중복 제거 확인용
+    pub duplicate_every: Option<u64>,
+}
+
+/// `--inject-delay-every N:ms`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelayEvery {
+    pub every: u64,
+    pub delay: Duration,
+}
+
+/// `send`번째 송신에 적용할 장애
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    Drop,
+    Delay(Duration),
+    Duplicate,
+}
+
+impl FaultInjection {
+    /// `send`(1부터)번째 송신의 장애 - 여럿 해당하면 버림, 중복, 지연 순으로 하나만
+    pub fn fault_for(&self, send: u64) -> Option<Fault> {
+        if self.drop_every.is_some_and(|every| send.is_multiple_of(every)) {
+            return Some(Fault::Drop);
+        }
+        if self.duplicate_every.is_some_and(|every| send.is_multiple_of(every)) {
+            return Some(Fault::Duplicate);
+        }
+        self.delay_every
+            .filter(|delay| send.is_multiple_of(delay.every))
+            .map(|delay| Fault::Delay(delay.delay))
+    }
+}
+
+impl FromStr for DelayEvery {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((every, millis)) = value.split_once(':') else {
+            return Err(format!("expected N:ms, got '{}'", value));
+        };
+        let every = match every.parse::<u64>() {
+            Ok(0) => return Err("N must be at least 1".to_string()),
+            Ok(every) => every,
+            Err(e) => return Err(format!("invalid N '{}': {}", every, e)),
+        };
+        let millis = millis
+            .parse::<u64>()
+            .map_err(|e| format!("invalid delay '{}': {}", millis, e))?;
+        Ok(DelayEvery {
+            every,
+            delay: Duration::from_millis(millis),
+        })
+    }
+}
+
+impl fmt::Display for DelayEvery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.every, self.delay.as_millis())
+    }
+}
@@ -0,0 +1,281 @@
+use playground_clock::SharedClock;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::streaming::DataMessage;
+
+/// 메시지 내용을 만드는 소스 - 세션마다의 id 진행은 `MessageGenerator`가 관리
+///
+/// 여러 세션이 같은 소스를 공유하므로 `id`번째 메시지를 상태 없이 만들 수 있어야 재연결/재개가 동작함.
+pub trait MessageSource: Send + Sync {
+    /// `max_messages`개를 보낼 세션의 `id`번째 메시지 - `None`이면 더 보낼 메시지가 없음
+    fn next(&self, id: u64, timestamp: u64, max_messages: u64) -> Option<DataMessage>;
+
+    /// 소스가 만들 수 있는 메시지 수 (무제한이면 `None`)
+    fn limit(&self) -> Option<u64> {
+        None
+    }
+}
+
+pub type SharedSource = Arc<dyn MessageSource>;
+
+/// `CounterSource`의 기본 payload 템플릿
+pub const DEFAULT_TEMPLATE: &str = "Message {id} from server (max: {max})";
+
+/// 기본 소스 - 템플릿의 `{id}`, `{ts}`, `{max}`를 채운 payload (그 밖의 `{...}`는 그대로 둠)
+pub struct CounterSource {
+    template: String,
+}
+
+impl Default for CounterSource {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl CounterSource {
+    /// `DEFAULT_TEMPLATE` 대신 `template`으로 payload를 만듦
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+}
+
+impl MessageSource for CounterSource {
+    fn next(&self, id: u64, timestamp: u64, max_messages: u64) -> Option<DataMessage> {
+        let payload = self
+            .template
+            .replace("{id}", &id.to_string())
+            .replace("{ts}", &timestamp.to_string())
+            .replace("{max}", &max_messages.to_string());
+        Some(DataMessage {
+            id,
+            timestamp,
+            payload,
+            heartbeat: false,
+            topic: String::new(),
+        })
+    }
+}
+
+/// 메시지마다 `size`바이트 길이의 임의 영숫자 payload
+pub struct RandomSource {
+    size: usize,
+}
+
+impl RandomSource {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+}
+
+impl MessageSource for RandomSource {
+    fn next(&self, id: u64, timestamp: u64, _max_messages: u64) -> Option<DataMessage> {
+        Some(DataMessage {
+            id,
+            timestamp,
+            payload: Alphanumeric.sample_string(&mut rand::thread_rng(), self.size),
+            heartbeat: false,
+            topic: String::new(),
+        })
+    }
+}
+
+/// 다른 소스의 payload를 정확히 `bytes`바이트로 맞춤 - 짧으면 `.`으로 채우고 길면 자름
+///
+/// 큰 메시지로 HTTP/2 flow-control window를 실제로 채워 보기 위한 용도.
+pub struct PaddedSource {
+    inner: SharedSource,
+    bytes: usize,
+}
+
+impl PaddedSource {
+    pub fn new(inner: SharedSource, bytes: usize) -> Self {
+        Self { inner, bytes }
+    }
+}
+
+impl MessageSource for PaddedSource {
+    fn next(&self, id: u64, timestamp: u64, max_messages: u64) -> Option<DataMessage> {
+        let mut message = self.inner.next(id, timestamp, max_messages)?;
+        if message.payload.len() > self.bytes {
+            // UTF-8 문자 중간에서 자르지 않도록 경계까지 물러난 뒤 나머지를 채움
+            let mut end = self.bytes;
+            while !message.payload.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.payload.truncate(end);
+        }
+        let padding = self.bytes - message.payload.len();
+        message.payload.extend(std::iter::repeat_n('.', padding));
+        Some(message)
+    }
+
+    fn limit(&self) -> Option<u64> {
+        self.inner.limit()
+    }
+}
+
+/// JSONL 파일의 각 줄(`{"payload": ...}`)을 순서대로 다시 보냄 - id/timestamp는 새로 매김
+pub struct FileSource {
+    payloads: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ReplayLine {
+    payload: String,
+}
+
+impl FileSource {
+    /// 빈 줄은 건너뛰고, 형식이 틀린 줄은 줄 번호와 함께 `InvalidData` 오류
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let payloads = fs::read_to_string(&path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<ReplayLine>(line)
+                    .map(|line| line.payload)
+                    .map_err(|e| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), index + 1, e))
+                    })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { payloads })
+    }
+}
+
+impl MessageSource for FileSource {
+    fn next(&self, id: u64, timestamp: u64, _max_messages: u64) -> Option<DataMessage> {
+        let index = usize::try_from(id.checked_sub(1)?).ok()?;
+        Some(DataMessage {
+            id,
+            timestamp,
+            payload: self.payloads.get(index)?.clone(),
+            heartbeat: false,
+            topic: String::new(),
+        })
+    }
+
+    fn limit(&self) -> Option<u64> {
+        Some(self.payloads.len() as u64)
+    }
+}
+
+/// `--source` 값 - `counter`, `random:<bytes>`, `file:<path>`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SourceSpec {
+    #[default]
+    Counter,
+    Random(usize),
+    File(PathBuf),
+}
+
+impl SourceSpec {
+    /// 소스 생성 (`file:`은 여기서 파일을 읽음)
+    pub fn open(&self) -> io::Result<SharedSource> {
+        Ok(match self {
+            SourceSpec::Counter => Arc::new(CounterSource::default()),
+            SourceSpec::Random(size) => Arc::new(RandomSource::new(*size)),
+            SourceSpec::File(path) => Arc::new(FileSource::load(path)?),
+        })
+    }
+}
+
+impl FromStr for SourceSpec {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "counter" => Ok(SourceSpec::Counter),
+            Some(("random", size)) => size
+                .parse()
+                .map(SourceSpec::Random)
+                .map_err(|e| format!("invalid random payload size '{}': {}", size, e)),
+            Some(("file", path)) if !path.is_empty() => Ok(SourceSpec::File(PathBuf::from(path))),
+            _ => Err(format!(
+                "unknown message source '{}' (expected counter, random:<bytes> or file:<path>)",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SourceSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceSpec::Counter => f.write_str("counter"),
+            SourceSpec::Random(size) => write!(f, "random:{}", size),
+            SourceSpec::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+/// 메시지 생성기 - 실시간으로 메시지 생성
+#[derive(Clone)]
+pub struct MessageGenerator {
+    next_id: Arc<Mutex<u64>>,
+    max_messages: u64,
+    clock: SharedClock,
+    source: SharedSource,
+}
+
+impl MessageGenerator {
+    pub fn new(max_messages: u64, clock: SharedClock) -> Self {
+        Self::starting_after(max_messages, clock, 0)
+    }
+
+    /// `last_id`까지 이미 생성된 상태에서 시작 (디스크에서 불러온 세션)
+    pub fn starting_after(max_messages: u64, clock: SharedClock, last_id: u64) -> Self {
+        Self {
+            next_id: Arc::new(Mutex::new(last_id.saturating_add(1).min(max_messages + 1))),
+            max_messages,
+            clock,
+            source: Arc::new(CounterSource::default()),
+        }
+    }
+
+    /// 메시지 내용을 `source`에서 만듦 - 소스가 유한하면 `max_messages`도 그만큼으로 줄어듦
+    pub fn with_source(mut self, source: SharedSource) -> Self {
+        if let Some(limit) = source.limit() {
+            self.max_messages = self.max_messages.min(limit);
+        }
+        self.source = source;
+        self
+    }
+
+    pub async fn generate_next(&self) -> Option<DataMessage> {
+        let mut next_id = self.next_id.lock().await;
+        if *next_id > self.max_messages {
+            return None; // 모든 메시지 생성 완료
+        }
+
+        let message = self.source.next(*next_id, self.clock.unix_secs(), self.max_messages)?;
+
+        *next_id += 1;
+        Some(message)
+    }
+
+    /// 클라이언트가 `last_id`까지 이미 받았으면 그 다음 id부터 생성
+    pub async fn skip_through(&self, last_id: u64) {
+        let mut next_id = self.next_id.lock().await;
+        *next_id = (*next_id).max(last_id.saturating_add(1)).min(self.max_messages + 1);
+    }
+
+    pub async fn get_progress(&self) -> (u64, u64) {
+        let next_id = self.next_id.lock().await;
+        let generated = (*next_id - 1).min(self.max_messages);
+        (generated, self.max_messages)
+    }
+}
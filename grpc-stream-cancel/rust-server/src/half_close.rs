@@ -0,0 +1,35 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// `BidirectionalStream` 클라이언트가 오류 없이 요청 스트림을 닫았을 때(half-close) 서버 동작 (`--on-half-close`)
+///
+/// END_STREAM만 온 것이라 연결은 살아 있음 - RST_STREAM(취소)이나 연결 끊김과 구분됨.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HalfClosePolicy {
+    /// 응답 스트림은 그대로 계속 보냄 (기본)
+    #[default]
+    Keep,
+    /// context를 `HALF_CLOSE_REASON`으로 취소하고 큐에 남은 메시지를 보낸 뒤 OK로 끝냄
+    Finish,
+}
+
+impl FromStr for HalfClosePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keep" => Ok(HalfClosePolicy::Keep),
+            "finish" => Ok(HalfClosePolicy::Finish),
+            _ => Err(format!("unknown half-close behavior '{}' (expected keep or finish)", value)),
+        }
+    }
+}
+
+impl fmt::Display for HalfClosePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HalfClosePolicy::Keep => "keep",
+            HalfClosePolicy::Finish => "finish",
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 메시지 생성 간격 - 고정 또는 매번 `min..=max`에서 뽑는 jitter
+///
+/// 고정 간격은 재정렬/타임아웃 버그를 가리기 쉬워서 범위를 주면 송신마다 간격이 달라짐.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageInterval {
+    Fixed(Duration),
+    Jitter { min: Duration, max: Duration },
+}
+
+impl MessageInterval {
+    /// 스트림 하나의 간격 생성기 - `seed`가 같으면 같은 간격 순서를 만듦 (없으면 무작위)
+    pub fn pacer(&self, seed: Option<u64>) -> Pacer {
+        Pacer {
+            interval: *self,
+            rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+        }
+    }
+}
+
+/// 초 단위 정수 - 기존 `--message-interval 2`와 같은 의미
+impl From<u64> for MessageInterval {
+    fn from(secs: u64) -> Self {
+        MessageInterval::Fixed(Duration::from_secs(secs))
+    }
+}
+
+pub struct Pacer {
+    interval: MessageInterval,
+    rng: StdRng,
+}
+
+impl Pacer {
+    /// 다음 메시지까지 기다릴 시간
+    pub fn next_delay(&mut self) -> Duration {
+        match self.interval {
+            MessageInterval::Fixed(interval) => interval,
+            MessageInterval::Jitter { min, max } => self.rng.gen_range(min..=max),
+        }
+    }
+}
+
+/// `2` (초), `2s`, `500ms`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.strip_suffix("ms") {
+        Some(number) => (number, Duration::from_millis(1)),
+        None => (value.strip_suffix('s').unwrap_or(value), Duration::from_secs(1)),
+    };
+    let count = number
+        .parse::<u32>()
+        .map_err(|e| format!("invalid duration '{}': {}", value, e))?;
+    Ok(unit * count)
+}
+
+fn format_duration(f: &mut fmt::Formatter<'_>, duration: Duration) -> fmt::Result {
+    if duration.subsec_millis() == 0 {
+        write!(f, "{}s", duration.as_secs())
+    } else {
+        write!(f, "{}ms", duration.as_millis())
+    }
+}
+
+impl FromStr for MessageInterval {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let interval = match value.split_once("..") {
+            None => MessageInterval::Fixed(parse_duration(value)?),
+            Some((min, max)) => {
+                let (min, max) = (parse_duration(min)?, parse_duration(max)?);
+                if min > max {
+                    return Err(format!("empty interval range '{}'", value));
+                }
+                MessageInterval::Jitter { min, max }
+            }
+        };
+        let shortest = match interval {
+            MessageInterval::Fixed(interval) => interval,
+            MessageInterval::Jitter { min, .. } => min,
+        };
+        if shortest.is_zero() {
+            return Err("interval must be greater than zero".to_string());
+        }
+        Ok(interval)
+    }
+}
+
+impl fmt::Display for MessageInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MessageInterval::Fixed(interval) => format_duration(f, interval),
+            MessageInterval::Jitter { min, max } => {
+                format_duration(f, min)?;
+                f.write_str("..")?;
+                format_duration(f, max)
+            }
+        }
+    }
+}
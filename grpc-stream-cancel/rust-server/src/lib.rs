@@ -0,0 +1,22 @@
+pub mod admin;
+pub mod broadcast;
+pub mod config;
+pub mod context;
+pub mod fault;
+pub mod generator;
+pub mod half_close;
+pub mod interval;
+pub mod overflow;
+pub mod rate_limit;
+pub mod request_metadata;
+pub mod restart;
+pub mod server;
+pub mod session;
+pub mod webhook;
+
+pub mod streaming {
+    tonic::include_proto!("streaming");
+}
+
+/// 리플렉션 서비스에 등록할 `streaming.proto`/`admin.proto` descriptor set
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("streaming_descriptor");
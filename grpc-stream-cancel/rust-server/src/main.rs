@@ -1,336 +1,474 @@
-use std::env;
+use clap::Parser;
+use grpc_stream_server::admin::AdminService;
+use grpc_stream_server::broadcast::Broadcaster;
+use grpc_stream_server::config;
+use grpc_stream_server::fault::{DelayEvery, FaultInjection};
+use grpc_stream_server::generator::{CounterSource, MessageGenerator, PaddedSource, SharedSource, SourceSpec};
+use grpc_stream_server::half_close::HalfClosePolicy;
+use grpc_stream_server::interval::MessageInterval;
+use grpc_stream_server::overflow::OverflowPolicy;
+use grpc_stream_server::rate_limit::RateLimit;
+use grpc_stream_server::request_metadata::RequestMetadata;
+use grpc_stream_server::restart::serve_restartable;
+use grpc_stream_server::server::{StreamingServer, DEFAULT_QUEUE_CAPACITY};
+use grpc_stream_server::streaming::streaming_service_server::StreamingServiceServer;
+use grpc_stream_server::webhook::Webhook;
+use playground_audit::AuditLog;
+use playground_auth::AuthArgs;
+use playground_clock::{SharedClock, SystemClock};
+use playground_shutdown::{Phase, Shutdown, DEFAULT_DRAIN_TIMEOUT};
+use playground_stats::{ServerStats, StatsService};
+use playground_telemetry::{LogFormat, TelemetryGuard};
+use playground_tls::TlsArgs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::{mpsc, Mutex};
-use tokio_stream::{wrappers::ReceiverStream, StreamExt};
-use tokio_util::sync::CancellationToken;
-use tonic::{transport::Server, Request, Response, Status, Streaming};
-
-pub mod streaming {
-    tonic::include_proto!("streaming");
-}
+use std::time::Duration;
+use tonic::codec::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Routes;
+use tonic::transport::Server;
+use tonic_health::ServingStatus;
+use tokio::net::TcpListener;
+use tokio_stream::Stream;
+use tracing::{info, warn};
+
+#[derive(Parser, Debug)]
+#[command(name = "grpc-stream-server")]
+#[command(about = "Stream generated messages and observe client cancellation vs. disconnection")]
+struct Args {
+    /// Address to listen on (the orchestrator assigns ephemeral ports through the env var)
+    #[arg(long, env = "GRPC_LISTEN_ADDRESS", default_value = "[::1]:50051")]
+    listen_addr: SocketAddr,
+
+    /// TOML file overriding message_interval, max_messages, channel_capacity and the inject_* faults; reloaded for new streams when it changes
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Delay between generated messages: seconds (2), a duration (500ms, 2s) or a jittered range sampled per message (1s..3s)
+    #[arg(long, default_value = "2")]
+    message_interval: MessageInterval,
+
+    /// Seed for --message-interval jitter so runs repeat the same delays (random when unset)
+    #[arg(long)]
+    jitter_seed: Option<u64>,
+
+    /// Concurrent BidirectionalStream/ServerStream calls; more are rejected with RESOURCE_EXHAUSTED (unlimited when unset)
+    #[arg(long, value_parser = parse_capacity)]
+    max_streams: Option<usize>,
+
+    /// Messages per second per stream, enforced with a token bucket instead of --message-interval
+    #[arg(long, value_parser = parse_rate)]
+    rate_limit: Option<f64>,
+
+    /// Messages a full token bucket sends back to back before --rate-limit applies
+    #[arg(long, default_value_t = 1, requires = "rate_limit", value_parser = clap::value_parser!(u32).range(1..))]
+    rate_burst: u32,
+
+    /// Drop every Nth generated message in the sender loop instead of queueing it (application-level loss)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    inject_drop_every: Option<u64>,
+
+    /// Delay every Nth generated message by the given milliseconds before queueing it, as N:ms
+    #[arg(long)]
+    inject_delay_every: Option<DelayEvery>,
+
+    /// Queue every Nth generated message twice with the same id (application-level duplicates for client dedup tests)
+    #[arg(long, alias = "duplicate-every", value_parser = clap::value_parser!(u64).range(1..))]
+    inject_duplicate_every: Option<u64>,
+
+    /// Messages generated per session before the stream completes
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    max_messages: u64,
 
-use streaming::{
-    streaming_service_server::{StreamingService, StreamingServiceServer},
-    DataMessage,
-};
+    /// Message payloads: counter, random:<bytes> (random alphanumeric payload) or file:<path> (replay the "payload" of each JSONL line)
+    #[arg(long, default_value_t = SourceSpec::Counter)]
+    source: SourceSpec,
 
+    /// Payload template for the counter source; {id}, {ts} (unix seconds) and {max} are filled in
+    #[arg(long, conflicts_with = "source")]
+    payload_template: Option<String>,
 
-/// 메시지 생성기 - 실시간으로 메시지 생성
-#[derive(Clone)]
-struct MessageGenerator {
-    next_id: Arc<Mutex<u64>>,
-    max_messages: u64,
-}
+    /// Pad (with '.') or truncate every payload to exactly this many bytes
+    #[arg(long)]
+    payload_bytes: Option<usize>,
 
-impl MessageGenerator {
-    fn new(max_messages: u64) -> Self {
-        Self {
-            next_id: Arc::new(Mutex::new(1)),
-            max_messages,
-        }
-    }
+    /// Messages buffered per stream before the generator waits
+    #[arg(long, default_value_t = DEFAULT_QUEUE_CAPACITY, value_parser = parse_capacity)]
+    channel_capacity: usize,
 
-    async fn generate_next(&self) -> Option<DataMessage> {
-        let mut next_id = self.next_id.lock().await;
-        if *next_id > self.max_messages {
-            return None; // 모든 메시지 생성 완료
-        }
-
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let message = DataMessage {
-            id: *next_id,
-            timestamp: current_time,
-            payload: format!("Message {} from server (max: {})", *next_id, self.max_messages),
-        };
-
-        *next_id += 1;
-        Some(message)
-    }
+    /// One generator for the whole server, fanned out to every stream through a tokio broadcast channel; a client more than --channel-capacity messages behind skips ahead
+    #[arg(long)]
+    broadcast: bool,
 
-    async fn get_progress(&self) -> (u64, u64) {
-        let next_id = self.next_id.lock().await;
-        let generated = (*next_id - 1).min(self.max_messages);
-        (generated, self.max_messages)
-    }
-}
+    /// What the generator does when the stream's queue is full: block, drop-oldest, drop-newest or cancel (RESOURCE_EXHAUSTED)
+    #[arg(long, default_value_t = OverflowPolicy::Block)]
+    overflow_policy: OverflowPolicy,
 
-/// Java gRPC의 Context.cancel()과 유사한 기능 - Tokio CancellationToken 사용
-#[derive(Clone)]
-struct GrpcContext {
-    cancellation_token: CancellationToken,
-    cancellation_reason: Arc<Mutex<Option<String>>>,
-}
+    /// What a bidirectional stream does when the client half-closes its request stream: keep (go on sending) or finish (send what is queued, then end with OK)
+    #[arg(long, default_value_t = HalfClosePolicy::Keep)]
+    on_half_close: HalfClosePolicy,
 
-impl GrpcContext {
-    fn new() -> Self {
-        Self {
-            cancellation_token: CancellationToken::new(),
-            cancellation_reason: Arc::new(Mutex::new(None)),
-        }
-    }
+    /// Directory for per-session reconnection buffers that survive expired sessions and restarts (memory only when unset)
+    #[arg(long)]
+    buffer_dir: Option<PathBuf>,
 
-    /// Java의 Context.isCancelled()와 동일
-    fn is_cancelled(&self) -> bool {
-        self.cancellation_token.is_cancelled()
-    }
+    /// Hold each message this many milliseconds before handing it to the transport (simulated slow consumer; off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    slow_consumer_ms: Option<u64>,
 
-    /// Java의 Context.cancel()과 동일
-    async fn cancel(&self, reason: String) {
-        {
-            let mut cancel_reason = self.cancellation_reason.lock().await;
-            *cancel_reason = Some(reason);
-        }
-        self.cancellation_token.cancel();
-    }
+    /// Seconds a disconnected session is kept for the client to reconnect
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    reconnect_timeout: u64,
+
+    /// Seconds without data before a heartbeat message is sent on the stream (off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    heartbeat_interval: Option<u64>,
+
+    /// Seconds to wait after SIGINT/SIGTERM for open streams to flush their buffered messages
+    #[arg(long, default_value_t = DEFAULT_DRAIN_TIMEOUT.as_secs())]
+    drain_timeout: u64,
+
+    /// Serve Prometheus metrics on this address at GET /metrics
+    #[arg(long, env = "METRICS_LISTEN_ADDRESS")]
+    metrics_addr: Option<SocketAddr>,
+
+    /// Log a one-line stats summary (active streams, msgs/sec, queued, cancellations since the last one) every N seconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    stats_interval: Option<u64>,
+
+    /// Shut down once every stream has finished (after at least one); exit code 1 if any stream ended without completing
+    #[arg(long)]
+    exit_when_done: bool,
 
-    async fn get_cancellation_reason(&self) -> Option<String> {
-        self.cancellation_reason.lock().await.clone()
+    /// Log user-agent, grpc-timeout, compression and x- headers plus the peer address of every StreamingService request
+    #[arg(long)]
+    log_request_metadata: bool,
+
+    /// Reject StreamingService requests without this metadata key (INVALID_ARGUMENT); repeat or comma-separate
+    #[arg(long, value_delimiter = ',')]
+    require_metadata: Vec<String>,
+
+    /// Seconds between HTTP/2 keepalive PINGs on each connection (off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    http2_keepalive_interval: Option<u64>,
+
+    /// Seconds to wait for a keepalive PING ack before closing the connection (tonic default 20)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive_timeout: Option<u64>,
+
+    /// Seconds after which each connection is sent GOAWAY and closed once its streams finish (off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_connection_age: Option<u64>,
+
+    /// Compress streamed responses with gzip or zstd when the client advertises it in grpc-accept-encoding (uncompressed when unset)
+    #[arg(long, value_parser = parse_encoding)]
+    send_compressed: Option<CompressionEncoding>,
+
+    /// Comma-separated encodings (gzip, zstd) accepted on compressed client messages
+    #[arg(long, value_delimiter = ',', value_parser = parse_encoding)]
+    accept_compressed: Vec<CompressionEncoding>,
+
+    /// Append one JSON line per stream lifecycle event (opened, sent, cancelled, closed) to this file instead of PLAYGROUND_AUDIT_DIR
+    #[arg(long)]
+    event_log: Option<PathBuf>,
+
+    /// POST a JSON body (stream id, reason, kind, progress) here whenever a stream's GrpcContext is cancelled (off when unset)
+    #[arg(long)]
+    webhook_url: Option<String>,
+
+    /// Log output format
+    #[arg(long, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+
+    /// OTLP (gRPC) collector endpoint for exporting stream lifecycle spans, e.g. http://localhost:4317 (off when unset)
+    #[arg(long, env = playground_telemetry::OTLP_ENDPOINT_ENV)]
+    otlp_endpoint: Option<String>,
+
+    /// Serve tokio-console task instrumentation (TOKIO_CONSOLE_BIND, default 127.0.0.1:6669)
+    #[cfg(feature = "console")]
+    #[arg(long)]
+    console: bool,
+
+    #[command(flatten)]
+    tls: TlsArgs,
+
+    #[command(flatten)]
+    auth: AuthArgs,
+}
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        Ok(_) => Err("must be a positive number".to_string()),
+        Err(e) => Err(e.to_string()),
     }
+}
 
-    /// Java의 Context.cancelled() future와 유사
-    async fn cancelled(&self) {
-        self.cancellation_token.cancelled().await;
+fn parse_capacity(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(capacity) => Ok(capacity),
+        Err(e) => Err(e.to_string()),
     }
+}
 
-    fn token(&self) -> CancellationToken {
-        self.cancellation_token.clone()
+/// `--send-compressed`/`--accept-compressed` 값 - tonic이 지원하는 gzip, zstd
+fn parse_encoding(value: &str) -> Result<CompressionEncoding, String> {
+    match value {
+        "gzip" => Ok(CompressionEncoding::Gzip),
+        "zstd" => Ok(CompressionEncoding::Zstd),
+        _ => Err(format!("unknown compression '{}' (expected gzip or zstd)", value)),
     }
 }
 
-struct StreamingServer {
-    message_interval: u64,
-    message_generator: MessageGenerator,
+/// `--source`/`--payload-template`/`--payload-bytes` 조합으로 메시지 소스 생성
+fn message_source(args: &Args) -> std::io::Result<SharedSource> {
+    let source: SharedSource = match &args.payload_template {
+        Some(template) => Arc::new(CounterSource::default().with_template(template)),
+        None => args.source.open()?,
+    };
+    Ok(match args.payload_bytes {
+        Some(bytes) => Arc::new(PaddedSource::new(source, bytes)),
+        None => source,
+    })
+}
+
+/// SIGHUP을 받을 때마다 항목 하나 - 핸들러를 설치하지 못하면 재시작 없이 서비스
+#[cfg(unix)]
+fn restart_signals() -> impl Stream<Item = ()> + Send {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let hangup = signal(SignalKind::hangup())
+        .inspect_err(|e| warn!(error = %e, "Failed to install SIGHUP handler - restart disabled"))
+        .ok();
+    futures_util::stream::unfold(hangup, |hangup| async move {
+        let mut hangup = hangup?;
+        hangup.recv().await?;
+        Some(((), Some(hangup)))
+    })
+}
+
+#[cfg(not(unix))]
+fn restart_signals() -> impl Stream<Item = ()> + Send {
+    tokio_stream::empty()
 }
 
-impl StreamingServer {
-    fn new(message_interval: u64, max_messages: u64) -> Self {
-        Self {
-            message_interval,
-            message_generator: MessageGenerator::new(max_messages),
-        }
+/// `console` feature로 빌드하고 `--console`을 주면 tokio-console 계층도 설치
+fn init_telemetry(args: &Args) -> TelemetryGuard {
+    #[cfg(feature = "console")]
+    if args.console {
+        return playground_telemetry::init_with_console("grpc-stream-cancel-server", args.log_format, args.otlp_endpoint.as_deref());
     }
+    playground_telemetry::init_with_endpoint("grpc-stream-cancel-server", args.log_format, args.otlp_endpoint.as_deref())
 }
 
-#[tonic::async_trait]
-impl StreamingService for StreamingServer {
-    type BidirectionalStreamStream = ReceiverStream<Result<DataMessage, Status>>;
-
-    async fn bidirectional_stream(
-        &self,
-        request: Request<Streaming<DataMessage>>,
-    ) -> Result<Response<Self::BidirectionalStreamStream>, Status> {
-        println!("[RUST SERVER] 🔗 New client connected");
-        
-        let mut in_stream = request.into_inner();
-        let (tx, rx) = mpsc::channel(10); // 10개 메시지 버퍼 (채널이 큐 역할)
-        let message_interval = self.message_interval;
-
-        // Java 스타일 gRPC Context 생성
-        let grpc_context = GrpcContext::new();
-        let context_sender = grpc_context.clone();
-        let context_receiver = grpc_context.clone();
-        let context_monitor = grpc_context.clone();
-
-        let tx_sender = tx.clone();
-        let generator = self.message_generator.clone();
-
-        // 채널 기반 실시간 메시지 생성 + 전송
-        let message_sender = tokio::spawn(async move {
-            println!("[RUST SERVER] 📤 Starting real-time message generation (1 msg/sec)...");
-            println!("[RUST SERVER] 📦 Channel buffer size: 10 messages");
-            
-            loop {
-                // 취소 상태 확인
-                if context_sender.is_cancelled() {
-                    let reason = context_sender.get_cancellation_reason().await
-                        .unwrap_or_else(|| "Unknown reason".to_string());
-                    println!("[RUST SERVER] 🚫 Context cancelled: {}", reason);
-                    break;
-                }
-
-                // 새 메시지 생성
-                let message = match generator.generate_next().await {
-                    Some(new_msg) => {
-                        println!("[RUST SERVER] 🆕 Generated message {}", new_msg.id);
-                        new_msg
-                    }
-                    None => {
-                        println!("[RUST SERVER] 🎉 All messages generated!");
-                        let (generated, max) = generator.get_progress().await;
-                        println!("[RUST SERVER] 📊 Final progress: {}/{} messages", generated, max);
-                        println!("[RUST SERVER] 🏁 Closing stream - all messages sent");
-                        
-                        // 모든 메시지 전송 완료 - 스트림을 정상 종료하기 위해 context cancel
-                        context_sender.cancel("All messages sent - normal completion".to_string()).await;
-                        drop(tx_sender);
-                        break;
-                    }
-                };
-
-                // 채널로 메시지 전송 (채널이 가득 차면 자동으로 대기)
-                tokio::select! {
-                    send_result = tx_sender.send(Ok(message.clone())) => {
-                        match send_result {
-                            Ok(_) => {
-                                let (generated, max) = generator.get_progress().await;
-                                println!("[RUST SERVER] ✅ Message {} sent to channel! Progress: {}/{}", 
-                                    message.id, generated, max);
-                            }
-                            Err(_) => {
-                                println!("[RUST SERVER] ❌ Channel closed - Client disconnected");
-                                context_sender.cancel("Network disconnection detected".to_string()).await;
-                                break;
-                            }
-                        }
-                    }
-                    _ = context_sender.cancelled() => {
-                        println!("[RUST SERVER] 🚫 Context cancellation detected");
-                        break;
-                    }
-                }
-                
-                // 1초 간격으로 메시지 생성
-                tokio::select! {
-                    _ = tokio::time::sleep(Duration::from_secs(message_interval)) => {}
-                    _ = context_sender.cancelled() => {
-                        println!("[RUST SERVER] 🚫 Context cancelled during sleep");
-                        break;
-                    }
-                }
-            }
-            
-            println!("[RUST SERVER] 🏁 Message generator finished");
-        });
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let telemetry = init_telemetry(&args);
+    let shutdown = Shutdown::install();
+
+    let addr = args.listen_addr;
+    let stats = Arc::new(ServerStats::default());
+    let clock: SharedClock = Arc::new(SystemClock);
+    let bus = playground_bus::from_env().await?;
+    let audit = match &args.event_log {
+        Some(path) => AuditLog::open_file(path, "grpc-stream-cancel-server", clock.clone())?,
+        None => AuditLog::from_env("grpc-stream-cancel-server", clock.clone())?,
+    };
+    let source = message_source(&args)?;
+    let mut streaming_server = StreamingServer::new(args.message_interval, args.max_messages, stats.clone(), clock.clone())
+        .with_queue_capacity(args.channel_capacity)
+        .with_overflow_policy(args.overflow_policy)
+        .with_half_close(args.on_half_close)
+        .with_reconnect_timeout(Duration::from_secs(args.reconnect_timeout))
+        .with_shutdown(shutdown.token())
+        .with_bus(bus.clone())
+        .with_audit(audit.clone())
+        .with_fault_injection(FaultInjection {
+            drop_every: args.inject_drop_every,
+            delay_every: args.inject_delay_every,
+            duplicate_every: args.inject_duplicate_every,
+        })
+        .with_source(source.clone())?;
+    if args.broadcast {
+        let generator = MessageGenerator::new(args.max_messages, clock).with_source(source);
+        streaming_server = streaming_server.with_broadcast(Broadcaster::start(
+            generator,
+            args.message_interval,
+            args.channel_capacity,
+            stats.clone(),
+            shutdown.token(),
+        ));
+    }
+    if let Some(seed) = args.jitter_seed {
+        streaming_server = streaming_server.with_jitter_seed(seed);
+    }
+    if let Some(dir) = &args.buffer_dir {
+        streaming_server = streaming_server.with_buffer_dir(dir)?;
+    }
+    if let Some(max) = args.max_streams {
+        streaming_server = streaming_server.with_max_streams(max);
+    }
+    if let Some(per_second) = args.rate_limit {
+        streaming_server = streaming_server.with_rate_limit(RateLimit { per_second, burst: args.rate_burst });
+    }
+    if let Some(interval) = args.heartbeat_interval {
+        streaming_server = streaming_server.with_heartbeat_interval(Duration::from_secs(interval));
+    }
+    if let Some(url) = &args.webhook_url {
+        streaming_server = streaming_server.with_webhook(Webhook::new(url));
+    }
+    if let Some(delay) = args.slow_consumer_ms {
+        streaming_server = streaming_server.with_slow_consumer(Duration::from_millis(delay));
+    }
+    // 파일 값이 CLI 값을 덮어씀 - 시작할 때 잘못된 파일은 오류, 실행 중에는 경고 후 이전 값 유지
+    if let Some(path) = &args.config {
+        let settings = streaming_server.settings();
+        let cli = *settings.borrow();
+        config::apply_file(path, cli, &settings)?;
+        config::watch(path.clone(), cli, settings, shutdown.token())?;
+    }
 
-        // 클라이언트 메시지 수신 및 gRPC 표준 상태 감지
-        let message_receiver = tokio::spawn(async move {
-            println!("[RUST SERVER] 👂 Starting to listen for client messages (pure gRPC standard)...");
-            
-            while let Some(message_result) = in_stream.next().await {
-                match message_result {
-                    Ok(data_msg) => {
-                        // 클라이언트가 데이터를 보냈다면 (실제로는 거의 없을 것)
-                        println!("[RUST SERVER] 📨 Received data from client: {}", data_msg.payload);
-                    }
-                    Err(status) => {
-                        println!("[RUST SERVER] ❌ gRPC Error from client:");
-                        println!("[RUST SERVER]   Status Code: {:?}", status.code());
-                        println!("[RUST SERVER]   Message: {}", status.message());
-                        
-                        // 순수 gRPC 상태 코드 기반 구분
-                        let cancel_reason = match status.code() {
-                            tonic::Code::Cancelled => {
-                                println!("[RUST SERVER] 🚫 CANCELLED: Client called cancel() → RST_STREAM sent");
-                                "gRPC standard cancellation - client called cancel()".to_string()
-                            }
-                            tonic::Code::Unavailable => {
-                                println!("[RUST SERVER] 🔌 UNAVAILABLE: Network disconnection or server unavailable");
-                                "gRPC unavailable - likely network disconnection".to_string()
-                            }
-                            tonic::Code::DeadlineExceeded => {
-                                println!("[RUST SERVER] ⏰ DEADLINE_EXCEEDED: Timeout occurred");
-                                "gRPC deadline exceeded - timeout".to_string()
-                            }
-                            _ => {
-                                println!("[RUST SERVER] ❓ Other gRPC error: {:?}", status.code());
-                                format!("gRPC error: {:?}", status.code())
-                            }
-                        };
-                        
-                        context_receiver.cancel(cancel_reason).await;
-                        break;
-                    }
-                }
+    info!(
+        address = %addr,
+        config = args.config.as_ref().map(|path| path.display().to_string()),
+        message_interval = %args.message_interval,
+        jitter_seed = args.jitter_seed,
+        rate_limit = args.rate_limit,
+        rate_burst = args.rate_limit.map(|_| args.rate_burst),
+        inject_drop_every = args.inject_drop_every,
+        inject_delay_every = args.inject_delay_every.map(|delay| delay.to_string()),
+        inject_duplicate_every = args.inject_duplicate_every,
+        max_messages = args.max_messages,
+        source = %args.source,
+        payload_template = args.payload_template.as_deref(),
+        payload_bytes = args.payload_bytes,
+        queue_capacity = args.channel_capacity,
+        overflow_policy = %args.overflow_policy,
+        on_half_close = %args.on_half_close,
+        broadcast = args.broadcast,
+        slow_consumer_ms = args.slow_consumer_ms,
+        max_streams = args.max_streams,
+        reconnect_timeout_secs = args.reconnect_timeout,
+        buffer_dir = args.buffer_dir.as_ref().map(|dir| dir.display().to_string()),
+        heartbeat_interval_secs = args.heartbeat_interval,
+        drain_timeout_secs = args.drain_timeout,
+        bus = bus.name(),
+        audit_log = audit.path().map(|path| path.display().to_string()),
+        webhook_url = args.webhook_url.as_deref(),
+        http2_keepalive_interval_secs = args.http2_keepalive_interval,
+        keepalive_timeout_secs = args.keepalive_timeout,
+        max_connection_age_secs = args.max_connection_age,
+        send_compressed = ?args.send_compressed,
+        accept_compressed = ?args.accept_compressed,
+        stats_interval_secs = args.stats_interval,
+        exit_when_done = args.exit_when_done,
+        log_request_metadata = args.log_request_metadata,
+        require_metadata = ?args.require_metadata,
+        log_format = %args.log_format,
+        otlp_endpoint = args.otlp_endpoint.as_deref(),
+        tls = args.tls.mode(),
+        auth = args.auth.mode(),
+        "Starting gRPC channel-based message server (one generator per x-session-id, x-resume-from replays after reconnect)"
+    );
+
+    if let Some(metrics_addr) = args.metrics_addr {
+        info!(address = %metrics_addr, "Serving Prometheus metrics at /metrics");
+        let metrics = playground_stats::metrics::serve(metrics_addr, "grpc-stream-cancel-server", stats.clone(), shutdown.token());
+        tokio::spawn(async move {
+            if let Err(e) = metrics.await {
+                warn!(error = %e, "Metrics endpoint failed");
             }
-            
-            // 정상 종료 감지 - 네트워크 단절로 가정하고 재연결 대기
-            println!("[RUST SERVER] 📋 Client stream ended → Assuming NETWORK DISCONNECTION");
-            println!("[RUST SERVER] 💡 Keeping message generator running for reconnection...");
-            println!("[RUST SERVER] 📦 Messages will continue buffering in channel");
-            
-            // 재연결을 위해 메시지 생성기는 계속 실행되도록 함
-            // context_receiver.cancel()을 호출하지 않음 - 재연결 대기
-            println!("[RUST SERVER] 🏁 Message receiver finished");
         });
+    }
 
-        // 취소 원인 분석 및 처리
-        let cancellation_monitor = tokio::spawn(async move {
-            context_monitor.cancelled().await;
-            
-            let reason = context_monitor.get_cancellation_reason().await
-                .unwrap_or_else(|| "Unknown".to_string());
-            
-            println!("[RUST SERVER] 🔔 Cancellation detected: {}", reason);
-            
-            // 의도적 취소 vs 네트워크 단절 vs 정상 완료 구분
-            if reason.contains("gRPC standard cancellation") {
-                println!("[RUST SERVER] 🚫 INTENTIONAL CANCELLATION:");
-                println!("[RUST SERVER]   - Client called cancel() explicitly");
-                println!("[RUST SERVER]   - Performing immediate cleanup");
-            } else if reason.contains("All messages sent") {
-                println!("[RUST SERVER] ✅ NORMAL COMPLETION:");
-                println!("[RUST SERVER]   - All messages successfully sent");
-                println!("[RUST SERVER]   - Stream closed gracefully");
-            } else if reason.contains("Network disconnection") {
-                println!("[RUST SERVER] 🔌 NETWORK DISCONNECTION:");
-                println!("[RUST SERVER]   - Temporary network issue detected");
-                println!("[RUST SERVER]   - Reconnection logic was applied");
-            } else if reason.contains("Reconnection timeout") {
-                println!("[RUST SERVER] ⏰ RECONNECTION TIMEOUT:");
-                println!("[RUST SERVER]   - Client did not reconnect within timeout");
-                println!("[RUST SERVER]   - Assuming permanent disconnection");
-            } else {
-                println!("[RUST SERVER] ❓ OTHER: {}", reason);
-            }
-            
-            println!("[RUST SERVER] 🏁 Cancellation monitor finished");
-        });
+    if let Some(interval) = args.stats_interval {
+        tokio::spawn(playground_stats::summary::log_every(stats.clone(), Duration::from_secs(interval), shutdown.token()));
+    }
 
-        // 정리 태스크
+    // CI 시나리오용 - 열린 스트림이 모두 끝나면 신호를 받은 것처럼 종료
+    let done_stats = stats.clone();
+    if args.exit_when_done {
+        let shutdown = shutdown.clone();
+        let stats = stats.clone();
         tokio::spawn(async move {
-            // 모든 태스크 완료 대기
-            let _ = tokio::join!(message_sender, message_receiver, cancellation_monitor);
-            
-            // 스트림 종료
-            drop(tx);
-            println!("[RUST SERVER] 🏁 All tasks completed - stream closed");
+            stats.all_streams_finished().await;
+            info!(
+                completed = stats.completed_streams.load(Ordering::Relaxed),
+                cancelled = stats.cancelled_streams.load(Ordering::Relaxed),
+                "All streams finished"
+            );
+            shutdown.trigger("--exit-when-done");
         });
-
-        println!("[RUST SERVER] ✅ Stream established with Java-style cancellation observer");
-        Ok(Response::new(ReceiverStream::new(rx)))
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    let message_interval = if args.len() > 1 {
-        args[1].parse::<u64>().unwrap_or(2)
-    } else {
-        2
+    // 표준 gRPC 헬스 체크 - 종료가 시작되면 drain 동안 NOT_SERVING (Watch 중인 프로브가 바로 알 수 있음)
+    let (mut health, health_service) = tonic_health::server::health_reporter();
+    health.set_serving::<StreamingServiceServer<StreamingServer>>().await;
+    let health_token = shutdown.token();
+    tokio::spawn(async move {
+        health_token.cancelled().await;
+        health.set_not_serving::<StreamingServiceServer<StreamingServer>>().await;
+        health.set_service_status("", ServingStatus::NotServing).await;
+    });
+
+    // 주기적 PING / GOAWAY로 연결을 정리 - 재연결 로직이 이를 어떻게 견디는지 관찰용
+    let mut builder = Server::builder()
+        .http2_keepalive_interval(args.http2_keepalive_interval.map(Duration::from_secs))
+        .http2_keepalive_timeout(args.keepalive_timeout.map(Duration::from_secs));
+    if let Some(age) = args.max_connection_age {
+        builder = builder.max_connection_age(Duration::from_secs(age));
+    }
+    if let Some(tls) = args.tls.server_config()? {
+        builder = builder.tls_config(tls)?;
+    }
+    // 헬스 체크, 리플렉션과 통계는 인증 없이 - 프로브/대시보드/grpcurl이 토큰 없이 볼 수 있게
+    let auth = args.auth.authenticator();
+    // grpcurl/grpcui가 proto 파일 없이 서비스를 조회하도록 리플렉션 (v1과 구버전 도구용 v1alpha)
+    let reflection = || {
+        tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(grpc_stream_server::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(playground_stats::proto::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
     };
+    let admin_service = AdminService::new(streaming_server.streams());
+    // 인증보다 먼저 실행 - 거절될 요청이 무엇을 보냈는지도 남김
+    let request_metadata = RequestMetadata::new()
+        .with_logging(args.log_request_metadata)
+        .with_required(args.require_metadata.iter().cloned());
+    // 압축은 스트리밍 서비스에만 - 대역폭을 줄인 프록시 너머로 큰 payload를 보내는 경우용
+    let mut streaming_service = StreamingServiceServer::new(streaming_server);
+    if let Some(encoding) = args.send_compressed {
+        streaming_service = streaming_service.send_compressed(encoding);
+    }
+    for encoding in &args.accept_compressed {
+        streaming_service = streaming_service.accept_compressed(*encoding);
+    }
+    let routes = Routes::new(health_service)
+        .add_service(reflection().build_v1()?)
+        .add_service(reflection().build_v1alpha()?)
+        .add_service(InterceptedService::new(admin_service, auth.clone()))
+        .add_service(InterceptedService::new(InterceptedService::new(streaming_service, auth), request_metadata))
+        .add_service(StatsService::new("grpc-stream-cancel-server", stats));
+    // SIGHUP마다 같은 소켓에서 Server를 새로 띄우고 기존 연결에는 GOAWAY
+    let listener = TcpListener::bind(addr).await?;
+    let server = serve_restartable(builder, routes, listener, restart_signals(), shutdown.token());
+
+    // 신호를 받으면 새 연결을 받지 않고, 모든 스트림을 취소한 뒤 큐에 남은 메시지를 보낼 때까지 기다리고 span을 flush
+    if let Some(result) = shutdown.drain(server, Duration::from_secs(args.drain_timeout)).await {
+        result?;
+    }
+    shutdown.advance(Phase::Flush);
+    drop(telemetry);
+    shutdown.advance(Phase::Exit);
 
-    let addr = "[::1]:50051".parse()?;
-    let streaming_server = StreamingServer::new(message_interval, 10); // 10개 메시지 생성
-
-    println!("🚀 [RUST SERVER] Starting gRPC channel-based message server");
-    println!("🔗 [RUST SERVER] Address: {}", addr);
-    println!("⏱️  [RUST SERVER] Message interval: {} seconds", message_interval);
-    println!("🎯 [RUST SERVER] Features:");
-    println!("   - Real-time message generation (10 messages total)");
-    println!("   - Channel buffer (10 messages) - automatic backpressure");
-    println!("   - Client disconnects every 5s, server continues from buffer");
-    println!();
-
-    Server::builder()
-        .add_service(StreamingServiceServer::new(streaming_server))
-        .serve(addr)
-        .await?;
+    if args.exit_when_done && done_stats.cancelled_streams.load(Ordering::Relaxed) > 0 {
+        std::process::exit(1);
+    }
 
     Ok(())
 }
\ No newline at end of file
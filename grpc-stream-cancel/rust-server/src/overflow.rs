@@ -0,0 +1,46 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// 스트림 큐가 가득 찼을 때 생성기가 새 메시지를 어떻게 할지 (`--overflow-policy`)
+///
+/// 버린 메시지도 재연결 버퍼에는 남으므로 `x-resume-from`으로 재개하면 다시 보내질 수 있음.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 자리가 날 때까지 생성기가 기다림 (기본)
+    #[default]
+    Block,
+    /// 아직 꺼내 가지 않은 가장 오래된 메시지를 버리고 새 메시지를 넣음
+    DropOldest,
+    /// 새 메시지를 버림
+    DropNewest,
+    /// 스트림을 `OVERFLOW_REASON`으로 취소하고 RESOURCE_EXHAUSTED로 끝냄
+    Cancel,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "block" => Ok(OverflowPolicy::Block),
+            "drop-oldest" => Ok(OverflowPolicy::DropOldest),
+            "drop-newest" => Ok(OverflowPolicy::DropNewest),
+            "cancel" => Ok(OverflowPolicy::Cancel),
+            _ => Err(format!(
+                "unknown overflow policy '{}' (expected block, drop-oldest, drop-newest or cancel)",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OverflowPolicy::Block => "block",
+            OverflowPolicy::DropOldest => "drop-oldest",
+            OverflowPolicy::DropNewest => "drop-newest",
+            OverflowPolicy::Cancel => "cancel",
+        })
+    }
+}
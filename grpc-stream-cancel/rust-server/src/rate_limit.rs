@@ -0,0 +1,55 @@
+//! 스트림별 전송 속도 제한 (토큰 버킷)
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 초당 `per_second`개, 쌓아 둘 수 있는 토큰은 최대 `burst`개
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: u32,
+}
+
+/// 메시지마다 토큰 하나 - 버킷이 가득 차 있으면 `burst`개까지는 기다리지 않고 보냄
+#[derive(Debug)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// 가득 찬 버킷으로 시작
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// 토큰이 생길 때까지 기다렸다가 하나를 씀 (도중에 취소되면 토큰을 쓰지 않음)
+    pub async fn acquire(&mut self) {
+        self.refill();
+        if self.tokens < 1.0 {
+            let wait = (1.0 - self.tokens) / self.limit.per_second;
+            tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+            self.refill();
+        }
+        // 타이머 반올림으로 1에 살짝 못 미칠 수 있음
+        self.tokens = (self.tokens - 1.0).max(0.0);
+    }
+
+    /// 지금 바로 쓸 수 있는 토큰 수
+    pub fn available(&mut self) -> u32 {
+        self.refill();
+        self.tokens as u32
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst as f64);
+        self.refilled_at = now;
+    }
+}
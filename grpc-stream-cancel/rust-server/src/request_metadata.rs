@@ -0,0 +1,86 @@
+//! 새 요청마다 클라이언트가 보낸 메타데이터를 로그로 남기고 필수 헤더를 검사하는 interceptor
+//!
+//! Java/Go 클라이언트가 실제로 보내는 `user-agent`, `grpc-timeout`, 압축 헤더와 `x-` 헤더를 확인하는 용도.
+//! 토큰이 실린 `authorization`은 남기지 않음.
+
+use std::sync::Arc;
+
+use tonic::metadata::{KeyAndValueRef, MetadataMap};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use tracing::{info, warn};
+
+/// `x-` 헤더 외에 로그에 남기는 표준 헤더
+const LOGGED_HEADERS: &[&str] = &["user-agent", "grpc-timeout", "grpc-encoding", "grpc-accept-encoding"];
+
+/// `--log-request-metadata`/`--require-metadata` - 설정이 없으면 모든 요청을 그대로 통과시킴
+#[derive(Clone, Default)]
+pub struct RequestMetadata {
+    log: bool,
+    required: Arc<[String]>,
+}
+
+impl RequestMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 요청마다 선택한 메타데이터와 클라이언트 주소를 info로 남김
+    pub fn with_logging(mut self, enabled: bool) -> Self {
+        self.log = enabled;
+        self
+    }
+
+    /// 이 헤더 중 하나라도 없는 요청은 INVALID_ARGUMENT로 거절 (헤더 이름은 소문자로 비교)
+    pub fn with_required(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+        self.required = keys.into_iter().map(|key| key.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// 요청에 없는 첫 번째 필수 헤더
+    pub fn missing<'a>(&'a self, metadata: &MetadataMap) -> Option<&'a str> {
+        self.required.iter().map(String::as_str).find(|key| !metadata.contains_key(*key))
+    }
+}
+
+/// 로그에 남길 (헤더, 값) 목록 - 바이너리(`-bin`) 값은 길이만
+pub fn selected(metadata: &MetadataMap) -> Vec<(String, String)> {
+    metadata
+        .iter()
+        .filter_map(|entry| match entry {
+            KeyAndValueRef::Ascii(key, value) if is_logged(key.as_str()) => {
+                Some((key.to_string(), value.to_str().unwrap_or("<non-utf8>").to_string()))
+            }
+            KeyAndValueRef::Binary(key, value) if is_logged(key.as_str()) => {
+                let length = value.to_bytes().map(|bytes| bytes.len()).unwrap_or_default();
+                Some((key.to_string(), format!("<{} bytes>", length)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_logged(key: &str) -> bool {
+    key.starts_with("x-") || LOGGED_HEADERS.contains(&key)
+}
+
+impl Interceptor for RequestMetadata {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let peer = request.remote_addr().map(|addr| addr.to_string()).unwrap_or_else(|| "unknown".to_string());
+        if self.log {
+            let metadata = selected(request.metadata())
+                .into_iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(" ");
+            info!(%peer, %metadata, "Request metadata");
+        }
+        match self.missing(request.metadata()) {
+            Some(key) => {
+                warn!(%peer, header = key, "Rejected request missing required metadata");
+                Err(Status::invalid_argument(format!("missing required metadata '{}'", key)))
+            }
+            None => Ok(request),
+        }
+    }
+}
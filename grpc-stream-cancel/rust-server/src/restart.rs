@@ -0,0 +1,79 @@
+//! SIGHUP 재시작 - 리스닝 소켓은 그대로 두고 tonic `Server`만 새로 띄움
+//!
+//! 재시작하면 기존 `Server`는 연결마다 HTTP/2 GOAWAY를 보내고 새 스트림을 거절하며, 진행 중인 스트림은
+//! 끝날 때까지 백그라운드에서 계속 보냄. 새 연결은 같은 포트에서 새 `Server`가 받음 - 클라이언트가
+//! RST_STREAM이 아닌 서버 쪽 연결 정리를 어떻게 견디는지 관찰용.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tonic::service::Routes;
+use tonic::transport::{Error, Server};
+use tracing::{info, warn};
+
+/// `restart`에 항목이 올 때마다 `listener`를 받는 `Server`를 교체하며 서비스 - `shutdown`이 취소되면
+/// 현재 `Server`와 아직 drain 중인 이전 `Server`들이 모두 끝날 때까지 기다림
+pub async fn serve_restartable<R>(
+    builder: Server,
+    routes: Routes,
+    listener: TcpListener,
+    restart: R,
+    shutdown: CancellationToken,
+) -> Result<(), Error>
+where
+    R: Stream<Item = ()> + Send,
+{
+    let listener = Arc::new(listener);
+    let mut restart = Box::pin(restart);
+    let mut retiring = JoinSet::new();
+    let mut generation = 1u64;
+    let result = loop {
+        // 서버 종료 시에도 취소되는 이 세대만의 종료 신호
+        let token = shutdown.child_token();
+        let mut server: BoxFuture<'static, Result<(), Error>> = Box::pin(
+            builder
+                .clone()
+                .add_routes(routes.clone())
+                .serve_with_incoming_shutdown(accept(listener.clone()), token.clone().cancelled_owned()),
+        );
+        tokio::select! {
+            result = &mut server => break result,
+            Some(()) = restart.next(), if !shutdown.is_cancelled() => {
+                info!(generation, "Restarting server - sending GOAWAY to connected clients, in-flight streams keep running");
+                token.cancel();
+                retiring.spawn(drain(generation, server));
+                generation += 1;
+                info!(generation, "Server listening again");
+            }
+        }
+    };
+
+    while let Some(joined) = retiring.join_next().await {
+        if let Err(e) = joined {
+            warn!(error = %e, "Retired server task failed");
+        }
+    }
+    result
+}
+
+/// 공유 리스너에서 연결을 받는 스트림 - 종료 신호를 받은 `Server`는 더 이상 poll하지 않으므로 새 `Server`와
+/// 연결을 나눠 갖지 않음
+fn accept(listener: Arc<TcpListener>) -> Pin<Box<dyn Stream<Item = std::io::Result<TcpStream>> + Send>> {
+    Box::pin(futures_util::stream::unfold(listener, |listener| async move {
+        let accepted = listener.accept().await.map(|(stream, _)| stream);
+        Some((accepted, listener))
+    }))
+}
+
+/// GOAWAY를 보낸 `Server`의 연결이 모두 닫힐 때까지 기다림
+async fn drain(generation: u64, server: BoxFuture<'static, Result<(), Error>>) {
+    match server.await {
+        Ok(()) => info!(generation, "Retired server closed its last connection"),
+        Err(e) => warn!(generation, error = %e, "Retired server failed"),
+    }
+}
@@ -0,0 +1,1313 @@
+use durable_queue::{DurableQueue, QueueConfig, QueueError};
+use playground_audit::{AuditLog, Outcome};
+use playground_bus::{BusMessage, InMemoryBus, SharedBus};
+use playground_clock::SharedClock;
+use playground_stats::{upload, ServerStats};
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Request, Response, Status, Streaming};
+use tracing::{debug, field, info, info_span, warn, Instrument};
+use uuid::Uuid;
+
+use crate::admin::{StreamHandle, StreamRegistry};
+use crate::broadcast::Broadcaster;
+use crate::config::StreamSettings;
+use crate::context::{parse_grpc_timeout, GrpcContext, GRPC_TIMEOUT_HEADER};
+use crate::fault::{Fault, FaultInjection};
+use crate::generator::SharedSource;
+use crate::half_close::HalfClosePolicy;
+use crate::interval::MessageInterval;
+use crate::overflow::OverflowPolicy;
+use crate::rate_limit::{RateLimit, TokenBucket};
+use crate::session::{SessionRegistry, MAX_MESSAGES_METADATA, RESUME_METADATA, SESSION_METADATA};
+use crate::streaming::{
+    streaming_service_server::StreamingService, DataMessage, DelayRequest, SubscribeRequest, UploadSummary,
+};
+use crate::webhook::{CancellationEvent, Webhook};
+
+/// 클라이언트로 나가는 응답 스트림
+pub type MessageStream = Pin<Box<dyn Stream<Item = Result<DataMessage, Status>> + Send>>;
+
+/// 연결마다 버퍼링할 메시지 수 기본값 - 가득 차면 생성기가 대기
+pub const DEFAULT_QUEUE_CAPACITY: usize = 10;
+/// `--inject-duplicate-every`로 한 번 더 넣는 메시지의 큐 키 - 큐는 같은 키를 두 번 받지 않으므로 원본 id에 이 비트를 더함
+const DUPLICATE_KEY: u64 = 1 << 63;
+/// 서버 종료 시 모든 스트림의 context에 남기는 취소 사유
+pub const SHUTDOWN_REASON: &str = "Server shutting down";
+/// 클라이언트 `grpc-timeout` 마감이 지났을 때의 취소 사유
+pub const DEADLINE_REASON: &str = "gRPC deadline exceeded - timeout";
+/// 클라이언트 스트림이 끝난 뒤 `reconnect_timeout` 동안 응답 스트림이 메시지를 가져가지 않았을 때의 취소 사유
+pub const RECONNECT_TIMEOUT_REASON: &str = "Reconnection timeout - client did not return";
+/// `OverflowPolicy::Cancel`에서 큐가 가득 찼을 때의 취소 사유
+pub const OVERFLOW_REASON: &str = "Queue overflow - client not keeping up";
+/// `HalfClosePolicy::Finish`에서 클라이언트가 요청 스트림을 닫았을 때의 취소 사유
+pub const HALF_CLOSE_REASON: &str = "Client half-closed the request stream";
+/// `AdminService.CancelStream`으로 취소할 때의 사유 접두어 (`{ADMIN_CANCEL_REASON}: {사유}`)
+pub const ADMIN_CANCEL_REASON: &str = "Server cancellation";
+
+/// 응답 스트림이 상태와 함께 끝날 때 붙이는 trailer - 이 스트림에서 전송 계층으로 넘긴 메시지 수
+pub const MESSAGES_SENT_TRAILER: &str = "x-messages-sent";
+/// 스트림을 끝낸 context 취소 사유 (ASCII가 아니면 생략)
+pub const CANCEL_REASON_TRAILER: &str = "x-cancel-reason";
+/// 스트림을 연 뒤 끝날 때까지 걸린 시간 (ms)
+pub const DURATION_TRAILER: &str = "x-duration-ms";
+
+/// 스트림을 열 때 클라이언트 요청에서 꺼낸 값
+#[derive(Debug, Clone, Default)]
+pub struct StreamRequest {
+    /// `x-session-id` - 같은 값으로 다시 연결하면 이전 연결의 생성기를 이어받음
+    pub session_id: Option<String>,
+    /// `x-resume-from` - 클라이언트가 마지막으로 받은 메시지 id
+    pub resume_from: Option<u64>,
+    /// `x-max-messages` - 이 연결의 메시지 수 (없으면 서버 기본값)
+    pub max_messages: Option<u64>,
+    /// `grpc-timeout` - 지나면 DEADLINE_EXCEEDED로 끝냄
+    pub deadline: Option<Duration>,
+    /// 클라이언트 주소 (`ListStreams`에 표시)
+    pub peer: Option<String>,
+    /// 요청 스트림이 오류 없이 끝났을 때의 동작 - 요청 스트림이 없는 호출(`ServerStream`)은 None
+    pub half_close: Option<HalfClosePolicy>,
+}
+
+impl StreamRequest {
+    pub fn session(id: impl Into<String>) -> Self {
+        Self {
+            session_id: Some(id.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_resume_from(mut self, last_id: u64) -> Self {
+        self.resume_from = Some(last_id);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_max_messages(mut self, max_messages: u64) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    pub fn with_half_close(mut self, policy: HalfClosePolicy) -> Self {
+        self.half_close = Some(policy);
+        self
+    }
+}
+
+/// 응답 스트림을 큐에 남은 메시지 없이 바로 `status`로 끝내라는 신호 (마감 초과, 관리자 취소)
+#[derive(Clone, Default)]
+pub(crate) struct StreamAbort {
+    token: CancellationToken,
+    status: Arc<Mutex<Option<Status>>>,
+}
+
+impl StreamAbort {
+    /// 먼저 보낸 상태가 이김
+    pub(crate) fn abort(&self, status: Status) {
+        self.status.lock().unwrap().get_or_insert(status);
+        self.token.cancel();
+    }
+
+    async fn aborted(&self) -> Status {
+        self.token.cancelled().await;
+        self.status.lock().unwrap().take().unwrap_or_else(|| Status::cancelled(ADMIN_CANCEL_REASON))
+    }
+}
+
+pub struct StreamingServer {
+    /// 새 스트림마다 읽는 간격, 메시지 수, 큐 크기, 장애 주입 - `--config` reload로 바뀜
+    settings: watch::Sender<StreamSettings>,
+    jitter_seed: Option<u64>,
+    clock: SharedClock,
+    sessions: Arc<SessionRegistry>,
+    stats: Arc<ServerStats>,
+    bus: SharedBus,
+    audit: AuditLog,
+    overflow: OverflowPolicy,
+    half_close: HalfClosePolicy,
+    reconnect_timeout: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    slow_consumer: Option<Duration>,
+    webhook: Option<Webhook>,
+    broadcast: Option<Broadcaster>,
+    rate_limit: Option<RateLimit>,
+    max_streams: usize,
+    stream_slots: Option<Arc<Semaphore>>,
+    shutdown: CancellationToken,
+    stream_count: AtomicU64,
+    streams: Arc<StreamRegistry>,
+}
+
+/// 버스는 발행 시각을 unix ms로, `DataMessage`는 unix 초로 실음
+impl From<DataMessage> for BusMessage {
+    fn from(message: DataMessage) -> Self {
+        BusMessage {
+            id: message.id,
+            timestamp: message.timestamp.saturating_mul(1000),
+            payload: message.payload,
+        }
+    }
+}
+
+impl From<BusMessage> for DataMessage {
+    fn from(message: BusMessage) -> Self {
+        DataMessage {
+            id: message.id,
+            timestamp: message.timestamp / 1000,
+            payload: message.payload,
+            heartbeat: false,
+            topic: String::new(),
+        }
+    }
+}
+
+impl StreamingServer {
+    /// `message_interval`은 `MessageInterval` 또는 초 단위 정수
+    pub fn new(message_interval: impl Into<MessageInterval>, max_messages: u64, stats: Arc<ServerStats>, clock: SharedClock) -> Self {
+        let settings = StreamSettings {
+            message_interval: message_interval.into(),
+            max_messages,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            faults: FaultInjection::default(),
+        };
+        Self {
+            settings: watch::Sender::new(settings),
+            jitter_seed: None,
+            sessions: Arc::new(SessionRegistry::new(max_messages, clock.clone())),
+            clock,
+            stats,
+            bus: Arc::new(InMemoryBus::default()),
+            audit: AuditLog::disabled(),
+            overflow: OverflowPolicy::default(),
+            half_close: HalfClosePolicy::default(),
+            reconnect_timeout: None,
+            heartbeat_interval: None,
+            slow_consumer: None,
+            webhook: None,
+            broadcast: None,
+            rate_limit: None,
+            max_streams: 0,
+            stream_slots: None,
+            shutdown: CancellationToken::new(),
+            stream_count: AtomicU64::new(0),
+            streams: Arc::new(StreamRegistry::default()),
+        }
+    }
+
+    /// 메시지 버스 백엔드 교체 (기본은 in-memory)
+    pub fn with_bus(mut self, bus: SharedBus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// 연결/메시지/취소 이벤트를 감사 로그에 기록 (기본은 기록하지 않음)
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// 연결마다 버퍼링할 메시지 수 (기본 `DEFAULT_QUEUE_CAPACITY`)
+    pub fn with_queue_capacity(self, capacity: usize) -> Self {
+        self.settings.send_modify(|settings| settings.queue_capacity = capacity);
+        self
+    }
+
+    /// 큐가 가득 찼을 때 새 메시지 처리 방식 (기본 `OverflowPolicy::Block`)
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    /// `BidirectionalStream` 클라이언트가 요청 스트림을 닫았을 때의 동작 (기본 `HalfClosePolicy::Keep`)
+    pub fn with_half_close(mut self, policy: HalfClosePolicy) -> Self {
+        self.half_close = policy;
+        self
+    }
+
+    /// 연결이 끊긴 세션을 재연결 없이 보관할 시간 (기본은 무기한)
+    ///
+    /// 클라이언트 스트림이 끝난 뒤 응답 스트림이 이 시간 동안 대기 중인 메시지를 하나도 가져가지 않으면
+    /// (전송 계층이 조용히 멈춘 경우) 버려진 스트림으로 보고 `RECONNECT_TIMEOUT_REASON`으로 취소하고 버퍼를 비움.
+    pub fn with_reconnect_timeout(mut self, timeout: Duration) -> Self {
+        self.reconnect_timeout = Some(timeout);
+        self
+    }
+
+    /// 보낼 데이터가 없는 동안 `interval`마다 heartbeat 메시지를 끼워 보냄 (기본은 보내지 않음)
+    ///
+    /// 프록시가 패킷을 조용히 버려도 클라이언트가 TCP keepalive보다 먼저 알아챌 수 있음.
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = Some(interval);
+        self
+    }
+
+    /// 응답 스트림이 큐에서 꺼낸 메시지를 `delay`만큼 붙잡았다가 전송 계층에 넘김 (기본은 바로 넘김)
+    ///
+    /// 느린 소비자 흉내 - 꺼낸 메시지도 ack 전까지 큐 자리를 차지하므로 큐가 가득 차 생성기가 기다림.
+    pub fn with_slow_consumer(mut self, delay: Duration) -> Self {
+        self.slow_consumer = Some(delay);
+        self
+    }
+
+    /// 스트림의 context가 취소될 때마다(정상 완료 포함) `webhook`으로 사유와 진행 상황을 보냄 (기본은 보내지 않음)
+    pub fn with_webhook(mut self, webhook: Webhook) -> Self {
+        self.webhook = Some(webhook);
+        self
+    }
+
+    /// jitter 간격을 재현 가능하게 - 스트림마다 `seed + 스트림 번호`로 RNG를 초기화 (기본은 무작위)
+    pub fn with_jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_seed = Some(seed);
+        self
+    }
+
+    /// 고정된 `message_interval` 대신 스트림마다 토큰 버킷으로 전송 속도를 제한
+    ///
+    /// 버킷이 차 있으면 `burst`개까지 바로 보낸 뒤 초당 `per_second`개로 조절됨.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// 송신 루프에서 N번째 메시지마다 버리거나 지연 (기본은 주입하지 않음)
+    ///
+    /// 프록시의 전송 계층 장애와 달리 스트림은 멀쩡하고 id만 비거나 늦게 도착함.
+    pub fn with_fault_injection(self, faults: FaultInjection) -> Self {
+        self.settings.send_modify(|settings| settings.faults = faults);
+        self
+    }
+
+    /// 동시에 열 수 있는 `BidirectionalStream`/`ServerStream` 수 - 넘치면 바로 RESOURCE_EXHAUSTED (기본은 무제한)
+    pub fn with_max_streams(mut self, max: usize) -> Self {
+        self.max_streams = max;
+        self.stream_slots = Some(Arc::new(Semaphore::new(max)));
+        self
+    }
+
+    /// 세션 id가 있는 세션의 재연결 버퍼를 `dir`에 파일로 남김 (기본은 메모리만)
+    ///
+    /// 재연결 타임아웃으로 세션이 정리되거나 서버가 재시작된 뒤에도 같은 세션 id와
+    /// `x-resume-from`으로 돌아오면 받지 못한 메시지를 다시 보냄. 스트림을 연 뒤에 부르면 에러.
+    pub fn with_buffer_dir(self, dir: impl Into<PathBuf>) -> io::Result<Self> {
+        self.configure_sessions(|sessions| sessions.with_buffer_dir(dir))
+    }
+
+    /// 스트림마다 생성기를 돌리는 대신 모든 스트림이 `broadcaster`의 메시지를 받음 (`--broadcast`)
+    ///
+    /// 세션, 재개, 큐/overflow 정책, 장애 주입은 적용되지 않고 `streams()`에도 등록되지 않음.
+    pub fn with_broadcast(mut self, broadcaster: Broadcaster) -> Self {
+        self.broadcast = Some(broadcaster);
+        self
+    }
+
+    /// 메시지 내용을 `source`에서 만듦 (기본은 `Message {id} from server` 카운터) - 스트림을 연 뒤에 부르면 에러
+    pub fn with_source(self, source: SharedSource) -> io::Result<Self> {
+        self.configure_sessions(|sessions| Ok(sessions.with_source(source)))
+    }
+
+    /// 세션 레지스트리 설정 변경 - 열린 스트림이 레지스트리를 잡고 있으면 바꾸지 않고 에러
+    fn configure_sessions(
+        mut self,
+        configure: impl FnOnce(SessionRegistry) -> io::Result<SessionRegistry>,
+    ) -> io::Result<Self> {
+        let sessions = Arc::try_unwrap(self.sessions)
+            .map_err(|_| io::Error::other("session settings must be applied before any stream is opened"))?;
+        self.sessions = Arc::new(configure(sessions)?);
+        Ok(self)
+    }
+
+    /// `token`이 취소되면 모든 스트림을 `SHUTDOWN_REASON`으로 취소하고 큐에 남은 메시지를 보낸 뒤 정상 종료
+    pub fn with_shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// 동시 스트림 한도의 자리 하나 - 응답 스트림이 버려질 때 반납 (한도가 없으면 `Ok(None)`)
+    fn acquire_stream_slot(&self) -> Result<Option<OwnedSemaphorePermit>, TryAcquireError> {
+        self.stream_slots.as_ref().map(|slots| slots.clone().try_acquire_owned()).transpose()
+    }
+
+    fn streams_exhausted(&self) -> Status {
+        warn!(max_streams = self.max_streams, "Rejecting stream - concurrent stream limit reached");
+        Status::resource_exhausted(format!("Too many concurrent streams (max {})", self.max_streams))
+    }
+
+    /// 새 스트림이 열릴 때 읽는 설정 - 바꾸면 이미 열린 스트림은 그대로, 이후 스트림부터 적용
+    pub fn settings(&self) -> watch::Sender<StreamSettings> {
+        self.settings.clone()
+    }
+
+    /// 열린 스트림 목록 - `AdminService`가 조회/취소에 사용
+    pub fn streams(&self) -> Arc<StreamRegistry> {
+        self.streams.clone()
+    }
+
+    /// 클라이언트가 스트림을 닫을 때까지 받은 메시지 수와 바이트를 집계 (`playground_stats::upload`)
+    ///
+    /// 도중에 취소되거나 끊기면 그때까지 받은 양을 기록하고 받은 상태를 그대로 돌려줌.
+    pub async fn receive_upload<S>(&self, in_stream: S) -> Result<UploadSummary, Status>
+    where
+        S: Stream<Item = Result<DataMessage, Status>> + Unpin,
+    {
+        let stream_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let tally = upload::receive(&self.stats, stream_id, in_stream).await?;
+        Ok(UploadSummary {
+            messages: tally.messages,
+            bytes: tally.bytes,
+            duration_ms: tally.duration_ms,
+        })
+    }
+
+    /// `delay`만큼 기다린 뒤 메시지 하나를 돌려줌 - 스트리밍 취소 데모의 unary 버전
+    ///
+    /// 클라이언트가 취소하거나 마감이 지나면 tonic이 이 future를 버리므로, 응답 전에 drop되면
+    /// `UnaryCall`이 `unary_cancelled`로 기록함. 서버가 종료되면 기다리지 않고 UNAVAILABLE로 끝냄.
+    pub async fn slow_unary(&self, delay: Duration) -> Result<DataMessage, Status> {
+        let call_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let unary_span = info_span!("unary", call_id, delay_ms = delay.as_millis() as u64);
+        async move {
+            let _stream_guard = self.stats.stream_opened();
+            let call = UnaryCall {
+                stats: self.stats.clone(),
+                started: Instant::now(),
+                finished: false,
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = self.shutdown.cancelled() => {
+                    call.finish();
+                    self.stats.stream_cancelled("server_shutdown");
+                    return Err(Status::unavailable(SHUTDOWN_REASON));
+                }
+            }
+            call.finish();
+            ServerStats::incr(&self.stats.completed_streams);
+            info!("Unary request completed");
+            Ok(DataMessage {
+                id: call_id,
+                timestamp: self.clock.unix_secs(),
+                payload: format!("Slept {}ms", delay.as_millis()),
+                heartbeat: false,
+                topic: String::new(),
+            })
+        }
+        .instrument(unary_span)
+        .await
+    }
+
+    /// 이 연결만의 메시지 생성기로 스트림 시작 (`open_session_stream` 참고)
+    pub fn open_stream<S>(&self, in_stream: S) -> MessageStream
+    where
+        S: Stream<Item = Result<DataMessage, Status>> + Send + Unpin + 'static,
+    {
+        self.open_session_stream(StreamRequest::default(), in_stream)
+    }
+
+    /// 토픽마다 독립된 생성기로 스트림을 열고 `topic`을 단 메시지를 하나의 응답 스트림으로 합침
+    ///
+    /// 토픽 하나하나가 `open_session_stream` 스트림이라 `streams()`와 메트릭에도 따로 잡히고,
+    /// 세션 id가 있으면 토픽별로 `{세션 id}/{토픽}` 세션을 이어받음. 마지막 id 하나로는 토픽마다의
+    /// 위치를 알 수 없어 `request.resume_from`은 무시함. 모든 토픽이 끝나면 처음 실패한 토픽의 상태로,
+    /// 모두 OK면 토픽별 `x-messages-sent`를 합친 OK 상태로 끝냄. 중복된 토픽은 한 번만 구독.
+    pub fn open_topics_stream(&self, request: StreamRequest, topics: Vec<String>) -> MessageStream {
+        if let Some(resume_from) = request.resume_from {
+            warn!(resume_from, topics = ?topics, "Ignoring resume position for multi-topic subscription");
+        }
+        let mut seen = HashSet::new();
+        let streams: Vec<_> = topics
+            .into_iter()
+            .filter(|topic| seen.insert(topic.clone()))
+            .map(|topic| {
+                let topic_request = StreamRequest {
+                    session_id: request.session_id.as_ref().map(|id| format!("{}/{}", id, topic)),
+                    resume_from: None,
+                    ..request.clone()
+                };
+                let stream = info_span!("topic", topic = %topic)
+                    .in_scope(|| self.open_session_stream(topic_request, tokio_stream::empty()));
+                with_topic(stream, topic)
+            })
+            .collect();
+
+        let merged = futures_util::stream::select_all(streams);
+        Box::pin(futures_util::stream::unfold(Some((merged, Vec::new())), |state| async move {
+            let (mut merged, mut finished) = state?;
+            loop {
+                match merged.next().await {
+                    Some(Ok(message)) => return Some((Ok(message), Some((merged, finished)))),
+                    // 토픽 스트림의 마지막 상태는 모아 두었다가 모든 토픽이 끝나면 한 번만 보냄
+                    Some(Err(status)) => finished.push(status),
+                    None => return Some((Err(topics_status(finished)), None)),
+                }
+            }
+        }))
+    }
+
+    /// 클라이언트 입력 스트림 하나에 대한 송신/수신/모니터 태스크 시작
+    ///
+    /// 같은 `request.session_id`로 다시 연결하면 이전 연결의 메시지 생성기를 이어받고,
+    /// 세션 id가 없거나 다르면 독립된 생성기를 사용함.
+    /// `request.resume_from`(클라이언트가 마지막으로 받은 id)이 있으면 그 이후 보관된 메시지를 먼저 다시 보내고
+    /// 이미 받은 id는 건너뜀.
+    /// `request.deadline`(클라이언트 `grpc-timeout`)이 지나면 context를 `DEADLINE_REASON`으로 취소하고
+    /// 큐에 남은 메시지 대신 DEADLINE_EXCEEDED 상태로 스트림을 끝냄.
+    /// 스트림은 끝날 때까지 `streams()`에 등록되어 있음.
+    /// tonic `Streaming`뿐 아니라 임의의 Stream을 받으므로 테스트에서 전송 계층 없이 구동 가능.
+    /// 태스크들은 호출 시점의 현재 span 아래 `stream` span(stream_id, session, cancel_reason 필드)에 기록됨.
+    pub fn open_session_stream<S>(&self, request: StreamRequest, mut in_stream: S) -> MessageStream
+    where
+        S: Stream<Item = Result<DataMessage, Status>> + Send + Unpin + 'static,
+    {
+        let StreamRequest { session_id, resume_from, max_messages, deadline, peer, half_close } = request;
+        let settings = *self.settings.borrow();
+        let session = self.sessions.open(session_id.as_deref(), max_messages.or(Some(settings.max_messages)));
+        let stream_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+        // stream_id는 이 프로세스 안에서만 유일 - 여러 실행의 로그/웹훅을 모을 때는 UUID로 구분
+        let stream_uuid = Uuid::new_v4().to_string();
+        let stream_span = info_span!(
+            "stream",
+            stream_id,
+            stream_uuid = %stream_uuid,
+            session = %session.id,
+            cancel_reason = field::Empty,
+            cancel_kind = field::Empty,
+            half_closed = field::Empty,
+            otel.status_code = field::Empty,
+        );
+        stream_span.in_scope(|| info!(resume_from, deadline_ms = deadline.map(|d| d.as_millis() as u64), "New client connected"));
+
+        // 응답 스트림이 꺼내 가기 전까지 메시지를 담아두는 큐 (꺼내는 즉시 ack - at-most-once)
+        let queue: DurableQueue<BusMessage> = DurableQueue::new(
+            QueueConfig {
+                capacity: settings.queue_capacity,
+                ..QueueConfig::default()
+            },
+            self.clock.clone(),
+        )
+        .expect("memory-only queue");
+        let message_interval = settings.message_interval;
+        let jitter_seed = self.jitter_seed;
+        let rate_limit = self.rate_limit;
+        let faults = settings.faults;
+        let queue_capacity = settings.queue_capacity;
+        let overflow = self.overflow;
+        let reconnect_timeout = self.reconnect_timeout;
+        let shutdown = self.shutdown.clone();
+        let queue_monitor = queue.clone();
+        let queue_receiver = queue.clone();
+        // 응답 스트림이 큐에서 꺼내 간 메시지 수 - 버려진 스트림 판단용
+        let taken = Arc::new(AtomicU64::new(0));
+        let taken_receiver = taken.clone();
+        let stats_receiver = self.stats.clone();
+
+        // Java 스타일 gRPC Context 생성
+        let grpc_context = GrpcContext::new();
+        let context_sender = grpc_context.clone();
+        let context_receiver = grpc_context.clone();
+        let span_receiver = stream_span.clone();
+        let context_monitor = grpc_context.clone();
+
+        // 마감 시간이 지나면 생성을 멈추고 응답 스트림을 DEADLINE_EXCEEDED로 끝냄
+        let abort = StreamAbort::default();
+        if let Some(deadline) = deadline {
+            let context = grpc_context.clone();
+            let abort = abort.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = context.cancelled() => {}
+                    _ = tokio::time::sleep(deadline) => {
+                        warn!(deadline_ms = deadline.as_millis() as u64, "Client deadline exceeded");
+                        context.cancel(DEADLINE_REASON.to_string()).await;
+                        abort.abort(Status::deadline_exceeded(DEADLINE_REASON));
+                    }
+                }
+            }.instrument(stream_span.clone()));
+        }
+
+        let abort_receiver = abort.clone();
+        let abort_sender = abort.clone();
+        self.streams.insert(stream_id, StreamHandle {
+            uuid: stream_uuid.clone(),
+            peer: peer.unwrap_or_else(|| "unknown".to_string()),
+            session_id: session.id.clone(),
+            generator: session.generator.clone(),
+            queue: queue.clone(),
+            queue_capacity,
+            opened_at: self.clock.unix_millis(),
+            context: grpc_context.clone(),
+            abort: abort.clone(),
+        });
+        let streams = self.streams.clone();
+
+        let queue_sender = queue.clone();
+        let generator = session.generator.clone();
+        let generator_monitor = session.generator.clone();
+        let webhook = self.webhook.clone();
+        let clock_monitor = self.clock.clone();
+        let stream_guard = self.stats.stream_opened();
+        let stats_sender = self.stats.clone();
+        let stats_monitor = self.stats.clone();
+        let bus = self.bus.clone();
+        let topic = session.topic.clone();
+        let sessions = self.sessions.clone();
+        let history = session.history.clone();
+        let session_id = session.id;
+        let connection = session.connection;
+        let audit = self.audit.stream();
+        let audit_monitor = audit.clone();
+
+        // 큐 기반 실시간 메시지 생성 + 전송
+        let message_sender = tokio::spawn(async move {
+            info!(
+                message_interval = %message_interval,
+                rate_limit = rate_limit.map(|limit| limit.per_second),
+                rate_burst = rate_limit.map(|limit| limit.burst),
+                inject_drop_every = faults.drop_every,
+                inject_delay_every = faults.delay_every.map(|delay| delay.to_string()),
+                inject_duplicate_every = faults.duplicate_every,
+                queue_capacity,
+                "Starting real-time message generation"
+            );
+            let mut rate_limiter = rate_limit.map(TokenBucket::new);
+            let mut pacer = message_interval.pacer(jitter_seed.map(|seed| seed.wrapping_add(stream_id)));
+            // 장애 주입 주기 계산용 - 이 스트림에서 새로 보낸 메시지 수
+            let mut sends = 0u64;
+
+            // 재연결한 클라이언트가 마지막으로 받은 id 이후의 메시지를 먼저 다시 보냄
+            if let Some(last_id) = resume_from {
+                generator.skip_through(last_id).await;
+                let replay = history.lock().unwrap().resume_after(last_id);
+                info!(last_id, replay = replay.len(), "Resuming session from buffered messages");
+
+                for (message, delivery) in replay {
+                    let id = message.id;
+                    if queue_sender.push(id, message).await.is_err() {
+                        warn!(message_id = id, "Queue closed during replay - client disconnected");
+                        context_sender.cancel("Network disconnection detected".to_string()).await;
+                        return;
+                    }
+                    info!(message_id = id, delivery, "Replayed message");
+                    ServerStats::incr(&stats_sender.retried_messages);
+                    ServerStats::incr(&stats_sender.messages_sent);
+                    ServerStats::incr(&stats_sender.queued_messages);
+                    audit.message_sent(id, delivery);
+                }
+            }
+
+            let mut subscription = match bus.subscribe(&topic).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    warn!(bus = bus.name(), error = %e, "Failed to subscribe to message bus");
+                    context_sender.cancel(format!("Message bus error: {}", e)).await;
+                    return;
+                }
+            };
+            
+            'generate: loop {
+                // 취소 상태 확인
+                if context_sender.is_cancelled() {
+                    let reason = context_sender.get_cancellation_reason().await
+                        .unwrap_or_else(|| "Unknown reason".to_string());
+                    info!(reason = %reason, "Context cancelled");
+                    break;
+                }
+
+                // 속도 제한이 있으면 토큰을 얻을 때까지 대기 (버스트만큼은 바로 통과)
+                if let Some(bucket) = rate_limiter.as_mut() {
+                    tokio::select! {
+                        _ = bucket.acquire() => {}
+                        _ = context_sender.cancelled() => {
+                            info!("Context cancelled while rate limited");
+                            break;
+                        }
+                    }
+                }
+
+                // 새 메시지 생성
+                let generated = match generator.generate_next().await {
+                    Some(new_msg) => {
+                        ServerStats::incr(&stats_sender.messages_generated);
+                        info!(message_id = new_msg.id, "Generated message");
+                        new_msg
+                    }
+                    None => {
+                        let (generated, max) = generator.get_progress().await;
+                        info!(generated, max, "All messages generated - closing stream");
+
+                        // 브로커가 재전송할 메시지가 남아 있으면 마저 전달 (in-memory 버스는 즉시 통과)
+                        while let Ok(Ok(delivery)) =
+                            tokio::time::timeout(bus.settle_time(), subscription.next()).await
+                        {
+                            let id = delivery.message.id;
+                            info!(message_id = id, delivery = delivery.attempt, "Broker redelivered message");
+                            ServerStats::incr(&stats_sender.retried_messages);
+                            if queue_sender.push(id, delivery.message).await.is_err() {
+                                break;
+                            }
+                            ServerStats::incr(&stats_sender.messages_sent);
+                            ServerStats::incr(&stats_sender.queued_messages);
+                            audit.message_sent(id, delivery.attempt);
+                            if let Err(e) = bus.ack(&topic, id).await {
+                                warn!(message_id = id, error = %e, "Failed to ack message on bus");
+                            }
+                        }
+                        
+                        // 모든 메시지 전송 완료 - 스트림을 정상 종료하기 위해 context cancel
+                        context_sender.cancel("All messages sent - normal completion".to_string()).await;
+                        queue_sender.close();
+                        break;
+                    }
+                };
+
+                // 생성한 메시지를 버스에 발행하고 구독에서 다음 전달을 꺼냄
+                // (in-memory 버스는 방금 발행한 메시지, 브로커는 재전송분이 먼저 나올 수 있음)
+                if let Err(e) = bus.publish(&topic, generated.into()).await {
+                    warn!(bus = bus.name(), error = %e, "Failed to publish to message bus");
+                    context_sender.cancel(format!("Message bus error: {}", e)).await;
+                    break;
+                }
+                let delivery = tokio::select! {
+                    delivery = subscription.next() => match delivery {
+                        Ok(delivery) => delivery,
+                        Err(e) => {
+                            warn!(bus = bus.name(), error = %e, "Failed to receive from message bus");
+                            context_sender.cancel(format!("Message bus error: {}", e)).await;
+                            break;
+                        }
+                    },
+                    _ = context_sender.cancelled() => {
+                        info!("Context cancellation detected");
+                        break;
+                    }
+                };
+                if delivery.attempt > 1 {
+                    info!(message_id = delivery.message.id, delivery = delivery.attempt, "Broker redelivered message");
+                    ServerStats::incr(&stats_sender.retried_messages);
+                }
+                let attempt = delivery.attempt;
+                let message = delivery.message;
+                // 큐에 넣기 전에 기록 - 전달하지 못하고 끊겨도 재연결 시 다시 보낼 수 있음
+                history.lock().unwrap().record(message.clone(), attempt);
+
+                sends += 1;
+                let fault = faults.fault_for(sends);
+                match fault {
+                    Some(Fault::Drop) => {
+                        // 애플리케이션 수준 손실 - 큐에 넣지 않고 브로커에도 ack해 재전송되지 않게 함
+                        warn!(message_id = message.id, send = sends, "Injected fault - dropping message");
+                        if let Err(e) = bus.ack(&topic, message.id).await {
+                            warn!(message_id = message.id, error = %e, "Failed to ack message on bus");
+                        }
+                    }
+                    Some(Fault::Delay(delay)) => {
+                        warn!(message_id = message.id, send = sends, delay_ms = delay.as_millis() as u64, "Injected fault - delaying message");
+                        tokio::select! {
+                            _ = tokio::time::sleep(delay) => {}
+                            _ = context_sender.cancelled() => {
+                                info!("Context cancelled during injected delay");
+                                break;
+                            }
+                        }
+                    }
+                    Some(Fault::Duplicate) => {
+                        warn!(message_id = message.id, send = sends, "Injected fault - duplicating message");
+                    }
+                    None => {}
+                }
+
+                // 버리지 않은 메시지는 큐에 추가 (큐가 가득 차면 overflow 정책에 따라 대기하거나 버림)
+                let copies = match fault {
+                    Some(Fault::Drop) => 0,
+                    Some(Fault::Duplicate) => 2,
+                    _ => 1,
+                };
+                for copy in 0..copies {
+                    let key = if copy > 0 { message.id | DUPLICATE_KEY } else { message.id };
+                    let send_span = info_span!("send_message", message_id = message.id, duplicate = copy > 0);
+                    tokio::select! {
+                        send_result = enqueue(&queue_sender, overflow, key, message.clone()).instrument(send_span) => {
+                            match send_result {
+                                Ok(enqueued @ (Enqueued::Queued | Enqueued::ReplacedOldest(_))) => {
+                                    if let Enqueued::ReplacedOldest(dropped_id) = enqueued {
+                                        // 버린 메시지 자리를 새 메시지가 차지 - 큐 깊이는 그대로
+                                        warn!(message_id = message.id, dropped_id, "Queue full - dropped oldest buffered message");
+                                        ServerStats::incr(&stats_sender.dropped_messages);
+                                    } else {
+                                        ServerStats::incr(&stats_sender.queued_messages);
+                                    }
+                                    ServerStats::incr(&stats_sender.messages_sent);
+                                    if copy > 0 {
+                                        // 주입한 중복 - 감사 로그와 버스 ack는 원본에서 이미 처리함
+                                        ServerStats::incr(&stats_sender.retried_messages);
+                                        info!(message_id = message.id, "Duplicate sent to queue");
+                                    } else {
+                                        audit.message_sent(message.id, attempt);
+                                        // 큐에 넘긴 메시지만 ack - 넘기지 못한 메시지는 브로커가 다음 연결에 재전송
+                                        if let Err(e) = bus.ack(&topic, message.id).await {
+                                            warn!(message_id = message.id, error = %e, "Failed to ack message on bus");
+                                        }
+                                        let (generated, max) = generator.get_progress().await;
+                                        info!(message_id = message.id, generated, max, "Message sent to queue");
+                                    }
+                                }
+                                Ok(Enqueued::Dropped) => {
+                                    warn!(message_id = message.id, "Queue full - dropped new message");
+                                    ServerStats::incr(&stats_sender.dropped_messages);
+                                    if let Err(e) = bus.ack(&topic, message.id).await {
+                                        warn!(message_id = message.id, error = %e, "Failed to ack message on bus");
+                                    }
+                                }
+                                Ok(Enqueued::Overflow) => {
+                                    warn!(message_id = message.id, capacity = queue_capacity, "Queue full - cancelling stream");
+                                    context_sender.cancel(OVERFLOW_REASON.to_string()).await;
+                                    abort_sender.abort(Status::resource_exhausted(OVERFLOW_REASON));
+                                    break 'generate;
+                                }
+                                // 종료/half-close로 context를 취소한 뒤 닫은 큐 - 먼저 남긴 사유를 덮어쓰지 않음
+                                Err(QueueError::Closed) if context_sender.is_cancelled() => break 'generate,
+                                Err(QueueError::Closed) => {
+                                    warn!(message_id = message.id, "Queue closed - client disconnected");
+                                    context_sender.cancel("Network disconnection detected".to_string()).await;
+                                    break 'generate;
+                                }
+                                Err(e) => {
+                                    warn!(message_id = message.id, error = %e, "Failed to queue message");
+                                    context_sender.cancel(format!("Queue error: {}", e)).await;
+                                    break 'generate;
+                                }
+                            }
+                        }
+                        _ = context_sender.cancelled() => {
+                            info!("Context cancellation detected");
+                            break 'generate;
+                        }
+                    }
+                }
+                
+                // 속도 제한이 없으면 고정 간격으로 메시지 생성
+                if rate_limiter.is_none() {
+                    tokio::select! {
+                        _ = tokio::time::sleep(pacer.next_delay()) => {}
+                        _ = context_sender.cancelled() => {
+                            info!("Context cancelled during sleep");
+                            break;
+                        }
+                    }
+                }
+            }
+            
+            info!("Message generator finished");
+        }.instrument(info_span!(parent: &stream_span, "message_sender")));
+
+        // 클라이언트 메시지 수신 및 gRPC 표준 상태 감지
+        let message_receiver = tokio::spawn(async move {
+            info!("Listening for client messages");
+            
+            while let Some(message_result) = in_stream.next().await {
+                match message_result {
+                    Ok(data_msg) => {
+                        // 클라이언트가 데이터를 보냈다면 (실제로는 거의 없을 것)
+                        info!(message_id = data_msg.id, payload = %data_msg.payload, "Received data from client");
+                    }
+                    Err(status) => {
+                        warn!(code = ?status.code(), message = status.message(), "gRPC error from client");
+
+                        // 순수 gRPC 상태 코드 기반 구분
+                        let cancel_reason = match status.code() {
+                            // 클라이언트가 cancel() 호출 → RST_STREAM
+                            tonic::Code::Cancelled => "gRPC standard cancellation - client called cancel()".to_string(),
+                            tonic::Code::Unavailable => "gRPC unavailable - likely network disconnection".to_string(),
+                            tonic::Code::DeadlineExceeded => DEADLINE_REASON.to_string(),
+                            code => format!("gRPC error: {:?}", code),
+                        };
+                        
+                        context_receiver.cancel(cancel_reason).await;
+                        return;
+                    }
+                }
+            }
+
+            // 오류 없이 끝남 = 클라이언트가 END_STREAM으로 half-close, 연결은 살아 있음
+            match half_close {
+                Some(HalfClosePolicy::Finish) => {
+                    // 생성을 멈추고 큐를 닫음 - 응답 스트림은 남은 메시지를 보낸 뒤 OK로 끝남
+                    span_receiver.record("half_closed", true);
+                    info!("Client half-closed request stream - finishing after buffered messages");
+                    context_receiver.cancel(HALF_CLOSE_REASON.to_string()).await;
+                    queue_receiver.close();
+                    return;
+                }
+                Some(HalfClosePolicy::Keep) => {
+                    span_receiver.record("half_closed", true);
+                    info!("Client half-closed request stream - keeping message generator running");
+                }
+                // 요청 스트림이 없는 호출 - 재연결을 위해 메시지 생성기는 계속 실행되도록 함
+                None => info!("Client stream ended - keeping message generator running for reconnection"),
+            }
+
+            // 클라이언트가 돌아오지 않고 응답 스트림도 멈췄으면 생성기와 버퍼를 정리
+            let Some(timeout) = reconnect_timeout else {
+                return;
+            };
+            loop {
+                let before = taken_receiver.load(Ordering::Relaxed);
+                tokio::select! {
+                    _ = tokio::time::sleep(timeout) => {}
+                    _ = context_receiver.cancelled() => return,
+                }
+                if taken_receiver.load(Ordering::Relaxed) != before || queue_receiver.is_empty() {
+                    continue;
+                }
+                warn!(timeout_secs = timeout.as_secs(), buffered = queue_receiver.len(), "Reconnection timeout - response stream abandoned, tearing down");
+                context_receiver.cancel(RECONNECT_TIMEOUT_REASON.to_string()).await;
+                queue_receiver.close();
+                let mut freed = 0;
+                while let Ok(Some(lease)) = queue_receiver.try_receive() {
+                    queue_receiver.ack(lease.id);
+                    freed += 1;
+                }
+                stats_receiver.queued_messages.fetch_sub(freed, Ordering::Relaxed);
+                abort_receiver.abort(Status::unavailable(RECONNECT_TIMEOUT_REASON));
+                return;
+            }
+        }.instrument(info_span!(parent: &stream_span, "message_receiver")));
+
+        // 취소 원인 분석 및 처리
+        let cancellation_monitor = tokio::spawn(async move {
+            tokio::select! {
+                _ = context_monitor.cancelled() => {}
+                _ = shutdown.cancelled() => {
+                    // 생성을 멈추고 큐를 닫음 - 응답 스트림은 남은 메시지를 보낸 뒤 OK로 끝남
+                    context_monitor.cancel(SHUTDOWN_REASON.to_string()).await;
+                    queue_monitor.close();
+                }
+            }
+            
+            let reason = context_monitor.get_cancellation_reason().await
+                .unwrap_or_else(|| "Unknown".to_string());
+            
+            tracing::Span::current().record("cancel_reason", reason.as_str());
+            info!(reason = %reason, "Stream cancelled");
+
+            // 의도적 취소 vs 네트워크 단절 vs 정상 완료 구분
+            let kind = if reason.contains("gRPC standard cancellation") {
+                "intentional_cancellation"
+            } else if reason.contains("All messages sent") {
+                "normal_completion"
+            } else if reason.contains("Network disconnection") {
+                "network_disconnection"
+            } else if reason == SHUTDOWN_REASON {
+                "server_shutdown"
+            } else if reason == DEADLINE_REASON {
+                "deadline_exceeded"
+            } else if reason.starts_with(ADMIN_CANCEL_REASON) {
+                "server_cancellation"
+            } else if reason == RECONNECT_TIMEOUT_REASON {
+                "reconnection_timeout"
+            } else if reason == OVERFLOW_REASON {
+                "queue_overflow"
+            } else if reason == HALF_CLOSE_REASON {
+                "client_half_closed"
+            } else {
+                "other"
+            };
+            if let Some(webhook) = &webhook {
+                let (generated, max_messages) = generator_monitor.get_progress().await;
+                webhook.notify(CancellationEvent {
+                    stream_id,
+                    stream_uuid,
+                    session_id: session_id.clone(),
+                    reason: reason.clone(),
+                    kind: kind.to_string(),
+                    generated,
+                    max_messages,
+                    timestamp_ms: clock_monitor.unix_millis(),
+                });
+            }
+            if kind == "normal_completion" {
+                ServerStats::incr(&stats_monitor.completed_streams);
+            } else {
+                stats_monitor.stream_cancelled(kind);
+                audit_monitor.cancelled(&reason);
+            }
+            audit_monitor.closed(if reason.contains("All messages sent") {
+                Outcome::Completed
+            } else if reason.contains("gRPC standard cancellation") {
+                Outcome::Cancelled
+            } else if reason == SHUTDOWN_REASON {
+                Outcome::ServerShutdown
+            } else if reason == DEADLINE_REASON {
+                Outcome::DeadlineExceeded
+            } else if reason.starts_with(ADMIN_CANCEL_REASON) || reason == OVERFLOW_REASON {
+                Outcome::ServerCancelled
+            } else if reason == HALF_CLOSE_REASON {
+                Outcome::HalfClosed
+            } else {
+                Outcome::Disconnected
+            });
+            
+            // 완료되었거나 클라이언트/관리자가 직접 취소(또는 half-close로 끝냄)한 세션은 더 이어받을 일이 없음
+            if matches!(kind, "normal_completion" | "intentional_cancellation" | "server_cancellation" | "client_half_closed") {
+                sessions.close(&session_id);
+            } else if let Some(timeout) = reconnect_timeout {
+                // 끊긴 세션은 timeout 안에 같은 세션 id로 다시 연결하지 않으면 정리
+                let sessions = sessions.clone();
+                let session_id = session_id.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(timeout).await;
+                    if sessions.expire(&session_id, connection) {
+                        warn!(timeout_secs = timeout.as_secs(), "Reconnection timeout - client did not reconnect, dropping session");
+                    }
+                }.instrument(tracing::Span::current()));
+            }
+
+            // 트레이스 뷰어에서 비정상 종료 스트림을 바로 골라낼 수 있도록 span 상태로 남김
+            let span = tracing::Span::current();
+            span.record("cancel_kind", kind);
+            if matches!(kind, "network_disconnection" | "deadline_exceeded" | "reconnection_timeout" | "queue_overflow" | "other") {
+                span.record("otel.status_code", "ERROR");
+            }
+            info!(kind, "Cancellation monitor finished");
+        }.instrument(stream_span.clone()));
+
+        // 정리 태스크
+        let queue_cleanup = queue.clone();
+        let cleanup_span = stream_span.clone();
+        tokio::spawn(async move {
+            // 모든 태스크 완료 대기
+            let _ = tokio::join!(message_sender, message_receiver, cancellation_monitor);
+            
+            // 스트림 종료
+            drop(stream_guard);
+            streams.remove(stream_id);
+            queue_cleanup.close();
+            info!("All tasks completed - stream closed");
+        }.instrument(cleanup_span));
+
+        info!(parent: &stream_span, "Stream established with Java-style cancellation observer");
+        let response_queue = ResponseQueue {
+            queue,
+            stats: self.stats.clone(),
+            abort,
+            taken,
+            heartbeat_interval: self.heartbeat_interval,
+            slow_consumer: self.slow_consumer,
+            clock: self.clock.clone(),
+            span: stream_span,
+            context: grpc_context,
+            opened_at: self.clock.unix_millis(),
+        };
+        Box::pin(futures_util::stream::unfold(Some(response_queue), |response| async move {
+            let response = response?;
+            let heartbeat = async {
+                match response.heartbeat_interval {
+                    Some(interval) => tokio::time::sleep(interval).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let lease = tokio::select! {
+                biased;
+                // 마감 초과/관리자 취소는 큐에 남은 메시지를 버리고 상태만 보낸 뒤 끝냄
+                status = response.abort.aborted() => {
+                    warn!(parent: &response.span, code = ?status.code(), "Response stream aborted");
+                    return Some((Err(response.with_trailers(status).await), None));
+                }
+                // 메모리 전용 큐라 spill 오류는 없음
+                lease = response.queue.receive() => match lease.ok().flatten() {
+                    Some(lease) => lease,
+                    // 큐가 닫힘(정상 완료, 서버 종료) - 요약 trailer를 붙인 OK 상태로 끝냄
+                    None => return Some((Err(response.with_trailers(Status::new(Code::Ok, "")).await), None)),
+                },
+                // 큐가 interval 동안 비어 있으면 heartbeat (receive는 취소해도 메시지를 잃지 않음)
+                _ = heartbeat => {
+                    let message = DataMessage {
+                        timestamp: response.clock.unix_secs(),
+                        heartbeat: true,
+                        ..DataMessage::default()
+                    };
+                    return Some((Ok(message), Some(response)));
+                }
+            };
+            if let Some(delay) = response.slow_consumer {
+                tokio::select! {
+                    biased;
+                    status = response.abort.aborted() => {
+                        warn!(parent: &response.span, code = ?status.code(), "Response stream aborted");
+                        return Some((Err(response.with_trailers(status).await), None));
+                    }
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+            response.queue.ack(lease.id);
+            response.taken.fetch_add(1, Ordering::Relaxed);
+            response.stats.queued_messages.fetch_sub(1, Ordering::Relaxed);
+            // 큐 push(send_message span)와 달리 tonic이 실제로 메시지를 가져가 인코딩하는 시점
+            debug!(parent: &response.span, message_id = lease.item.id, "Message handed to transport");
+            Some((Ok(lease.item.into()), Some(response)))
+        }))
+    }
+}
+
+/// `enqueue` 결과
+enum Enqueued {
+    Queued,
+    /// drop-oldest - 새 메시지를 넣으려고 이 id의 메시지를 버림
+    ReplacedOldest(u64),
+    /// drop-newest - 새 메시지를 버림
+    Dropped,
+    /// cancel - 큐가 가득 참
+    Overflow,
+}
+
+/// `id` 키로 `policy`에 따라 큐에 넣음 - block이 아니면 가득 찬 큐에서 기다리지 않음
+async fn enqueue(queue: &DurableQueue<BusMessage>, policy: OverflowPolicy, id: u64, message: BusMessage) -> Result<Enqueued, QueueError> {
+    if policy == OverflowPolicy::Block {
+        return queue.push(id, message).await.map(|()| Enqueued::Queued);
+    }
+    match queue.try_push(id, message.clone()) {
+        Err(QueueError::Full) => {}
+        result => return result.map(|()| Enqueued::Queued),
+    }
+    match policy {
+        OverflowPolicy::DropOldest => {
+            // 응답 스트림이 이미 꺼낸 메시지는 건드리지 않음 - 모두 꺼내 간 상태면 새 메시지를 버림
+            let Some(oldest) = queue.try_receive()? else {
+                return Ok(Enqueued::Dropped);
+            };
+            queue.ack(oldest.id);
+            queue.try_push(id, message)?;
+            Ok(Enqueued::ReplacedOldest(oldest.item.id))
+        }
+        OverflowPolicy::DropNewest => Ok(Enqueued::Dropped),
+        OverflowPolicy::Block | OverflowPolicy::Cancel => Ok(Enqueued::Overflow),
+    }
+}
+
+/// 토픽 스트림들의 마지막 상태를 하나로 - 처음 실패한 상태, 모두 OK면 보낸 메시지 수를 합친 OK
+fn topics_status(statuses: Vec<Status>) -> Status {
+    if let Some(failed) = statuses.iter().find(|status| status.code() != Code::Ok) {
+        return failed.clone();
+    }
+    let sent: u64 = statuses
+        .iter()
+        .filter_map(|status| status.metadata().get(MESSAGES_SENT_TRAILER)?.to_str().ok()?.parse::<u64>().ok())
+        .sum();
+    let mut status = Status::new(Code::Ok, "");
+    status.metadata_mut().insert(MESSAGES_SENT_TRAILER, sent.into());
+    status
+}
+
+/// 토픽 스트림의 메시지(heartbeat 포함)에 `topic`을 붙임
+fn with_topic(stream: MessageStream, topic: String) -> MessageStream {
+    Box::pin(futures_util::stream::unfold(stream, move |mut stream| {
+        let topic = topic.clone();
+        async move {
+            let mut item = stream.next().await?;
+            if let Ok(message) = &mut item {
+                message.topic = topic;
+            }
+            Some((item, stream))
+        }
+    }))
+}
+
+/// 응답 스트림이 끝나거나 버려질 때까지 동시 스트림 자리를 붙잡아 둠
+fn hold_slot(stream: MessageStream, slot: Option<OwnedSemaphorePermit>) -> MessageStream {
+    Box::pin(futures_util::stream::unfold((stream, slot), |(mut stream, slot)| async move {
+        let item = stream.next().await?;
+        Some((item, (stream, slot)))
+    }))
+}
+
+/// 진행 중인 `slow_unary` 호출 - 응답 전에 drop되면(tonic이 취소된 요청의 future를 버림) 취소로 기록
+struct UnaryCall {
+    stats: Arc<ServerStats>,
+    started: Instant,
+    finished: bool,
+}
+
+impl UnaryCall {
+    fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for UnaryCall {
+    fn drop(&mut self) {
+        if !self.finished {
+            // unary는 요청 스트림이 없어 CANCELLED와 마감 초과를 구분할 수 없음 (RST_STREAM만 옴)
+            warn!(elapsed_ms = self.started.elapsed().as_millis() as u64, "Unary request dropped before completion");
+            self.stats.stream_cancelled("unary_cancelled");
+        }
+    }
+}
+
+/// 응답 스트림이 버려지면(클라이언트 연결 종료) 큐를 닫아 생성기에 알림
+struct ResponseQueue {
+    queue: DurableQueue<BusMessage>,
+    stats: Arc<ServerStats>,
+    abort: StreamAbort,
+    taken: Arc<AtomicU64>,
+    heartbeat_interval: Option<Duration>,
+    slow_consumer: Option<Duration>,
+    clock: SharedClock,
+    span: tracing::Span,
+    context: GrpcContext,
+    opened_at: u64,
+}
+
+impl ResponseQueue {
+    /// `status`에 스트림 요약 trailer(`x-messages-sent`, `x-cancel-reason`, `x-duration-ms`)를 붙임
+    ///
+    /// tonic은 응답 스트림의 마지막 `Err(status)`를 trailer로 보내므로 OK로 끝날 때도 상태를 만들어 넘김.
+    /// 클라이언트가 cancel()한 스트림은 RST_STREAM으로 끝나 trailer를 받을 수 없음.
+    async fn with_trailers(&self, status: Status) -> Status {
+        let mut metadata = status.metadata().clone();
+        metadata.insert(MESSAGES_SENT_TRAILER, self.taken.load(Ordering::Relaxed).into());
+        metadata.insert(DURATION_TRAILER, self.clock.unix_millis().saturating_sub(self.opened_at).into());
+        if let Some(reason) = self.context.get_cancellation_reason().await
+            && let Ok(reason) = MetadataValue::try_from(reason.as_str())
+        {
+            metadata.insert(CANCEL_REASON_TRAILER, reason);
+        }
+        Status::with_metadata(status.code(), status.message(), metadata)
+    }
+}
+
+impl Drop for ResponseQueue {
+    fn drop(&mut self) {
+        self.queue.close();
+        // 꺼내 가지 못하고 버려지는 메시지는 큐 깊이에서 뺌
+        self.stats.queued_messages.fetch_sub(self.queue.len() as u64, Ordering::Relaxed);
+    }
+}
+
+impl StreamRequest {
+    /// 요청 메타데이터(`x-session-id`, `x-resume-from`, `x-max-messages`, `grpc-timeout`)와 클라이언트 주소
+    ///
+    /// 숫자가 아니거나 0인 `x-max-messages`는 무시하고 서버 기본값을 씀.
+    fn from_request<T>(request: &Request<T>) -> Self {
+        let metadata = request.metadata();
+        let header = |key: &str| metadata.get(key).and_then(|value| value.to_str().ok());
+        Self {
+            session_id: header(SESSION_METADATA).map(str::to_string),
+            resume_from: header(RESUME_METADATA).and_then(|value| value.parse::<u64>().ok()),
+            max_messages: header(MAX_MESSAGES_METADATA)
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|&max| max > 0),
+            deadline: header(GRPC_TIMEOUT_HEADER).and_then(parse_grpc_timeout),
+            peer: Some(
+                request
+                    .remote_addr()
+                    .map_or_else(|| "unknown".to_string(), |addr| addr.to_string()),
+            ),
+            half_close: None,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl StreamingService for StreamingServer {
+    type BidirectionalStreamStream = MessageStream;
+    type ServerStreamStream = MessageStream;
+
+    async fn bidirectional_stream(
+        &self,
+        request: Request<Streaming<DataMessage>>,
+    ) -> Result<Response<Self::BidirectionalStreamStream>, Status> {
+        let stream_request = StreamRequest::from_request(&request).with_half_close(self.half_close);
+        // 클라이언트가 보낸 traceparent를 이어받아 스트림 전체를 하나의 trace로 묶음
+        let stream_span = info_span!("bidirectional_stream", peer = stream_request.peer.as_deref());
+        playground_telemetry::set_remote_parent(&stream_span, request.metadata());
+
+        let Ok(slot) = self.acquire_stream_slot() else {
+            return Err(stream_span.in_scope(|| self.streams_exhausted()));
+        };
+        let in_stream = request.into_inner();
+        let out_stream = stream_span.in_scope(|| match &self.broadcast {
+            Some(broadcaster) => broadcaster.subscribe(self.stream_count.fetch_add(1, Ordering::Relaxed) + 1, in_stream),
+            None => self.open_session_stream(stream_request, in_stream),
+        });
+        Ok(Response::new(hold_slot(out_stream, slot)))
+    }
+
+    async fn server_stream(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::ServerStreamStream>, Status> {
+        let mut stream_request = StreamRequest::from_request(&request);
+        let stream_span = info_span!("server_stream", peer = stream_request.peer.as_deref());
+        playground_telemetry::set_remote_parent(&stream_span, request.metadata());
+
+        let Ok(slot) = self.acquire_stream_slot() else {
+            return Err(stream_span.in_scope(|| self.streams_exhausted()));
+        };
+        let subscribe = request.into_inner();
+        if let Some(broadcaster) = &self.broadcast {
+            if !subscribe.topics.is_empty() {
+                return Err(Status::failed_precondition("Topic subscriptions are not available in broadcast mode"));
+            }
+            let stream_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let out_stream = stream_span.in_scope(|| broadcaster.subscribe(stream_id, tokio_stream::empty()));
+            return Ok(Response::new(hold_slot(out_stream, slot)));
+        }
+        if !subscribe.session_id.is_empty() {
+            stream_request.session_id = Some(subscribe.session_id);
+        }
+        if subscribe.resume_from > 0 {
+            stream_request.resume_from = Some(subscribe.resume_from);
+        }
+        if !subscribe.topics.is_empty() {
+            // 빈 토픽은 토픽 없는 메시지와 구분할 수 없음
+            if subscribe.topics.iter().any(String::is_empty) {
+                return Err(Status::invalid_argument("Topic names must not be empty"));
+            }
+            let out_stream = stream_span.in_scope(|| self.open_topics_stream(stream_request, subscribe.topics));
+            return Ok(Response::new(hold_slot(out_stream, slot)));
+        }
+        // 요청 스트림이 없으므로 수신 태스크는 바로 끝남 - 클라이언트 취소(RST_STREAM)는
+        // 응답 스트림이 버려져 큐가 닫히는 것으로만 감지되어 네트워크 단절과 구분되지 않음
+        let out_stream = stream_span.in_scope(|| self.open_session_stream(stream_request, tokio_stream::empty()));
+        Ok(Response::new(hold_slot(out_stream, slot)))
+    }
+
+    async fn client_stream(
+        &self,
+        request: Request<Streaming<DataMessage>>,
+    ) -> Result<Response<UploadSummary>, Status> {
+        let peer = request
+            .remote_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
+        let upload_span = info_span!("client_stream", peer = %peer);
+        playground_telemetry::set_remote_parent(&upload_span, request.metadata());
+
+        let summary = self.receive_upload(request.into_inner()).instrument(upload_span).await?;
+        Ok(Response::new(summary))
+    }
+
+    async fn slow_unary(&self, request: Request<DelayRequest>) -> Result<Response<DataMessage>, Status> {
+        let peer = request
+            .remote_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
+        let unary_span = info_span!("slow_unary", peer = %peer);
+        playground_telemetry::set_remote_parent(&unary_span, request.metadata());
+
+        let delay = Duration::from_millis(request.into_inner().delay_ms);
+        let message = StreamingServer::slow_unary(self, delay).instrument(unary_span).await?;
+        Ok(Response::new(message))
+    }
+}
@@ -0,0 +1,326 @@
+use playground_bus::BusMessage;
+use playground_clock::SharedClock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use crate::generator::{CounterSource, MessageGenerator, SharedSource};
+
+/// 클라이언트가 재연결 시 같은 생성기를 이어받기 위해 보내는 메타데이터 키
+pub const SESSION_METADATA: &str = "x-session-id";
+/// 재연결한 클라이언트가 마지막으로 받은 메시지 id를 보내는 메타데이터 키
+pub const RESUME_METADATA: &str = "x-resume-from";
+/// 클라이언트가 이 연결에서 받을 메시지 수를 지정하는 메타데이터 키 (없으면 서버의 `--max-messages`)
+pub const MAX_MESSAGES_METADATA: &str = "x-max-messages";
+/// 재연결 시 다시 보낼 수 있도록 세션마다 보관하는 최근 메시지 수
+pub const RESUME_HISTORY: usize = 100;
+
+/// 세션에서 보낸 최근 메시지 - 클라이언트가 받았다고 알려오기 전까지 보관
+///
+/// `persistent`로 만들면 디스크 파일에도 남겨 긴 단절이나 서버 재시작 뒤에도 다시 보낼 수 있음.
+#[derive(Default)]
+pub struct ResumeBuffer {
+    /// (메시지, 전달 횟수) - id 오름차순
+    entries: VecDeque<(BusMessage, u32)>,
+    file: Option<BufferFile>,
+}
+
+impl ResumeBuffer {
+    /// `path`에 남은 기록이 있으면 불러오고, 이후 변경을 계속 기록
+    pub fn persistent(path: PathBuf) -> io::Result<Self> {
+        let mut buffer = Self::default();
+        for BufferEntry { message, delivery } in BufferFile::load(&path)? {
+            buffer.insert(message, delivery);
+        }
+        let mut file = BufferFile { path, lines: 0 };
+        // 중복 줄을 정리해 두고 시작
+        file.rewrite(&buffer.entries)?;
+        buffer.file = Some(file);
+        Ok(buffer)
+    }
+
+    /// 보낼 메시지 기록 - 이미 있는 id(브로커 재전송)는 전달 횟수만 갱신, 넘치면 오래된 것부터 버림
+    pub fn record(&mut self, message: BusMessage, delivery: u32) {
+        let Some(delivery) = self.insert(message.clone(), delivery) else {
+            return;
+        };
+        if let Some(file) = &mut self.file {
+            let result = if file.lines >= 2 * RESUME_HISTORY {
+                file.rewrite(&self.entries)
+            } else {
+                file.append(&BufferEntry { message, delivery })
+            };
+            if let Err(e) = result {
+                warn!(path = %file.path.display(), error = %e, "Failed to persist reconnection buffer");
+            }
+        }
+    }
+
+    /// 클라이언트가 `last_id`까지 받았으므로 그 이하는 버리고, 이후 메시지를 다시 보낼 전달 횟수와 함께 반환
+    pub fn resume_after(&mut self, last_id: u64) -> Vec<(BusMessage, u32)> {
+        self.entries.retain(|(message, _)| message.id > last_id);
+        let replay = self
+            .entries
+            .iter_mut()
+            .map(|(message, delivery)| {
+                *delivery += 1;
+                (message.clone(), *delivery)
+            })
+            .collect();
+        if let Some(file) = &mut self.file
+            && let Err(e) = file.rewrite(&self.entries)
+        {
+            warn!(path = %file.path.display(), error = %e, "Failed to persist reconnection buffer");
+        }
+        replay
+    }
+
+    /// 가장 큰 메시지 id - 재시작 후 생성기를 그 다음부터 이어가는 데 사용
+    pub fn last_id(&self) -> Option<u64> {
+        self.entries.back().map(|(message, _)| message.id)
+    }
+
+    /// 더 이어받을 일이 없으므로 디스크 기록 삭제
+    pub fn discard(&mut self) {
+        self.entries.clear();
+        if let Some(file) = self.file.take()
+            && let Err(e) = fs::remove_file(&file.path)
+        {
+            warn!(path = %file.path.display(), error = %e, "Failed to remove reconnection buffer");
+        }
+    }
+
+    /// 메모리에 반영 - 바뀐 전달 횟수 (같은 id에 더 작은 전달 횟수면 None)
+    fn insert(&mut self, message: BusMessage, delivery: u32) -> Option<u32> {
+        if let Some(entry) = self.entries.iter_mut().find(|(entry, _)| entry.id == message.id) {
+            if entry.1 >= delivery {
+                return None;
+            }
+            entry.1 = delivery;
+            return Some(delivery);
+        }
+        if self.entries.len() >= RESUME_HISTORY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((message, delivery));
+        Some(delivery)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 디스크에 남기는 재연결 버퍼 한 줄
+#[derive(Serialize, Deserialize)]
+struct BufferEntry {
+    message: BusMessage,
+    delivery: u32,
+}
+
+/// 세션별 JSONL 파일 - 바뀐 항목을 덧붙이고, 불러올 때 같은 id는 나중 줄이 이김
+struct BufferFile {
+    path: PathBuf,
+    /// 마지막 정리 이후 파일 줄 수 - 너무 길어지면 다시 씀
+    lines: usize,
+}
+
+impl BufferFile {
+    fn load(path: &Path) -> io::Result<Vec<BufferEntry>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            match serde_json::from_str(&line?) {
+                Ok(entry) => entries.push(entry),
+                // 쓰다 만 마지막 줄(비정상 종료)은 건너뜀
+                Err(e) => warn!(path = %path.display(), error = %e, "Skipping unreadable reconnection buffer line"),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn append(&mut self, entry: &BufferEntry) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// 현재 항목만 남기도록 임시 파일에 쓴 뒤 교체
+    fn rewrite(&mut self, entries: &VecDeque<(BusMessage, u32)>) -> io::Result<()> {
+        let tmp = self.path.with_extension("jsonl.tmp");
+        let mut file = File::create(&tmp)?;
+        for (message, delivery) in entries {
+            let entry = BufferEntry { message: message.clone(), delivery: *delivery };
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        }
+        file.flush()?;
+        fs::rename(&tmp, &self.path)?;
+        self.lines = entries.len();
+        Ok(())
+    }
+}
+
+/// 세션 하나의 메시지 생성기와 버스 토픽
+#[derive(Clone)]
+pub struct Session {
+    /// 세션 id (메타데이터가 없으면 연결마다 만든 `conn-{번호}`)
+    pub id: String,
+    pub generator: MessageGenerator,
+    pub topic: String,
+    pub history: Arc<Mutex<ResumeBuffer>>,
+    /// 이 세션으로 몇 번째 연결인지 (1부터) - 재연결 여부 판단용
+    pub connection: u64,
+}
+
+/// 세션 id별 생성기 - 다른 클라이언트끼리는 진행 상황을 공유하지 않음
+pub struct SessionRegistry {
+    max_messages: u64,
+    clock: SharedClock,
+    /// 세션마다 `{topic_prefix}-session-{세션 id}`, 익명 연결은 `{topic_prefix}-conn-{번호}` 토픽을 사용
+    topic_prefix: String,
+    sessions: Mutex<HashMap<String, Session>>,
+    anonymous_count: AtomicU64,
+    /// 있으면 세션 id가 있는 세션의 재연결 버퍼를 여기에 파일로 남김
+    buffer_dir: Option<PathBuf>,
+    /// 모든 세션이 공유하는 메시지 내용 소스
+    source: SharedSource,
+}
+
+impl SessionRegistry {
+    pub fn new(max_messages: u64, clock: SharedClock) -> Self {
+        Self {
+            max_messages,
+            topic_prefix: format!("cancel-server-{}", clock.unix_millis()),
+            clock,
+            sessions: Mutex::new(HashMap::new()),
+            anonymous_count: AtomicU64::new(0),
+            buffer_dir: None,
+            source: Arc::new(CounterSource::default()),
+        }
+    }
+
+    /// 새 세션의 생성기가 메시지 내용을 `source`에서 만듦 (기본은 `CounterSource`)
+    pub fn with_source(mut self, source: SharedSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// 세션 id가 있는 세션의 재연결 버퍼를 `dir/{세션 id}.jsonl`에 남김
+    ///
+    /// 같은 세션 id로 다시 열면(서버 재시작 후 포함) 남은 메시지를 불러와 이어서 보냄.
+    pub fn with_buffer_dir(mut self, dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        self.buffer_dir = Some(dir);
+        Ok(self)
+    }
+
+    /// 세션 id가 있으면 기존 생성기를 이어받고, 없으면 이 연결만의 생성기를 만듦
+    ///
+    /// `max_messages`는 새로 만드는 생성기에만 적용 - 이어받은 세션은 처음 정한 개수를 유지.
+    pub fn open(&self, session_id: Option<&str>, max_messages: Option<u64>) -> Session {
+        let Some(id) = session_id else {
+            let id = format!("conn-{}", self.anonymous_count.fetch_add(1, Ordering::Relaxed) + 1);
+            let topic = format!("{}-{}", self.topic_prefix, id);
+            return self.create(id, topic, ResumeBuffer::default(), max_messages);
+        };
+
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get_mut(id) {
+            session.connection += 1;
+            return session.clone();
+        }
+        // 클라이언트가 보낸 값이므로 브로커 토픽 이름에 쓸 수 없는 문자는 치환
+        let topic_id: String = id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let topic = format!("{}-session-{}", self.topic_prefix, topic_id);
+        let session = self.create(id.to_string(), topic, self.load_buffer(id), max_messages);
+        sessions.insert(id.to_string(), session.clone());
+        session
+    }
+
+    /// 더 이어받을 일이 없는 세션 정리 (정상 완료 또는 의도적 취소) - 디스크 버퍼도 삭제
+    pub fn close(&self, id: &str) {
+        let removed = self.sessions.lock().unwrap().remove(id);
+        if let Some(session) = removed {
+            session.history.lock().unwrap().discard();
+        } else if let Some(path) = self.buffer_path(id) {
+            // 만료된 세션의 파일이 남아 있을 수 있음
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// `connection`번째 연결 이후 재연결이 없었으면 세션 정리 - 정리했으면 true
+    ///
+    /// 메모리에서만 지우고 디스크 버퍼는 남겨, 더 늦게 같은 세션 id로 돌아와도 이어갈 수 있음.
+    pub fn expire(&self, id: &str, connection: u64) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.get(id).is_some_and(|session| session.connection == connection) {
+            sessions.remove(id);
+            return true;
+        }
+        false
+    }
+
+    /// 재연결을 기다리는 세션 수
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn create(&self, id: String, topic: String, history: ResumeBuffer, max_messages: Option<u64>) -> Session {
+        let max_messages = max_messages.unwrap_or(self.max_messages);
+        // 불러온 버퍼의 메시지는 이미 생성된 것이므로 그 다음 id부터 생성
+        let generator = MessageGenerator::starting_after(max_messages, self.clock.clone(), history.last_id().unwrap_or(0))
+            .with_source(self.source.clone());
+        Session {
+            topic,
+            history: Arc::new(Mutex::new(history)),
+            connection: 1,
+            generator,
+            id,
+        }
+    }
+
+    /// `--buffer-dir` 아래 세션 파일 - 파일 이름에 쓸 수 없는 문자는 `%XX`로 바꿔 세션끼리 겹치지 않게 함
+    fn buffer_path(&self, id: &str) -> Option<PathBuf> {
+        let dir = self.buffer_dir.as_ref()?;
+        let name: String = id
+            .bytes()
+            .map(|b| match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        Some(dir.join(format!("{}.jsonl", name)))
+    }
+
+    fn load_buffer(&self, id: &str) -> ResumeBuffer {
+        let Some(path) = self.buffer_path(id) else {
+            return ResumeBuffer::default();
+        };
+        ResumeBuffer::persistent(path.clone()).unwrap_or_else(|e| {
+            warn!(path = %path.display(), error = %e, "Failed to open reconnection buffer - keeping it in memory only");
+            ResumeBuffer::default()
+        })
+    }
+}
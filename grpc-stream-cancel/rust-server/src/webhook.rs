@@ -0,0 +1,60 @@
+//! 스트림의 `GrpcContext`가 취소될 때 외부 HTTP 엔드포인트에 알림 (`--webhook-url`)
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, warn, Instrument};
+
+/// 요청 하나의 제한 시간 - 받는 쪽이 느려도 알림이 쌓이지 않게 함
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 웹훅으로 POST하는 JSON 본문
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancellationEvent {
+    pub stream_id: u64,
+    /// 서버 재시작 뒤에도 겹치지 않는 스트림 id - 여러 실행을 모을 때의 키
+    pub stream_uuid: String,
+    pub session_id: String,
+    /// context에 남은 취소 사유 원문
+    pub reason: String,
+    /// 사유 분류 (`intentional_cancellation`, `network_disconnection`, `normal_completion` 등 메트릭과 같은 값)
+    pub kind: String,
+    /// 취소 시점까지 생성한 메시지 수
+    pub generated: u64,
+    pub max_messages: u64,
+    /// 취소 시각 (unix ms)
+    pub timestamp_ms: u64,
+}
+
+/// 취소 이벤트를 `url`로 보내는 클라이언트 - 실패해도 재시도하지 않고 로그만 남김
+#[derive(Clone)]
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Webhook {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 백그라운드 태스크로 POST - 스트림 정리는 응답을 기다리지 않음
+    pub fn notify(&self, event: CancellationEvent) {
+        let request = self.client.post(&self.url).json(&event).timeout(WEBHOOK_TIMEOUT);
+        tokio::spawn(
+            async move {
+                match request.send().await.and_then(|response| response.error_for_status()) {
+                    Ok(response) => debug!(status = %response.status(), "Cancellation webhook delivered"),
+                    Err(e) => warn!(error = %e, "Cancellation webhook failed"),
+                }
+            }
+            .in_current_span(),
+        );
+    }
+}
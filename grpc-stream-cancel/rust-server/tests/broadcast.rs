@@ -0,0 +1,96 @@
+//! `--broadcast` 모드 - 생성기 하나를 여러 스트림이 나눠 받을 때의 lag/취소 집계 (tokio 일시정지 시간)
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_server::broadcast::{Broadcaster, LAGGED_TRAILER};
+use grpc_stream_server::generator::MessageGenerator;
+use grpc_stream_server::server::{MessageStream, MESSAGES_SENT_TRAILER};
+use grpc_stream_server::streaming::DataMessage;
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tonic::{Code, Status};
+
+/// 1초마다 `max_messages`개를 보내고, 수신자마다 `capacity`개까지 뒤처질 수 있는 브로드캐스트
+fn broadcaster(max_messages: u64, capacity: usize, stats: Arc<ServerStats>) -> Broadcaster {
+    let generator = MessageGenerator::new(max_messages, Arc::new(TokioClock::new()));
+    Broadcaster::start(generator, 1.into(), capacity, stats, CancellationToken::new())
+}
+
+/// 받은 메시지 id와 마지막 상태
+async fn drain(mut out: MessageStream) -> (Vec<u64>, Status) {
+    let mut ids = Vec::new();
+    loop {
+        match out.next().await.unwrap() {
+            Ok(message) => ids.push(message.id),
+            Err(status) => return (ids, status),
+        }
+    }
+}
+
+fn trailer<'a>(status: &'a Status, key: &str) -> &'a str {
+    status.metadata().get(key).unwrap().to_str().unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn lagging_receiver_skips_to_the_oldest_buffered_message() {
+    let stats = Arc::new(ServerStats::default());
+    let broadcaster = broadcaster(6, 2, stats.clone());
+    let fast = tokio::spawn(drain(broadcaster.subscribe(1, tokio_stream::empty())));
+    let slow = broadcaster.subscribe(2, tokio_stream::empty());
+
+    // 느린 클라이언트는 생성기가 다 보낼 때까지 읽지 않음 - 채널에는 마지막 2개만 남음
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    let (slow_ids, slow_status) = drain(slow).await;
+    let (fast_ids, fast_status) = fast.await.unwrap();
+
+    assert_eq!(fast_ids, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(trailer(&fast_status, LAGGED_TRAILER), "0");
+    assert_eq!(slow_ids, vec![5, 6]);
+    assert_eq!(slow_status.code(), Code::Ok);
+    assert_eq!(trailer(&slow_status, LAGGED_TRAILER), "4");
+    assert_eq!(trailer(&slow_status, MESSAGES_SENT_TRAILER), "2");
+    // 생성기는 느린 클라이언트를 기다리지 않고 6개만 만듦
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 6);
+    assert_eq!(stats.lagged_messages.load(Ordering::Relaxed), 4);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn client_cancellation_and_disconnection_are_recorded_per_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let broadcaster = broadcaster(100, 10, stats.clone());
+
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut cancelled = broadcaster.subscribe(1, ReceiverStream::new(client_rx));
+    let mut dropped = broadcaster.subscribe(2, tokio_stream::empty());
+    for expected in 1..=2 {
+        assert_eq!(cancelled.next().await.unwrap().unwrap().id, expected);
+        assert_eq!(dropped.next().await.unwrap().unwrap().id, expected);
+    }
+
+    client_tx.send(Err(Status::cancelled("Cancelled by client"))).await.unwrap();
+    assert!(cancelled.next().await.is_none());
+    drop(dropped);
+
+    let cancellations = stats.cancellations();
+    assert_eq!(cancellations.get("intentional_cancellation"), Some(&1));
+    assert_eq!(cancellations.get("network_disconnection"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn subscribing_after_the_broadcast_ended_closes_immediately() {
+    let stats = Arc::new(ServerStats::default());
+    let broadcaster = broadcaster(2, 4, stats.clone());
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    let (ids, status) = drain(broadcaster.subscribe(1, tokio_stream::empty())).await;
+    assert!(ids.is_empty());
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "0");
+}
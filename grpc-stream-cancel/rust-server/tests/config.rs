@@ -0,0 +1,111 @@
+//! `--config` 파일 파싱과 reload - 바뀐 설정은 새 스트림에만 적용됨
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_server::config::{self, Config, StreamSettings};
+use grpc_stream_server::fault::{DelayEvery, FaultInjection};
+use grpc_stream_server::interval::MessageInterval;
+use grpc_stream_server::server::{MessageStream, StreamingServer};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::sync::watch;
+use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+fn cli() -> StreamSettings {
+    StreamSettings {
+        message_interval: MessageInterval::Fixed(Duration::from_secs(2)),
+        max_messages: 10,
+        queue_capacity: 10,
+        faults: FaultInjection::default(),
+    }
+}
+
+async fn ids(mut out: MessageStream) -> Vec<u64> {
+    let mut ids = Vec::new();
+    while let Some(Ok(message)) = out.next().await {
+        ids.push(message.id);
+    }
+    ids
+}
+
+#[test]
+fn file_values_override_cli_values_and_missing_keys_keep_them() {
+    let config = Config::parse(
+        r#"
+        message_interval = "500ms"
+        channel_capacity = 4
+        inject_delay_every = "3:250"
+        "#,
+    )
+    .unwrap();
+    let settings = config.apply(cli()).unwrap();
+
+    assert_eq!(settings.message_interval, MessageInterval::Fixed(Duration::from_millis(500)));
+    assert_eq!(settings.queue_capacity, 4);
+    assert_eq!(settings.faults.delay_every, Some(DelayEvery { every: 3, delay: Duration::from_millis(250) }));
+    assert_eq!(settings.max_messages, 10);
+    assert_eq!(settings.faults.drop_every, None);
+
+    let seconds = Config::parse("message_interval = 3").unwrap().apply(cli()).unwrap();
+    assert_eq!(seconds.message_interval, MessageInterval::Fixed(Duration::from_secs(3)));
+    assert_eq!(Config::parse("").unwrap().apply(cli()).unwrap(), cli());
+}
+
+#[test]
+fn invalid_values_and_unknown_keys_are_rejected() {
+    for text in ["interval = 2", "max_messages = \"ten\"", "message_interval = [1]"] {
+        let error = Config::parse(text).and_then(|config| config.apply(cli())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", text);
+    }
+    for text in ["message_interval = \"0s\"", "channel_capacity = 0", "inject_drop_every = 0", "inject_delay_every = \"3\""] {
+        let error = Config::parse(text).unwrap().apply(cli()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", text);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn changed_settings_apply_to_new_streams_only() {
+    let server = StreamingServer::new(1, 3, Arc::new(ServerStats::default()), Arc::new(TokioClock::new()));
+    let before = server.open_stream(tokio_stream::pending());
+
+    server.settings().send_modify(|settings| {
+        settings.max_messages = 5;
+        settings.faults.drop_every = Some(2);
+    });
+    let after = server.open_stream(tokio_stream::pending());
+
+    assert_eq!(ids(before).await, vec![1, 2, 3]);
+    assert_eq!(ids(after).await, vec![1, 3, 5]);
+}
+
+#[tokio::test]
+async fn watcher_reloads_the_file_and_keeps_settings_on_errors() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("config-reload");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "max_messages = 20\n").unwrap();
+
+    let settings = watch::Sender::new(cli());
+    assert!(config::apply_file(&path, cli(), &settings).unwrap());
+    let mut changes = settings.subscribe();
+    let shutdown = CancellationToken::new();
+    config::watch(path.clone(), cli(), settings.clone(), shutdown.clone()).unwrap();
+
+    // 잘못된 내용은 무시되고 이전 값이 남음
+    std::fs::write(&path, "max_messages = -1\n").unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(settings.borrow().max_messages, 20);
+
+    std::fs::write(&path, "message_interval = \"250ms\"\n").unwrap();
+    tokio::time::timeout(Duration::from_secs(5), changes.changed()).await.unwrap().unwrap();
+    let reloaded = *settings.borrow();
+    assert_eq!(reloaded.message_interval, MessageInterval::Fixed(Duration::from_millis(250)));
+    // 파일에서 지운 키는 CLI 값으로 돌아감
+    assert_eq!(reloaded.max_messages, 10);
+    shutdown.cancel();
+}
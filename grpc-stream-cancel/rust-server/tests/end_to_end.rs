@@ -0,0 +1,180 @@
+//! 임시 포트에 띄운 tonic 서버와 tonic 클라이언트로 실제 HTTP/2를 거치는 테스트
+//!
+//! 서버와 클라이언트가 같은 런타임에 있어 tokio 시간을 멈춰 두어도 메시지 간격만큼 기다리지 않음.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_server::server::{StreamingServer, MESSAGES_SENT_TRAILER};
+use grpc_stream_server::streaming::streaming_service_client::StreamingServiceClient;
+use grpc_stream_server::streaming::streaming_service_server::StreamingServiceServer;
+use grpc_stream_server::streaming::DataMessage;
+use playground_audit::{AuditEvent, AuditLog, Outcome};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::codec::CompressionEncoding;
+use tonic::transport::{Channel, Server};
+use tonic::{Request, Streaming};
+
+/// 127.0.0.1의 임시 포트에서 `server`를 서비스 - 테스트가 끝나면 런타임과 함께 정리됨
+async fn serve(server: StreamingServer) -> SocketAddr {
+    serve_service(StreamingServiceServer::new(server)).await
+}
+
+/// 압축 설정 등을 마친 서비스를 그대로 띄움
+async fn serve_service(service: StreamingServiceServer<StreamingServer>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(Server::builder().add_service(service).serve_with_incoming(TcpListenerStream::new(listener)));
+    addr
+}
+
+async fn connect(addr: SocketAddr) -> StreamingServiceClient<Channel> {
+    StreamingServiceClient::connect(format!("http://{}", addr)).await.unwrap()
+}
+
+/// BidirectionalStream 호출 - `metadata`는 (키, 값) 목록, 돌려준 sender를 버리면 요청 스트림이 끝남
+async fn open(
+    client: &mut StreamingServiceClient<Channel>,
+    metadata: &[(&'static str, &str)],
+) -> (mpsc::Sender<DataMessage>, Streaming<DataMessage>) {
+    let (requests, request_rx) = mpsc::channel(1);
+    let mut request = Request::new(ReceiverStream::new(request_rx));
+    for (key, value) in metadata {
+        request.metadata_mut().insert(*key, value.parse().unwrap());
+    }
+    (requests, client.bidirectional_stream(request).await.unwrap().into_inner())
+}
+
+fn audit_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 서버가 스트림을 닫았다고 기록할 때까지 기다린 뒤의 감사 이벤트
+async fn events_after_close(path: &Path) -> Vec<AuditEvent> {
+    loop {
+        let events: Vec<_> = playground_audit::read(path)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        if matches!(events.last(), Some(AuditEvent::StreamClosed { .. })) {
+            return events;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn normal_completion_ends_the_stream_with_ok() {
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve(StreamingServer::new(2, 3, stats.clone(), Arc::new(TokioClock::new()))).await;
+    let mut client = connect(addr).await;
+
+    let (_requests, mut stream) = open(&mut client, &[]).await;
+    let mut ids = Vec::new();
+    while let Some(message) = stream.message().await.unwrap() {
+        ids.push(message.id);
+    }
+
+    assert_eq!(ids, vec![1, 2, 3]);
+    let trailers = stream.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers.get(MESSAGES_SENT_TRAILER).unwrap(), "3");
+    assert_eq!(stats.cancelled_streams.load(std::sync::atomic::Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropping_the_client_cancels_the_context_as_disconnection() {
+    let path = audit_path("end-to-end-drop.jsonl");
+    let clock = Arc::new(TokioClock::new());
+    let audit = AuditLog::open_file(&path, "cancel-server", clock.clone()).unwrap();
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve(StreamingServer::new(1, 100, stats.clone(), clock).with_audit(audit)).await;
+    let mut client = connect(addr).await;
+
+    let (requests, mut stream) = open(&mut client, &[]).await;
+    for expected in 1..=2 {
+        assert_eq!(stream.message().await.unwrap().unwrap().id, expected);
+    }
+    // 클라이언트를 통째로 버림 - tonic은 요청 스트림이 끝난 뒤에야 RST_STREAM(CANCEL)을 보내므로
+    // 서버는 CANCELLED 상태를 받지 못하고 응답 스트림이 버려진 것으로만 알게 됨
+    drop(requests);
+    drop(stream);
+    drop(client);
+
+    let events = events_after_close(&path).await;
+    assert!(matches!(
+        &events[events.len() - 2],
+        AuditEvent::StreamCancelled { stream: 1, reason } if reason.contains("Network disconnection")
+    ));
+    assert_eq!(events[events.len() - 1], AuditEvent::StreamClosed { stream: 1, outcome: Outcome::Disconnected });
+    assert_eq!(stats.cancellations().get("network_disconnection"), Some(&1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn reconnect_resumes_buffered_messages_after_last_received_id() {
+    let addr = serve(StreamingServer::new(1, 6, Arc::new(ServerStats::default()), Arc::new(TokioClock::new()))).await;
+
+    let mut client = connect(addr).await;
+    let (requests, mut stream) = open(&mut client, &[("x-session-id", "client-a")]).await;
+    for expected in 1..=3 {
+        assert_eq!(stream.message().await.unwrap().unwrap().id, expected);
+    }
+    drop(requests);
+    drop(stream);
+    drop(client);
+
+    // 새 연결에서 2까지만 처리했다고 알림 → 3을 다시 받고 끝까지 이어서 받음
+    let mut client = connect(addr).await;
+    let (_requests, mut resumed) = open(&mut client, &[("x-session-id", "client-a"), ("x-resume-from", "2")]).await;
+    let mut ids = Vec::new();
+    while let Some(message) = resumed.message().await.unwrap() {
+        ids.push(message.id);
+    }
+    assert_eq!(ids, vec![3, 4, 5, 6]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn responses_are_compressed_only_for_clients_that_accept_the_encoding() {
+    let server = StreamingServer::new(1, 2, Arc::new(ServerStats::default()), Arc::new(TokioClock::new()));
+    let addr = serve_service(
+        StreamingServiceServer::new(server)
+            .send_compressed(CompressionEncoding::Zstd)
+            .accept_compressed(CompressionEncoding::Gzip),
+    )
+    .await;
+
+    // zstd를 받겠다고 알리고 요청은 gzip으로 압축해 보냄
+    let mut client = connect(addr)
+        .await
+        .accept_compressed(CompressionEncoding::Zstd)
+        .send_compressed(CompressionEncoding::Gzip);
+    assert_eq!(stream_encoding(&mut client).await.as_deref(), Some("zstd"));
+
+    // grpc-accept-encoding이 없는 클라이언트에는 압축하지 않음
+    let mut plain = connect(addr).await;
+    assert_eq!(stream_encoding(&mut plain).await, None);
+}
+
+/// 메시지 하나를 보내고 끝까지 받은 스트림의 응답 헤더 grpc-encoding
+async fn stream_encoding(client: &mut StreamingServiceClient<Channel>) -> Option<String> {
+    let (requests, request_rx) = mpsc::channel(1);
+    requests.send(DataMessage { id: 1, ..Default::default() }).await.unwrap();
+    let response = client.bidirectional_stream(ReceiverStream::new(request_rx)).await.unwrap();
+    let encoding = response.metadata().get("grpc-encoding").map(|value| value.to_str().unwrap().to_string());
+    let mut stream = response.into_inner();
+    let mut ids = Vec::new();
+    while let Some(message) = stream.message().await.unwrap() {
+        ids.push(message.id);
+    }
+    assert_eq!(ids, vec![1, 2]);
+    drop(requests);
+    encoding
+}
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use grpc_stream_server::fault::{DelayEvery, Fault, FaultInjection};
+
+#[test]
+fn delay_every_parses_n_and_millis() {
+    let delay: DelayEvery = "3:250".parse().unwrap();
+    assert_eq!(delay, DelayEvery { every: 3, delay: Duration::from_millis(250) });
+    assert_eq!(delay.to_string(), "3:250");
+    for invalid in ["3", "0:250", "x:250", "3:soon"] {
+        assert!(invalid.parse::<DelayEvery>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn drop_wins_when_both_faults_hit_the_same_send() {
+    let faults = FaultInjection {
+        drop_every: Some(4),
+        delay_every: Some(DelayEvery { every: 2, delay: Duration::from_millis(100) }),
+        duplicate_every: None,
+    };
+    let applied: Vec<_> = (1..=4).map(|send| faults.fault_for(send)).collect();
+    assert_eq!(applied, vec![None, Some(Fault::Delay(Duration::from_millis(100))), None, Some(Fault::Drop)]);
+    assert_eq!(FaultInjection::default().fault_for(1), None);
+}
+
+#[test]
+fn duplicate_takes_precedence_over_delay_but_not_drop() {
+    let faults = FaultInjection {
+        drop_every: Some(6),
+        delay_every: Some(DelayEvery { every: 2, delay: Duration::from_millis(100) }),
+        duplicate_every: Some(3),
+    };
+    let applied: Vec<_> = (1..=6).map(|send| faults.fault_for(send)).collect();
+    let delay = Some(Fault::Delay(Duration::from_millis(100)));
+    assert_eq!(applied, vec![None, delay, Some(Fault::Duplicate), delay, None, Some(Fault::Drop)]);
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use grpc_stream_server::generator::{CounterSource, FileSource, MessageGenerator, MessageSource, PaddedSource, SourceSpec};
+use playground_clock::TokioClock;
+
+fn fixture(name: &str, contents: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn source_spec_round_trips_through_cli_values() {
+    for spec in [SourceSpec::Counter, SourceSpec::Random(1024), SourceSpec::File(PathBuf::from("msgs.jsonl"))] {
+        assert_eq!(spec.to_string().parse::<SourceSpec>(), Ok(spec));
+    }
+    assert!("random:big".parse::<SourceSpec>().is_err());
+    assert!("file:".parse::<SourceSpec>().is_err());
+    assert!("sine".parse::<SourceSpec>().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn random_source_generates_payloads_of_requested_size() {
+    let generator = MessageGenerator::new(3, Arc::new(TokioClock::new()))
+        .with_source(SourceSpec::Random(1024).open().unwrap());
+
+    let mut ids = Vec::new();
+    while let Some(message) = generator.generate_next().await {
+        assert_eq!(message.payload.len(), 1024);
+        ids.push(message.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn file_source_replays_lines_and_ends_with_the_file() {
+    let path = fixture(
+        "replay.jsonl",
+        "{\"payload\": \"first\"}\n\n{\"id\": 9, \"payload\": \"second\"}\n{\"payload\": \"third\"}\n",
+    );
+    let generator = MessageGenerator::new(10, Arc::new(TokioClock::new()))
+        .with_source(Arc::new(FileSource::load(&path).unwrap()));
+    generator.skip_through(1).await;
+
+    let mut sent = Vec::new();
+    while let Some(message) = generator.generate_next().await {
+        sent.push((message.id, message.payload));
+    }
+    assert_eq!(sent, vec![(2, "second".to_string()), (3, "third".to_string())]);
+    assert_eq!(generator.get_progress().await, (3, 3));
+}
+
+#[test]
+fn file_source_reports_the_malformed_line() {
+    let path = fixture("malformed.jsonl", "{\"payload\": \"ok\"}\nnot json\n");
+    let error = FileSource::load(&path).err().unwrap();
+    assert!(error.to_string().contains("malformed.jsonl:2"), "{}", error);
+}
+
+#[test]
+fn template_fills_placeholders_and_padding_fixes_size() {
+    let counter = CounterSource::default().with_template("Message {id} at {ts} of {max} {unknown}");
+    assert_eq!(counter.next(1, 42, 2).unwrap().payload, "Message 1 at 42 of 2 {unknown}");
+
+    let padded = PaddedSource::new(Arc::new(CounterSource::default().with_template("#{id}")), 8);
+    assert_eq!(padded.next(7, 0, 10).unwrap().payload, "#7......");
+
+    let truncated = PaddedSource::new(Arc::new(CounterSource::default().with_template("é{id}")), 1);
+    assert_eq!(truncated.next(1, 0, 10).unwrap().payload, ".");
+}
@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+use grpc_stream_server::context::parse_grpc_timeout;
+
+#[test]
+fn parses_grpc_timeout_units() {
+    assert_eq!(parse_grpc_timeout("2H"), Some(Duration::from_secs(7200)));
+    assert_eq!(parse_grpc_timeout("3M"), Some(Duration::from_secs(180)));
+    assert_eq!(parse_grpc_timeout("30S"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_grpc_timeout("500m"), Some(Duration::from_millis(500)));
+    assert_eq!(parse_grpc_timeout("99999999u"), Some(Duration::from_micros(99_999_999)));
+    assert_eq!(parse_grpc_timeout("10n"), Some(Duration::from_nanos(10)));
+
+    for invalid in ["", "S", "100", "123456789S", "1.5S", "-1S", "10x", "10é"] {
+        assert_eq!(parse_grpc_timeout(invalid), None, "{:?}", invalid);
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use grpc_stream_server::interval::MessageInterval;
+
+#[test]
+fn interval_parses_seconds_durations_and_ranges() {
+    assert_eq!("2".parse(), Ok(MessageInterval::Fixed(Duration::from_secs(2))));
+    assert_eq!("500ms".parse(), Ok(MessageInterval::Fixed(Duration::from_millis(500))));
+    let jitter: MessageInterval = "1s..3s".parse().unwrap();
+    assert_eq!(jitter, MessageInterval::Jitter { min: Duration::from_secs(1), max: Duration::from_secs(3) });
+    assert_eq!(jitter.to_string(), "1s..3s");
+    assert_eq!("250ms..1s".parse::<MessageInterval>().unwrap().to_string(), "250ms..1s");
+    for invalid in ["0", "0s..1s", "3s..1s", "fast", "1m"] {
+        assert!(invalid.parse::<MessageInterval>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn seeded_jitter_repeats_and_stays_in_range() {
+    let interval: MessageInterval = "1s..3s".parse().unwrap();
+    let delays = |seed| {
+        let mut pacer = interval.pacer(Some(seed));
+        (0..20).map(|_| pacer.next_delay()).collect::<Vec<_>>()
+    };
+
+    let first = delays(7);
+    assert_eq!(first, delays(7));
+    assert_ne!(first, delays(8));
+    assert!(first.iter().all(|delay| (Duration::from_secs(1)..=Duration::from_secs(3)).contains(delay)));
+}
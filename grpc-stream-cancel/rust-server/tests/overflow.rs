@@ -0,0 +1,98 @@
+//! 느린 소비자로 큐를 채웠을 때 `--overflow-policy`별 동작 (tokio 일시정지 시간)
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_server::overflow::OverflowPolicy;
+use grpc_stream_server::server::{MessageStream, StreamingServer, OVERFLOW_REASON};
+use grpc_stream_server::streaming::DataMessage;
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{Code, Status};
+
+/// 1초마다 10개를 만들지만 메시지마다 3초씩 붙잡는 소비자, 큐는 2칸
+fn slow_stream(policy: OverflowPolicy, stats: Arc<ServerStats>) -> (mpsc::Sender<Result<DataMessage, Status>>, MessageStream) {
+    let server = StreamingServer::new(1, 10, stats, Arc::new(TokioClock::new()))
+        .with_queue_capacity(2)
+        .with_slow_consumer(Duration::from_secs(3))
+        .with_overflow_policy(policy);
+    let (client_tx, client_rx) = mpsc::channel(1);
+    (client_tx, server.open_stream(ReceiverStream::new(client_rx)))
+}
+
+/// 받은 메시지 id와 마지막 상태
+async fn drain(mut out: MessageStream) -> (Vec<u64>, Status) {
+    let mut ids = Vec::new();
+    loop {
+        match out.next().await.unwrap() {
+            Ok(message) => ids.push(message.id),
+            Err(status) => return (ids, status),
+        }
+    }
+}
+
+#[test]
+fn overflow_policy_round_trips_through_cli_values() {
+    for policy in [OverflowPolicy::Block, OverflowPolicy::DropOldest, OverflowPolicy::DropNewest, OverflowPolicy::Cancel] {
+        assert_eq!(policy.to_string().parse::<OverflowPolicy>(), Ok(policy));
+    }
+    assert_eq!(OverflowPolicy::default(), OverflowPolicy::Block);
+    assert!("drop".parse::<OverflowPolicy>().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn block_delivers_every_message_late() {
+    let stats = Arc::new(ServerStats::default());
+    let (_client_tx, out) = slow_stream(OverflowPolicy::Block, stats.clone());
+
+    let (ids, status) = drain(out).await;
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+    assert_eq!(stats.dropped_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drop_newest_keeps_the_buffered_messages_and_skips_new_ones() {
+    let stats = Arc::new(ServerStats::default());
+    let (_client_tx, out) = slow_stream(OverflowPolicy::DropNewest, stats.clone());
+
+    let (ids, status) = drain(out).await;
+    assert_eq!(status.code(), Code::Ok);
+    let dropped = stats.dropped_messages.load(Ordering::Relaxed);
+    assert!(dropped > 0);
+    assert_eq!(ids.len() as u64 + dropped, 10);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", ids);
+    // 꺼내 간 1과 큐에 남은 2가 두 칸을 차지하는 동안 만든 3은 버려짐
+    assert_eq!(&ids[..3], &[1, 2, 4]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drop_oldest_replaces_stale_messages_so_the_latest_arrives() {
+    let stats = Arc::new(ServerStats::default());
+    let (_client_tx, out) = slow_stream(OverflowPolicy::DropOldest, stats.clone());
+
+    let (ids, status) = drain(out).await;
+    assert_eq!(status.code(), Code::Ok);
+    let dropped = stats.dropped_messages.load(Ordering::Relaxed);
+    assert!(dropped > 0);
+    assert_eq!(ids.len() as u64 + dropped, 10);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", ids);
+    // 큐에 남은 가장 최신 메시지들이 마지막에 전달됨
+    assert_eq!(&ids[ids.len() - 2..], &[9, 10]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancel_ends_the_stream_with_resource_exhausted() {
+    let stats = Arc::new(ServerStats::default());
+    let (_client_tx, out) = slow_stream(OverflowPolicy::Cancel, stats.clone());
+
+    let (ids, status) = drain(out).await;
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(status.message(), OVERFLOW_REASON);
+    assert!(ids.len() < 10, "{:?}", ids);
+    assert_eq!(stats.cancellations().get("queue_overflow"), Some(&1));
+    assert_eq!(stats.dropped_messages.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use grpc_stream_server::rate_limit::{RateLimit, TokenBucket};
+use tokio::time::Instant;
+
+#[tokio::test(start_paused = true)]
+async fn bucket_sends_burst_then_throttles() {
+    let mut bucket = TokenBucket::new(RateLimit { per_second: 2.0, burst: 3 });
+    let start = Instant::now();
+
+    let mut sent_at = Vec::new();
+    for _ in 0..6 {
+        bucket.acquire().await;
+        sent_at.push(start.elapsed().as_millis());
+    }
+    assert_eq!(sent_at, vec![0, 0, 0, 500, 1000, 1500]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn idle_bucket_refills_up_to_burst() {
+    let mut bucket = TokenBucket::new(RateLimit { per_second: 1.0, burst: 2 });
+    bucket.acquire().await;
+    bucket.acquire().await;
+    assert_eq!(bucket.available(), 0);
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(bucket.available(), 2);
+}
@@ -0,0 +1,57 @@
+//! 요청 메타데이터 로그 대상 선택과 필수 헤더 거절
+
+use grpc_stream_server::request_metadata::{selected, RequestMetadata};
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Code, Request};
+
+fn request(headers: &[(&'static str, &str)]) -> Request<()> {
+    let mut request = Request::new(());
+    for (key, value) in headers {
+        request.metadata_mut().insert(*key, value.parse().unwrap());
+    }
+    request
+}
+
+#[test]
+fn selected_keeps_client_identifying_headers_and_skips_credentials() {
+    let mut metadata = MetadataMap::new();
+    for (key, value) in [
+        ("user-agent", "grpc-java-netty/1.62.2"),
+        ("grpc-timeout", "30S"),
+        ("authorization", "Bearer secret"),
+        ("x-session-id", "abc"),
+        ("content-type", "application/grpc"),
+    ] {
+        metadata.insert(key, value.parse().unwrap());
+    }
+    metadata.insert_bin("x-trace-bin", MetadataValue::from_bytes(&[1, 2, 3]));
+
+    let mut pairs = selected(&metadata);
+    pairs.sort();
+    let pairs: Vec<(&str, &str)> = pairs.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("grpc-timeout", "30S"),
+            ("user-agent", "grpc-java-netty/1.62.2"),
+            ("x-session-id", "abc"),
+            ("x-trace-bin", "<3 bytes>"),
+        ]
+    );
+}
+
+#[test]
+fn missing_required_header_is_rejected_with_invalid_argument() {
+    let mut interceptor = RequestMetadata::new()
+        .with_logging(true)
+        .with_required(["X-Client-Id".to_string()]);
+
+    let status = interceptor.call(request(&[("x-session-id", "abc")])).unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "missing required metadata 'x-client-id'");
+
+    assert!(interceptor.call(request(&[("x-client-id", "go-client")])).is_ok());
+    // 설정이 없으면 모두 통과
+    assert!(RequestMetadata::new().call(request(&[])).is_ok());
+}
@@ -0,0 +1,66 @@
+//! SIGHUP 재시작 - 같은 소켓에서 `Server`를 교체해도 진행 중인 스트림은 끝까지 받음 (tokio 일시정지 시간)
+
+use std::sync::Arc;
+
+use grpc_stream_server::restart::serve_restartable;
+use grpc_stream_server::server::{StreamingServer, MESSAGES_SENT_TRAILER};
+use grpc_stream_server::streaming::streaming_service_client::StreamingServiceClient;
+use grpc_stream_server::streaming::streaming_service_server::StreamingServiceServer;
+use grpc_stream_server::streaming::DataMessage;
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tonic::service::Routes;
+use tonic::transport::{Channel, Server};
+use tonic::Streaming;
+
+async fn open(client: &mut StreamingServiceClient<Channel>) -> (mpsc::Sender<DataMessage>, Streaming<DataMessage>) {
+    let (requests, request_rx) = mpsc::channel(1);
+    (requests, client.bidirectional_stream(ReceiverStream::new(request_rx)).await.unwrap().into_inner())
+}
+
+#[tokio::test(start_paused = true)]
+async fn restart_keeps_in_flight_streams_and_serves_new_connections() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, 3, stats, Arc::new(TokioClock::new()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let (restart_tx, restart_rx) = mpsc::channel(1);
+    let shutdown = CancellationToken::new();
+    let serving = tokio::spawn(serve_restartable(
+        Server::builder(),
+        Routes::new(StreamingServiceServer::new(server)),
+        listener,
+        ReceiverStream::new(restart_rx),
+        shutdown.clone(),
+    ));
+
+    let mut before = StreamingServiceClient::connect(addr.clone()).await.unwrap();
+    let (before_requests, mut in_flight) = open(&mut before).await;
+    assert_eq!(in_flight.message().await.unwrap().unwrap().id, 1);
+
+    restart_tx.send(()).await.unwrap();
+
+    // 새 연결은 같은 포트에서 새 Server가 받음
+    let mut after = StreamingServiceClient::connect(addr).await.unwrap();
+    let (after_requests, mut fresh) = open(&mut after).await;
+    assert_eq!(fresh.message().await.unwrap().unwrap().id, 1);
+
+    // GOAWAY를 받은 연결의 스트림도 RST_STREAM 없이 끝까지 옴
+    let mut ids = vec![1];
+    while let Some(message) = in_flight.message().await.unwrap() {
+        ids.push(message.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3]);
+    let trailers = in_flight.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers.get(MESSAGES_SENT_TRAILER).unwrap(), "3");
+
+    while fresh.message().await.unwrap().is_some() {}
+    // 요청 스트림까지 닫아야 연결이 끝나 graceful shutdown이 마무리됨
+    drop((before_requests, after_requests, in_flight, fresh, before, after));
+    shutdown.cancel();
+    serving.await.unwrap().unwrap();
+}
@@ -0,0 +1,696 @@
+//! tokio 일시정지 시간으로 구동하는 서버 테스트 - 실제 sleep 없이 즉시 끝남
+
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_server::fault::{DelayEvery, FaultInjection};
+use grpc_stream_server::half_close::HalfClosePolicy;
+use grpc_stream_server::rate_limit::RateLimit;
+use grpc_stream_server::server::{
+    MessageStream, StreamRequest, StreamingServer, CANCEL_REASON_TRAILER, DEADLINE_REASON, DURATION_TRAILER,
+    HALF_CLOSE_REASON, MESSAGES_SENT_TRAILER, RECONNECT_TIMEOUT_REASON,
+};
+use playground_audit::{AuditConfig, AuditEvent, AuditLog, Outcome};
+use grpc_stream_server::streaming::streaming_service_server::StreamingService;
+use grpc_stream_server::streaming::{DataMessage, SubscribeRequest};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use prost::Message;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{Code, Request, Status};
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn server(interval_secs: u64, max_messages: u64, stats: Arc<ServerStats>) -> StreamingServer {
+    StreamingServer::new(interval_secs, max_messages, stats, Arc::new(TokioClock::new()))
+}
+
+/// 응답 스트림의 마지막 항목 - 요약 trailer를 담은 상태이고 그 뒤로 스트림이 끝남
+async fn final_status(out: &mut MessageStream) -> Status {
+    let status = out.next().await.unwrap().unwrap_err();
+    assert!(out.next().await.is_none());
+    status
+}
+
+/// 끝까지 받은 메시지 id - 스트림은 OK 상태로 끝나야 함
+async fn message_ids(mut out: MessageStream) -> Vec<u64> {
+    let mut ids = Vec::new();
+    loop {
+        match out.next().await.unwrap() {
+            Ok(message) => ids.push(message.id),
+            Err(status) => {
+                assert_eq!(status.code(), Code::Ok);
+                break;
+            }
+        }
+    }
+    assert!(out.next().await.is_none());
+    ids
+}
+
+fn trailer<'a>(status: &'a Status, key: &str) -> &'a str {
+    status.metadata().get(key).unwrap().to_str().unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn sends_all_messages_at_interval_then_closes() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(2, 5, stats.clone());
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let start = Instant::now();
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let mut received = Vec::new();
+    for _ in 0..5 {
+        received.push(out.next().await.unwrap().unwrap());
+    }
+
+    // 클라이언트 half-close 후 서버가 스트림을 정상 종료 - 요약은 trailer로
+    drop(client_tx);
+    let status = final_status(&mut out).await;
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "5");
+    assert_eq!(trailer(&status, CANCEL_REASON_TRAILER), "All messages sent - normal completion");
+    assert_eq!(trailer(&status, DURATION_TRAILER), "10000");
+
+    let ids: Vec<u64> = received.iter().map(|m| m.id).collect();
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+    for pair in received.windows(2) {
+        assert_eq!(pair[1].timestamp - pair[0].timestamp, 2);
+    }
+    // 5번째 메시지 후 한 번 더 대기한 뒤 완료 감지
+    assert_eq!(start.elapsed(), Duration::from_secs(10));
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn client_cancel_stops_generator() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+    let (client_tx, client_rx) = mpsc::channel(1);
+    let start = Instant::now();
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    for expected in 1..=3 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+
+    client_tx.send(Err(Status::cancelled("client cancel"))).await.unwrap();
+    assert_eq!(final_status(&mut out).await.code(), Code::Ok);
+
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.cancelled_streams.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.cancellations().get("intentional_cancellation"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn audit_log_records_sends_and_cancellation() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cancel-server-audit");
+    let _ = std::fs::remove_dir_all(&dir);
+    let clock = Arc::new(TokioClock::new());
+    let audit = AuditLog::open(AuditConfig::new(&dir, "cancel-server", "test"), clock.clone()).unwrap();
+    let server = StreamingServer::new(1, 100, Arc::new(ServerStats::default()), clock).with_audit(audit.clone());
+    let (client_tx, client_rx) = mpsc::channel(1);
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    for _ in 0..2 {
+        out.next().await.unwrap().unwrap();
+    }
+    client_tx.send(Err(Status::cancelled("client cancel"))).await.unwrap();
+    assert_eq!(final_status(&mut out).await.code(), Code::Ok);
+
+    let events: Vec<_> = playground_audit::read(&audit.path().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|record| record.event)
+        .collect();
+    assert_eq!(events[0], AuditEvent::StreamOpened { stream: 1 });
+    assert_eq!(events[1], AuditEvent::MessageSent { stream: 1, id: 1, attempt: 1 });
+    assert_eq!(events[2], AuditEvent::MessageSent { stream: 1, id: 2, attempt: 1 });
+    assert!(matches!(
+        &events[events.len() - 2],
+        AuditEvent::StreamCancelled { stream: 1, reason } if reason.contains("gRPC standard cancellation")
+    ));
+    assert_eq!(
+        events[events.len() - 1],
+        AuditEvent::StreamClosed { stream: 1, outcome: Outcome::Cancelled }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn half_close_finish_sends_buffered_messages_then_ends_ok() {
+    let dir = fresh_dir("cancel-server-half-close");
+    let clock = Arc::new(TokioClock::new());
+    let audit = AuditLog::open(AuditConfig::new(&dir, "cancel-server", "test"), clock.clone()).unwrap();
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(1, 100, stats.clone(), clock).with_audit(audit.clone());
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let request = StreamRequest::default().with_half_close(HalfClosePolicy::Finish);
+    let mut out = server.open_session_stream(request, ReceiverStream::new(client_rx));
+    for expected in 1..=2 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    // 오류 없이 요청 스트림만 닫음 (END_STREAM)
+    drop(client_tx);
+
+    let mut ids = Vec::new();
+    let status = loop {
+        match out.next().await.unwrap() {
+            Ok(message) => ids.push(message.id),
+            Err(status) => break status,
+        }
+    };
+    assert!(ids.len() <= 1, "{:?}", ids);
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(trailer(&status, CANCEL_REASON_TRAILER), HALF_CLOSE_REASON);
+    assert_eq!(stats.cancellations().get("client_half_closed"), Some(&1));
+
+    let events = playground_audit::read(&audit.path().unwrap()).unwrap();
+    assert_eq!(
+        events.last().unwrap().event,
+        AuditEvent::StreamClosed { stream: 1, outcome: Outcome::HalfClosed }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn half_close_keep_streams_to_completion() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 3, stats.clone());
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    drop(client_tx);
+
+    let request = StreamRequest::default().with_half_close(HalfClosePolicy::Keep);
+    let out = server.open_session_stream(request, ReceiverStream::new(client_rx));
+    assert_eq!(message_ids(out).await, vec![1, 2, 3]);
+    assert!(stats.cancellations().is_empty());
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+
+    assert_eq!("finish".parse::<HalfClosePolicy>(), Ok(HalfClosePolicy::Finish));
+    assert_eq!(HalfClosePolicy::Keep.to_string(), "keep");
+    assert!("close".parse::<HalfClosePolicy>().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn connections_without_session_have_own_generators() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 3, stats.clone());
+    let (_first_tx, first_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let (_second_tx, second_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let first = server.open_stream(ReceiverStream::new(first_rx));
+    let second = server.open_stream(ReceiverStream::new(second_rx));
+    let first = message_ids(first).await;
+    let second = message_ids(second).await;
+
+    assert_eq!(first, vec![1, 2, 3]);
+    assert_eq!(second, vec![1, 2, 3]);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn max_messages_metadata_overrides_server_default_per_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 3, stats.clone());
+
+    let mut request = Request::new(SubscribeRequest::default());
+    request.metadata_mut().insert("x-max-messages", "5".parse().unwrap());
+    let requested = server.server_stream(request).await.unwrap().into_inner();
+    let mut invalid = Request::new(SubscribeRequest::default());
+    invalid.metadata_mut().insert("x-max-messages", "many".parse().unwrap());
+    let fallback = server.server_stream(invalid).await.unwrap().into_inner();
+    let shorter = server.open_session_stream(StreamRequest::default().with_max_messages(1), tokio_stream::empty());
+
+    assert_eq!(message_ids(requested).await, vec![1, 2, 3, 4, 5]);
+    assert_eq!(message_ids(fallback).await, vec![1, 2, 3]);
+    assert_eq!(message_ids(shorter).await, vec![1]);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn subscribe_topics_fans_independent_generators_into_one_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 3, stats.clone());
+
+    let topics = ["orders", "prices", "orders"].map(String::from).to_vec();
+    let mut out = server
+        .server_stream(Request::new(SubscribeRequest { topics, ..SubscribeRequest::default() }))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut received = Vec::new();
+    let status = loop {
+        match out.next().await.unwrap() {
+            Ok(message) => received.push((message.topic, message.id)),
+            Err(status) => break status,
+        }
+    };
+    assert!(out.next().await.is_none());
+
+    // 토픽마다 id 1부터 따로 세고, 중복 구독한 토픽은 한 번만 받음
+    for topic in ["orders", "prices"] {
+        let ids: Vec<_> = received.iter().filter(|(t, _)| t == topic).map(|(_, id)| *id).collect();
+        assert_eq!(ids, vec![1, 2, 3], "{}", topic);
+    }
+    assert_eq!(received.len(), 6);
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "6");
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 2);
+
+    let empty = vec!["orders".to_string(), String::new()];
+    let rejected = server.server_stream(Request::new(SubscribeRequest { topics: empty, ..SubscribeRequest::default() })).await;
+    assert_eq!(rejected.err().unwrap().code(), Code::InvalidArgument);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reconnect_with_same_session_resumes_generator() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut out = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    for expected in 1..=2 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    // 응답 스트림을 버림 = 네트워크 단절
+    drop(out);
+
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut resumed = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    assert!(resumed.next().await.unwrap().unwrap().id > 2);
+
+    // 다른 세션은 처음부터
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut other = server.open_session_stream(StreamRequest::session("client-b"), ReceiverStream::new(client_rx));
+    assert_eq!(other.next().await.unwrap().unwrap().id, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn resume_replays_messages_after_last_received_id() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut out = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    for expected in 1..=3 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    drop(out);
+
+    // 클라이언트는 1까지만 처리했다고 알림 → 2, 3을 다시 받고 이어서 새 메시지
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut resumed = server.open_session_stream(StreamRequest::session("client-a").with_resume_from(1), ReceiverStream::new(client_rx));
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        ids.push(resumed.next().await.unwrap().unwrap().id);
+    }
+    assert_eq!(ids, vec![2, 3, 4]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn resume_on_unknown_session_skips_delivered_ids() {
+    let server = server(1, 10, Arc::new(ServerStats::default()));
+
+    // 서버가 재시작되어 세션 기록이 없어도 이미 받은 id는 다시 만들지 않음
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let resumed = server.open_session_stream(StreamRequest::session("client-a").with_resume_from(7), ReceiverStream::new(client_rx));
+    assert_eq!(message_ids(resumed).await, vec![8, 9, 10]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn session_is_dropped_after_reconnect_timeout() {
+    let server = server(1, 100, Arc::new(ServerStats::default())).with_reconnect_timeout(Duration::from_secs(5));
+
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut out = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    assert_eq!(out.next().await.unwrap().unwrap().id, 1);
+    drop(out);
+
+    // 시간 안에 재연결하면 이어받음
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut resumed = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    assert!(resumed.next().await.unwrap().unwrap().id > 1);
+    drop(resumed);
+
+    // 단절 감지 후 5초가 지나면 세션이 정리되어 처음부터 시작
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let mut fresh = server.open_session_stream(StreamRequest::session("client-a"), ReceiverStream::new(client_rx));
+    assert_eq!(fresh.next().await.unwrap().unwrap().id, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn abandoned_stream_is_torn_down_after_reconnect_timeout() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone())
+        .with_queue_capacity(3)
+        .with_reconnect_timeout(Duration::from_secs(5));
+
+    // 요청 스트림은 바로 끝나도 응답을 계속 읽는 클라이언트는 정리되지 않음
+    let mut out = server.open_session_stream(StreamRequest::session("client-a"), tokio_stream::empty());
+    for expected in 1..=8 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+
+    // 읽기를 멈추면(조용히 멈춘 전송 계층) 큐가 차고 5초 뒤 정리됨
+    tokio::time::sleep(Duration::from_secs(12)).await;
+    assert_eq!(stats.cancellations().get("reconnection_timeout"), Some(&1));
+    assert_eq!(stats.queued_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+    assert!(server.streams().is_empty());
+
+    let status = out.next().await.unwrap().unwrap_err();
+    assert_eq!((status.code(), status.message()), (Code::Unavailable, RECONNECT_TIMEOUT_REASON));
+    assert!(out.next().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_flushes_buffered_messages_then_ends_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = server(1, 100, stats.clone()).with_shutdown(shutdown.clone());
+
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let out = server.open_stream(ReceiverStream::new(client_rx));
+    // 클라이언트가 읽지 않는 동안 큐에 쌓임
+    tokio::time::sleep(Duration::from_millis(4500)).await;
+    shutdown.cancel();
+
+    // 쌓인 메시지는 모두 전달되고, 그 뒤 새 메시지 없이 스트림이 끝남
+    assert_eq!(message_ids(out).await, vec![1, 2, 3, 4, 5]);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(stats.cancelled_streams.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.cancellations().get("server_shutdown"), Some(&1));
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 5);
+    // 큐에 있던 메시지를 모두 보냈으므로 큐 깊이는 0으로 돌아옴
+    assert_eq!(stats.queued_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn deadline_cancels_stream_with_deadline_exceeded() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let start = Instant::now();
+
+    let mut out = server.open_session_stream(StreamRequest::default().with_deadline(Duration::from_millis(2500)), ReceiverStream::new(client_rx));
+    for expected in 1..=3 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    let status = final_status(&mut out).await;
+    assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "3");
+    assert_eq!(trailer(&status, CANCEL_REASON_TRAILER), DEADLINE_REASON);
+    assert_eq!(trailer(&status, DURATION_TRAILER), "2500");
+    assert_eq!(start.elapsed(), Duration::from_millis(2500));
+    // 상태를 보낸 뒤 전송 계층이 요청 스트림을 닫음
+    drop(client_tx);
+
+    // 생성기도 마감 시점에 멈춤
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.cancellations().get("deadline_exceeded"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn admin_lists_and_cancels_streams() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+    let streams = server.streams();
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let request = StreamRequest {
+        peer: Some("127.0.0.1:5000".to_string()),
+        ..StreamRequest::session("client-a")
+    };
+    let mut out = server.open_session_stream(request, ReceiverStream::new(client_rx));
+    for expected in 1..=2 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+
+    let listed = streams.list().await;
+    assert_eq!(listed.len(), 1);
+    assert_eq!((listed[0].stream_id, listed[0].peer.as_str(), listed[0].session_id.as_str()), (1, "127.0.0.1:5000", "client-a"));
+    assert_eq!((listed[0].generated, listed[0].max_messages), (2, 100));
+    assert!(uuid::Uuid::parse_str(&listed[0].stream_uuid).is_ok(), "{}", listed[0].stream_uuid);
+    assert!(!streams.cancel(42, "missing").await);
+
+    // 서버 측 취소 - 클라이언트는 사유와 함께 CANCELLED를 받음
+    assert!(streams.cancel(1, "maintenance").await);
+    let status = out.next().await.unwrap().unwrap_err();
+    assert_eq!((status.code(), status.message()), (tonic::Code::Cancelled, "maintenance"));
+    assert!(out.next().await.is_none());
+    drop(client_tx);
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(streams.is_empty());
+    assert_eq!(stats.cancellations().get("server_cancellation"), Some(&1));
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn server_streaming_cancel_looks_like_disconnection() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone());
+
+    // ServerStream과 같은 구성 - 요청 스트림 없이 응답만
+    let mut out = server.open_session_stream(StreamRequest::session("client-a"), tokio_stream::empty());
+    for expected in 1..=2 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    // 클라이언트 cancel() → RST_STREAM → tonic이 응답 스트림을 버림
+    drop(out);
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(stats.cancellations().get("network_disconnection"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+    assert!(server.streams().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn upload_counts_messages_and_bytes_until_client_closes() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 10, stats.clone());
+    let (client_tx, client_rx) = mpsc::channel(4);
+    let message = DataMessage { id: 1, timestamp: 0, payload: "x".repeat(100), heartbeat: false, topic: String::new() };
+
+    let upload = tokio::spawn(async move { server.receive_upload(ReceiverStream::new(client_rx)).await });
+    for _ in 0..3 {
+        client_tx.send(Ok(message.clone())).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    drop(client_tx);
+
+    let summary = upload.await.unwrap().unwrap();
+    assert_eq!((summary.messages, summary.bytes), (3, 3 * message.encoded_len() as u64));
+    assert_eq!(summary.duration_ms, 1500);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn heartbeats_fill_gaps_between_messages() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(5, 2, stats.clone()).with_heartbeat_interval(Duration::from_secs(2));
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let start = Instant::now();
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let mut received = Vec::new();
+    for _ in 0..4 {
+        let message = out.next().await.unwrap().unwrap();
+        received.push((message.heartbeat, message.id, start.elapsed().as_secs()));
+    }
+
+    // 데이터가 없는 5초 동안 2초마다 heartbeat, 데이터가 오면 다시 2초를 셈
+    assert_eq!(received, vec![(false, 1, 0), (true, 0, 2), (true, 0, 4), (false, 2, 5)]);
+    assert_eq!(stats.messages_sent.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limit_bursts_then_paces_messages() {
+    let stats = Arc::new(ServerStats::default());
+    // message_interval(10초)는 속도 제한이 있으면 쓰이지 않음
+    let server = server(10, 6, stats.clone()).with_rate_limit(RateLimit { per_second: 1.0, burst: 3 });
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let start = Instant::now();
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let mut received_at = Vec::new();
+    for expected in 1..=6 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+        received_at.push(start.elapsed().as_secs());
+    }
+    assert_eq!(received_at, vec![0, 0, 0, 1, 2, 3]);
+
+    drop(client_tx);
+    assert_eq!(final_status(&mut out).await.code(), Code::Ok);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn max_streams_rejects_with_resource_exhausted_until_a_slot_frees() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone()).with_max_streams(2);
+    let subscribe = || Request::new(SubscribeRequest::default());
+
+    let first = server.server_stream(subscribe()).await.unwrap().into_inner();
+    let _second = server.server_stream(subscribe()).await.unwrap().into_inner();
+    let Err(status) = server.server_stream(subscribe()).await else {
+        panic!("third stream should be rejected");
+    };
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 2);
+
+    // 응답 스트림이 버려지면 자리가 반납되어 다시 열 수 있음
+    drop(first);
+    let mut third = server.server_stream(subscribe()).await.unwrap().into_inner();
+    assert_eq!(third.next().await.unwrap().unwrap().id, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn buffered_messages_survive_server_restart() {
+    let dir = fresh_dir("restart-buffer");
+    let first = server(1, 10, Arc::new(ServerStats::default())).with_buffer_dir(&dir).unwrap();
+    let mut out = first.open_session_stream(StreamRequest::session("client/a"), tokio_stream::empty());
+    for expected in 1..=3 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    // 4, 5번은 생성되어 큐에 있지만 클라이언트가 받기 전에 서버가 죽음
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    drop(out);
+    drop(first);
+    assert!(dir.join("client%2Fa.jsonl").exists());
+
+    let stats = Arc::new(ServerStats::default());
+    let restarted = server(1, 10, stats.clone()).with_buffer_dir(&dir).unwrap();
+    let request = StreamRequest::session("client/a").with_resume_from(3);
+    let mut out = restarted.open_session_stream(request, tokio_stream::empty());
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        ids.push(out.next().await.unwrap().unwrap().id);
+    }
+    assert_eq!(ids, vec![4, 5, 6]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn buffer_dir_is_rejected_once_a_stream_holds_the_sessions() {
+    let server = server(1, 10, Arc::new(ServerStats::default()));
+    let mut out = server.open_session_stream(StreamRequest::session("client-c"), tokio_stream::empty());
+    assert_eq!(out.next().await.unwrap().unwrap().id, 1);
+
+    let error = server.with_buffer_dir(fresh_dir("late-buffer")).err().unwrap();
+    assert!(error.to_string().contains("before any stream"), "{}", error);
+}
+
+#[tokio::test(start_paused = true)]
+async fn completed_session_removes_buffer_file() {
+    let dir = fresh_dir("completed-buffer");
+    let server = server(1, 2, Arc::new(ServerStats::default())).with_buffer_dir(&dir).unwrap();
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let mut out = server.open_session_stream(StreamRequest::session("client-b"), ReceiverStream::new(client_rx));
+    for expected in 1..=2 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    assert!(dir.join("client-b.jsonl").exists());
+
+    drop(client_tx);
+    assert_eq!(final_status(&mut out).await.code(), Code::Ok);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(!dir.join("client-b.jsonl").exists());
+}
+
+#[tokio::test(start_paused = true)]
+async fn injected_faults_drop_and_delay_every_nth_send() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 6, stats.clone()).with_fault_injection(FaultInjection {
+        drop_every: Some(3),
+        delay_every: Some(DelayEvery { every: 2, delay: Duration::from_millis(500) }),
+        duplicate_every: None,
+    });
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+    let start = Instant::now();
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let mut received = Vec::new();
+    for _ in 0..4 {
+        let message = out.next().await.unwrap().unwrap();
+        received.push((message.id, start.elapsed().as_millis()));
+    }
+    // 3, 6번째는 버려지고(6번째는 지연보다 버림이 우선) 2, 4번째는 500ms 늦게 도착
+    assert_eq!(received, vec![(1, 0), (2, 1500), (4, 4000), (5, 5000)]);
+    let status = final_status(&mut out).await;
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "4");
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn injected_duplicates_repeat_every_nth_message_with_the_same_id() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 6, stats.clone()).with_fault_injection(FaultInjection {
+        duplicate_every: Some(3),
+        ..FaultInjection::default()
+    });
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let mut ids = Vec::new();
+    for _ in 0..8 {
+        ids.push(out.next().await.unwrap().unwrap().id);
+    }
+    assert_eq!(ids, vec![1, 2, 3, 3, 4, 5, 6, 6]);
+    let status = final_status(&mut out).await;
+    assert_eq!(status.code(), Code::Ok);
+    assert_eq!(trailer(&status, MESSAGES_SENT_TRAILER), "8");
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_unary_replies_after_delay_and_records_dropped_calls() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 3, stats.clone());
+
+    let started = Instant::now();
+    let message = server.slow_unary(Duration::from_millis(1500)).await.unwrap();
+    assert_eq!((message.payload.as_str(), started.elapsed()), ("Slept 1500ms", Duration::from_millis(1500)));
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+
+    // 클라이언트 취소 = tonic이 핸들러 future를 버림
+    let dropped = tokio::time::timeout(Duration::from_secs(1), server.slow_unary(Duration::from_secs(30))).await;
+    assert!(dropped.is_err());
+    assert_eq!(stats.cancellations().get("unary_cancelled"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_consumer_fills_the_queue_and_holds_back_the_generator() {
+    let stats = Arc::new(ServerStats::default());
+    let server = server(1, 100, stats.clone())
+        .with_queue_capacity(3)
+        .with_slow_consumer(Duration::from_secs(3));
+    let (_client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let start = Instant::now();
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    for expected in 1..=5 {
+        assert_eq!(out.next().await.unwrap().unwrap().id, expected);
+    }
+    // 메시지마다 3초씩 붙잡으므로 생성 간격(1초)과 상관없이 3초에 하나
+    assert_eq!(start.elapsed(), Duration::from_secs(15));
+    // 생성기는 15초 동안 15개가 아니라 보낸 5개 + 큐 3칸만큼만 만들고 기다림
+    assert_eq!(stats.messages_generated.load(Ordering::Relaxed), 8);
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use grpc_stream_server::server::StreamingServer;
+use grpc_stream_server::streaming::DataMessage;
+use grpc_stream_server::webhook::{CancellationEvent, Webhook};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::Status;
+
+/// HTTP 요청 하나를 받아 204로 답하고 본문을 돌려줌
+async fn receive_post(listener: &TcpListener) -> (String, Vec<u8>) {
+    let (mut socket, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    let (head, body_start) = loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+            break (String::from_utf8(request[..end].to_vec()).unwrap(), end + 4);
+        }
+    };
+    let length: usize = head
+        .lines()
+        .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse().unwrap()))
+        .unwrap();
+    while request.len() < body_start + length {
+        let n = socket.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+    }
+    socket.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+    (head, request[body_start..body_start + length].to_vec())
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancellation_posts_reason_and_progress() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/cancellations", listener.local_addr().unwrap());
+    let server = StreamingServer::new(1, 10, Arc::new(ServerStats::default()), Arc::new(TokioClock::new()))
+        .with_webhook(Webhook::new(url));
+    let (client_tx, client_rx) = mpsc::channel::<Result<DataMessage, Status>>(1);
+
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    for _ in 0..2 {
+        out.next().await.unwrap().unwrap();
+    }
+    client_tx.send(Err(Status::cancelled("client cancel"))).await.unwrap();
+
+    let (head, body) = receive_post(&listener).await;
+    assert!(head.starts_with("POST /cancellations HTTP/1.1"), "{}", head);
+    let event: CancellationEvent = serde_json::from_slice(&body).unwrap();
+    assert_eq!((event.stream_id, event.session_id.as_str()), (1, "conn-1"));
+    assert_eq!(event.stream_uuid.len(), 36);
+    assert_eq!(event.kind, "intentional_cancellation");
+    assert!(event.reason.contains("gRPC standard cancellation"), "{}", event.reason);
+    assert_eq!(event.max_messages, 10);
+    assert!(event.generated >= 2);
+}
@@ -9,7 +9,7 @@ pkill -f 'grpc-stream-server' 2>/dev/null || true
 # 1. 서버 시작 (프록시 없이 직접 연결)
 echo "🚀 Starting gRPC server..."
 cd rust-server
-cargo run --release 1 &
+cargo run --release -- --message-interval 1 &
 SERVER_PID=$!
 cd ..
 sleep 3
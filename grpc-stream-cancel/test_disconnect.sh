@@ -10,7 +10,7 @@ pkill -f 'rust-proxy' 2>/dev/null || true
 # 1. 서버 시작
 echo "🚀 Starting gRPC server..."
 cd rust-server
-cargo run --release 1 &
+cargo run --release -- --message-interval 1 &
 SERVER_PID=$!
 cd ..
 sleep 3
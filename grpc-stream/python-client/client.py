@@ -1,34 +1,146 @@
 import asyncio
+import contextlib
 import grpc
+import os
 import time
 import random
+import sys
 from asyncio import Queue
 
 import streaming_pb2
 import streaming_pb2_grpc
 
+# OpenTelemetry (선택) - OTEL_EXPORTER_OTLP_ENDPOINT가 설정되면 traceparent를 gRPC 메타데이터로 전달
+try:
+    from opentelemetry import propagate, trace
+    from opentelemetry.exporter.otlp.proto.grpc.trace_exporter import OTLPSpanExporter
+    from opentelemetry.sdk.resources import Resource
+    from opentelemetry.sdk.trace import TracerProvider
+    from opentelemetry.sdk.trace.export import BatchSpanProcessor
+except ImportError:
+    trace = None
+
+
+def init_tracer(service_name):
+    if trace is None or not os.getenv('OTEL_EXPORTER_OTLP_ENDPOINT'):
+        return None
+    provider = TracerProvider(resource=Resource.create({'service.name': service_name}))
+    provider.add_span_processor(BatchSpanProcessor(OTLPSpanExporter()))
+    trace.set_tracer_provider(provider)
+    return trace.get_tracer(service_name)
+
+
+def start_span(tracer, name):
+    if tracer is None:
+        return contextlib.nullcontext()
+    return tracer.start_as_current_span(name)
+
+
+def trace_metadata():
+    """현재 span 컨텍스트를 gRPC 메타데이터로 변환 (트레이싱 비활성 시 빈 리스트)"""
+    if trace is None:
+        return []
+    carrier = {}
+    propagate.inject(carrier)
+    return list(carrier.items())
+
+
+def auth_metadata():
+    """GRPC_AUTH_TOKEN이 있으면 서버 --auth-token/--auth-hmac-secret용 bearer 토큰 메타데이터"""
+    token = os.getenv('GRPC_AUTH_TOKEN')
+    return [('authorization', f'Bearer {token}')] if token else []
+
+
+def open_channel(address):
+    """GRPC_TLS_CA가 있으면 TLS 채널 (GRPC_TLS_CLIENT_CERT/KEY까지 있으면 mTLS), 없으면 평문"""
+    ca_path = os.getenv('GRPC_TLS_CA')
+    if not ca_path:
+        return grpc.aio.insecure_channel(address)
+
+    def read(path):
+        with open(path, 'rb') as f:
+            return f.read()
+
+    cert_path = os.getenv('GRPC_TLS_CLIENT_CERT')
+    key_path = os.getenv('GRPC_TLS_CLIENT_KEY')
+    credentials = grpc.ssl_channel_credentials(
+        root_certificates=read(ca_path),
+        private_key=read(key_path) if key_path else None,
+        certificate_chain=read(cert_path) if cert_path else None,
+    )
+    return grpc.aio.secure_channel(address, credentials)
+
+
+# 서버와 주고받는 Hello의 프로토콜 버전 (Hello가 없던 이전 버전이 1)
+PROTOCOL_VERSION = 2
+# 이 클라이언트가 처리하는 기능 - checksum 검사와 Pause/Resume은 하지 않음
+FEATURES = ['batch', 'ack_up_to', 'nack', 'summary']
+
 
 class StreamingClient:
-    def __init__(self, server_address='[::1]:50051'):
+    def __init__(self, server_address='[::1]:50051', nack_probability=0.0, cumulative_ack=False,
+                 client_id=None, last_acked_id=None, exactly_once=False, features=FEATURES, weight=None):
         self.server_address = server_address
         self.response_queue = Queue()
+        # (channel, id) - 서버가 --channels로 나누면 id가 채널마다 따로 1부터
         self.received_messages = set()
         self.should_simulate_drops = True
         self.drop_probability = 0.1
+        # 손상된 payload를 감지한 것처럼 NACK를 보내는 확률 - 서버는 바로 다시 보냄
+        self.nack_probability = nack_probability
+        # 메시지마다 ACK 대신 0.5초마다 빠짐없이 받은 마지막 id까지 AckUpTo 한 번
+        self.cumulative_ack = cumulative_ack
+        # 채널마다 누적 ACK를 보낸 마지막 id
+        self.acked_up_to = {}
+        # 같은 client id로 재연결하면서 마지막으로 ACK한 id를 알려주면 서버가 그 다음부터 이어서 보냄
+        self.client_id = client_id
+        self.last_acked_id = last_acked_id
+        # 이미 처리한 id의 중복 전달은 처리하지 않고 ACK만 다시 보내며, 서버의 dedup 계약 위반을 기록
+        self.exactly_once = exactly_once
+        self.last_delivery = {}
+        self.duplicates = 0
+        self.violations = []
+        # 스트림을 열면 먼저 Hello로 알릴 기능 - None이면 Hello를 보내지 않는 이전 버전 클라이언트처럼 동작
+        self.features = features
+        # 서버 --fair-share-rate에서 전송 슬롯을 나눠 받는 가중치 (x-stream-weight, 없으면 서버 기본값 1)
+        self.weight = weight
+        self.tracer = init_tracer('grpc-stream-client')
+
+    def session_metadata(self):
+        """서버가 pending 큐를 클라이언트별로 두고 재연결 시 이어서 보내도록 x-client-id/x-last-acked-id 메타데이터"""
+        metadata = []
+        if self.client_id:
+            metadata.append(('x-client-id', self.client_id))
+        if self.last_acked_id is not None:
+            metadata.append(('x-last-acked-id', str(self.last_acked_id)))
+        if self.weight is not None:
+            metadata.append(('x-stream-weight', str(self.weight)))
+        return metadata
 
     async def bidirectional_stream(self):
-        async with grpc.aio.insecure_channel(self.server_address) as channel:
+        with start_span(self.tracer, 'bidirectional_stream'):
+            await self._bidirectional_stream()
+
+    async def _bidirectional_stream(self):
+        async with open_channel(self.server_address) as channel:
             stub = streaming_pb2_grpc.StreamingServiceStub(channel)
             
             # 스트림 종료를 위한 플래그
             stream_finished = asyncio.Event()
             
             async def request_generator():
+                if self.features is not None:
+                    yield streaming_pb2.StreamMessage(hello=streaming_pb2.HelloMessage(
+                        protocol_version=PROTOCOL_VERSION, features=self.features))
+                    print(f"[PYTHON CLIENT] Sent Hello with features {self.features}")
                 while not stream_finished.is_set():
                     try:
                         ack_msg = await asyncio.wait_for(self.response_queue.get(), timeout=0.5)
                         yield ack_msg
                     except asyncio.TimeoutError:
+                        if self.cumulative_ack:
+                            for ack_up_to in self.take_cumulative_acks():
+                                yield ack_up_to
                         # 타임아웃 시에도 스트림이 종료되지 않았다면 계속 대기
                         continue
                     except Exception as e:
@@ -37,52 +149,228 @@ class StreamingClient:
                 print("[PYTHON CLIENT] Request generator finished")
 
             try:
-                call = stub.BidirectionalStream(request_generator())
+                call = stub.BidirectionalStream(request_generator(), metadata=trace_metadata() + auth_metadata() + self.session_metadata())
                 
                 async for response in call:
                     if response.HasField('data'):
                         data_msg = response.data
                         message_id = data_msg.id
+                        channel = data_msg.channel
                         
-                        print(f"[PYTHON CLIENT] Received message {message_id}: {data_msg.payload}")
+                        print(f"[PYTHON CLIENT] Received message {message_id}: {data_msg.payload} "
+                              f"(channel {channel}, epoch {data_msg.epoch}, attempt {data_msg.delivery_attempt}, "
+                              f"priority {data_msg.priority})")
+                        if data_msg.deadline_unix_ms and time.time() * 1000 > data_msg.deadline_unix_ms:
+                            # 서버 --message-ttl 기한이 지난 전달 - 서버는 더 재전송하지 않음
+                            print(f"[PYTHON CLIENT] Message {message_id} arrived past its deadline")
+
+                        if self.exactly_once and not self.check_delivery(data_msg):
+                            # 이미 처리한 메시지 - 처리하지 않고 ACK만 다시 (누적 ACK는 이미 포함)
+                            if not self.cumulative_ack:
+                                await self.response_queue.put(streaming_pb2.StreamMessage(
+                                    ack=streaming_pb2.AckMessage(ack_id=message_id, timestamp=int(time.time()), channel=channel)
+                                ))
+                            continue
                         
                         should_drop = (self.should_simulate_drops and 
                                      random.random() < self.drop_probability)
                         
                         if should_drop:
                             print(f"[PYTHON CLIENT] Simulating drop for message {message_id}")
+                        elif random.random() < self.nack_probability:
+                            nack = streaming_pb2.StreamMessage(
+                                nack=streaming_pb2.NackMessage(
+                                    nack_id=message_id,
+                                    reason="simulated corrupt payload",
+                                    channel=channel
+                                )
+                            )
+                            await self.response_queue.put(nack)
+                            print(f"[PYTHON CLIENT] Sent NACK for message {message_id}")
+                        elif self.cumulative_ack:
+                            # 빠진 id가 있으면 재전송으로 채워질 때까지 누적 ACK가 그 앞에서 멈춤
+                            self.received_messages.add((channel, message_id))
                         else:
-                            self.received_messages.add(message_id)
+                            self.received_messages.add((channel, message_id))
                             
                             ack = streaming_pb2.StreamMessage(
                                 ack=streaming_pb2.AckMessage(
                                     ack_id=message_id,
-                                    timestamp=int(time.time())
+                                    timestamp=int(time.time()),
+                                    channel=channel
                                 )
                             )
                             
                             await self.response_queue.put(ack)
                             print(f"[PYTHON CLIENT] Sent ACK for message {message_id}")
                 
+                    elif response.HasField('batch'):
+                        await self.handle_batch(response.batch)
+
+                    elif response.HasField('summary'):
+                        self.check_summary(response.summary)
+
+                    elif response.HasField('hello'):
+                        print(f"[PYTHON CLIENT] Server hello: protocol {response.hello.protocol_version}, "
+                              f"features {list(response.hello.features)}")
+                
                 # 서버 스트림이 종료되면 request_generator도 종료
                 print("[PYTHON CLIENT] Server stream ended, finishing client...")
                 stream_finished.set()
                             
             except grpc.aio.AioRpcError as e:
-                print(f"[PYTHON CLIENT] RPC error: {e}")
+                if e.code() == grpc.StatusCode.UNAUTHENTICATED:
+                    # 인증 실패는 취소/끊김과 달리 다시 연결해도 해결되지 않음
+                    print(f"[PYTHON CLIENT] Authentication failed: {e.details()} (check GRPC_AUTH_TOKEN)")
+                else:
+                    print(f"[PYTHON CLIENT] RPC error: {e}")
                 stream_finished.set()
             except Exception as e:
                 print(f"[PYTHON CLIENT] Unexpected error: {e}")
                 stream_finished.set()
 
+    async def handle_batch(self, batch):
+        """DataBatch - 메시지 하나라도 잃어버리면 batch ACK를 보내지 않으므로 서버가 batch 전체를 다시 보냄"""
+        ids = [data_msg.id for data_msg in batch.messages]
+        print(f"[PYTHON CLIENT] Received batch {batch.batch_id} with messages {ids}")
+        if self.exactly_once:
+            for data_msg in batch.messages:
+                self.check_delivery(data_msg)
+
+        lost = [message_id for message_id in ids
+                if self.should_simulate_drops and random.random() < self.drop_probability]
+        if lost:
+            print(f"[PYTHON CLIENT] Simulating drop of messages {lost} in batch {batch.batch_id}, not acknowledging it")
+            return
+
+        self.received_messages.update((batch.channel, message_id) for message_id in ids)
+        await self.response_queue.put(streaming_pb2.StreamMessage(
+            batch_ack=streaming_pb2.BatchAckMessage(batch_id=batch.batch_id, timestamp=int(time.time()),
+                                                    channel=batch.channel)
+        ))
+        print(f"[PYTHON CLIENT] Sent batch ACK for batch {batch.batch_id}")
+
+    def check_summary(self, summary):
+        """서버가 닫기 직전에 보낸 집계를 받은 기록과 맞춰 봄 - 서버가 ACK로 센 메시지는 모두 받았어야 함 (채널마다 하나)"""
+        print(f"[PYTHON CLIENT] Server summary for channel {summary.channel}: sent {summary.sent}, acked {summary.acked}, "
+              f"retried {summary.retried}, dead-lettered {summary.dead_lettered}, expired {summary.expired}")
+        received = sum(1 for channel, _ in self.received_messages if channel == summary.channel)
+        if summary.acked > received:
+            print(f"[PYTHON CLIENT] Summary mismatch: server counted {summary.acked} ACKs "
+                  f"but only {received} messages were received on channel {summary.channel}")
+
+    def check_delivery(self, data_msg):
+        """dedup 계약 확인 - 처리할 메시지면 True, 이미 처리한 id의 중복 전달이면 False
+
+        같은 id의 (epoch, delivery_attempt)는 전달마다 커져야 하고, --last-acked-id 이하는 다시 오지 않아야 함.
+        """
+        message_id = data_msg.id
+        key = (data_msg.channel, message_id)
+        delivery = (data_msg.epoch, data_msg.delivery_attempt)
+        previous = self.last_delivery.get(key)
+        self.last_delivery[key] = delivery
+        if previous is not None and delivery <= previous:
+            self.violations.append(f"message {key} delivered as {delivery} after {previous}")
+        if self.last_acked_id is not None and message_id <= self.last_acked_id:
+            self.violations.append(f"message {message_id} resent after x-last-acked-id {self.last_acked_id}")
+        if key in self.received_messages:
+            self.duplicates += 1
+            print(f"[PYTHON CLIENT] Duplicate delivery of message {message_id}, skipping processing")
+            return False
+        return True
+
+    def exactly_once_summary(self):
+        for violation in self.violations:
+            print(f"[PYTHON CLIENT] Dedup contract violation: {violation}")
+        print(f"[PYTHON CLIENT] Exactly-once: {len(self.received_messages)} processed, "
+              f"{self.duplicates} duplicates skipped, {len(self.violations)} violations")
+
+    def take_cumulative_acks(self):
+        """채널마다 빠짐없이 받은 마지막 id가 지난번보다 늘었으면 AckUpTo 메시지 하나씩"""
+        acks = []
+        for channel in sorted({channel for channel, _ in self.received_messages}):
+            acked = self.acked_up_to.get(channel, 0)
+            up_to = acked
+            while (channel, up_to + 1) in self.received_messages:
+                up_to += 1
+            if up_to == acked:
+                continue
+            self.acked_up_to[channel] = up_to
+            print(f"[PYTHON CLIENT] Sent cumulative ACK up to message {up_to} on channel {channel}")
+            acks.append(streaming_pb2.StreamMessage(
+                ack_up_to=streaming_pb2.AckUpToMessage(ack_id=up_to, timestamp=int(time.time()), channel=channel)
+            ))
+        return acks
+
+    async def upload(self, count, cancel_after=None):
+        """ClientStream으로 count개를 0.5초 간격으로 올리고 요약을 받음 - cancel_after초가 지나면 업로드 도중 cancel()"""
+        async with open_channel(self.server_address) as channel:
+            stub = streaming_pb2_grpc.StreamingServiceStub(channel)
+
+            async def messages():
+                for i in range(1, count + 1):
+                    yield streaming_pb2.DataMessage(id=i, timestamp=int(time.time()), payload=f"Upload {i} from client")
+                    print(f"[PYTHON CLIENT] Uploaded message {i}/{count}")
+                    await asyncio.sleep(0.5)
+
+            call = stub.ClientStream(messages(), metadata=trace_metadata() + auth_metadata())
+            if cancel_after is not None:
+                asyncio.get_running_loop().call_later(cancel_after, call.cancel)
+            try:
+                summary = await call
+                print(f"[PYTHON CLIENT] Upload summary: {summary.messages} messages, "
+                      f"{summary.bytes} bytes in {summary.duration_ms}ms")
+            except asyncio.CancelledError:
+                print("[PYTHON CLIENT] Upload cancelled mid-stream")
+            except grpc.aio.AioRpcError as e:
+                print(f"[PYTHON CLIENT] Upload failed: {e.code()} {e.details()}")
+
     def run(self):
         print("[PYTHON CLIENT] Starting Python gRPC client...")
         print(f"[PYTHON CLIENT] Connecting to server at {self.server_address}")
         print(f"[PYTHON CLIENT] Simulating message drops with {self.drop_probability * 100}% probability")
         
         asyncio.run(self.bidirectional_stream())
+        if self.exactly_once:
+            self.exactly_once_summary()
 
 
 if __name__ == "__main__":
-    client = StreamingClient()
-    client.run()
\ No newline at end of file
+    import argparse
+
+    parser = argparse.ArgumentParser(description='gRPC ACK streaming client')
+    parser.add_argument('--upload', type=int, metavar='COUNT',
+                        help='Upload COUNT messages with ClientStream instead of running the ACK stream')
+    parser.add_argument('--cancel-after', type=float, metavar='SECONDS',
+                        help='With --upload, cancel the upload after SECONDS')
+    parser.add_argument('--nack-probability', type=float, default=0.0, metavar='P',
+                        help='NACK received messages with probability P as if their payload were corrupt')
+    parser.add_argument('--cumulative-ack', action='store_true',
+                        help='Acknowledge with one AckUpTo every 0.5s instead of one ACK per message')
+    parser.add_argument('--client-id', metavar='ID',
+                        help='Send x-client-id so the server keeps unACKed messages for a reconnect')
+    parser.add_argument('--last-acked-id', type=int, metavar='ID',
+                        help='Reconnect with x-last-acked-id so the server resumes after message ID')
+    parser.add_argument('--exactly-once', action='store_true',
+                        help='Process each message ID once, check the epoch/delivery_attempt dedup contract '
+                             'and exit 1 on a violation')
+    parser.add_argument('--features', default=','.join(FEATURES), metavar='LIST',
+                        help='Comma-separated features to advertise in the opening Hello; leave out batch to get '
+                             'one message per frame')
+    parser.add_argument('--no-hello', action='store_true',
+                        help='Send no Hello, like a client from before the handshake existed')
+    parser.add_argument('--weight', type=int, metavar='N',
+                        help='Send x-stream-weight so a server with --fair-share-rate gives this stream N shares '
+                             '(0 starves it while others wait)')
+    args = parser.parse_args()
+    features = None if args.no_hello else [feature for feature in args.features.split(',') if feature]
+
+    # 프록시 경유나 임시 포트 실행 시 주소를 환경변수로 지정
+    client = StreamingClient(os.getenv('GRPC_SERVER_ADDRESS', '[::1]:50051'), args.nack_probability, args.cumulative_ack,
+                             args.client_id, args.last_acked_id, args.exactly_once, features, args.weight)
+    if args.upload is not None:
+        asyncio.run(client.upload(args.upload, args.cancel_after))
+    else:
+        client.run()
+        if client.violations:
+            sys.exit(1)
\ No newline at end of file
@@ -14,19 +14,37 @@ _sym_db = _symbol_database.Default()
 
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0fstreaming.proto\x12\tstreaming\"m\n\rStreamMessage\x12&\n\x04\x64\x61ta\x18\x01 \x01(\x0b\x32\x16.streaming.DataMessageH\x00\x12$\n\x03\x61\x63k\x18\x02 \x01(\x0b\x32\x15.streaming.AckMessageH\x00\x42\x0e\n\x0cmessage_type\"P\n\x0b\x44\x61taMessage\x12\n\n\x02id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07payload\x18\x03 \x01(\t\x12\x11\n\tneeds_ack\x18\x04 \x01(\x08\"/\n\nAckMessage\x12\x0e\n\x06\x61\x63k_id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x32\x61\n\x10StreamingService\x12M\n\x13\x42idirectionalStream\x12\x18.streaming.StreamMessage\x1a\x18.streaming.StreamMessage(\x01\x30\x01\x62\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0fstreaming.proto\x12\tstreaming\"\xca\x03\n\rStreamMessage\x12&\n\x04\x64\x61ta\x18\x01 \x01(\x0b\x32\x16.streaming.DataMessageH\x00\x12$\n\x03\x61\x63k\x18\x02 \x01(\x0b\x32\x15.streaming.AckMessageH\x00\x12&\n\x04nack\x18\x03 \x01(\x0b\x32\x16.streaming.NackMessageH\x00\x12.\n\tack_up_to\x18\x04 \x01(\x0b\x32\x19.streaming.AckUpToMessageH\x00\x12%\n\x05\x62\x61tch\x18\x05 \x01(\x0b\x32\x14.streaming.DataBatchH\x00\x12/\n\tbatch_ack\x18\x06 \x01(\x0b\x32\x1a.streaming.BatchAckMessageH\x00\x12(\n\x05pause\x18\x07 \x01(\x0b\x32\x17.streaming.PauseMessageH\x00\x12*\n\x06resume\x18\x08 \x01(\x0b\x32\x18.streaming.ResumeMessageH\x00\x12(\n\x05hello\x18\t \x01(\x0b\x32\x17.streaming.HelloMessageH\x00\x12+\n\x07summary\x18\n \x01(\x0b\x32\x18.streaming.StreamSummaryH\x00\x42\x0e\n\x0cmessage_type\"\xc8\x01\n\x0b\x44\x61taMessage\x12\n\n\x02id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07payload\x18\x03 \x01(\t\x12\x11\n\tneeds_ack\x18\x04 \x01(\x08\x12\r\n\x05\x65poch\x18\x05 \x01(\x04\x12\x18\n\x10\x64\x65livery_attempt\x18\x06 \x01(\r\x12\x10\n\x08priority\x18\x07 \x01(\r\x12\x10\n\x08\x63hecksum\x18\x08 \x01(\r\x12\x0f\n\x07\x63hannel\x18\t \x01(\r\x12\x18\n\x10\x64\x65\x61\x64line_unix_ms\x18\n \x01(\x04\"X\n\tDataBatch\x12\x10\n\x08\x62\x61tch_id\x18\x01 \x01(\x04\x12(\n\x08messages\x18\x02 \x03(\x0b\x32\x16.streaming.DataMessage\x12\x0f\n\x07\x63hannel\x18\x03 \x01(\r\"G\n\x0f\x42\x61tchAckMessage\x12\x10\n\x08\x62\x61tch_id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07\x63hannel\x18\x03 \x01(\r\"@\n\nAckMessage\x12\x0e\n\x06\x61\x63k_id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07\x63hannel\x18\x03 \x01(\r\"D\n\x0e\x41\x63kUpToMessage\x12\x0e\n\x06\x61\x63k_id\x18\x01 \x01(\x04\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x0f\n\x07\x63hannel\x18\x03 \x01(\r\"?\n\x0bNackMessage\x12\x0f\n\x07nack_id\x18\x01 \x01(\x04\x12\x0e\n\x06reason\x18\x02 \x01(\t\x12\x0f\n\x07\x63hannel\x18\x03 \x01(\r\"E\n\rUploadSummary\x12\x10\n\x08messages\x18\x01 \x01(\x04\x12\r\n\x05\x62ytes\x18\x02 \x01(\x04\x12\x13\n\x0b\x64uration_ms\x18\x03 \x01(\x04\"8\n\x0cPauseMessage\x12\x15\n\rpause_retries\x18\x01 \x01(\x08\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\"\"\n\rResumeMessage\x12\x11\n\ttimestamp\x18\x01 \x01(\x04\":\n\x0cHelloMessage\x12\x18\n\x10protocol_version\x18\x01 \x01(\r\x12\x10\n\x08\x66\x65\x61tures\x18\x02 \x03(\t\"v\n\rStreamSummary\x12\x0c\n\x04sent\x18\x01 \x01(\x04\x12\r\n\x05\x61\x63ked\x18\x02 \x01(\x04\x12\x0f\n\x07retried\x18\x03 \x01(\x04\x12\x15\n\rdead_lettered\x18\x04 \x01(\x04\x12\x0f\n\x07\x65xpired\x18\x05 \x01(\x04\x12\x0f\n\x07\x63hannel\x18\x06 \x01(\r2\xa5\x01\n\x10StreamingService\x12M\n\x13\x42idirectionalStream\x12\x18.streaming.StreamMessage\x1a\x18.streaming.StreamMessage(\x01\x30\x01\x12\x42\n\x0c\x43lientStream\x12\x16.streaming.DataMessage\x1a\x18.streaming.UploadSummary(\x01\x62\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
 _builder.BuildTopDescriptorsAndMessages(DESCRIPTOR, 'streaming_pb2', _globals)
 if _descriptor._USE_C_DESCRIPTORS == False:
   DESCRIPTOR._options = None
-  _globals['_STREAMMESSAGE']._serialized_start=30
-  _globals['_STREAMMESSAGE']._serialized_end=139
-  _globals['_DATAMESSAGE']._serialized_start=141
-  _globals['_DATAMESSAGE']._serialized_end=221
-  _globals['_ACKMESSAGE']._serialized_start=223
-  _globals['_ACKMESSAGE']._serialized_end=270
-  _globals['_STREAMINGSERVICE']._serialized_start=272
-  _globals['_STREAMINGSERVICE']._serialized_end=369
+  _globals['_STREAMMESSAGE']._serialized_start=31
+  _globals['_STREAMMESSAGE']._serialized_end=489
+  _globals['_DATAMESSAGE']._serialized_start=492
+  _globals['_DATAMESSAGE']._serialized_end=692
+  _globals['_DATABATCH']._serialized_start=694
+  _globals['_DATABATCH']._serialized_end=782
+  _globals['_BATCHACKMESSAGE']._serialized_start=784
+  _globals['_BATCHACKMESSAGE']._serialized_end=855
+  _globals['_ACKMESSAGE']._serialized_start=857
+  _globals['_ACKMESSAGE']._serialized_end=921
+  _globals['_ACKUPTOMESSAGE']._serialized_start=923
+  _globals['_ACKUPTOMESSAGE']._serialized_end=991
+  _globals['_NACKMESSAGE']._serialized_start=993
+  _globals['_NACKMESSAGE']._serialized_end=1056
+  _globals['_UPLOADSUMMARY']._serialized_start=1058
+  _globals['_UPLOADSUMMARY']._serialized_end=1127
+  _globals['_PAUSEMESSAGE']._serialized_start=1129
+  _globals['_PAUSEMESSAGE']._serialized_end=1185
+  _globals['_RESUMEMESSAGE']._serialized_start=1187
+  _globals['_RESUMEMESSAGE']._serialized_end=1221
+  _globals['_HELLOMESSAGE']._serialized_start=1223
+  _globals['_HELLOMESSAGE']._serialized_end=1281
+  _globals['_STREAMSUMMARY']._serialized_start=1283
+  _globals['_STREAMSUMMARY']._serialized_end=1401
+  _globals['_STREAMINGSERVICE']._serialized_start=1404
+  _globals['_STREAMINGSERVICE']._serialized_end=1569
 # @@protoc_insertion_point(module_scope)
@@ -19,6 +19,11 @@ class StreamingServiceStub(object):
                 request_serializer=streaming__pb2.StreamMessage.SerializeToString,
                 response_deserializer=streaming__pb2.StreamMessage.FromString,
                 )
+        self.ClientStream = channel.stream_unary(
+                '/streaming.StreamingService/ClientStream',
+                request_serializer=streaming__pb2.DataMessage.SerializeToString,
+                response_deserializer=streaming__pb2.UploadSummary.FromString,
+                )
 
 
 class StreamingServiceServicer(object):
@@ -30,6 +35,13 @@ class StreamingServiceServicer(object):
         context.set_details('Method not implemented!')
         raise NotImplementedError('Method not implemented!')
 
+    def ClientStream(self, request_iterator, context):
+        """클라이언트 스트리밍 업로드 - 클라이언트가 스트림을 닫으면 받은 메시지 수와 바이트를 돌려줌
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
 
 def add_StreamingServiceServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -38,6 +50,11 @@ def add_StreamingServiceServicer_to_server(servicer, server):
                     request_deserializer=streaming__pb2.StreamMessage.FromString,
                     response_serializer=streaming__pb2.StreamMessage.SerializeToString,
             ),
+            'ClientStream': grpc.stream_unary_rpc_method_handler(
+                    servicer.ClientStream,
+                    request_deserializer=streaming__pb2.DataMessage.FromString,
+                    response_serializer=streaming__pb2.UploadSummary.SerializeToString,
+            ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
             'streaming.StreamingService', rpc_method_handlers)
@@ -64,3 +81,20 @@ class StreamingService(object):
             streaming__pb2.StreamMessage.FromString,
             options, channel_credentials,
             insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
+
+    @staticmethod
+    def ClientStream(request_iterator,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.stream_unary(request_iterator, target, '/streaming.StreamingService/ClientStream',
+            streaming__pb2.DataMessage.SerializeToString,
+            streaming__pb2.UploadSummary.FromString,
+            options, channel_credentials,
+            insecure, call_credentials, compression, wait_for_ready, timeout, metadata)
@@ -0,0 +1,219 @@
+//! ACK 재전송 프로토콜의 Rust 참조 클라이언트
+//!
+//! ACK 서버에 스트림을 열어 `StreamMessage`를 받고, 설정한 확률로 ACK를 버리거나 늦게 보내
+//! 서버의 재전송을 유도한다. checksum이 맞지 않는 메시지는 기록하지 않고 NACK으로 다시 요청하며,
+//! 받은 메시지는 `DeliveryReport`로 집계한다. 스트림을 열면 먼저 Hello로 쓸 수 있는 기능을 알린다.
+
+pub mod report;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ack_server::checksum::verify;
+use ack_server::client::{CLIENT_ID_METADATA, STREAM_WEIGHT_METADATA};
+use ack_server::hello;
+use ack_server::streaming::stream_message::MessageType;
+use ack_server::streaming::streaming_service_client::StreamingServiceClient;
+use ack_server::streaming::{AckMessage, BatchAckMessage, DataMessage, HelloMessage, NackMessage, StreamMessage};
+use playground_auth::{metadata_value, AUTHORIZATION_METADATA};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Endpoint;
+use tonic::{Request, Status};
+
+use report::DeliveryReport;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// `http://host:port`
+    pub addr: String,
+    /// ACK(batch ACK 포함)를 보내지 않을 확률 (0.0~1.0)
+    pub ack_drop_rate: f64,
+    /// 받은 뒤 이만큼 늦게 ACK - 수신은 막지 않음
+    pub ack_delay: Duration,
+    pub seed: u64,
+    /// `x-client-id` 메타데이터 (재연결 세션)
+    pub client_id: Option<String>,
+    /// 서버 인증용 bearer 토큰
+    pub auth_token: Option<String>,
+    /// `x-stream-weight` 메타데이터 - 서버 `--fair-share-rate`의 전송 슬롯 가중치
+    pub weight: Option<u32>,
+    /// Hello로 알릴 기능 - None이면 Hello를 보내지 않는 이전 버전 클라이언트처럼 동작
+    pub features: Option<Vec<String>>,
+    /// 받은 메시지와 ACK를 한 줄씩 출력
+    pub verbose: bool,
+}
+
+impl ClientConfig {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            ack_drop_rate: 0.0,
+            ack_delay: Duration::ZERO,
+            seed: 0,
+            client_id: None,
+            auth_token: None,
+            weight: None,
+            features: Some(hello::FEATURES.iter().map(|feature| feature.to_string()).collect()),
+            verbose: false,
+        }
+    }
+}
+
+/// 서버가 스트림을 끝낼 때까지 받으며 `report`에 기록 - 끊기거나 거절되면 그 상태를 반환
+pub async fn run(config: &ClientConfig, report: &mut DeliveryReport) -> Result<(), Status> {
+    let channel = Endpoint::try_from(config.addr.clone())
+        .map_err(|e| Status::invalid_argument(e.to_string()))?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .connect()
+        .await
+        .map_err(|e| Status::unavailable(e.to_string()))?;
+
+    let (ack_tx, ack_rx) = mpsc::channel(64);
+    if let Some(features) = &config.features {
+        let hello = HelloMessage { protocol_version: hello::PROTOCOL_VERSION, features: features.clone() };
+        let _ = ack_tx.send(StreamMessage { message_type: Some(MessageType::Hello(hello)) }).await;
+    }
+    let mut request = Request::new(ReceiverStream::new(ack_rx));
+    if let Some(id) = &config.client_id {
+        request.metadata_mut().insert(CLIENT_ID_METADATA, metadata_value(id)?);
+    }
+    if let Some(weight) = config.weight {
+        request.metadata_mut().insert(STREAM_WEIGHT_METADATA, MetadataValue::from(weight));
+    }
+    if let Some(token) = &config.auth_token {
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_METADATA, metadata_value(&format!("Bearer {}", token))?);
+    }
+    let mut stream = StreamingServiceClient::new(channel)
+        .bidirectional_stream(request)
+        .await?
+        .into_inner();
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    while let Some(message) = stream.message().await? {
+        let replies = match message.message_type {
+            Some(MessageType::Data(data)) => match receive(config, report, &data) {
+                Some(nack) => vec![nack],
+                None => vec![MessageType::Ack(AckMessage { ack_id: data.id, timestamp: unix_secs(), channel: data.channel })],
+            },
+            Some(MessageType::Batch(batch)) => {
+                if config.verbose {
+                    let ids: Vec<u64> = batch.messages.iter().map(|data| data.id).collect();
+                    println!("[RUST CLIENT] Received batch {} {:?}", batch.batch_id, ids);
+                }
+                let (mut acks, mut nacks) = (Vec::new(), Vec::new());
+                for data in &batch.messages {
+                    match receive(config, report, data) {
+                        Some(nack) => nacks.push(nack),
+                        None => acks.push(MessageType::Ack(AckMessage {
+                            ack_id: data.id,
+                            timestamp: unix_secs(),
+                            channel: data.channel,
+                        })),
+                    }
+                }
+                if nacks.is_empty() {
+                    vec![MessageType::BatchAck(BatchAckMessage {
+                        batch_id: batch.batch_id,
+                        timestamp: unix_secs(),
+                        channel: batch.channel,
+                    })]
+                } else {
+                    // batch ACK은 손상된 메시지까지 확인하므로 멀쩡한 메시지만 하나씩 ACK
+                    acks.append(&mut nacks);
+                    acks
+                }
+            }
+            Some(MessageType::Summary(summary)) => {
+                report.add_server_summary(summary);
+                continue;
+            }
+            Some(MessageType::Hello(hello)) => {
+                if config.verbose {
+                    println!(
+                        "[RUST CLIENT] Server hello: protocol {}, features {:?}",
+                        hello.protocol_version, hello.features
+                    );
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        for reply in replies {
+            // 일부러 잃어버리는 것은 ACK뿐 - NACK은 손상을 알리므로 항상 보냄
+            if !matches!(reply, MessageType::Nack(_)) {
+                if rng.gen_bool(config.ack_drop_rate) {
+                    report.acks_dropped += 1;
+                    if config.verbose {
+                        println!("[RUST CLIENT] Dropping {:?}", reply);
+                    }
+                    continue;
+                }
+                report.acks_sent += 1;
+            }
+            send(config, &ack_tx, StreamMessage { message_type: Some(reply) }).await;
+        }
+    }
+    Ok(())
+}
+
+/// 데이터 메시지 하나를 검사해 기록 - checksum이 맞지 않으면 기록하지 않고 보낼 NACK을 반환
+fn receive(config: &ClientConfig, report: &mut DeliveryReport, data: &DataMessage) -> Option<MessageType> {
+    if !verify(data) {
+        report.corrupted += 1;
+        if config.verbose {
+            println!("[RUST CLIENT] Checksum mismatch on message {}, sending NACK", data.id);
+        }
+        return Some(MessageType::Nack(NackMessage {
+            nack_id: data.id,
+            reason: "checksum mismatch".to_string(),
+            channel: data.channel,
+        }));
+    }
+    let first = report.record_on(data.channel, data.id);
+    let late = data.deadline_unix_ms != 0 && unix_millis() > data.deadline_unix_ms;
+    if late {
+        report.past_deadline += 1;
+    }
+    if config.verbose {
+        println!(
+            "[RUST CLIENT] Received message {} (channel {}, epoch {}, attempt {}{}{})",
+            data.id,
+            data.channel,
+            data.epoch,
+            data.delivery_attempt,
+            if first { "" } else { ", duplicate" },
+            if late { ", past deadline" } else { "" }
+        );
+    }
+    None
+}
+
+/// 서버가 먼저 끊은 경우 전송 실패는 무시하고 스트림 결과를 따름
+async fn send(config: &ClientConfig, ack_tx: &mpsc::Sender<StreamMessage>, reply: StreamMessage) {
+    if config.ack_delay.is_zero() {
+        let _ = ack_tx.send(reply).await;
+    } else {
+        // 지연이 모두 같으므로 응답 순서는 유지됨
+        let (ack_tx, delay) = (ack_tx.clone(), config.ack_delay);
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = ack_tx.send(reply).await;
+        });
+    }
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
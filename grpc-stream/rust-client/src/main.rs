@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use clap::Parser;
+use grpc_stream_ack_client::report::DeliveryReport;
+use ack_server::hello;
+use grpc_stream_ack_client::ClientConfig;
+
+#[derive(Parser, Debug)]
+#[command(name = "grpc-stream-ack-client")]
+#[command(about = "Reference client for the ACK-retry protocol: receives messages, ACKs them lossily and reports delivery")]
+struct Args {
+    #[arg(long, default_value = "http://[::1]:50051")]
+    addr: String,
+
+    /// Probability of not sending an ACK (or batch ACK) for a received frame, to make the server retry
+    #[arg(long, default_value_t = 0.0, value_parser = parse_rate)]
+    ack_drop_rate: f64,
+
+    /// Send every ACK this many milliseconds after the frame arrived
+    #[arg(long, default_value_t = 0)]
+    ack_delay_ms: u64,
+
+    /// RNG seed for --ack-drop-rate
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Session ID sent as x-client-id metadata
+    #[arg(long)]
+    client_id: Option<String>,
+
+    /// Bearer token for a server started with --auth-token or --auth-hmac-secret
+    #[arg(long, env = "GRPC_AUTH_TOKEN", hide_env_values = true)]
+    auth_token: Option<String>,
+
+    /// Weight sent as x-stream-weight metadata; a server with --fair-share-rate gives this stream that many send slots per round (0 starves it while others wait)
+    #[arg(long)]
+    weight: Option<u32>,
+
+    /// Comma-separated features to advertise in the opening Hello (default: all the client supports); leave out `batch` to get one message per frame
+    #[arg(long, value_delimiter = ',', conflicts_with = "no_hello")]
+    features: Option<Vec<String>>,
+
+    /// Send no Hello, like a client from before the handshake existed
+    #[arg(long)]
+    no_hello: bool,
+
+    /// Only print the delivery report, not every message
+    #[arg(long)]
+    quiet: bool,
+}
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        Ok(_) => Err(format!("rate must be between 0 and 1, got {}", value)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    let config = ClientConfig {
+        addr: args.addr,
+        ack_drop_rate: args.ack_drop_rate,
+        ack_delay: Duration::from_millis(args.ack_delay_ms),
+        seed: args.seed,
+        client_id: args.client_id,
+        auth_token: args.auth_token,
+        weight: args.weight,
+        features: if args.no_hello {
+            None
+        } else {
+            Some(args.features.unwrap_or_else(|| hello::FEATURES.iter().map(|feature| feature.to_string()).collect()))
+        },
+        verbose: !args.quiet,
+    };
+
+    println!(
+        "[RUST CLIENT] Connecting to {} (ack drop rate {}, ack delay {:?})",
+        config.addr, config.ack_drop_rate, config.ack_delay
+    );
+    let mut report = DeliveryReport::default();
+    let result = grpc_stream_ack_client::run(&config, &mut report).await;
+
+    println!("[RUST CLIENT] Delivery report: {}", report);
+    let mismatches = report.summary_mismatches();
+    for mismatch in &mismatches {
+        println!("[RUST CLIENT] Summary mismatch: {}", mismatch);
+    }
+    if let Err(status) = result {
+        println!("[RUST CLIENT] Stream ended with {:?}: {}", status.code(), status.message());
+        std::process::exit(1);
+    }
+    if !mismatches.is_empty() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,142 @@
+//! 받은 메시지로 전달 보고서를 만듦 - 중복, 빠진 id, 순서 위반, 손상
+//!
+//! 서버가 채널을 나눴으면(`--channels`) id는 채널마다 따로 1부터이므로 중복, 빠진 id, 순서는 채널별로 봄.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use ack_server::streaming::StreamSummary;
+
+#[derive(Debug, Default)]
+pub struct DeliveryReport {
+    /// (channel, id)
+    seen: HashSet<(u32, u64)>,
+    /// 채널마다 지금까지 처음 받은 id 중 가장 큰 것
+    highest: BTreeMap<u32, u64>,
+    /// 받은 데이터 메시지 수 (재전송 포함, batch 안의 메시지도 하나씩)
+    pub received: u64,
+    /// 이미 받은 id를 다시 받은 횟수
+    pub duplicates: u64,
+    /// 더 큰 id보다 늦게 처음 도착한 메시지 수
+    pub order_violations: u64,
+    /// checksum이 맞지 않아 기록하지 않고 NACK한 메시지 수
+    pub corrupted: u64,
+    /// 서버가 붙인 기한(`deadline_unix_ms`)이 지난 뒤 도착한 전달 수
+    pub past_deadline: u64,
+    pub acks_sent: u64,
+    /// `--ack-drop-rate`로 일부러 보내지 않은 ACK 수
+    pub acks_dropped: u64,
+    /// 서버가 닫기 직전에 보낸 이 스트림의 집계 - 채널마다 보내면 합계
+    pub server_summary: Option<StreamSummary>,
+}
+
+impl DeliveryReport {
+    /// 채널 0의 데이터 메시지 하나를 받음 - 처음 받은 id면 true
+    pub fn record(&mut self, id: u64) -> bool {
+        self.record_on(0, id)
+    }
+
+    /// `channel`의 데이터 메시지 하나를 받음 - 그 채널에서 처음 받은 id면 true
+    pub fn record_on(&mut self, channel: u32, id: u64) -> bool {
+        self.received += 1;
+        if !self.seen.insert((channel, id)) {
+            self.duplicates += 1;
+            return false;
+        }
+        let highest = self.highest.entry(channel).or_default();
+        if id < *highest {
+            self.order_violations += 1;
+        }
+        *highest = (*highest).max(id);
+        true
+    }
+
+    /// 서버 집계 프레임을 받음 - 채널마다 오면 더함
+    pub fn add_server_summary(&mut self, summary: StreamSummary) {
+        let total = self.server_summary.get_or_insert_with(StreamSummary::default);
+        total.sent += summary.sent;
+        total.acked += summary.acked;
+        total.retried += summary.retried;
+        total.dead_lettered += summary.dead_lettered;
+        total.expired += summary.expired;
+    }
+
+    pub fn unique(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// 서버 집계와 맞지 않는 점 - 서버가 ACK로 센 메시지는 모두 받았어야 하고, 보낸 것보다 많이 받을 수 없음
+    pub fn summary_mismatches(&self) -> Vec<String> {
+        let Some(summary) = &self.server_summary else {
+            return Vec::new();
+        };
+        let mut mismatches = Vec::new();
+        if summary.acked > self.unique() as u64 {
+            mismatches.push(format!(
+                "server counted {} ACKs but only {} messages arrived",
+                summary.acked,
+                self.unique()
+            ));
+        }
+        let deliveries = summary.sent + summary.retried;
+        if self.received + self.corrupted > deliveries {
+            mismatches.push(format!(
+                "received {} deliveries but the server sent only {}",
+                self.received + self.corrupted,
+                deliveries
+            ));
+        }
+        mismatches
+    }
+
+    /// 채널 0에서 가장 큰 id까지 중 끝내 받지 못한 id
+    pub fn gaps(&self) -> Vec<u64> {
+        self.channel_gaps(0)
+    }
+
+    /// `channel`에서 가장 큰 id까지 중 끝내 받지 못한 id
+    pub fn channel_gaps(&self, channel: u32) -> Vec<u64> {
+        let highest = self.highest.get(&channel).copied().unwrap_or_default();
+        (1..highest).filter(|&id| !self.seen.contains(&(channel, id))).collect()
+    }
+
+    /// 메시지를 받은 채널
+    pub fn channels(&self) -> impl Iterator<Item = u32> + '_ {
+        self.highest.keys().copied()
+    }
+}
+
+impl fmt::Display for DeliveryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "received={} unique={} duplicates={} ",
+            self.received,
+            self.unique(),
+            self.duplicates,
+        )?;
+        if self.highest.len() > 1 {
+            let gaps: BTreeMap<u32, Vec<u64>> = self.channels().map(|channel| (channel, self.channel_gaps(channel))).collect();
+            write!(f, "gaps={:?}", gaps)?;
+        } else {
+            write!(f, "gaps={:?}", self.gaps())?;
+        }
+        write!(
+            f,
+            " order_violations={} corrupted={} past_deadline={} acks_sent={} acks_dropped={}",
+            self.order_violations,
+            self.corrupted,
+            self.past_deadline,
+            self.acks_sent,
+            self.acks_dropped
+        )?;
+        if let Some(summary) = &self.server_summary {
+            write!(
+                f,
+                " server_sent={} server_acked={} server_retried={} server_dead_lettered={} server_expired={}",
+                summary.sent, summary.acked, summary.retried, summary.dead_lettered, summary.expired
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use ack_server::server::StreamingServer;
+use ack_server::streaming::streaming_service_server::StreamingServiceServer;
+use grpc_stream_ack_client::report::DeliveryReport;
+use grpc_stream_ack_client::ClientConfig;
+use playground_clock::SystemClock;
+use playground_stats::ServerStats;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+/// 재전송은 중복, 늦게 채워진 id는 순서 위반, 끝내 오지 않은 id는 빈 칸
+#[test]
+fn report_counts_duplicates_gaps_and_order_violations() {
+    let mut report = DeliveryReport::default();
+    for id in [1, 2, 4, 3, 4, 6] {
+        report.record(id);
+    }
+
+    assert_eq!((report.received, report.unique(), report.duplicates), (6, 5, 1));
+    assert_eq!(report.order_violations, 1);
+    assert_eq!(report.gaps(), vec![5]);
+}
+
+/// 채널마다 id가 따로 1부터 - 다른 채널의 같은 id는 중복이 아니고 빈 칸도 채널별
+#[test]
+fn report_tracks_each_channel_separately() {
+    let mut report = DeliveryReport::default();
+    for (channel, id) in [(0, 1), (1, 1), (1, 3), (0, 2)] {
+        report.record_on(channel, id);
+    }
+
+    assert_eq!((report.unique(), report.duplicates, report.order_violations), (4, 0, 0));
+    assert!(report.channel_gaps(0).is_empty());
+    assert_eq!(report.channel_gaps(1), vec![2]);
+}
+
+/// ACK 서버를 임시 포트에 띄우고 ACK를 모두 보내면 빠짐없이 순서대로 받는지 확인
+#[tokio::test]
+async fn receives_every_message_in_order_from_ack_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(SystemClock));
+    tokio::spawn(
+        Server::builder()
+            .add_service(StreamingServiceServer::new(server))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let mut report = DeliveryReport::default();
+    grpc_stream_ack_client::run(&ClientConfig::new(format!("http://{}", addr)), &mut report)
+        .await
+        .unwrap();
+
+    assert_eq!((report.received, report.duplicates, report.order_violations), (3, 0, 0));
+    assert!(report.gaps().is_empty());
+    assert_eq!(report.acks_sent, 3);
+    let summary = report.server_summary.expect("summary frame before the stream closed");
+    assert_eq!((summary.sent, summary.acked, summary.retried), (3, 3, 0));
+    assert!(report.summary_mismatches().is_empty());
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,95 @@
+//! 재전송 스케줄링 비용 비교 - pending 큐 점검(`requeue_expired`) vs 메시지별 타이머(`TimerWheel`)
+//!
+//! `cargo bench --bench retry_scheduler`
+//!
+//! 점검 방식은 점검 한 번마다 in-flight 전체를 훑으므로 in-flight 수에 비례하고, 타이머 방식은 메시지 하나를
+//! 보내고(set) ACK 받고(clear) 재전송하는(expire) 비용이 in-flight 수와 무관해야 함.
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use durable_queue::{DurableQueue, QueueConfig};
+use grpc_stream_ack_server::timers::RetryTimers;
+use playground_clock::TokioClock;
+
+const IN_FLIGHT: [usize; 3] = [1_000, 10_000, 100_000];
+/// 점검을 몇 번 재서 평균을 낼지
+const CHECKS: u32 = 50;
+/// 타이머를 몇 개 걸고 풀고 만료시켜 평균을 낼지
+const MESSAGES: u64 = 10_000;
+const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    let _guard = runtime.enter();
+
+    println!("{:>10} {:>16} {:>16} {:>16}", "in-flight", "scan/check", "set+clear/msg", "expire/msg");
+    for in_flight in IN_FLIGHT {
+        let scan = scan_per_check(in_flight);
+        let set_clear = set_clear_per_message(in_flight);
+        let expire = runtime.block_on(expire_per_message(in_flight));
+        println!("{:>10} {:>16?} {:>16?} {:>16?}", in_flight, scan, set_clear, expire);
+    }
+}
+
+/// ACK 대기 중인 메시지가 `in_flight`개일 때 만료된 것이 없는 점검 한 번 - 폴링 방식이 ack-timeout마다 치르는 비용
+fn scan_per_check(in_flight: usize) -> Duration {
+    let queue = DurableQueue::new(
+        QueueConfig {
+            capacity: in_flight,
+            visibility_timeout: ACK_TIMEOUT,
+            ..QueueConfig::default()
+        },
+        Arc::new(TokioClock::new()),
+    )
+    .unwrap();
+    for id in 1..=in_flight as u64 {
+        queue.lease(id, id).unwrap();
+    }
+
+    let start = Instant::now();
+    for _ in 0..CHECKS {
+        black_box(queue.requeue_expired());
+    }
+    start.elapsed() / CHECKS
+}
+
+/// `in_flight`개의 타이머가 걸린 상태에서 메시지 하나를 보내고 ACK 받는 비용
+fn set_clear_per_message(in_flight: usize) -> Duration {
+    let (timers, mut wheel) = RetryTimers::new();
+    for id in 1..=in_flight as u64 {
+        timers.set(id, ACK_TIMEOUT);
+    }
+    black_box(wheel.try_expired());
+
+    let first = in_flight as u64 + 1;
+    let start = Instant::now();
+    for id in first..first + MESSAGES {
+        timers.set(id, ACK_TIMEOUT);
+        timers.clear(id);
+        black_box(wheel.try_expired());
+    }
+    start.elapsed() / MESSAGES as u32
+}
+
+/// `in_flight`개의 타이머가 걸린 상태에서 ACK를 놓친 메시지의 타이머를 꺼내는 비용
+async fn expire_per_message(in_flight: usize) -> Duration {
+    let (timers, mut wheel) = RetryTimers::new();
+    for id in 1..=in_flight as u64 {
+        timers.set(id, ACK_TIMEOUT);
+    }
+    let first = in_flight as u64 + 1;
+    for id in first..first + MESSAGES {
+        timers.set(id, Duration::from_millis(1));
+    }
+    // 요청을 먼저 반영 - 그새 1ms가 지났으면 하나가 미리 나올 수 있음
+    let early = u64::from(wheel.try_expired().is_some());
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    let start = Instant::now();
+    for _ in early..MESSAGES {
+        black_box(wheel.next_expired().await);
+    }
+    start.elapsed() / (MESSAGES - early) as u32
+}
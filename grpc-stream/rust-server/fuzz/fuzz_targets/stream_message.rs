@@ -0,0 +1,34 @@
+#![no_main]
+
+use std::sync::Arc;
+
+use durable_queue::{DurableQueue, QueueConfig};
+use grpc_stream_ack_server::batch::Batches;
+use grpc_stream_ack_server::incoming::handle_incoming;
+use grpc_stream_ack_server::pause::PauseGate;
+use grpc_stream_ack_server::streaming::StreamMessage;
+use libfuzzer_sys::fuzz_target;
+use playground_bus::BusMessage;
+use playground_clock::SystemClock;
+use prost::Message;
+
+// 클라이언트 프레임 디코딩 + ACK 처리 (잘못된 ack id, 알 수 없는 oneof variant 포함)
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = StreamMessage::decode(data) else {
+        return;
+    };
+
+    let pending = DurableQueue::new(QueueConfig::default(), Arc::new(SystemClock)).unwrap();
+    for id in 1..=10 {
+        let message = BusMessage {
+            id,
+            timestamp: 0,
+            payload: format!("Message {}", id),
+        };
+        pending.lease(id, message).unwrap();
+    }
+    let batches = Batches::default();
+    batches.record(vec![1, 2, 3]);
+
+    handle_incoming(message, &pending, &batches, &PauseGate::default(), false);
+});
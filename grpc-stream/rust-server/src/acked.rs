@@ -0,0 +1,77 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+/// 세션에서 ACK한 메시지 id - pending에 없는 id의 ACK가 중복인지 구분하기 위해 기록
+///
+/// id는 1부터 차례로 발행되므로 빈틈없이 ACK한 구간은 `floor` 하나로 줄이고 그 위만 따로 둠.
+/// 재전송이 끝나면 `above`는 ACK 타임아웃 동안 순서가 바뀐 몇 개만 남음.
+#[derive(Debug, Clone, Default)]
+pub struct AckedIds {
+    inner: Arc<Mutex<Compact>>,
+}
+
+#[derive(Debug, Default)]
+struct Compact {
+    /// 이 id까지는 모두 ACK됨
+    floor: u64,
+    above: BTreeSet<u64>,
+}
+
+/// pending에 없는 id에 대한 ACK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmatchedAck {
+    /// 이미 ACK한 id를 다시 ACK
+    Duplicate,
+    /// 보냈지만 ACK 없이 pending에서 빠진 id (TTL 만료, dead letter, `DropPending`)
+    Late,
+    /// 아직 보내지 않은 id
+    Unknown,
+}
+
+impl AckedIds {
+    /// `last_acked`까지는 이전 세션에서 ACK된 것으로 시작 (`x-last-acked-id`)
+    pub fn after(last_acked: u64) -> Self {
+        let ids = Self::default();
+        ids.inner.lock().unwrap().floor = last_acked;
+        ids
+    }
+
+    pub fn insert(&self, id: u64) {
+        let mut compact = self.inner.lock().unwrap();
+        if id <= compact.floor {
+            return;
+        }
+        compact.above.insert(id);
+        while compact.above.first() == Some(&(compact.floor + 1)) {
+            compact.above.pop_first();
+            compact.floor += 1;
+        }
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        let compact = self.inner.lock().unwrap();
+        id <= compact.floor || compact.above.contains(&id)
+    }
+
+    /// pending에 없는 `id`의 ACK가 어떤 경우인지 - `next_id`는 다음에 발행할 id
+    pub fn classify(&self, id: u64, next_id: u64) -> UnmatchedAck {
+        if self.contains(id) {
+            UnmatchedAck::Duplicate
+        } else if id == 0 || id >= next_id {
+            UnmatchedAck::Unknown
+        } else {
+            UnmatchedAck::Late
+        }
+    }
+
+    /// (이 id까지 모두 ACK됨, 그 위에서 ACK한 id)
+    pub fn snapshot(&self) -> (u64, Vec<u64>) {
+        let compact = self.inner.lock().unwrap();
+        (compact.floor, compact.above.iter().copied().collect())
+    }
+
+    /// `floor` 위에 따로 기록한 id 수
+    pub fn sparse(&self) -> usize {
+        self.inner.lock().unwrap().above.len()
+    }
+}
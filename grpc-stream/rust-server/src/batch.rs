@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 스트림에서 보낸 `DataBatch` 프레임 - batch ACK를 메시지 id로 풀기 위해 기록
+///
+/// 재전송도 새 batch id로 보내므로, 앞선 batch를 늦게 ACK해도 그 안의 메시지는 처리됨.
+/// 기록은 스트림이 끝날 때 함께 사라짐.
+#[derive(Debug, Clone, Default)]
+pub struct Batches {
+    inner: Arc<Mutex<BatchState>>,
+}
+
+#[derive(Debug, Default)]
+struct BatchState {
+    last_id: u64,
+    sent: HashMap<u64, Vec<u64>>,
+}
+
+impl Batches {
+    /// `ids`를 담은 batch를 기록하고 새 batch id(1부터)를 반환
+    pub fn record(&self, ids: Vec<u64>) -> u64 {
+        let mut state = self.inner.lock().unwrap();
+        state.last_id += 1;
+        let batch_id = state.last_id;
+        state.sent.insert(batch_id, ids);
+        batch_id
+    }
+
+    /// batch ACK - 보낸 적 없거나 이미 ACK한 batch면 None
+    pub fn take(&self, batch_id: u64) -> Option<Vec<u64>> {
+        self.inner.lock().unwrap().sent.remove(&batch_id)
+    }
+}
@@ -0,0 +1,84 @@
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt, StreamMap};
+use tonic::Status;
+use tracing::warn;
+
+use crate::streaming::{stream_message::MessageType, StreamMessage};
+
+/// 채널 하나의 ACK 핸들러가 밀렸을 때 쌓아 두는 클라이언트 프레임 수 - 차면 다른 채널의 프레임도 기다림
+pub(crate) const CHANNEL_INBOUND_BUFFER: usize = 16;
+
+type Frames = mpsc::Sender<Result<StreamMessage, Status>>;
+
+/// 클라이언트 프레임이 갈 채널 - None이면 모든 채널 (Pause, Resume, Hello)
+///
+/// ACK, NACK, 누적 ACK, batch ACK는 확인하는 메시지의 `channel`을 그대로 돌려보냄.
+pub fn channel_of(message: &StreamMessage) -> Option<u32> {
+    match message.message_type.as_ref()? {
+        MessageType::Ack(ack) => Some(ack.channel),
+        MessageType::AckUpTo(ack) => Some(ack.channel),
+        MessageType::Nack(nack) => Some(nack.channel),
+        MessageType::BatchAck(ack) => Some(ack.channel),
+        MessageType::Data(data) => Some(data.channel),
+        MessageType::Batch(batch) => Some(batch.channel),
+        MessageType::Pause(_) | MessageType::Resume(_) | MessageType::Hello(_) | MessageType::Summary(_) => None,
+    }
+}
+
+/// 클라이언트 스트림을 채널별 입력으로 나눔 - 없는 채널의 프레임은 버리고, 수신 오류는 모든 채널에 전달
+///
+/// 클라이언트가 스트림을 닫거나 오류가 나면 채널 입력을 모두 닫음.
+pub(crate) async fn demux<S>(mut in_stream: S, channels: Vec<Frames>)
+where
+    S: Stream<Item = Result<StreamMessage, Status>> + Unpin,
+{
+    while let Some(frame) = in_stream.next().await {
+        match frame {
+            Ok(frame) => match channel_of(&frame) {
+                Some(channel) => match channels.get(channel as usize) {
+                    // 채널이 이미 끝났으면 받을 곳이 없음
+                    Some(input) => {
+                        let _ = input.send(Ok(frame)).await;
+                    }
+                    None => warn!(channel, "Ignoring frame for a channel this stream does not have"),
+                },
+                None => {
+                    for input in &channels {
+                        let _ = input.send(Ok(frame.clone())).await;
+                    }
+                }
+            },
+            Err(status) => {
+                for input in &channels {
+                    let _ = input.send(Err(status.clone())).await;
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// 채널별 출력을 한 스트림으로 합침 - 프레임에 채널 번호를 붙이고 서버 Hello는 한 번만 보냄
+///
+/// 모든 채널이 끝나면 닫힘. 클라이언트가 끊겨 보낼 곳이 없으면 채널 출력을 버려 각 채널도 끝나게 함.
+pub(crate) async fn mux(mut outputs: StreamMap<u32, ReceiverStream<Result<StreamMessage, Status>>>, tx: Frames) {
+    let mut hello_sent = false;
+    while let Some((channel, mut frame)) = outputs.next().await {
+        if let Ok(message) = &mut frame {
+            match &mut message.message_type {
+                Some(MessageType::Hello(_)) if hello_sent => continue,
+                Some(MessageType::Hello(_)) => hello_sent = true,
+                Some(MessageType::Data(data)) => data.channel = channel,
+                Some(MessageType::Batch(batch)) => {
+                    batch.channel = channel;
+                    batch.messages.iter_mut().for_each(|data| data.channel = channel);
+                }
+                Some(MessageType::Summary(summary)) => summary.channel = channel,
+                _ => {}
+            }
+        }
+        if tx.send(frame).await.is_err() {
+            return;
+        }
+    }
+}
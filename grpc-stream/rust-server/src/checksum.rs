@@ -0,0 +1,18 @@
+use crate::streaming::DataMessage;
+
+/// `DataMessage.checksum` - id, timestamp(빅엔디언)와 payload 바이트의 CRC-32 (IEEE)
+///
+/// 프록시가 바이트를 바꿔도 HTTP/2 프레임과 protobuf 디코딩은 통과할 수 있으므로, 클라이언트가
+/// 애플리케이션 계층에서 손상을 찾아 NACK로 재전송을 요청할 수 있게 함.
+pub fn checksum(id: u64, timestamp: u64, payload: &str) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&id.to_be_bytes());
+    hasher.update(&timestamp.to_be_bytes());
+    hasher.update(payload.as_bytes());
+    hasher.finalize()
+}
+
+/// 받은 메시지가 서버가 계산한 checksum과 맞는지 - checksum이 0(보내지 않음)이면 검사하지 않음
+pub fn verify(message: &DataMessage) -> bool {
+    message.checksum == 0 || message.checksum == checksum(message.id, message.timestamp, &message.payload)
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use tonic::metadata::MetadataMap;
+
+/// 클라이언트가 자신을 구분하기 위해 보내는 메타데이터 키
+pub const CLIENT_ID_METADATA: &str = "x-client-id";
+/// 재연결한 클라이언트가 마지막으로 ACK한 메시지 id를 알려주는 메타데이터 키
+pub const LAST_ACKED_METADATA: &str = "x-last-acked-id";
+/// 공유 전송 슬롯(`--fair-share-rate`)을 나눠 받는 가중치 메타데이터 키
+pub const STREAM_WEIGHT_METADATA: &str = "x-stream-weight";
+/// `x-stream-weight`가 없을 때의 가중치
+pub const DEFAULT_STREAM_WEIGHT: u32 = 1;
+
+/// pending 큐를 구분하는 키
+///
+/// `x-client-id`를 보낸 클라이언트는 그 값으로, 보내지 않은 연결은 스트림마다 따로 구분함.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    Client(String),
+    Stream(u64),
+}
+
+impl fmt::Display for ClientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientKey::Client(id) => f.write_str(id),
+            ClientKey::Stream(stream_id) => write!(f, "stream-{}", stream_id),
+        }
+    }
+}
+
+/// 스트림을 연 클라이언트가 요청 메타데이터로 알려준 정보
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// `x-client-id` - 없으면 스트림마다 따로 pending 큐를 둠
+    pub id: Option<String>,
+    /// `x-last-acked-id` - 있으면 이전 스트림의 pending 메시지 중 이 id 이후부터 다시 보냄
+    pub last_acked: Option<u64>,
+    /// `x-stream-weight` - 0이면 가중치 있는 스트림이 기다리지 않을 때만 슬롯을 받음
+    pub weight: u32,
+}
+
+impl ClientInfo {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: Some(id.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_last_acked(mut self, id: u64) -> Self {
+        self.last_acked = Some(id);
+        self
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// 요청 메타데이터에서 읽음 - 잘못된 값은 보내지 않은 것으로 취급
+    pub fn from_metadata(metadata: &MetadataMap) -> Self {
+        Self {
+            id: client_id(metadata),
+            last_acked: last_acked_id(metadata),
+            weight: stream_weight(metadata).unwrap_or(DEFAULT_STREAM_WEIGHT),
+        }
+    }
+}
+
+impl Default for ClientInfo {
+    fn default() -> Self {
+        Self {
+            id: None,
+            last_acked: None,
+            weight: DEFAULT_STREAM_WEIGHT,
+        }
+    }
+}
+
+/// 요청 메타데이터의 `x-client-id` - 없거나 비어 있거나 ASCII가 아니면 None
+fn client_id(metadata: &MetadataMap) -> Option<String> {
+    metadata
+        .get(CLIENT_ID_METADATA)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// 요청 메타데이터의 `x-last-acked-id` - 없거나 숫자가 아니면 None
+fn last_acked_id(metadata: &MetadataMap) -> Option<u64> {
+    metadata
+        .get(LAST_ACKED_METADATA)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+/// 요청 메타데이터의 `x-stream-weight` - 없거나 숫자가 아니면 None
+fn stream_weight(metadata: &MetadataMap) -> Option<u32> {
+    metadata
+        .get(STREAM_WEIGHT_METADATA)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u32>().ok())
+}
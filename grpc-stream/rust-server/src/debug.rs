@@ -0,0 +1,154 @@
+//! `DebugService` - 테스트 스크립트가 실행 도중 pending 큐를 보고 바꾸는 관리용 RPC
+//!
+//! 로그로 재전송 상태를 짐작하는 대신 직접 조회하고, ACK 타임아웃을 기다리지 않고 재전송하거나
+//! 메시지를 빼서 재연결·dead letter 경로를 원하는 시점에 만듦. `Snapshot`은 모든 스트림을 잠깐 멈추고
+//! pending, ACK 기록, 전송 위치를 한 시점에 읽어 외부 검사기가 불변식을 확인하게 함. 인증은 스트리밍 서비스와 같음.
+
+use durable_queue::{DurableQueue, Nacked};
+use playground_bus::BusMessage;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use crate::server::{ClientSessions, StreamHooks};
+use crate::state_lock::StateLock;
+
+pub mod proto {
+    tonic::include_proto!("streaming.debug");
+
+    /// 리플렉션 서비스에 등록할 `debug.proto` descriptor set
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("debug_descriptor");
+}
+
+use proto::debug_service_server::DebugService as DebugServiceTrait;
+use proto::{
+    DropPendingResponse, ForceRetryResponse, ListPendingRequest, ListPendingResponse, PendingMessage, PendingMessageRef,
+    SessionSnapshot, SnapshotRequest, SnapshotResponse,
+};
+
+/// `StreamingServer::debug_service`로 만듦 - 같은 세션 테이블을 봄 (`DebugServiceServer`로 감싸 등록)
+pub struct DebugService {
+    sessions: ClientSessions,
+    state: StateLock,
+}
+
+impl DebugService {
+    pub(crate) fn new(sessions: ClientSessions, state: StateLock) -> Self {
+        Self { sessions, state }
+    }
+
+    /// `target`이 가리키는 세션의 pending 큐와 (스트림이 열려 있으면) 그 태스크 핸들
+    fn find(&self, target: &PendingMessageRef) -> Result<(DurableQueue<BusMessage>, Option<StreamHooks>), FindError> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut found = sessions
+            .iter()
+            .filter(|(key, _)| target.client.is_empty() || key.to_string() == target.client)
+            .filter(|(_, session)| session.pending.contains(target.id));
+        let Some((_, session)) = found.next() else {
+            return Err(FindError::NotPending(target.id));
+        };
+        if found.next().is_some() {
+            return Err(FindError::Ambiguous(target.id));
+        }
+        Ok((session.pending.clone(), session.hooks.clone()))
+    }
+}
+
+/// `find` 실패 - 핸들러에서 `?`로 `Status`가 됨
+#[derive(Debug)]
+enum FindError {
+    NotPending(u64),
+    /// client를 비워 두었는데 여러 세션에 pending
+    Ambiguous(u64),
+}
+
+impl From<FindError> for Status {
+    fn from(error: FindError) -> Self {
+        match error {
+            FindError::NotPending(id) => Status::not_found(format!("message {} is not pending", id)),
+            FindError::Ambiguous(id) => {
+                Status::invalid_argument(format!("message {} is pending for several clients, set client", id))
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl DebugServiceTrait for DebugService {
+    async fn list_pending(&self, request: Request<ListPendingRequest>) -> Result<Response<ListPendingResponse>, Status> {
+        let client = request.into_inner().client;
+        let sessions = self.sessions.lock().unwrap();
+        let mut messages: Vec<PendingMessage> = sessions
+            .iter()
+            .filter(|(key, _)| client.is_empty() || key.to_string() == client)
+            .flat_map(|(key, session)| {
+                session.pending.list().into_iter().map(|listed| PendingMessage {
+                    client: key.to_string(),
+                    id: listed.id,
+                    delivery_attempt: listed.deliveries,
+                    in_flight: listed.visible_in.is_some(),
+                    retry_in_ms: listed.visible_in.map_or(0, |after| after.as_millis() as u64),
+                    stream_open: session.open,
+                })
+            })
+            .collect();
+        messages.sort_by(|a, b| (&a.client, a.id).cmp(&(&b.client, b.id)));
+        Ok(Response::new(ListPendingResponse { messages }))
+    }
+
+    async fn force_retry(&self, request: Request<PendingMessageRef>) -> Result<Response<ForceRetryResponse>, Status> {
+        let target = request.into_inner();
+        let (pending, hooks) = self.find(&target)?;
+        let nacked = {
+            let _state = self.state.change();
+            pending.nack(target.id)
+        };
+        let Some(nacked) = nacked else {
+            return Err(Status::failed_precondition(format!(
+                "message {} is not in flight (already waiting for a retry)",
+                target.id
+            )));
+        };
+        let dead_letter = nacked == Nacked::DeadLetter;
+        warn!(message_id = target.id, dead_letter, "DebugService forced a retry");
+        // 닫힌 세션은 재연결할 때 다시 보냄
+        if let Some(hooks) = hooks {
+            match nacked {
+                Nacked::Requeued => hooks.requeued(target.id),
+                Nacked::DeadLetter => hooks.removed(target.id),
+            }
+        }
+        Ok(Response::new(ForceRetryResponse { dead_letter }))
+    }
+
+    async fn drop_pending(&self, request: Request<PendingMessageRef>) -> Result<Response<DropPendingResponse>, Status> {
+        let target = request.into_inner();
+        let (pending, hooks) = self.find(&target)?;
+        let removed = {
+            let _state = self.state.change();
+            pending.remove(target.id, false)
+        };
+        if !removed {
+            return Err(Status::not_found(format!("message {} is not pending", target.id)));
+        }
+        info!(message_id = target.id, "DebugService dropped a pending message");
+        // 닫힌 세션의 pending 수는 스트림이 끝날 때 이미 통계에서 뺐음
+        if let Some(hooks) = hooks {
+            hooks.removed(target.id);
+        }
+        Ok(Response::new(DropPendingResponse {}))
+    }
+
+    async fn snapshot(&self, request: Request<SnapshotRequest>) -> Result<Response<SnapshotResponse>, Status> {
+        let client = request.into_inner().client;
+        let sessions = self.sessions.lock().unwrap();
+        // 진행 중인 전송, 재전송, ACK 처리가 끝나길 기다렸다가 읽는 동안 모두 멈춤
+        let _frozen = self.state.freeze();
+        let mut sessions: Vec<SessionSnapshot> = sessions
+            .iter()
+            .filter(|(key, _)| client.is_empty() || key.to_string() == client)
+            .map(|(key, session)| session.snapshot(key))
+            .collect();
+        sessions.sort_by(|a, b| a.client.cmp(&b.client));
+        Ok(Response::new(SnapshotResponse { sessions }))
+    }
+}
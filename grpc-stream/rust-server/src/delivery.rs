@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use playground_clock::SharedClock;
+use playground_stats::ServerStats;
+
+use crate::report::AckedMessage;
+
+/// 메시지마다 마지막으로 보낸 시각과 전달 횟수 - ACK가 오면 지연과 재전송 횟수를 히스토그램에 기록
+///
+/// 보낸 시각은 outbox에 넣은 시각. 기록은 스트림이 끝날 때 함께 사라지므로, 재연결 뒤
+/// `x-last-acked-id`로 ACK한 메시지는 관측하지 않음.
+#[derive(Clone)]
+pub struct DeliveryTimes {
+    sent: Arc<Mutex<HashMap<u64, Sent>>>,
+    clock: SharedClock,
+    stats: Arc<ServerStats>,
+    /// `--report-dir` - ACK 받은 메시지마다 지연과 재전송 횟수를 모아 둠
+    acks: Option<Arc<Mutex<Vec<AckedMessage>>>>,
+}
+
+struct Sent {
+    at: Duration,
+    attempt: u32,
+}
+
+impl DeliveryTimes {
+    pub fn new(clock: SharedClock, stats: Arc<ServerStats>) -> Self {
+        Self {
+            sent: Arc::default(),
+            clock,
+            stats,
+            acks: None,
+        }
+    }
+
+    /// 히스토그램과 함께 메시지별 기록도 남김 - 스트림이 끝날 때 `take_acks`로 꺼냄
+    pub fn keep_acks(mut self) -> Self {
+        self.acks = Some(Arc::default());
+        self
+    }
+
+    /// 지금까지 모은 메시지별 기록 (`keep_acks`를 부르지 않았으면 빈 목록)
+    pub fn take_acks(&self) -> Vec<AckedMessage> {
+        self.acks
+            .as_ref()
+            .map(|acks| std::mem::take(&mut *acks.lock().unwrap()))
+            .unwrap_or_default()
+    }
+
+    /// `attempt`번째 전송 - 이전 전송 시각을 덮어씀
+    pub fn sent(&self, id: u64, attempt: u32) {
+        let at = self.clock.unix_time();
+        self.sent.lock().unwrap().insert(id, Sent { at, attempt });
+    }
+
+    /// ACK 받은 메시지의 지연(마지막 전송부터)과 재전송 횟수를 기록
+    pub fn acked(&self, id: u64) {
+        let Some(sent) = self.sent.lock().unwrap().remove(&id) else {
+            return;
+        };
+        let latency = self.clock.unix_time().saturating_sub(sent.at);
+        self.stats.delivery.ack_latency.observe(latency.as_secs_f64());
+        self.stats.delivery.retries.observe(f64::from(sent.attempt.saturating_sub(1)));
+        if let Some(acks) = &self.acks {
+            acks.lock().unwrap().push(AckedMessage {
+                id,
+                latency_ms: latency.as_millis() as u64,
+                retries: sent.attempt.saturating_sub(1),
+            });
+        }
+    }
+
+    /// ACK 없이 끝난 메시지(만료, 한도 초과) - 기록하지 않고 버림
+    pub fn forget(&self, id: u64) {
+        self.sent.lock().unwrap().remove(&id);
+    }
+}
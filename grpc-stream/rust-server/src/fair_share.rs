@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use playground_stats::ServerStats;
+use tokio::sync::{oneshot, Notify};
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// 공정성 지수를 계산하는 최근 슬롯 수
+const FAIRNESS_WINDOW: usize = 100;
+
+/// 모든 스트림이 나눠 쓰는 새 메시지 전송 슬롯 - 초당 `rate`개를 가중 라운드로빈으로 나눠줌
+///
+/// 슬롯이 모자라면 스트림마다 `SEND_INTERVAL`대로 보내지 못하고 가중치 비율로 차례를 기다림.
+/// 가중치 0인 스트림은 가중치 있는 스트림이 모두 기다리지 않을 때만 받으므로 일부러 굶길 수 있음.
+/// 재전송은 슬롯을 쓰지 않음.
+#[derive(Clone)]
+pub struct FairShare {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    interval: Duration,
+    state: Mutex<State>,
+    /// 슬롯을 기다리는 스트림이 생김 - 분배 태스크가 기다림
+    waiting: Notify,
+    stats: Arc<ServerStats>,
+}
+
+#[derive(Default)]
+struct State {
+    streams: BTreeMap<u64, Share>,
+    /// 최근 슬롯을 받은 stream_id (`FAIRNESS_WINDOW`개)
+    recent: VecDeque<u64>,
+    /// 분배 태스크를 띄웠음 - 첫 요청 때 띄움 (서버를 만들 때는 런타임 밖일 수 있음)
+    driving: bool,
+}
+
+struct Share {
+    weight: u32,
+    /// smooth weighted round-robin 점수 - 기다리는 스트림끼리 가중치만큼 올리고 뽑힌 스트림은 합계만큼 내림
+    current: i64,
+    waiters: VecDeque<oneshot::Sender<()>>,
+}
+
+impl FairShare {
+    /// 초당 `rate`개 (0보다 커야 함)
+    pub fn new(rate: f64, stats: Arc<ServerStats>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                interval: Duration::from_secs_f64(1.0 / rate),
+                state: Mutex::default(),
+                waiting: Notify::new(),
+                stats,
+            }),
+        }
+    }
+
+    /// 스트림을 `weight`로 등록 - 반환된 핸들이 drop되면 빠짐
+    pub(crate) fn join(&self, stream_id: u64, weight: u32) -> ShareHandle {
+        self.inner.state.lock().unwrap().streams.insert(
+            stream_id,
+            Share {
+                weight,
+                current: 0,
+                waiters: VecDeque::new(),
+            },
+        );
+        ShareHandle {
+            share: self.clone(),
+            stream_id,
+        }
+    }
+
+    /// 슬롯 간격마다 기다리는 스트림 하나에 슬롯을 줌 - 기다리는 스트림이 없으면 쉼
+    async fn drive(self) {
+        let mut ticks = tokio::time::interval(self.inner.interval);
+        // 쉬다가 다시 요청이 오면 바로 주고 그때부터 간격을 셈
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            loop {
+                // 상태를 보기 전에 등록해야 그 사이의 요청을 놓치지 않음
+                let waiting = self.inner.waiting.notified();
+                if self.inner.state.lock().unwrap().has_waiters() {
+                    break;
+                }
+                waiting.await;
+            }
+            ticks.tick().await;
+            self.grant();
+        }
+    }
+
+    fn grant(&self) {
+        let mut state = self.inner.state.lock().unwrap();
+        let Some(stream_id) = state.pick() else {
+            return;
+        };
+        state.recent.push_back(stream_id);
+        if state.recent.len() > FAIRNESS_WINDOW {
+            state.recent.pop_front();
+        }
+        let index = state.fairness_index();
+        if let Some(share) = state.streams.get_mut(&stream_id) {
+            // 기다리다 그만둔(취소된) 요청은 건너뜀
+            while let Some(waiter) = share.waiters.pop_front() {
+                if waiter.send(()).is_ok() {
+                    break;
+                }
+            }
+        }
+        drop(state);
+        if let Some(index) = index {
+            self.inner.stats.fair_share.set_index(index);
+        }
+    }
+}
+
+impl State {
+    fn has_waiters(&self) -> bool {
+        self.streams.values().any(|share| !share.waiters.is_empty())
+    }
+
+    /// 다음 슬롯을 받을 스트림 - 가중치 있는 스트림끼리 smooth weighted round-robin, 없으면 가중치 0인 스트림 순서대로
+    fn pick(&mut self) -> Option<u64> {
+        let total: i64 = self
+            .streams
+            .values()
+            .filter(|share| !share.waiters.is_empty())
+            .map(|share| i64::from(share.weight))
+            .sum();
+        if total == 0 {
+            return self
+                .streams
+                .iter()
+                .find(|(_, share)| !share.waiters.is_empty())
+                .map(|(&stream_id, _)| stream_id);
+        }
+        let mut picked: Option<(u64, i64)> = None;
+        for (&stream_id, share) in self.streams.iter_mut() {
+            if share.waiters.is_empty() || share.weight == 0 {
+                continue;
+            }
+            share.current += i64::from(share.weight);
+            // 같은 점수면 먼저 연 스트림
+            if picked.is_none_or(|(_, best)| share.current > best) {
+                picked = Some((stream_id, share.current));
+            }
+        }
+        let (stream_id, _) = picked?;
+        if let Some(share) = self.streams.get_mut(&stream_id) {
+            share.current -= total;
+        }
+        Some(stream_id)
+    }
+
+    /// 등록된 가중치 있는 스트림의 최근 슬롯 수 / 가중치로 계산한 Jain 지수 - 슬롯을 못 받은 스트림도 0으로 셈
+    fn fairness_index(&self) -> Option<f64> {
+        let shares: Vec<f64> = self
+            .streams
+            .iter()
+            .filter(|(_, share)| share.weight > 0)
+            .map(|(stream_id, share)| {
+                let granted = self.recent.iter().filter(|&&id| id == *stream_id).count();
+                granted as f64 / f64::from(share.weight)
+            })
+            .collect();
+        let sum: f64 = shares.iter().sum();
+        let squares: f64 = shares.iter().map(|share| share * share).sum();
+        (squares > 0.0).then(|| sum * sum / (shares.len() as f64 * squares))
+    }
+}
+
+/// 등록된 스트림 하나의 슬롯 요청 핸들
+pub(crate) struct ShareHandle {
+    share: FairShare,
+    stream_id: u64,
+}
+
+impl ShareHandle {
+    /// 이 스트림 차례가 올 때까지 기다림 - 기다린 시간은 `stats.fair_share.wait`에 기록
+    pub(crate) async fn turn(&self) {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut state = self.share.inner.state.lock().unwrap();
+            if let Some(share) = state.streams.get_mut(&self.stream_id) {
+                share.waiters.push_back(tx);
+            }
+            if !state.driving {
+                state.driving = true;
+                tokio::spawn(self.share.clone().drive());
+            }
+        }
+        self.share.inner.waiting.notify_one();
+        let started = Instant::now();
+        let _ = rx.await;
+        self.share.inner.stats.fair_share.wait.observe(started.elapsed().as_secs_f64());
+    }
+}
+
+impl Drop for ShareHandle {
+    fn drop(&mut self) {
+        self.share.inner.state.lock().unwrap().streams.remove(&self.stream_id);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::streaming::HelloMessage;
+
+/// 이 서버가 말하는 ACK 프로토콜 버전 - Hello가 없던 이전 버전이 1
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// `DataBatch`로 묶어 받고 `BatchAck`로 확인할 수 있음 - 없으면 서버가 `--batch-size`를 무시하고 하나씩 보냄
+pub const BATCH: &str = "batch";
+/// `DataMessage.checksum`을 검사함 - 없으면 서버가 checksum을 0(검사하지 않음)으로 보냄
+pub const CHECKSUM: &str = "checksum";
+/// 누적 ACK(`AckUpTo`)를 보낼 수 있음
+pub const ACK_UP_TO: &str = "ack_up_to";
+pub const NACK: &str = "nack";
+/// `Pause`/`Resume` 흐름 제어
+pub const PAUSE: &str = "pause";
+/// 닫기 직전의 `StreamSummary` 프레임을 받음 - 알리지 않은 클라이언트에는 보내지 않음
+pub const SUMMARY: &str = "summary";
+
+/// 서버가 Hello로 알리는 기능
+pub const FEATURES: &[&str] = &[BATCH, CHECKSUM, ACK_UP_TO, NACK, PAUSE, SUMMARY];
+
+/// 서버가 클라이언트 Hello에 답하는 Hello
+pub fn server_hello() -> HelloMessage {
+    HelloMessage {
+        protocol_version: PROTOCOL_VERSION,
+        features: FEATURES.iter().map(|feature| feature.to_string()).collect(),
+    }
+}
+
+/// 클라이언트 Hello로 정한 상대 프로토콜
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+    /// 양쪽이 모두 아는 버전 (클라이언트 버전과 `PROTOCOL_VERSION` 중 작은 것)
+    pub protocol_version: u32,
+    pub features: BTreeSet<String>,
+}
+
+/// 스트림 하나의 handshake 상태 - ACK 핸들러가 Hello를 기록하고 송신 쪽이 기능을 확인
+///
+/// Hello를 보내지 않은 클라이언트는 handshake 이전 버전으로 보고 모든 기능을 쓸 수 있다고 가정함.
+#[derive(Clone, Default)]
+pub struct Handshake {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    peer: Mutex<Option<Peer>>,
+    received: Notify,
+}
+
+impl Handshake {
+    /// 클라이언트 Hello를 기록 - 이미 받았으면 무시하고 None
+    pub fn receive(&self, hello: HelloMessage) -> Option<Peer> {
+        let mut peer = self.inner.peer.lock().unwrap();
+        if peer.is_some() {
+            return None;
+        }
+        let received = Peer {
+            protocol_version: hello.protocol_version.min(PROTOCOL_VERSION),
+            features: hello.features.into_iter().collect(),
+        };
+        *peer = Some(received.clone());
+        self.inner.received.notify_waiters();
+        Some(received)
+    }
+
+    pub fn peer(&self) -> Option<Peer> {
+        self.inner.peer.lock().unwrap().clone()
+    }
+
+    /// 클라이언트가 `feature`를 쓸 수 있는지 - Hello를 받지 못했으면 true
+    pub fn supports(&self, feature: &str) -> bool {
+        self.inner
+            .peer
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_none_or(|peer| peer.features.contains(feature))
+    }
+
+    /// 클라이언트가 Hello로 `feature`를 알렸는지 - 이전 버전 클라이언트가 모르는 프레임을 보낼 때 확인
+    pub fn advertised(&self, feature: &str) -> bool {
+        self.inner
+            .peer
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|peer| peer.features.contains(feature))
+    }
+
+    /// Hello를 받을 때까지 최대 `timeout` 기다림 - 받았으면 true
+    pub async fn wait(&self, timeout: Duration) -> bool {
+        // 상태를 보기 전에 등록해야 그 사이의 Hello를 놓치지 않음
+        let received = self.inner.received.notified();
+        if self.peer().is_some() {
+            return true;
+        }
+        tokio::time::timeout(timeout, received).await.is_ok()
+    }
+}
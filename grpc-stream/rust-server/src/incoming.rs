@@ -0,0 +1,121 @@
+use durable_queue::{DurableQueue, Nacked};
+use playground_bus::BusMessage;
+
+use crate::batch::Batches;
+use crate::pause::PauseGate;
+
+use crate::streaming::{stream_message::MessageType, HelloMessage, StreamMessage};
+
+/// 클라이언트가 보낸 프레임을 처리한 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incoming {
+    /// pending에 있던 메시지의 정상 ACK
+    Acked(u64),
+    /// 보낸 적 없거나 이미 처리된 id에 대한 ACK - 어느 쪽인지는 `AckedIds::classify`로 구분
+    UnknownAck(u64),
+    /// 누적 ACK로 처리한 pending 메시지 id (오름차순, 없으면 빈 목록)
+    AckedUpTo { up_to: u64, ids: Vec<u64> },
+    /// 클라이언트가 거절한 메시지 - `redelivery`에 따라 다시 보냄
+    Nacked { id: u64, reason: String, redelivery: Redelivery },
+    /// 보낸 적 없거나 이미 처리된 id에 대한 NACK
+    UnknownNack(u64),
+    /// batch ACK로 처리한 pending 메시지 id (이미 개별 ACK된 메시지는 빠짐)
+    BatchAcked { batch_id: u64, ids: Vec<u64> },
+    /// 보낸 적 없거나 이미 ACK한 batch
+    UnknownBatch(u64),
+    /// 클라이언트가 전송을 멈춤 - `retries`면 재전송도 멈춤
+    Paused { retries: bool },
+    /// 멈춘 전송을 재개
+    Resumed,
+    /// 멈추지 않은 스트림에 대한 Resume
+    NotPaused,
+    /// 클라이언트의 프로토콜 버전과 기능 - 스트림의 `Handshake`에 기록하는 것은 호출하는 쪽 몫
+    Hello(HelloMessage),
+    /// 서버 → 클라이언트 방향 전용인 Data 프레임을 클라이언트가 보냄
+    UnexpectedData(u64),
+    /// 서버 → 클라이언트 방향 전용인 DataBatch 프레임을 클라이언트가 보냄
+    UnexpectedBatch(u64),
+    /// 서버 → 클라이언트 방향 전용인 StreamSummary 프레임을 클라이언트가 보냄 (채널)
+    UnexpectedSummary(u32),
+    /// oneof가 비어있음 (알 수 없는 variant는 prost가 None으로 디코딩)
+    Empty,
+}
+
+/// NACK 받은 메시지를 어떻게 다시 보낼지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redelivery {
+    /// pending 큐 맨 앞으로 - ACK 타임아웃을 기다리지 않고 바로 재전송
+    Now,
+    /// 재전송 한도를 다 써서 dead letter
+    DeadLetter,
+    /// 브로커가 재전송하는 백엔드 - in-flight로 두고 브로커에 맡김
+    Broker,
+}
+
+/// 클라이언트 프레임 하나를 pending 큐에 반영
+///
+/// 네트워크에서 들어온 값이므로 어떤 id든 panic 없이 처리해야 함.
+/// `broker_redelivers`면 NACK도 pending 큐를 건드리지 않음 (브로커의 재전송과 겹치지 않도록).
+/// Pause/Resume은 `pause`에 반영 - 멈춘 동안 쌓인 재전송을 보내도록 재전송 핸들러를 깨우는 것은 호출하는 쪽 몫.
+pub fn handle_incoming(
+    message: StreamMessage,
+    pending: &DurableQueue<BusMessage>,
+    batches: &Batches,
+    pause: &PauseGate,
+    broker_redelivers: bool,
+) -> Incoming {
+    match message.message_type {
+        Some(MessageType::Ack(ack)) => {
+            if pending.ack(ack.ack_id) {
+                Incoming::Acked(ack.ack_id)
+            } else {
+                Incoming::UnknownAck(ack.ack_id)
+            }
+        }
+        Some(MessageType::AckUpTo(ack)) => Incoming::AckedUpTo {
+            up_to: ack.ack_id,
+            ids: pending.ack_up_to(ack.ack_id),
+        },
+        Some(MessageType::Nack(nack)) => {
+            let redelivery = if broker_redelivers {
+                Some(Redelivery::Broker)
+            } else {
+                pending.nack(nack.nack_id).map(|nacked| match nacked {
+                    Nacked::Requeued => Redelivery::Now,
+                    Nacked::DeadLetter => Redelivery::DeadLetter,
+                })
+            };
+            match redelivery {
+                Some(redelivery) => Incoming::Nacked {
+                    id: nack.nack_id,
+                    reason: nack.reason,
+                    redelivery,
+                },
+                None => Incoming::UnknownNack(nack.nack_id),
+            }
+        }
+        Some(MessageType::BatchAck(ack)) => match batches.take(ack.batch_id) {
+            Some(ids) => Incoming::BatchAcked {
+                batch_id: ack.batch_id,
+                ids: ids.into_iter().filter(|&id| pending.ack(id)).collect(),
+            },
+            None => Incoming::UnknownBatch(ack.batch_id),
+        },
+        Some(MessageType::Pause(control)) => {
+            pause.pause(control.pause_retries);
+            Incoming::Paused { retries: control.pause_retries }
+        }
+        Some(MessageType::Resume(_)) => {
+            if pause.resume() {
+                Incoming::Resumed
+            } else {
+                Incoming::NotPaused
+            }
+        }
+        Some(MessageType::Hello(hello)) => Incoming::Hello(hello),
+        Some(MessageType::Data(data)) => Incoming::UnexpectedData(data.id),
+        Some(MessageType::Batch(batch)) => Incoming::UnexpectedBatch(batch.batch_id),
+        Some(MessageType::Summary(summary)) => Incoming::UnexpectedSummary(summary.channel),
+        None => Incoming::Empty,
+    }
+}
@@ -0,0 +1,27 @@
+pub mod acked;
+pub mod batch;
+pub mod channels;
+pub mod checksum;
+pub mod client;
+pub mod debug;
+pub mod delivery;
+pub mod fair_share;
+pub mod hello;
+pub mod incoming;
+pub mod outbox;
+pub mod pause;
+pub mod report;
+pub mod retry;
+pub mod send_drop;
+pub mod server;
+pub mod shuffle;
+pub mod state_lock;
+pub mod summary;
+pub mod timers;
+
+pub mod streaming {
+    tonic::include_proto!("streaming");
+}
+
+/// 리플렉션 서비스에 등록할 `streaming.proto` descriptor set
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("streaming_descriptor");
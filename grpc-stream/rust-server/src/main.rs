@@ -1,221 +1,424 @@
-use std::collections::HashMap;
-use std::env;
+use clap::Parser;
+use grpc_stream_ack_server::debug::proto::debug_service_server::DebugServiceServer;
+use grpc_stream_ack_server::fair_share::FairShare;
+use grpc_stream_ack_server::report::ReportDir;
+use grpc_stream_ack_server::retry::{self, BackoffKind, RetryPolicy};
+use grpc_stream_ack_server::send_drop::SendDrops;
+use grpc_stream_ack_server::shuffle::Shuffle;
+use grpc_stream_ack_server::server::StreamingServer;
+use grpc_stream_ack_server::streaming::streaming_service_server::StreamingServiceServer;
+use playground_audit::AuditLog;
+use playground_auth::AuthArgs;
+use playground_clock::{SharedClock, SystemClock};
+use playground_shutdown::{Phase, Shutdown, DEFAULT_DRAIN_TIMEOUT};
+use playground_stats::{ServerStats, StatsService};
+use playground_telemetry::{LogFormat, TelemetryGuard};
+use playground_tls::TlsArgs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::{mpsc, Mutex};
-use tokio_stream::{wrappers::ReceiverStream, StreamExt};
-use tonic::{transport::Server, Request, Response, Status, Streaming};
+use std::time::Duration;
+use tonic::codec::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use tonic_health::ServingStatus;
+use tracing::{info, warn};
 
-pub mod streaming {
-    tonic::include_proto!("streaming");
+#[derive(Parser, Debug)]
+#[command(name = "grpc-stream-ack-server")]
+#[command(about = "Stream messages that clients must ACK, retrying unacknowledged ones")]
+struct Args {
+    /// Messages to send per stream
+    #[arg(default_value_t = 10)]
+    message_count: u64,
+
+    /// Address to listen on (the orchestrator assigns ephemeral ports through the env var)
+    #[arg(long, env = "GRPC_LISTEN_ADDRESS", default_value = "[::1]:50051")]
+    listen_addr: SocketAddr,
+
+    /// Serve Prometheus metrics on this address at GET /metrics
+    #[arg(long, env = "METRICS_LISTEN_ADDRESS")]
+    metrics_addr: Option<SocketAddr>,
+
+    /// Seconds to wait for an ACK before retrying a message
+    #[arg(long, default_value_t = retry::DEFAULT_ACK_TIMEOUT.as_secs(), value_parser = clap::value_parser!(u64).range(1..))]
+    ack_timeout: u64,
+
+    /// Retries per message before it becomes a dead letter
+    #[arg(long, default_value_t = retry::MAX_RETRIES)]
+    max_retries: u32,
+
+    /// How the ACK wait grows with each retry: fixed or exponential
+    #[arg(long, default_value_t = BackoffKind::Fixed)]
+    backoff: BackoffKind,
+
+    /// Multiplier applied to the ACK wait on each retry with --backoff exponential
+    #[arg(long, default_value_t = retry::DEFAULT_BACKOFF_FACTOR, value_parser = parse_backoff_factor)]
+    backoff_factor: f64,
+
+    /// Upper bound in seconds for the ACK wait with --backoff exponential
+    #[arg(long, default_value_t = retry::DEFAULT_MAX_BACKOFF.as_secs(), value_parser = clap::value_parser!(u64).range(1..))]
+    max_backoff: u64,
+
+    /// Randomize each ACK wait by up to ±this percent so messages that missed their ACKs together are not retried in one burst
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=100))]
+    retry_jitter: u32,
+
+    /// Expire a message this many seconds after it was first sent if still unACKed, instead of retrying it
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    message_ttl: Option<u64>,
+
+    /// With --message-ttl, move expired messages to the stream's dead letters instead of dropping them
+    #[arg(long, requires = "message_ttl")]
+    expired_to_dlq: bool,
+
+    /// Stop sending new messages on a stream while this many are waiting for ACKs (sliding window; unlimited when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_in_flight: Option<u64>,
+
+    /// Send messages in DataBatch frames of this many (retries too); clients ACK a whole batch at once
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    batch_size: u64,
+
+    /// Give messages priorities 0..N (message ID modulo N); when the client falls behind, higher priorities and their retries are sent first
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    priority_levels: u32,
+
+    /// When a message is retried, resend every later in-flight message after it and hold back new ones, so a client can process strictly in order
+    #[arg(long, conflicts_with = "priority_levels")]
+    ordered_redelivery: bool,
+
+    /// Share this many new-message sends per second across all streams in weighted round-robin order (x-stream-weight metadata, default 1; 0 only gets slots nobody else wants) instead of every stream sending once a second
+    #[arg(long, value_parser = parse_fair_share_rate)]
+    fair_share_rate: Option<f64>,
+
+    /// Multiplex this many independent channels over each stream, each with its own message IDs, pending queue and retry timers (clients echo DataMessage.channel in their ACKs)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    channels: u32,
+
+    /// Probability (0..1) of silently skipping each outgoing frame while still recording it as sent, to exercise ACK timeouts and retries without the proxy
+    #[arg(long, default_value_t = 0.0, value_parser = parse_drop_rate)]
+    send_drop_rate: f64,
+
+    /// Seed for --send-drop-rate so runs skip the same frames (random when unset)
+    #[arg(long, requires = "send_drop_rate")]
+    send_drop_seed: Option<u64>,
+
+    /// Hold up to this many outgoing data frames per stream and release them in random order, to test client-side reordering (held time counts against the ACK timeout; retries release everything held)
+    #[arg(long, default_value_t = 0)]
+    shuffle_window: usize,
+
+    /// Seed for --shuffle-window so runs release frames in the same order (random when unset)
+    #[arg(long, requires = "shuffle_window")]
+    shuffle_seed: Option<u64>,
+
+    /// Fill each payload with this many random alphanumeric bytes instead of "Message N", so the send channel and HTTP/2 windows actually fill up (at most 4 MB, tonic's default decode limit)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..=4_000_000))]
+    payload_bytes: Option<u64>,
+
+    /// Wait up to this many milliseconds for the client's Hello before sending the first message, so batching and checksums match its features from the start (no wait when unset)
+    #[arg(long)]
+    hello_timeout_ms: Option<u64>,
+
+    /// Log a one-line stats summary (active streams, msgs/sec, queued, cancellations since the last one) every N seconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    stats_interval: Option<u64>,
+
+    /// Shut down once every stream has finished (after at least one); exit code 1 if any stream ended without completing
+    #[arg(long)]
+    exit_when_done: bool,
+
+    /// Seconds between HTTP/2 keepalive PINGs on each connection (off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    http2_keepalive_interval: Option<u64>,
+
+    /// Seconds to wait for a keepalive PING ack before closing the connection (tonic default 20)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive_timeout: Option<u64>,
+
+    /// Seconds after which each connection is sent GOAWAY and closed once its streams finish (off when unset)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_connection_age: Option<u64>,
+
+    /// Compress streamed responses with gzip or zstd when the client advertises it in grpc-accept-encoding (uncompressed when unset)
+    #[arg(long, value_parser = parse_encoding)]
+    send_compressed: Option<CompressionEncoding>,
+
+    /// Comma-separated encodings (gzip, zstd) accepted on compressed client messages (ACKs)
+    #[arg(long, value_delimiter = ',', value_parser = parse_encoding)]
+    accept_compressed: Vec<CompressionEncoding>,
+
+    /// Serve DebugService (ListPending, ForceRetry, DropPending) so test scripts can inspect and change retry state; it can drop messages, so leave it off outside experiments
+    #[arg(long)]
+    debug_service: bool,
+
+    /// Append one JSON line per stream lifecycle event (opened, sent, cancelled, closed) to this file instead of PLAYGROUND_AUDIT_DIR
+    #[arg(long)]
+    event_log: Option<PathBuf>,
+
+    /// Write a JSON delivery report (sent, retries, per-message ACK latencies, outcome) per stream into this directory when it ends
+    #[arg(long)]
+    report_dir: Option<PathBuf>,
+
+    /// Log output format
+    #[arg(long, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+
+    /// OTLP (gRPC) collector endpoint for exporting stream lifecycle spans, e.g. http://localhost:4317 (off when unset)
+    #[arg(long, env = playground_telemetry::OTLP_ENDPOINT_ENV)]
+    otlp_endpoint: Option<String>,
+
+    /// Serve tokio-console task instrumentation (TOKIO_CONSOLE_BIND, default 127.0.0.1:6669)
+    #[cfg(feature = "console")]
+    #[arg(long)]
+    console: bool,
+
+    #[command(flatten)]
+    tls: TlsArgs,
+
+    #[command(flatten)]
+    auth: AuthArgs,
+}
+
+/// `--send-compressed`/`--accept-compressed` 값 - tonic이 지원하는 gzip, zstd
+fn parse_encoding(value: &str) -> Result<CompressionEncoding, String> {
+    match value {
+        "gzip" => Ok(CompressionEncoding::Gzip),
+        "zstd" => Ok(CompressionEncoding::Zstd),
+        _ => Err(format!("unknown compression '{}' (expected gzip or zstd)", value)),
+    }
 }
 
-use streaming::{
-    streaming_service_server::{StreamingService, StreamingServiceServer},
-    DataMessage, StreamMessage,
-};
+/// `--backoff-factor` 값 - 1보다 작으면 재전송할수록 대기가 짧아지므로 거절
+fn parse_backoff_factor(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(factor) if factor >= 1.0 && factor.is_finite() => Ok(factor),
+        Ok(_) => Err(format!("backoff factor must be at least 1, got {}", value)),
+        Err(e) => Err(e.to_string()),
+    }
+}
 
-#[derive(Debug, Clone)]
-struct PendingMessage {
-    message: DataMessage,
-    sent_at: u64,
-    retry_count: u32,
+/// `--fair-share-rate` 값 - 초당 슬롯 수, 0보다 커야 함
+fn parse_fair_share_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate),
+        Ok(_) => Err(format!("fair share rate must be greater than 0, got {}", value)),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
-#[derive(Default)]
-struct StreamingServer {
-    pending_messages: Arc<Mutex<HashMap<u64, PendingMessage>>>,
-    total_messages: u64,
+/// `--send-drop-rate` 값 - 0..=1 확률
+fn parse_drop_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        Ok(_) => Err(format!("send drop rate must be between 0 and 1, got {}", value)),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
-#[tonic::async_trait]
-impl StreamingService for StreamingServer {
-    type BidirectionalStreamStream = ReceiverStream<Result<StreamMessage, Status>>;
-
-    async fn bidirectional_stream(
-        &self,
-        request: Request<Streaming<StreamMessage>>,
-    ) -> Result<Response<Self::BidirectionalStreamStream>, Status> {
-        let mut in_stream = request.into_inner();
-        let (tx, rx) = mpsc::channel(128);
-        let pending_messages = self.pending_messages.clone();
-        let pending_messages_sender = pending_messages.clone();
-        let pending_messages_retry = pending_messages.clone();
-        let pending_messages_ack = pending_messages.clone();
-
-        let tx_clone = tx.clone();
-        let total_messages = self.total_messages;
-        let message_sending_finished = Arc::new(tokio::sync::Notify::new());
-        let message_sending_finished_notify = message_sending_finished.clone();
-        
-        let message_sender = tokio::spawn(async move {
-            for message_id in 1..=total_messages {
-                let current_time = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-
-                let data_msg = DataMessage {
-                    id: message_id,
-                    timestamp: current_time,
-                    payload: format!("Message {}", message_id),
-                    needs_ack: true,
-                };
-
-                let pending_msg = PendingMessage {
-                    message: data_msg.clone(),
-                    sent_at: current_time,
-                    retry_count: 0,
-                };
-
-                {
-                    let mut pending = pending_messages_sender.lock().await;
-                    pending.insert(message_id, pending_msg);
-                }
-
-                let stream_msg = StreamMessage {
-                    message_type: Some(streaming::stream_message::MessageType::Data(data_msg)),
-                };
-
-                if tx_clone.send(Ok(stream_msg)).await.is_err() {
-                    break;
-                }
-
-                println!("[RUST SERVER] Sent message {}/{}", message_id, total_messages);
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            }
-            
-            println!("[RUST SERVER] All {} messages sent, waiting for ACKs and retries...", total_messages);
-            // retry handler에게 메시지 전송 완료 알림
-            message_sending_finished_notify.notify_one();
-        });
+/// `console` feature로 빌드하고 `--console`을 주면 tokio-console 계층도 설치
+fn init_telemetry(args: &Args) -> TelemetryGuard {
+    #[cfg(feature = "console")]
+    if args.console {
+        return playground_telemetry::init_with_console("grpc-stream-ack-server", args.log_format, args.otlp_endpoint.as_deref());
+    }
+    playground_telemetry::init_with_endpoint("grpc-stream-ack-server", args.log_format, args.otlp_endpoint.as_deref())
+}
 
-        let tx_retry = tx.clone();
-        let message_sending_finished_clone = message_sending_finished.clone();
-        
-        let retry_handler = tokio::spawn(async move {
-            let mut retry_interval = tokio::time::interval(Duration::from_secs(2));
-            let mut message_sending_done = false;
-            
-            loop {
-                tokio::select! {
-                    _ = retry_interval.tick() => {
-                        let current_time = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs();
-
-                        let mut to_retry = Vec::new();
-                        let mut all_completed = false;
-                        
-                        {
-                            let mut pending = pending_messages_retry.lock().await;
-                            
-                            // 재전송할 메시지 찾기
-                            for (id, msg) in pending.iter_mut() {
-                                if current_time - msg.sent_at > 2 && msg.retry_count < 3 {
-                                    msg.retry_count += 1;
-                                    msg.sent_at = current_time;
-                                    to_retry.push((*id, msg.message.clone()));
-                                } else if msg.retry_count >= 3 {
-                                    println!("[RUST SERVER] Message {} failed after 3 retries", id);
-                                }
-                            }
-                            
-                            // 모든 메시지가 완료되었는지 확인 (메시지 전송이 끝나고 pending이 비어있을 때만)
-                            all_completed = message_sending_done && pending.is_empty();
-                        }
-
-                        // 재전송
-                        for (id, data_msg) in to_retry {
-                            println!("[RUST SERVER] Retrying message {}", id);
-                            let stream_msg = StreamMessage {
-                                message_type: Some(streaming::stream_message::MessageType::Data(data_msg)),
-                            };
-                            
-                            if tx_retry.send(Ok(stream_msg)).await.is_err() {
-                                println!("[RUST SERVER] Failed to send retry message, stopping retry handler");
-                                return;
-                            }
-                        }
-                        
-                        // 모든 메시지가 완료되면 종료
-                        if all_completed {
-                            println!("[RUST SERVER] All messages completed, stopping retry handler");
-                            break;
-                        }
-                    }
-                    _ = message_sending_finished_clone.notified() => {
-                        println!("[RUST SERVER] Message sending finished, retry handler will continue until all ACKs received");
-                        message_sending_done = true;
-                    }
-                }
-            }
-        });
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let message_count = args.message_count;
+
+    let telemetry = init_telemetry(&args);
+    let shutdown = Shutdown::install();
+
+    let addr = args.listen_addr;
+    let stats = Arc::new(ServerStats::default());
+    let clock: SharedClock = Arc::new(SystemClock);
+    let bus = playground_bus::from_env().await?;
+    let audit = match &args.event_log {
+        Some(path) => AuditLog::open_file(path, "grpc-stream-ack-server", clock.clone())?,
+        None => AuditLog::from_env("grpc-stream-ack-server", clock.clone())?,
+    };
+    let retry_policy = RetryPolicy {
+        ack_timeout: Duration::from_secs(args.ack_timeout),
+        max_retries: args.max_retries,
+        backoff: args.backoff,
+        backoff_factor: args.backoff_factor,
+        max_backoff: Duration::from_secs(args.max_backoff),
+        jitter: f64::from(args.retry_jitter) / 100.0,
+        message_ttl: args.message_ttl.map(Duration::from_secs),
+        expired_to_dlq: args.expired_to_dlq,
+    };
+    let mut streaming_server = StreamingServer::new(message_count, stats.clone(), clock)
+        .with_bus(bus.clone())
+        .with_audit(audit.clone())
+        .with_retry_policy(retry_policy)
+        .with_batch_size(args.batch_size as usize)
+        .with_priority_levels(args.priority_levels)
+        .with_channels(args.channels)
+        .with_send_drops(SendDrops::new(args.send_drop_rate, args.send_drop_seed))
+        .with_shuffle(Shuffle::new(args.shuffle_window, args.shuffle_seed));
+    if let Some(max) = args.max_in_flight {
+        streaming_server = streaming_server.with_max_in_flight(max as usize);
+    }
+    if args.ordered_redelivery {
+        streaming_server = streaming_server.with_ordered_redelivery();
+    }
+    if let Some(dir) = &args.report_dir {
+        streaming_server = streaming_server.with_report_dir(ReportDir::create(dir)?);
+    }
+    if let Some(rate) = args.fair_share_rate {
+        streaming_server = streaming_server.with_fair_share(FairShare::new(rate, stats.clone()));
+    }
+    if let Some(bytes) = args.payload_bytes {
+        streaming_server = streaming_server.with_payload_bytes(bytes as usize);
+    }
+    if let Some(timeout) = args.hello_timeout_ms {
+        streaming_server = streaming_server.with_hello_timeout(Duration::from_millis(timeout));
+    }
+
+    info!(
+        address = %addr,
+        message_count,
+        bus = bus.name(),
+        broker_redelivery = bus.redelivers(),
+        audit_log = audit.path().map(|path| path.display().to_string()),
+        ack_timeout_secs = args.ack_timeout,
+        max_retries = args.max_retries,
+        backoff = %args.backoff,
+        backoff_factor = args.backoff_factor,
+        max_backoff_secs = args.max_backoff,
+        retry_jitter_percent = args.retry_jitter,
+        message_ttl_secs = args.message_ttl,
+        expired_to_dlq = args.expired_to_dlq,
+        max_in_flight = args.max_in_flight,
+        batch_size = args.batch_size,
+        priority_levels = args.priority_levels,
+        ordered_redelivery = args.ordered_redelivery,
+        channels = args.channels,
+        fair_share_rate = args.fair_share_rate,
+        send_drop_rate = args.send_drop_rate,
+        send_drop_seed = args.send_drop_seed,
+        shuffle_window = args.shuffle_window,
+        shuffle_seed = args.shuffle_seed,
+        hello_timeout_ms = args.hello_timeout_ms,
+        payload_bytes = args.payload_bytes,
+        report_dir = args.report_dir.as_ref().map(|dir| dir.display().to_string()),
+        http2_keepalive_interval_secs = args.http2_keepalive_interval,
+        keepalive_timeout_secs = args.keepalive_timeout,
+        max_connection_age_secs = args.max_connection_age,
+        send_compressed = ?args.send_compressed,
+        accept_compressed = ?args.accept_compressed,
+        stats_interval_secs = args.stats_interval,
+        exit_when_done = args.exit_when_done,
+        debug_service = args.debug_service,
+        log_format = %args.log_format,
+        otlp_endpoint = args.otlp_endpoint.as_deref(),
+        tls = args.tls.mode(),
+        auth = args.auth.mode(),
+        "Starting gRPC server (messages sent at 1-second intervals)"
+    );
 
-        let ack_handler = tokio::spawn(async move {
-            while let Some(message) = in_stream.next().await {
-                match message {
-                    Ok(stream_msg) => {
-                        if let Some(streaming::stream_message::MessageType::Ack(ack)) = stream_msg.message_type {
-                            println!("[RUST SERVER] Received ACK for message {}", ack.ack_id);
-                            let mut pending = pending_messages_ack.lock().await;
-                            pending.remove(&ack.ack_id);
-                        }
-                    }
-                    Err(e) => {
-                        println!("[RUST SERVER] Error receiving message: {}", e);
-                        break;
-                    }
-                }
+    if let Some(metrics_addr) = args.metrics_addr {
+        info!(address = %metrics_addr, "Serving Prometheus metrics at /metrics");
+        let metrics = playground_stats::metrics::serve(metrics_addr, "grpc-stream-ack-server", stats.clone(), shutdown.token());
+        tokio::spawn(async move {
+            if let Err(e) = metrics.await {
+                warn!(error = %e, "Metrics endpoint failed");
             }
         });
+    }
 
-        // 모든 작업 완료 후 스트림 종료
+    if let Some(interval) = args.stats_interval {
+        tokio::spawn(playground_stats::summary::log_every(stats.clone(), Duration::from_secs(interval), shutdown.token()));
+    }
+
+    // CI 시나리오용 - 열린 스트림이 모두 끝나면 신호를 받은 것처럼 종료
+    let done_stats = stats.clone();
+    if args.exit_when_done {
+        let shutdown = shutdown.clone();
+        let stats = stats.clone();
         tokio::spawn(async move {
-            // 메시지 전송 완료 대기
-            let _ = message_sender.await;
-            println!("[RUST SERVER] Message sending completed, waiting for retries to finish...");
-            
-            // 재전송 핸들러 완료 대기
-            let _ = retry_handler.await;
-            
-            // 모든 채널 닫기
-            drop(tx);
-            println!("[RUST SERVER] All messages processed, closing stream");
-            
-            // ACK 핸들러 완료 대기
-            let _ = ack_handler.await;
-            println!("[RUST SERVER] Stream closed completely");
+            stats.all_streams_finished().await;
+            info!(
+                completed = stats.completed_streams.load(Ordering::Relaxed),
+                cancelled = stats.cancelled_streams.load(Ordering::Relaxed),
+                "All streams finished"
+            );
+            shutdown.trigger("--exit-when-done");
         });
-
-        Ok(Response::new(ReceiverStream::new(rx)))
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    let message_count = if args.len() > 1 {
-        args[1].parse::<u64>().unwrap_or(10)
-    } else {
-        10
-    };
+    // 서버 실행 (스트림이 자동으로 종료되면 서버도 종료됨)
+    // 표준 gRPC 헬스 체크 - 종료가 시작되면 drain 동안 NOT_SERVING (Watch 중인 프로브가 바로 알 수 있음)
+    let (mut health, health_service) = tonic_health::server::health_reporter();
+    health.set_serving::<StreamingServiceServer<StreamingServer>>().await;
+    let health_token = shutdown.token();
+    tokio::spawn(async move {
+        health_token.cancelled().await;
+        health.set_not_serving::<StreamingServiceServer<StreamingServer>>().await;
+        health.set_service_status("", ServingStatus::NotServing).await;
+    });
 
-    let addr = "[::1]:50051".parse()?;
-    let streaming_server = StreamingServer {
-        pending_messages: Arc::new(Mutex::new(HashMap::new())),
-        total_messages: message_count,
+    // 주기적 PING / GOAWAY로 연결을 정리 - 재연결 로직이 이를 어떻게 견디는지 관찰용
+    let mut builder = Server::builder()
+        .http2_keepalive_interval(args.http2_keepalive_interval.map(Duration::from_secs))
+        .http2_keepalive_timeout(args.keepalive_timeout.map(Duration::from_secs));
+    if let Some(age) = args.max_connection_age {
+        builder = builder.max_connection_age(Duration::from_secs(age));
+    }
+    if let Some(tls) = args.tls.server_config()? {
+        builder = builder.tls_config(tls)?;
+    }
+    // 헬스 체크, 리플렉션과 통계는 인증 없이 - 프로브/대시보드/grpcurl이 토큰 없이 볼 수 있게
+    let auth = args.auth.authenticator();
+    // grpcurl/grpcui가 proto 파일 없이 서비스를 조회하도록 리플렉션 (v1과 구버전 도구용 v1alpha)
+    let reflection = || {
+        let builder = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(grpc_stream_ack_server::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(playground_stats::proto::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET);
+        if args.debug_service {
+            builder.register_encoded_file_descriptor_set(grpc_stream_ack_server::debug::proto::FILE_DESCRIPTOR_SET)
+        } else {
+            builder
+        }
     };
+    // 스트리밍 서비스로 옮기기 전에 같은 세션 테이블을 보는 DebugService를 만들어 둠
+    let debug_service = args
+        .debug_service
+        .then(|| InterceptedService::new(DebugServiceServer::new(streaming_server.debug_service()), auth.clone()));
+    // 압축은 스트리밍 서비스에만 - 대역폭을 줄인 프록시 너머로 큰 payload를 보내는 경우용
+    let mut streaming_service = StreamingServiceServer::new(streaming_server);
+    if let Some(encoding) = args.send_compressed {
+        streaming_service = streaming_service.send_compressed(encoding);
+    }
+    for encoding in &args.accept_compressed {
+        streaming_service = streaming_service.accept_compressed(*encoding);
+    }
+    let server = builder
+        .add_service(health_service)
+        .add_service(reflection().build_v1()?)
+        .add_service(reflection().build_v1alpha()?)
+        .add_service(InterceptedService::new(streaming_service, auth))
+        .add_service(StatsService::new("grpc-stream-ack-server", stats))
+        .add_optional_service(debug_service)
+        .serve_with_shutdown(addr, shutdown.token().cancelled_owned());
 
-    println!("[RUST SERVER] Starting gRPC server on {}", addr);
-    println!("[RUST SERVER] Will send {} messages at 1-second intervals", message_count);
+    // 신호를 받으면 새 연결을 받지 않고, 진행 중인 스트림을 기다린 뒤 span을 flush
+    if let Some(result) = shutdown.drain(server, DEFAULT_DRAIN_TIMEOUT).await {
+        result?;
+    }
+    shutdown.advance(Phase::Flush);
+    drop(telemetry);
+    shutdown.advance(Phase::Exit);
 
-    // 서버 실행 (스트림이 자동으로 종료되면 서버도 종료됨)
-    Server::builder()
-        .add_service(StreamingServiceServer::new(streaming_server))
-        .serve(addr)
-        .await?;
+    if args.exit_when_done && done_stats.cancelled_streams.load(Ordering::Relaxed) > 0 {
+        std::process::exit(1);
+    }
 
     Ok(())
 }
@@ -0,0 +1,125 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+
+use playground_stats::ServerStats;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::Instant;
+use tonic::Status;
+
+use crate::streaming::StreamMessage;
+
+type Frame = Result<StreamMessage, Status>;
+
+/// 스트림으로 나갈 프레임의 우선순위 큐
+///
+/// 송신 태스크의 새 메시지와 재전송 핸들러의 재전송이 모두 여기를 거침. 클라이언트(HTTP/2 flow control)가
+/// 따라오지 못해 프레임이 쌓이면 우선순위가 높은 것부터, 같으면 먼저 넣은 것부터 보냄.
+#[derive(Clone, Default)]
+pub struct Outbox {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<State>,
+    changed: Notify,
+}
+
+#[derive(Default)]
+struct State {
+    frames: BinaryHeap<Queued>,
+    pushed: u64,
+    closed: bool,
+}
+
+struct Queued {
+    priority: u32,
+    seq: u64,
+    /// 넣은 시각 - 채널로 넘어갈 때까지 기다린 시간을 `send_blocked`에 기록
+    queued_at: Instant,
+    frame: Frame,
+}
+
+impl Queued {
+    fn key(&self) -> (u32, Reverse<u64>) {
+        (self.priority, Reverse(self.seq))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Outbox {
+    /// 프레임을 넣음 - 클라이언트 스트림이 닫혔거나 outbox를 닫았으면 false
+    pub fn push(&self, priority: u32, frame: Frame) -> bool {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.closed {
+            return false;
+        }
+        let seq = state.pushed;
+        state.pushed += 1;
+        state.frames.push(Queued {
+            priority,
+            seq,
+            queued_at: Instant::now(),
+            frame,
+        });
+        drop(state);
+        self.inner.changed.notify_one();
+        true
+    }
+
+    /// 더 이상 받지 않음 - 남은 프레임은 `run`이 마저 보내고 끝남
+    pub fn close(&self) {
+        self.inner.state.lock().unwrap().closed = true;
+        self.inner.changed.notify_one();
+    }
+
+    /// 채널에 자리가 날 때마다 그 시점에 가장 급한 프레임을 보냄 - 닫고 다 보냈거나 클라이언트가 끊기면 종료
+    ///
+    /// 프레임마다 outbox에서 기다린 시간을 `stats.delivery.send_blocked`에 기록 - 클라이언트가 읽지 않거나
+    /// HTTP/2 윈도가 차서 채널이 비지 않는 동안 늘어남.
+    pub async fn run(self, tx: mpsc::Sender<Frame>, stats: Arc<ServerStats>) {
+        // 자리를 먼저 확보한 뒤에 고름 - 기다리는 동안 들어온 급한 프레임이 앞설 수 있도록
+        while let Ok(permit) = tx.reserve().await {
+            loop {
+                let (next, closed) = {
+                    let mut state = self.inner.state.lock().unwrap();
+                    (state.frames.pop(), state.closed)
+                };
+                if let Some(queued) = next {
+                    stats.delivery.send_blocked.observe(queued.queued_at.elapsed().as_secs_f64());
+                    permit.send(queued.frame);
+                    break;
+                }
+                if closed {
+                    return;
+                }
+                tokio::select! {
+                    _ = self.inner.changed.notified() => {}
+                    _ = tx.closed() => break,
+                }
+            }
+        }
+        // 클라이언트가 끊김 - 이후 push는 실패
+        self.close();
+    }
+}
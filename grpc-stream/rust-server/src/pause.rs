@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+/// 클라이언트가 `Pause`/`Resume` 프레임으로 켜고 끄는 전송 중지 상태
+///
+/// 전송 계층(HTTP/2 flow control) 위에서 소비자가 직접 조절하는 흐름 제어. 멈춘 동안 새 메시지는
+/// 보내지 않고, `pause_retries`로 멈췄으면 재전송도 pending 큐에 쌓아 두었다가 재개할 때 보냄.
+#[derive(Clone, Default)]
+pub struct PauseGate {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<Paused>,
+    resumed: Notify,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Paused {
+    #[default]
+    Running,
+    /// 새 메시지만 멈춤 - 이미 보낸 메시지의 재전송은 계속
+    NewMessages,
+    /// 재전송도 멈춤
+    Everything,
+}
+
+impl PauseGate {
+    pub fn state(&self) -> Paused {
+        *self.inner.state.lock().unwrap()
+    }
+
+    /// 전송을 멈춤 - 이미 멈춘 상태에서 다시 받으면 `retries`만 새 값으로 바뀜
+    pub fn pause(&self, retries: bool) {
+        *self.inner.state.lock().unwrap() = if retries { Paused::Everything } else { Paused::NewMessages };
+    }
+
+    /// 멈춘 전송을 재개하고 기다리던 태스크를 깨움 - 멈춰 있지 않았으면 false
+    pub fn resume(&self) -> bool {
+        let previous = std::mem::take(&mut *self.inner.state.lock().unwrap());
+        self.inner.resumed.notify_waiters();
+        previous != Paused::Running
+    }
+
+    /// 지금 새 메시지(`retry`면 재전송)를 보내면 안 되는지
+    pub fn holds(&self, retry: bool) -> bool {
+        match self.state() {
+            Paused::Running => false,
+            Paused::NewMessages => !retry,
+            Paused::Everything => true,
+        }
+    }
+
+    /// 새 메시지(`retry`면 재전송)를 보내도 될 때까지 기다림
+    pub async fn released(&self, retry: bool) {
+        loop {
+            // 상태를 보기 전에 등록해야 그 사이의 resume을 놓치지 않음
+            let resumed = self.inner.resumed.notified();
+            if !self.holds(retry) {
+                return;
+            }
+            resumed.await;
+        }
+    }
+}
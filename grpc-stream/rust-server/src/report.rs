@@ -0,0 +1,75 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use playground_audit::Outcome;
+use serde::{Deserialize, Serialize};
+
+use crate::client::ClientKey;
+
+/// 스트림 하나가 끝났을 때의 전달 보고서 - `--report-dir` 아래에 JSON 파일 하나
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryReport {
+    /// `x-client-id` (없으면 `stream-{stream_id}`)
+    pub client: String,
+    pub stream_id: u64,
+    /// 같은 `x-client-id`로 스트림을 연 횟수
+    pub epoch: u64,
+    pub outcome: Outcome,
+    pub started_at_ms: u64,
+    pub ended_at_ms: u64,
+    pub sent: u64,
+    pub acked: u64,
+    pub retried: u64,
+    pub dead_lettered: u64,
+    pub expired: u64,
+    /// ACK를 받을 때까지 다시 보내지 못한 메시지 수
+    pub abandoned: u64,
+    /// ACK 받은 순서대로 메시지마다 마지막 전송부터의 지연과 재전송 횟수
+    pub acks: Vec<AckedMessage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AckedMessage {
+    pub id: u64,
+    pub latency_ms: u64,
+    pub retries: u32,
+}
+
+/// 보고서를 쓰는 디렉터리
+#[derive(Debug, Clone)]
+pub struct ReportDir {
+    dir: PathBuf,
+}
+
+impl ReportDir {
+    /// 없으면 만듦
+    pub fn create(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `{client}-epoch{epoch}.json` - 재연결한 클라이언트는 스트림마다 파일이 따로 남음
+    ///
+    /// `x-client-id`는 클라이언트가 정한 값이므로 파일 이름에 쓸 수 없는 문자는 `_`로 바꿈.
+    pub fn file_for(&self, client: &ClientKey, epoch: u64) -> PathBuf {
+        let name: String = client
+            .to_string()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}-epoch{}.json", name.trim_start_matches('.'), epoch))
+    }
+
+    /// 보고서를 쓰고 경로를 반환 - 같은 클라이언트와 epoch의 이전 파일은 덮어씀
+    pub fn write(&self, client: &ClientKey, report: &DeliveryReport) -> io::Result<PathBuf> {
+        let path = self.file_for(client, report.epoch);
+        let json = serde_json::to_vec_pretty(report).map_err(io::Error::other)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
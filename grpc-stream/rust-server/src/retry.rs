@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use durable_queue::{Backoff, QueueConfig};
+
+/// ACK 타임아웃 기본값
+pub const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+/// 메시지당 최대 재전송 횟수 기본값 (at-least-once 중복 상한)
+pub const MAX_RETRIES: u32 = 3;
+/// exponential backoff에서 재전송마다 곱하는 값 기본값
+pub const DEFAULT_BACKOFF_FACTOR: f64 = 2.0;
+/// exponential backoff의 ACK 대기 상한 기본값
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// 재전송할 때마다 ACK 대기 시간을 어떻게 늘릴지 (`--backoff`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackoffKind {
+    /// 매번 `--ack-timeout` (기본)
+    #[default]
+    Fixed,
+    /// `--ack-timeout × --backoff-factor^재전송 횟수`, `--max-backoff`까지
+    Exponential,
+}
+
+impl FromStr for BackoffKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "fixed" => Ok(BackoffKind::Fixed),
+            "exponential" => Ok(BackoffKind::Exponential),
+            _ => Err(format!("unknown backoff '{}' (expected fixed or exponential)", value)),
+        }
+    }
+}
+
+impl fmt::Display for BackoffKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BackoffKind::Fixed => "fixed",
+            BackoffKind::Exponential => "exponential",
+        })
+    }
+}
+
+/// 스트림마다 pending 큐에 적용하는 재전송 정책
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// 이 시간보다 오래 ACK가 없으면 재전송 - 메시지마다 이 시간(backoff, jitter 반영)에 맞춰 타이머를 걺
+    pub ack_timeout: Duration,
+    pub max_retries: u32,
+    pub backoff: BackoffKind,
+    pub backoff_factor: f64,
+    pub max_backoff: Duration,
+    /// ACK 대기를 메시지마다 ±이 비율(0.0~1.0) 안에서 흔듦 - 함께 ACK를 놓친 메시지들이 한꺼번에 재전송되지 않도록
+    pub jitter: f64,
+    /// 최초 발행 후 이 시간이 지나도록 ACK가 없으면 재전송하지 않고 pending에서 뺌 (None이면 만료 없음)
+    pub message_ttl: Option<Duration>,
+    /// 만료된 메시지를 버리지 않고 pending 큐의 dead letter로 옮김
+    pub expired_to_dlq: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+            max_retries: MAX_RETRIES,
+            backoff: BackoffKind::Fixed,
+            backoff_factor: DEFAULT_BACKOFF_FACTOR,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: 0.0,
+            message_ttl: None,
+            expired_to_dlq: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 최초 전송 + `max_retries`번까지 전달하고 나면 dead letter가 되는 pending 큐 설정
+    pub fn queue_config(&self) -> QueueConfig {
+        let backoff = match self.backoff {
+            BackoffKind::Fixed => Backoff::Fixed,
+            BackoffKind::Exponential => Backoff::Exponential {
+                factor: self.backoff_factor,
+                max: self.max_backoff,
+            },
+        };
+        QueueConfig {
+            capacity: usize::MAX,
+            visibility_timeout: self.ack_timeout,
+            backoff,
+            jitter: self.jitter,
+            max_deliveries: Some(self.max_retries + 1),
+            ..QueueConfig::default()
+        }
+    }
+
+    /// 메시지의 기한 (unix ms) - `published_ms`(발행 시각, unix ms)에 TTL을 더함 (TTL이 없으면 None)
+    ///
+    /// `DataMessage.deadline_unix_ms`로 클라이언트에도 알림. 재전송해도 발행 시각은 그대로라 기한도 같음.
+    pub fn deadline_ms(&self, published_ms: u64) -> Option<u64> {
+        self.message_ttl
+            .map(|ttl| published_ms.saturating_add(ttl.as_millis() as u64))
+    }
+
+    /// 재전송할 가치가 없어진 메시지인지 - `now_ms`(unix ms)가 기한을 지남
+    pub fn is_expired(&self, published_ms: u64, now_ms: u64) -> bool {
+        self.deadline_ms(published_ms).is_some_and(|deadline| now_ms >= deadline)
+    }
+}
@@ -0,0 +1,47 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+
+/// 전송 실패 흉내 - 프레임을 보낸 것으로 기록하고 실제로는 outbox에 넣지 않음
+///
+/// 외부 프록시 없이 ACK 타임아웃과 재전송 경로를 시험할 때 사용. `seed`가 있으면 스트림마다
+/// `seed + stream_id`로 RNG를 만들어 같은 실행을 반복해도 같은 프레임이 빠짐.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SendDrops {
+    /// 프레임 하나가 빠질 확률 (0..=1)
+    pub rate: f64,
+    pub seed: Option<u64>,
+}
+
+impl SendDrops {
+    pub fn new(rate: f64, seed: Option<u64>) -> Self {
+        Self {
+            rate: rate.clamp(0.0, 1.0),
+            seed,
+        }
+    }
+
+    /// 스트림 하나의 판정기 - 비율이 0이면 None
+    pub(crate) fn for_stream(&self, stream_id: u64) -> Option<SendDropper> {
+        (self.rate > 0.0).then(|| SendDropper {
+            rate: self.rate,
+            rng: Arc::new(Mutex::new(
+                self.seed
+                    .map_or_else(StdRng::from_entropy, |seed| StdRng::seed_from_u64(seed.wrapping_add(stream_id))),
+            )),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct SendDropper {
+    rate: f64,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl SendDropper {
+    /// 이번 프레임을 빠뜨릴지
+    pub(crate) fn drops(&self) -> bool {
+        self.rng.lock().unwrap().gen_bool(self.rate)
+    }
+}
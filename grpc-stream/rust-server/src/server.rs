@@ -0,0 +1,1325 @@
+use durable_queue::{DurableQueue, Lease, Nacked};
+use playground_audit::{AuditLog, Outcome, StreamAudit};
+use playground_bus::{BusMessage, Delivery, InMemoryBus, SharedBus};
+use playground_clock::SharedClock;
+use playground_stats::{upload, ServerStats};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt, StreamMap};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{field, info, info_span, warn, Instrument, Span};
+
+use crate::acked::{AckedIds, UnmatchedAck};
+use crate::batch::Batches;
+use crate::channels::{self, CHANNEL_INBOUND_BUFFER};
+use crate::checksum::checksum;
+use crate::client::{ClientInfo, ClientKey};
+use crate::debug::proto::{PendingEntry, SessionSnapshot};
+use crate::debug::DebugService;
+use crate::delivery::DeliveryTimes;
+use crate::fair_share::FairShare;
+use crate::hello::{self, Handshake};
+use crate::incoming::{handle_incoming, Incoming, Redelivery};
+use crate::outbox::Outbox;
+use crate::pause::PauseGate;
+use crate::report::{DeliveryReport, ReportDir};
+use crate::retry::RetryPolicy;
+use crate::send_drop::{SendDropper, SendDrops};
+use crate::shuffle::{Shuffle, Shuffler};
+use crate::state_lock::StateLock;
+use crate::summary::StreamTally;
+use crate::timers::RetryTimers;
+use crate::streaming::{
+    self,
+    streaming_service_server::StreamingService,
+    DataBatch, DataMessage, StreamMessage, UploadSummary,
+};
+
+/// 메시지 전송 간격
+const SEND_INTERVAL: Duration = Duration::from_secs(1);
+/// 스트림 채널에 미리 넣어두는 프레임 수 - 나머지는 outbox에서 보낼 차례가 될 때 우선순위로 고름
+const OUTBOUND_BUFFER: usize = 1;
+
+pub(crate) type ClientSessions = Arc<Mutex<HashMap<ClientKey, ClientSession>>>;
+
+/// 클라이언트 하나의 pending 큐와 전송 위치
+///
+/// `x-client-id` 클라이언트는 스트림이 끝나도 남은 메시지가 있으면 세션을 남겨두고,
+/// `x-last-acked-id`로 재연결하면 이어서 보냄.
+pub(crate) struct ClientSession {
+    pub(crate) pending: DurableQueue<BusMessage>,
+    topic: String,
+    /// 다음에 새로 발행할 메시지 id
+    next_id: Arc<AtomicU64>,
+    /// 처음 보낸 id 중 가장 큰 것 - `DebugService::snapshot`
+    highest_sent: Arc<AtomicU64>,
+    /// ACK한 id - pending에 없는 id의 ACK를 중복, 늦은 ACK, 보낸 적 없는 id로 구분
+    acked: AckedIds,
+    /// 이 세션으로 스트림을 연 횟수 - 재연결한 클라이언트가 `DataMessage.epoch`로 이전 스트림의 전송과 구분
+    epoch: u64,
+    /// 이 세션의 스트림이 열려 있음
+    pub(crate) open: bool,
+    /// 열린 스트림의 태스크 - `DebugService`가 pending 큐를 바꾼 뒤 알림
+    pub(crate) hooks: Option<StreamHooks>,
+}
+
+impl ClientSession {
+    /// 호출하는 쪽이 `StateLock::freeze`를 잡고 있어야 pending, ACK 기록, 전송 위치가 같은 시점의 값
+    pub(crate) fn snapshot(&self, key: &ClientKey) -> SessionSnapshot {
+        let (acked_through, acked_above) = self.acked.snapshot();
+        SessionSnapshot {
+            client: key.to_string(),
+            epoch: self.epoch,
+            stream_open: self.open,
+            highest_sent_id: self.highest_sent.load(Ordering::Relaxed),
+            next_id: self.next_id.load(Ordering::Relaxed),
+            pending: self
+                .pending
+                .list()
+                .into_iter()
+                .map(|listed| PendingEntry {
+                    id: listed.id,
+                    retries: listed.deliveries.saturating_sub(1),
+                    in_flight: listed.visible_in.is_some(),
+                })
+                .collect(),
+            acked_through,
+            acked_above,
+        }
+    }
+}
+
+/// pending 큐를 스트림 태스크 밖에서 바꿨을 때 ACK 타이머, 재전송 핸들러, 윈도, 통계를 맞추는 핸들
+#[derive(Clone)]
+pub(crate) struct StreamHooks {
+    timers: RetryTimers,
+    wake_retry: Arc<tokio::sync::Notify>,
+    window: Arc<tokio::sync::Notify>,
+    delivery_times: DeliveryTimes,
+    stats: Arc<ServerStats>,
+}
+
+impl StreamHooks {
+    /// in-flight였던 메시지를 ready로 되돌림 - 타이머를 풀고 재전송 핸들러를 깨움
+    pub(crate) fn requeued(&self, id: u64) {
+        self.timers.clear(id);
+        self.wake_retry.notify_one();
+    }
+
+    /// 메시지가 ACK 없이 pending에서 빠짐 (dead letter, 강제 삭제)
+    pub(crate) fn removed(&self, id: u64) {
+        self.timers.clear(id);
+        self.delivery_times.forget(id);
+        self.stats.pending_messages.fetch_sub(1, Ordering::Relaxed);
+        self.window.notify_one();
+    }
+}
+
+pub struct StreamingServer {
+    total_messages: u64,
+    stats: Arc<ServerStats>,
+    clock: SharedClock,
+    bus: SharedBus,
+    audit: AuditLog,
+    retry: RetryPolicy,
+    /// 스트림마다 ACK를 기다리는 메시지 상한 (None이면 제한 없음)
+    max_in_flight: Option<usize>,
+    /// 한 프레임에 묶어 보내는 메시지 수 - 1보다 크면 `DataBatch`로 보냄
+    batch_size: usize,
+    /// 메시지 우선순위 단계 수 - id를 이 값으로 나눈 나머지가 우선순위 (클수록 먼저)
+    priority_levels: u32,
+    /// 재전송할 때 그 뒤에 보낸 in-flight 메시지도 다시 보내 순서를 지킴 (go-back-N)
+    ordered_redelivery: bool,
+    /// 모든 스트림이 가중치대로 나눠 쓰는 새 메시지 전송 슬롯 (None이면 스트림마다 `SEND_INTERVAL`대로 보냄)
+    fair_share: Option<FairShare>,
+    /// 스트림 하나에 싣는 논리 채널 수 - 채널마다 따로 id, pending 큐, 재전송 타이머를 둠
+    channels: u32,
+    /// 보낸 것으로 기록한 프레임을 일부러 빠뜨려 재전송을 유도
+    send_drops: SendDrops,
+    /// 보내는 데이터 프레임을 몇 개씩 잡아 두고 무작위 순서로 내보냄
+    shuffle: Shuffle,
+    /// 첫 메시지를 보내기 전에 클라이언트 Hello를 기다리는 시간 (None이면 기다리지 않음)
+    hello_timeout: Option<Duration>,
+    /// 스트림이 끝날 때마다 클라이언트별 전달 보고서를 JSON으로 씀 (None이면 쓰지 않음)
+    report_dir: Option<ReportDir>,
+    /// 메시지 payload 크기 - 있으면 `Message N` 대신 이 길이의 무작위 문자열
+    payload_bytes: Option<usize>,
+    /// 세션마다 `{topic_prefix}-{번호}` 토픽을 사용 (재연결해도 같은 토픽)
+    topic_prefix: String,
+    stream_count: AtomicU64,
+    /// `DebugService::snapshot`이 읽는 동안 모든 스트림의 상태 변경을 멈춤
+    state_lock: StateLock,
+    /// 클라이언트마다 하나 - 열린 스트림과 재연결을 기다리는 `x-client-id` 클라이언트
+    sessions: ClientSessions,
+}
+
+impl StreamingServer {
+    pub fn new(total_messages: u64, stats: Arc<ServerStats>, clock: SharedClock) -> Self {
+        Self {
+            total_messages,
+            stats,
+            topic_prefix: format!("ack-server-{}", clock.unix_millis()),
+            clock,
+            bus: Arc::new(InMemoryBus::default()),
+            audit: AuditLog::disabled(),
+            retry: RetryPolicy::default(),
+            max_in_flight: None,
+            batch_size: 1,
+            priority_levels: 1,
+            ordered_redelivery: false,
+            fair_share: None,
+            channels: 1,
+            send_drops: SendDrops::default(),
+            shuffle: Shuffle::default(),
+            hello_timeout: None,
+            report_dir: None,
+            payload_bytes: None,
+            stream_count: AtomicU64::new(0),
+            state_lock: StateLock::default(),
+            sessions: ClientSessions::default(),
+        }
+    }
+
+    /// 메시지 버스 백엔드 교체 (기본은 in-memory)
+    pub fn with_bus(mut self, bus: SharedBus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// 연결/메시지 이벤트를 감사 로그에 기록 (기본은 기록하지 않음)
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// ACK 타임아웃, 재전송 횟수, backoff 변경 (기본은 2초 고정, 3회)
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 슬라이딩 윈도 - ACK를 기다리는 메시지가 `max`개면 ACK나 dead letter로 자리가 날 때까지 새 메시지를 보내지 않음
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max);
+        self
+    }
+
+    /// 메시지를 `size`개씩 `DataBatch` 프레임으로 묶어 보냄 (재전송 포함) - 클라이언트는 batch 단위로 ACK
+    pub fn with_batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// 메시지에 0..`levels` 우선순위를 매김 - 클라이언트가 밀려 프레임이 쌓이면 높은 우선순위(재전송 포함)부터 보냄
+    pub fn with_priority_levels(mut self, levels: u32) -> Self {
+        self.priority_levels = levels.max(1);
+        self
+    }
+
+    /// 메시지 N을 재전송하면 N보다 뒤에 보낸 in-flight 메시지도 N 뒤로 다시 보내고, 그동안 새 메시지는 보내지 않음
+    ///
+    /// 클라이언트는 다음 차례가 아닌 메시지를 버리고 누적 ACK(`AckUpTo`)만 보내도 모든 메시지를 순서대로 받음.
+    pub fn with_ordered_redelivery(mut self) -> Self {
+        self.ordered_redelivery = true;
+        self
+    }
+
+    /// 새 메시지를 보내기 전에 `share`에서 차례를 받음 - 스트림은 `x-stream-weight`(기본 1) 비율로 슬롯을 나눠 받음
+    pub fn with_fair_share(mut self, share: FairShare) -> Self {
+        self.fair_share = Some(share);
+        self
+    }
+
+    /// 스트림 하나에 `channels`개의 독립된 메시지 순서를 실음 - 채널마다 id 1부터 보내고 따로 ACK를 기다리고 재전송함
+    ///
+    /// 보내는 프레임에 `channel`을 붙이고, 클라이언트 ACK/NACK는 그 `channel`로 나눠 해당 채널에 전달.
+    /// 채널 하나가 밀리면 같은 HTTP/2 스트림을 쓰는 다른 채널도 함께 밀림.
+    pub fn with_channels(mut self, channels: u32) -> Self {
+        self.channels = channels.max(1);
+        self
+    }
+
+    /// 프레임을 `drops.rate` 확률로 보내지 않음 - 전송 기록과 ACK 타이머는 그대로라 타임아웃 뒤 재전송됨
+    pub fn with_send_drops(mut self, drops: SendDrops) -> Self {
+        self.send_drops = drops;
+        self
+    }
+
+    /// 새 메시지 프레임을 스트림마다 `shuffle.window`개까지 잡아 두고 무작위 순서로 보냄
+    ///
+    /// 재전송할 때, 새 메시지를 다 보냈을 때, 윈도가 차서 ACK를 기다릴 때는 잡아 둔 프레임을 모두 무작위 순서로 내보냄.
+    pub fn with_shuffle(mut self, shuffle: Shuffle) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// 첫 메시지를 보내기 전에 클라이언트 Hello를 최대 `timeout` 기다림
+    ///
+    /// 기다리지 않으면 Hello보다 먼저 나간 메시지는 설정대로 보내고, Hello를 받은 뒤부터 클라이언트 기능에 맞춤.
+    /// Hello를 보내지 않는 이전 버전 클라이언트는 첫 메시지가 그만큼 늦어짐.
+    pub fn with_hello_timeout(mut self, timeout: Duration) -> Self {
+        self.hello_timeout = Some(timeout);
+        self
+    }
+
+    /// 스트림이 끝날 때마다 `dir`에 전달 보고서(보낸 수, 재전송, 메시지별 ACK 지연, 결과)를 씀
+    pub fn with_report_dir(mut self, dir: ReportDir) -> Self {
+        self.report_dir = Some(dir);
+        self
+    }
+
+    /// payload를 `bytes` 길이의 무작위 영숫자로 채움 - 채널과 HTTP/2 윈도가 실제로 차도록
+    pub fn with_payload_bytes(mut self, bytes: usize) -> Self {
+        self.payload_bytes = Some(bytes);
+        self
+    }
+
+    /// 클라이언트가 스트림을 닫을 때까지 받은 메시지 수와 바이트를 집계 (`playground_stats::upload`)
+    ///
+    /// 도중에 취소되거나 끊기면 그때까지 받은 양을 기록하고 받은 상태를 그대로 돌려줌.
+    pub async fn receive_upload<S>(&self, in_stream: S) -> Result<UploadSummary, Status>
+    where
+        S: Stream<Item = Result<DataMessage, Status>> + Unpin,
+    {
+        let stream_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let tally = upload::receive(&self.stats, stream_id, in_stream).await?;
+        Ok(UploadSummary {
+            messages: tally.messages,
+            bytes: tally.bytes,
+            duration_ms: tally.duration_ms,
+        })
+    }
+
+    /// 이 서버의 세션 테이블을 보고 바꾸는 `DebugService` - 서비스로 등록하기 전에 만들어 둠
+    pub fn debug_service(&self) -> DebugService {
+        DebugService::new(self.sessions.clone(), self.state_lock.clone())
+    }
+
+    /// `x-client-id`가 `id`인 클라이언트의 pending 메시지 수 - 세션이 없으면 None
+    pub fn pending_for(&self, id: &str) -> Option<usize> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.get(&ClientKey::Client(id.to_string())).map(|session| session.pending.len())
+    }
+
+    /// `x-client-id` 없는 클라이언트 입력 스트림 하나에 대한 전송/재전송/ACK 태스크 시작
+    ///
+    /// 임의의 Stream을 받으므로 테스트에서 tonic 전송 계층 없이 구동 가능.
+    /// 태스크들은 호출 시점의 현재 span 아래 `stream` span(stream_id, client, cancel_reason 필드)에 기록됨.
+    pub fn open_stream<S>(&self, in_stream: S) -> ReceiverStream<Result<StreamMessage, Status>>
+    where
+        S: Stream<Item = Result<StreamMessage, Status>> + Send + Unpin + 'static,
+    {
+        self.open_client_stream(ClientInfo::default(), in_stream)
+            .expect("streams without a client id never conflict")
+    }
+
+    /// `client`의 세션으로 스트림 시작 - 같은 클라이언트의 스트림이 이미 열려 있으면 None
+    ///
+    /// 한 클라이언트가 두 스트림을 동시에 열면 ACK가 어느 쪽 것인지 구분할 수 없음.
+    /// `client.id`가 없으면 스트림마다 따로 세션을 두고 id 1부터 보냄.
+    ///
+    /// `client.last_acked`가 있으면 이전 세션에서 그 id까지는 ACK된 것으로 보고, 나머지 pending 메시지를
+    /// 바로 다시 보낸 뒤 이어서 새 메시지를 보냄. 세션이 없으면 그 다음 id부터 시작.
+    /// 같은 세션으로 다시 열 때마다 payload의 epoch가 1씩 늘어남.
+    ///
+    /// `with_channels`로 채널을 나눴으면 채널마다 따로 세션을 열고, `x-last-acked-id`는 어느 채널의 id인지
+    /// 알 수 없으므로 무시함.
+    pub fn open_client_stream<S>(
+        &self,
+        client: ClientInfo,
+        in_stream: S,
+    ) -> Option<ReceiverStream<Result<StreamMessage, Status>>>
+    where
+        S: Stream<Item = Result<StreamMessage, Status>> + Send + Unpin + 'static,
+    {
+        if self.channels > 1 {
+            return self.open_channels(client, in_stream);
+        }
+        self.open_channel(client, None, in_stream)
+    }
+
+    /// 채널마다 스트림을 열고 클라이언트 프레임을 `channel`로 나눠 전달, 채널 출력은 한 스트림으로 합침
+    fn open_channels<S>(&self, client: ClientInfo, in_stream: S) -> Option<ReceiverStream<Result<StreamMessage, Status>>>
+    where
+        S: Stream<Item = Result<StreamMessage, Status>> + Send + Unpin + 'static,
+    {
+        if let Some(last_acked) = client.last_acked {
+            warn!(last_acked, channels = self.channels, "Ignoring x-last-acked-id on a multi-channel stream");
+        }
+        let mut inputs = Vec::with_capacity(self.channels as usize);
+        let mut outputs = StreamMap::new();
+        for channel in 0..self.channels {
+            let (input, rx) = mpsc::channel(CHANNEL_INBOUND_BUFFER);
+            let client = ClientInfo {
+                last_acked: None,
+                ..client.clone()
+            };
+            // 거절되면 앞서 연 채널은 입력과 출력이 버려지면서 끝남
+            let output = self.open_channel(client, Some(channel), ReceiverStream::new(rx))?;
+            inputs.push(input);
+            outputs.insert(channel, output);
+        }
+        tokio::spawn(channels::demux(in_stream, inputs).in_current_span());
+        let (tx, rx) = mpsc::channel(OUTBOUND_BUFFER);
+        tokio::spawn(channels::mux(outputs, tx).in_current_span());
+        Some(ReceiverStream::new(rx))
+    }
+
+    /// 세션 하나(채널을 나눴으면 채널 하나)의 전송/재전송/ACK 태스크 시작
+    ///
+    /// 채널 세션은 `{x-client-id}/ch{channel}`로 구분 - `x-client-id`가 없으면 채널마다 stream_id가 따로 붙음.
+    fn open_channel<S>(
+        &self,
+        client: ClientInfo,
+        channel: Option<u32>,
+        mut in_stream: S,
+    ) -> Option<ReceiverStream<Result<StreamMessage, Status>>>
+    where
+        S: Stream<Item = Result<StreamMessage, Status>> + Send + Unpin + 'static,
+    {
+        let stream_id = self.stream_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let key = match (client.id, channel) {
+            (Some(id), Some(channel)) => ClientKey::Client(format!("{}/ch{}", id, channel)),
+            (Some(id), None) => ClientKey::Client(id),
+            (None, _) => ClientKey::Stream(stream_id),
+        };
+        let stream_span = info_span!("stream", stream_id, client = %key, channel, cancel_reason = field::Empty);
+        let (pending_messages, topic, next_id, highest_sent, acked, epoch, acked_on_resume) = {
+            let mut sessions = self.sessions.lock().unwrap();
+            if sessions.get(&key).is_some_and(|session| session.open) {
+                stream_span.in_scope(|| warn!("Rejecting second stream for client"));
+                return None;
+            }
+            let previous = sessions.remove(&key);
+            let (session, acked_on_resume) = match (previous, client.last_acked) {
+                (Some(session), Some(last_acked)) => self.resume_session(session, last_acked, &stream_span),
+                (previous, last_acked) => {
+                    let first_id = last_acked.map_or(1, |id| id.saturating_add(1));
+                    let session = ClientSession {
+                        pending: DurableQueue::new(self.retry.queue_config(), self.clock.clone())
+                            .expect("memory-only queue"),
+                        topic: format!("{}-{}", self.topic_prefix, stream_id),
+                        next_id: Arc::new(AtomicU64::new(first_id)),
+                        highest_sent: Arc::new(AtomicU64::new(first_id - 1)),
+                        acked: AckedIds::after(first_id - 1),
+                        epoch: previous.map_or(1, |session| session.epoch + 1),
+                        open: true,
+                        hooks: None,
+                    };
+                    (session, Vec::new())
+                }
+            };
+            let opened = (
+                session.pending.clone(),
+                session.topic.clone(),
+                session.next_id.clone(),
+                session.highest_sent.clone(),
+                session.acked.clone(),
+                session.epoch,
+                acked_on_resume,
+            );
+            sessions.insert(key.clone(), session);
+            opened
+        };
+        let sessions = self.sessions.clone();
+        let next_id_ack = next_id.clone();
+        let state_ack = self.state_lock.clone();
+
+        stream_span.in_scope(|| {
+            info!(
+                total_messages = self.total_messages,
+                first_id = next_id.load(Ordering::Relaxed),
+                epoch,
+                "New client connected"
+            )
+        });
+
+        let (tx, rx) = mpsc::channel(OUTBOUND_BUFFER);
+        let outbox = Outbox::default();
+        let pending_messages_sender = pending_messages.clone();
+        let pending_messages_retry = pending_messages.clone();
+        let pending_messages_ack = pending_messages.clone();
+        let stream_guard = self.stats.stream_opened();
+        let stats_sender = self.stats.clone();
+        let stats_retry = self.stats.clone();
+        let stats_ack = self.stats.clone();
+        let stats_cleanup = self.stats.clone();
+        let audit = self.audit.stream();
+        let audit_ack = audit.clone();
+
+        let batches = Batches::default();
+        let batches_ack = batches.clone();
+        // 클라이언트의 Pause/Resume - 송신 태스크와 재전송이 확인
+        let pause = PauseGate::default();
+        let pause_ack = pause.clone();
+        // 클라이언트 Hello - ACK 핸들러가 기록하고 송신 쪽이 batch/checksum을 맞춤
+        let handshake = Handshake::default();
+        let handshake_ack = handshake.clone();
+        let handshake_cleanup = handshake.clone();
+        // 이 스트림의 전송 집계 - 닫기 직전에 StreamSummary로 보냄
+        let tally = Arc::new(StreamTally::default());
+        let tally_ack = tally.clone();
+        let tally_cleanup = tally.clone();
+        let outbox_ack = outbox.clone();
+        // 메시지마다 ACK 타이머 - 브로커가 재전송하는 백엔드는 걸지 않음
+        let (timers, mut timer_wheel) = RetryTimers::new();
+        let timers_ack = timers.clone();
+        // ACK 지연과 메시지당 재전송 횟수 히스토그램
+        let mut delivery_times = DeliveryTimes::new(self.clock.clone(), self.stats.clone());
+        if self.report_dir.is_some() {
+            delivery_times = delivery_times.keep_acks();
+        }
+        let delivery_times_report = delivery_times.clone();
+        let report_dir = self.report_dir.clone();
+        let clock_report = self.clock.clone();
+        let started_at_ms = self.clock.unix_millis();
+        let delivery_times_retry = delivery_times.clone();
+        let delivery_times_ack = delivery_times.clone();
+        // pending이 줄면 깨움 - 윈도가 찬 송신 태스크가 기다림
+        let window = Arc::new(tokio::sync::Notify::new());
+        let window_sender = window.clone();
+        let window_retry = window.clone();
+        let forwarder = Forwarder {
+            outbox: outbox.clone(),
+            pending: pending_messages_sender,
+            epoch,
+            priority_levels: self.priority_levels,
+            batch_size: self.batch_size,
+            batches,
+            timers: (!self.bus.redelivers()).then_some(timers),
+            ordered_redelivery: self.ordered_redelivery,
+            pause,
+            handshake,
+            tally,
+            send_order: Arc::default(),
+            send_drops: self.send_drops.for_stream(stream_id),
+            shuffler: self.shuffle.for_stream(stream_id),
+            retry: self.retry,
+            clock: self.clock.clone(),
+            window: window.clone(),
+            highest_sent,
+            state: self.state_lock.clone(),
+            delivery_times,
+            stats: stats_sender,
+            audit: audit.clone(),
+        };
+        let forwarder_retry = forwarder.clone();
+        let total_messages = self.total_messages;
+        let message_sending_finished = Arc::new(tokio::sync::Notify::new());
+        let message_sending_finished_notify = message_sending_finished.clone();
+        let clock_sender = self.clock.clone();
+
+        let topic_sender = topic.clone();
+        let bus_sender = self.bus.clone();
+        let bus_ack = self.bus.clone();
+        let broker_redelivers = self.bus.redelivers();
+        let retry = self.retry;
+        // 재전송 핸들러가 끝나면 취소 - 브로커 재전송을 전달하던 송신 태스크도 함께 종료
+        let stream_done = CancellationToken::new();
+        let stream_done_sender = stream_done.clone();
+        let max_in_flight = self.max_in_flight;
+        let hello_timeout = self.hello_timeout;
+        let payload_bytes = self.payload_bytes;
+        // 새 메시지를 다 보내면 핸들이 drop되어 분배에서 빠짐
+        let fair_share = self.fair_share.as_ref().map(|share| share.join(stream_id, client.weight));
+        
+        let message_sender = tokio::spawn(async move {
+            let mut subscription = match bus_sender.subscribe(&topic_sender).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    warn!(bus = bus_sender.name(), error = %e, "Failed to subscribe to message bus");
+                    return;
+                }
+            };
+
+            if let Some(timeout) = hello_timeout
+                && !forwarder.handshake.wait(timeout).await
+            {
+                info!(timeout_ms = timeout.as_millis() as u64, "No Hello from client, assuming a pre-handshake client");
+            }
+
+            // 재연결: 이전 스트림에서 ACK 받지 못한 메시지를 새 메시지보다 먼저 다시 보냄
+            let replayed = {
+                let _state = forwarder.state.change();
+                let mut replays = Vec::new();
+                while let Ok(Some(lease)) = forwarder.pending.try_receive() {
+                    // 끊긴 동안 기한이 지났으면 다시 보내지 않음
+                    if forwarder.past_deadline(&lease.item) {
+                        forwarder.expire(lease.id);
+                        continue;
+                    }
+                    info!(message_id = lease.id, delivery = lease.delivery, "Replaying message after reconnect");
+                    ServerStats::incr(&forwarder.stats.retried_messages);
+                    ServerStats::incr(&forwarder.tally.retried);
+                    replays.push(lease);
+                }
+                forwarder.resend(replays)
+            };
+            if !replayed {
+                return;
+            }
+
+            let mut message_id = next_id.load(Ordering::Relaxed);
+            while message_id <= total_messages {
+                if forwarder.pause.holds(false) {
+                    info!(message_id, "Paused by client, waiting for Resume");
+                    tokio::select! {
+                        _ = forwarder.pause.released(false) => {}
+                        _ = stream_done_sender.cancelled() => return,
+                    }
+                    info!(message_id, "Resumed by client");
+                    // 멈춘 동안 쌓인 재전송을 새 메시지보다 먼저 보냄
+                    if !forwarder.retry_ready() {
+                        return;
+                    }
+                }
+                if let Some(max) = max_in_flight {
+                    // 잡아 둔 프레임이 나가야 ACK가 옴
+                    if forwarder.pending.len() >= max && !forwarder.release_shuffled() {
+                        return;
+                    }
+                    while forwarder.pending.len() >= max {
+                        info!(message_id, in_flight = max, "Window full, waiting for ACKs");
+                        tokio::select! {
+                            _ = window_sender.notified() => {}
+                            _ = stream_done_sender.cancelled() => return,
+                        }
+                    }
+                }
+
+                if let Some(share) = &fair_share {
+                    tokio::select! {
+                        _ = share.turn() => {}
+                        _ = stream_done_sender.cancelled() => return,
+                    }
+                }
+
+                // batch 하나 분량 (윈도에 남은 자리까지)
+                let batch_size = forwarder.batch_size();
+                let mut deliveries = Vec::with_capacity(batch_size);
+                let mut failed = false;
+                while deliveries.len() < batch_size
+                    && message_id <= total_messages
+                    && max_in_flight.is_none_or(|max| forwarder.pending.len() + deliveries.len() < max)
+                {
+                    // 버스에는 TTL 기한을 ms 단위로 맞추도록 unix ms로 싣고, 클라이언트에는 초로 보냄
+                    let message = BusMessage {
+                        id: message_id,
+                        timestamp: clock_sender.unix_millis(),
+                        payload: payload(message_id, payload_bytes),
+                    };
+                    if let Err(e) = bus_sender.publish(&topic_sender, message).await {
+                        warn!(message_id, error = %e, "Failed to publish message");
+                        failed = true;
+                        break;
+                    }
+
+                    // in-memory 버스는 방금 발행한 메시지, 브로커는 재전송분이 먼저 나올 수 있음
+                    let delivery = match subscription.next().await {
+                        Ok(delivery) => delivery,
+                        Err(e) => {
+                            warn!(bus = bus_sender.name(), error = %e, "Failed to receive from message bus");
+                            failed = true;
+                            break;
+                        }
+                    };
+                    // 전달하면 pending에 남으므로 재연결하면 이 다음부터 새로 보냄
+                    next_id.store(message_id + 1, Ordering::Relaxed);
+                    deliveries.push(delivery);
+                    message_id += 1;
+                }
+                if !forwarder.forward(deliveries, total_messages).await || failed {
+                    break;
+                }
+                tokio::time::sleep(SEND_INTERVAL).await;
+            }
+            
+            drop(fair_share);
+            if !forwarder.release_shuffled() {
+                return;
+            }
+            info!(total_messages, "All messages sent, waiting for ACKs and retries");
+            // retry handler에게 메시지 전송 완료 알림
+            message_sending_finished_notify.notify_one();
+
+            // 브로커가 재전송하는 백엔드: 스트림이 끝날 때까지 재전송분을 계속 전달
+            if bus_sender.redelivers() {
+                loop {
+                    tokio::select! {
+                        delivery = subscription.next() => match delivery {
+                            Ok(delivery) => {
+                                // 재전송을 멈춘 클라이언트에는 재개할 때까지 전달을 미룸
+                                tokio::select! {
+                                    _ = forwarder.pause.released(true) => {}
+                                    _ = stream_done_sender.cancelled() => break,
+                                }
+                                if !forwarder.forward(vec![delivery], total_messages).await {
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                warn!(bus = bus_sender.name(), error = %e, "Failed to receive from message bus");
+                                break;
+                            }
+                        },
+                        _ = stream_done_sender.cancelled() => break,
+                    }
+                }
+            }
+        }.instrument(info_span!(parent: &stream_span, "message_sender")));
+
+        let tx_retry = tx.clone();
+        tokio::spawn(outbox.clone().run(tx, self.stats.clone()).instrument(info_span!(parent: &stream_span, "outbox")));
+        let message_sending_finished_clone = message_sending_finished.clone();
+        // NACK나 Resume을 받으면 ACK 타이머를 기다리지 않고 재전송하도록 재전송 핸들러를 깨움
+        let wake_retry = Arc::new(tokio::sync::Notify::new());
+        let wake_retry_handler = wake_retry.clone();
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(&key) {
+            session.hooks = Some(StreamHooks {
+                timers: timers_ack.clone(),
+                wake_retry: wake_retry.clone(),
+                window: window.clone(),
+                delivery_times: delivery_times_ack.clone(),
+                stats: self.stats.clone(),
+            });
+        }
+        
+        let retry_handler = tokio::spawn(async move {
+            let _stream_done = stream_done.drop_guard();
+            let mut message_sending_done = false;
+            
+            loop {
+                tokio::select! {
+                    // 클라이언트가 끊겼으면 재전송할 곳이 없음
+                    _ = tx_retry.closed() => {
+                        info!("Client stream closed, stopping retry handler");
+                        return false;
+                    }
+                    // 모든 메시지가 완료되면 종료 (메시지 전송이 끝나고 pending이 비어있을 때만)
+                    _ = pending_messages_retry.drained(), if message_sending_done => {
+                        ServerStats::incr(&stats_retry.completed_streams);
+                        info!("All messages completed, stopping retry handler");
+                        return true;
+                    }
+                    // ACK 대기가 끝난 메시지 (브로커가 재전송하는 백엔드는 타이머를 걸지 않음)
+                    Some(id) = timer_wheel.next_expired() => {
+                        // 같은 순간에 끝난 타이머를 모아 한 번에 재전송 - batch로 보낸 메시지는 다시 batch로
+                        let mut due: Vec<u64> = std::iter::once(id).chain(std::iter::from_fn(|| timer_wheel.try_expired())).collect();
+                        // nack은 ready 맨 앞에 넣으므로 큰 id부터 되돌려 id 순서를 유지
+                        due.sort_unstable_by(|a, b| b.cmp(a));
+                        let state = forwarder_retry.state.change();
+                        for id in due {
+                            let expired = pending_messages_retry
+                                .peek_in_flight(id)
+                                .is_some_and(|message| forwarder_retry.past_deadline(&message));
+                            if expired {
+                                forwarder_retry.expire(id);
+                            } else if let Some(Nacked::DeadLetter) = pending_messages_retry.nack(id) {
+                                warn!(message_id = id, retries = retry.max_retries, "Message failed after max retries");
+                                ServerStats::incr(&forwarder_retry.tally.dead_lettered);
+                                delivery_times_retry.forget(id);
+                                stats_retry.pending_messages.fetch_sub(1, Ordering::Relaxed);
+                                window_retry.notify_one();
+                            }
+                        }
+                        // retry_ready가 다시 잡음
+                        drop(state);
+                        if !forwarder_retry.retry_ready() {
+                            warn!("Failed to send retry message, stopping retry handler");
+                            return false;
+                        }
+                    }
+                    _ = message_sending_finished_clone.notified() => {
+                        info!("Message sending finished, retry handler will continue until all ACKs received");
+                        message_sending_done = true;
+                    }
+                    _ = wake_retry_handler.notified() => {
+                        if !forwarder_retry.retry_ready() {
+                            warn!("Failed to send retry message, stopping retry handler");
+                            return false;
+                        }
+                    }
+                }
+            }
+        }.instrument(info_span!(parent: &stream_span, "retry_handler")));
+
+        let ack_span = stream_span.clone();
+        let ack_handler = tokio::spawn(async move {
+            // 재연결하며 x-last-acked-id로 ACK한 메시지
+            for id in acked_on_resume {
+                ServerStats::incr(&stats_ack.acked_messages);
+                ServerStats::incr(&tally_ack.acked);
+                audit_ack.message_acked(id);
+                if let Err(e) = bus_ack.ack(&topic, id).await {
+                    warn!(message_id = id, error = %e, "Failed to ack message on bus");
+                }
+            }
+            while let Some(message) = in_stream.next().await {
+                match message {
+                    Ok(stream_msg) => {
+                        let outcome = {
+                            // pending에서 빼는 것과 ACK 기록을 한 번에 - snapshot에서 둘 다에 있거나 둘 다에 없지 않도록
+                            let _state = state_ack.change();
+                            let outcome = info_span!("client_frame")
+                                .in_scope(|| handle_incoming(stream_msg, &pending_messages_ack, &batches_ack, &pause_ack, broker_redelivers));
+                            match &outcome {
+                                Incoming::Acked(id) => acked.insert(*id),
+                                Incoming::AckedUpTo { ids, .. } | Incoming::BatchAcked { ids, .. } => {
+                                    ids.iter().for_each(|&id| acked.insert(id));
+                                }
+                                _ => {}
+                            }
+                            outcome
+                        };
+                        if let Incoming::Acked(_) | Incoming::Nacked { redelivery: Redelivery::DeadLetter, .. } = outcome {
+                            stats_ack.pending_messages.fetch_sub(1, Ordering::Relaxed);
+                            window.notify_one();
+                        }
+                        if let Incoming::AckedUpTo { ids, .. } | Incoming::BatchAcked { ids, .. } = &outcome {
+                            stats_ack.pending_messages.fetch_sub(ids.len() as u64, Ordering::Relaxed);
+                            window.notify_one();
+                        }
+                        // 더 이상 in-flight가 아니므로 ACK 타이머를 풂 (NACK 재전송은 다시 보낼 때 새로 걺)
+                        match &outcome {
+                            Incoming::Acked(id) | Incoming::Nacked { id, .. } => timers_ack.clear(*id),
+                            Incoming::AckedUpTo { ids, .. } | Incoming::BatchAcked { ids, .. } => {
+                                ids.iter().for_each(|&id| timers_ack.clear(id));
+                            }
+                            _ => {}
+                        }
+
+                        match outcome {
+                            Incoming::Acked(id) => {
+                                ServerStats::incr(&stats_ack.acked_messages);
+                                ServerStats::incr(&tally_ack.acked);
+                                delivery_times_ack.acked(id);
+                                info!(message_id = id, "Received ACK");
+                                audit_ack.message_acked(id);
+                                if let Err(e) = bus_ack.ack(&topic, id).await {
+                                    warn!(message_id = id, error = %e, "Failed to ack message on bus");
+                                }
+                            }
+                            Incoming::UnknownAck(id) => match acked.classify(id, next_id_ack.load(Ordering::Relaxed)) {
+                                UnmatchedAck::Duplicate => {
+                                    ServerStats::incr(&stats_ack.duplicate_acks);
+                                    warn!(message_id = id, "Ignoring duplicate ACK for an already acknowledged message");
+                                }
+                                UnmatchedAck::Late => {
+                                    ServerStats::incr(&stats_ack.late_acks);
+                                    warn!(message_id = id, "Ignoring late ACK for a message that expired, failed or was dropped");
+                                }
+                                UnmatchedAck::Unknown => {
+                                    ServerStats::incr(&stats_ack.unknown_acks);
+                                    warn!(message_id = id, "Ignoring ACK for a message that was never sent");
+                                }
+                            },
+                            Incoming::AckedUpTo { up_to, ids } if ids.is_empty() => {
+                                warn!(up_to, "Ignoring cumulative ACK with no pending messages up to it");
+                            }
+                            Incoming::AckedUpTo { up_to, ids } => {
+                                info!(up_to, acked = ids.len(), "Received cumulative ACK");
+                                for id in ids {
+                                    ServerStats::incr(&stats_ack.acked_messages);
+                                    ServerStats::incr(&tally_ack.acked);
+                                    delivery_times_ack.acked(id);
+                                    audit_ack.message_acked(id);
+                                    if let Err(e) = bus_ack.ack(&topic, id).await {
+                                        warn!(message_id = id, error = %e, "Failed to ack message on bus");
+                                    }
+                                }
+                            }
+                            Incoming::BatchAcked { batch_id, ids } => {
+                                info!(batch_id, acked = ids.len(), "Received batch ACK");
+                                for id in ids {
+                                    ServerStats::incr(&stats_ack.acked_messages);
+                                    ServerStats::incr(&tally_ack.acked);
+                                    delivery_times_ack.acked(id);
+                                    audit_ack.message_acked(id);
+                                    if let Err(e) = bus_ack.ack(&topic, id).await {
+                                        warn!(message_id = id, error = %e, "Failed to ack message on bus");
+                                    }
+                                }
+                            }
+                            Incoming::UnknownBatch(batch_id) => {
+                                warn!(batch_id, "Ignoring ACK for unknown batch");
+                            }
+                            Incoming::Nacked { id, reason, redelivery } => match redelivery {
+                                Redelivery::Now => {
+                                    warn!(message_id = id, reason = %reason, "Received NACK, redelivering now");
+                                    wake_retry.notify_one();
+                                }
+                                Redelivery::DeadLetter => {
+                                    ServerStats::incr(&tally_ack.dead_lettered);
+                                    delivery_times_ack.forget(id);
+                                    warn!(message_id = id, reason = %reason, retries = retry.max_retries, "Received NACK after max retries, message failed");
+                                }
+                                Redelivery::Broker => {
+                                    warn!(message_id = id, reason = %reason, "Received NACK, leaving redelivery to the broker");
+                                }
+                            },
+                            Incoming::UnknownNack(id) => {
+                                warn!(message_id = id, "Ignoring NACK for unknown message");
+                            }
+                            Incoming::Paused { retries } => {
+                                info!(pause_retries = retries, "Client paused the stream");
+                            }
+                            Incoming::Resumed => {
+                                info!("Client resumed the stream");
+                                wake_retry.notify_one();
+                            }
+                            Incoming::NotPaused => {
+                                warn!("Ignoring Resume for a stream that is not paused");
+                            }
+                            Incoming::Hello(hello) => match handshake_ack.receive(hello) {
+                                Some(peer) => {
+                                    info!(protocol_version = peer.protocol_version, features = ?peer.features, "Received client Hello");
+                                    // 아직 보내지 않은 메시지보다 먼저 나가도록 가장 높은 우선순위로
+                                    let reply = StreamMessage {
+                                        message_type: Some(streaming::stream_message::MessageType::Hello(hello::server_hello())),
+                                    };
+                                    outbox_ack.push(u32::MAX, Ok(reply));
+                                }
+                                None => warn!("Ignoring repeated Hello from client"),
+                            },
+                            Incoming::UnexpectedData(id) => {
+                                warn!(message_id = id, "Ignoring unexpected data frame from client");
+                            }
+                            Incoming::UnexpectedBatch(batch_id) => {
+                                warn!(batch_id, "Ignoring unexpected batch frame from client");
+                            }
+                            Incoming::UnexpectedSummary(channel) => {
+                                warn!(channel, "Ignoring unexpected summary frame from client");
+                            }
+                            Incoming::Empty => {
+                                warn!("Ignoring empty or unknown frame from client");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let reason = format!("{:?}: {}", e.code(), e.message());
+                        ack_span.record("cancel_reason", reason.as_str());
+                        warn!(code = ?e.code(), message = e.message(), "Error receiving message");
+                        audit_ack.cancelled(&reason);
+                        return e.code() == tonic::Code::Cancelled;
+                    }
+                }
+            }
+            false
+        }.instrument(info_span!(parent: &stream_span, "ack_handler")));
+
+        // 모든 작업 완료 후 스트림 종료
+        tokio::spawn(async move {
+            // 메시지 전송 완료 대기
+            let _ = message_sender.await;
+            info!("Message sending completed, waiting for retries to finish");
+            
+            // 재전송 핸들러 완료 대기
+            let completed = retry_handler.await.unwrap_or(false);
+            
+            // 클라이언트가 자기 기록과 맞춰 보도록 이 스트림의 집계를 마지막 프레임으로
+            if handshake_cleanup.advertised(hello::SUMMARY) {
+                let summary = tally_cleanup.summary();
+                info!(sent = summary.sent, acked = summary.acked, retried = summary.retried, dead_lettered = summary.dead_lettered, expired = summary.expired, "Sending stream summary");
+                let frame = StreamMessage {
+                    message_type: Some(streaming::stream_message::MessageType::Summary(summary)),
+                };
+                outbox.push(0, Ok(frame));
+            }
+            // outbox에 남은 프레임을 보내고 나면 채널이 닫힘
+            outbox.close();
+            info!("All messages processed, closing stream");
+            
+            // ACK 핸들러 완료 대기
+            let client_cancelled = ack_handler.await.unwrap_or(false);
+            if !completed {
+                stats_cleanup.stream_cancelled(if client_cancelled {
+                    "intentional_cancellation"
+                } else {
+                    "network_disconnection"
+                });
+            }
+            let outcome = if completed {
+                Outcome::Completed
+            } else if client_cancelled {
+                Outcome::Cancelled
+            } else {
+                Outcome::Disconnected
+            };
+            audit.closed(outcome);
+
+            // ACK 받지 못하고 끝난 메시지는 서버 전체 pending 수에서 제외
+            let abandoned = pending_messages.len() as u64;
+            stats_cleanup.pending_messages.fetch_sub(abandoned, Ordering::Relaxed);
+            if let Some(report_dir) = &report_dir {
+                let summary = tally_cleanup.summary();
+                let report = DeliveryReport {
+                    client: key.to_string(),
+                    stream_id,
+                    epoch,
+                    outcome,
+                    started_at_ms,
+                    ended_at_ms: clock_report.unix_millis(),
+                    sent: summary.sent,
+                    acked: summary.acked,
+                    retried: summary.retried,
+                    dead_lettered: summary.dead_lettered,
+                    expired: summary.expired,
+                    abandoned,
+                    acks: delivery_times_report.take_acks(),
+                };
+                match report_dir.write(&key, &report) {
+                    Ok(path) => info!(path = %path.display(), "Wrote delivery report"),
+                    Err(e) => warn!(error = %e, "Failed to write delivery report"),
+                }
+            }
+            {
+                let mut sessions = sessions.lock().unwrap();
+                // 다 보내지 못한 x-client-id 클라이언트는 x-last-acked-id로 이어받을 수 있도록 세션을 남겨둠
+                let resumable = !completed && matches!(key, ClientKey::Client(_));
+                match sessions.get_mut(&key) {
+                    Some(session) if resumable => {
+                        session.open = false;
+                        session.hooks = None;
+                    }
+                    _ => {
+                        sessions.remove(&key);
+                    }
+                }
+            }
+            drop(stream_guard);
+            info!(abandoned, "Stream closed completely");
+        }.instrument(stream_span));
+
+        Some(ReceiverStream::new(rx))
+    }
+
+    /// 스트림이 끝난 세션을 다시 엶 - `last_acked`까지 ACK하고 나머지 pending 메시지를 바로 다시 보내도록 되돌림
+    ///
+    /// `last_acked`까지 ACK한 메시지 id를 함께 반환.
+    fn resume_session(&self, mut session: ClientSession, last_acked: u64, stream_span: &Span) -> (ClientSession, Vec<u64>) {
+        let acked = {
+            let _state = self.state_lock.change();
+            let acked = session.pending.ack_up_to(last_acked);
+            acked.iter().for_each(|&id| session.acked.insert(id));
+            acked
+        };
+        // 브로커가 재전송하는 백엔드는 같은 토픽을 다시 구독하면 브로커가 다시 보냄
+        let replayed = if self.bus.redelivers() { 0 } else { session.pending.requeue_in_flight() };
+        // 이전 스트림이 끝날 때 서버 전체 pending 수에서 뺐던 메시지를 다시 셈
+        self.stats
+            .pending_messages
+            .fetch_add(session.pending.len() as u64, Ordering::Relaxed);
+        session.next_id.fetch_max(last_acked.saturating_add(1), Ordering::Relaxed);
+        session.epoch += 1;
+        session.open = true;
+        stream_span.in_scope(|| {
+            info!(last_acked, acked = acked.len(), replayed, epoch = session.epoch, "Resuming client session")
+        });
+        (session, acked)
+    }
+}
+
+/// 메시지 payload - `payload_bytes`가 있으면 그 길이의 무작위 영숫자 (`payload`는 string이라 UTF-8이어야 함)
+fn payload(message_id: u64, payload_bytes: Option<usize>) -> String {
+    match payload_bytes {
+        Some(len) => rand::thread_rng().sample_iter(&Alphanumeric).take(len).map(char::from).collect(),
+        None => format!("Message {}", message_id),
+    }
+}
+
+/// 버스에서 꺼낸 전달을 pending 큐에 in-flight로 기록하고 클라이언트로 전송
+#[derive(Clone)]
+struct Forwarder {
+    outbox: Outbox,
+    pending: DurableQueue<BusMessage>,
+    epoch: u64,
+    priority_levels: u32,
+    batch_size: usize,
+    batches: Batches,
+    /// 보낸 메시지의 ACK 타이머 (브로커가 재전송하는 백엔드면 None)
+    timers: Option<RetryTimers>,
+    ordered_redelivery: bool,
+    /// 클라이언트가 멈춘 동안 새 메시지(와 `pause_retries`면 재전송)를 보내지 않음
+    pause: PauseGate,
+    /// 클라이언트가 Hello로 알리지 않은 기능(batch, checksum)은 쓰지 않음
+    handshake: Handshake,
+    /// 이 스트림의 전송 집계 (`StreamSummary`)
+    tally: Arc<StreamTally>,
+    /// 새 메시지 전송과 재전송이 서로 끼어들지 않도록 - 재전송 뒤로 다시 보낼 메시지를 고르는 동안 새 메시지를 막음
+    send_order: Arc<Mutex<()>>,
+    /// `--send-drop-rate` - 보낸 것으로 기록하되 outbox에 넣지 않을 프레임을 고름
+    send_drops: Option<SendDropper>,
+    /// `--shuffle-window` - 보낼 프레임을 잡아 두고 순서를 뒤섞음
+    shuffler: Option<Shuffler>,
+    /// 메시지 기한(`message_ttl`)과 만료된 메시지를 dead letter로 옮길지
+    retry: RetryPolicy,
+    clock: SharedClock,
+    /// pending이 줄면 깨움 (윈도가 찬 송신 태스크)
+    window: Arc<tokio::sync::Notify>,
+    /// 처음 보낸 id 중 가장 큰 것 (세션과 공유)
+    highest_sent: Arc<AtomicU64>,
+    /// pending 큐를 바꾸는 동안 잡음 - `DebugService::snapshot`이 멈춤
+    state: StateLock,
+    delivery_times: DeliveryTimes,
+    stats: Arc<ServerStats>,
+    audit: StreamAudit,
+}
+
+impl Forwarder {
+    /// 새 메시지를 한 프레임(batch 모드가 아니면 메시지마다 한 프레임)으로 전송 - 클라이언트 스트림이 닫혔으면 false
+    async fn forward(&self, deliveries: Vec<Delivery>, total_messages: u64) -> bool {
+        let _order = self.send_order.lock().unwrap();
+        let _state = self.state.change();
+        let messages: Vec<DataMessage> = deliveries.into_iter().map(|delivery| self.track(delivery)).collect();
+        let Some(first) = messages.first() else {
+            return true;
+        };
+        let span = info_span!("send_message", message_id = first.id, messages = messages.len());
+        if !span.in_scope(|| self.send(messages.clone())) {
+            return false;
+        }
+
+        for message in messages {
+            ServerStats::incr(&self.stats.messages_sent);
+            ServerStats::incr(&self.tally.sent);
+            self.delivery_times.sent(message.id, message.delivery_attempt);
+            self.audit.message_sent(message.id, message.delivery_attempt);
+            info!(message_id = message.id, total_messages, delivery_attempt = message.delivery_attempt, "Sent message");
+        }
+        true
+    }
+
+    /// pending 큐에 되돌아온 메시지(ACK 타임아웃, NACK)를 모두 다시 전송 - 클라이언트 스트림이 닫혔으면 false
+    ///
+    /// 클라이언트가 재전송까지 멈췄으면 ready 상태로 남겨 두고 Resume 때 보냄.
+    fn retry_ready(&self) -> bool {
+        if self.pause.holds(true) {
+            return true;
+        }
+        let _order = self.send_order.lock().unwrap();
+        let _state = self.state.change();
+        let leases = if self.ordered_redelivery {
+            // 가장 앞의 재전송 뒤로, 그보다 뒤에 보낸 in-flight 메시지를 id 순서로 다시 보냄
+            self.pending.go_back_n().unwrap_or_default()
+        } else {
+            std::iter::from_fn(|| self.pending.try_receive_ready().ok().flatten()).collect()
+        };
+        let leases = self.drop_expired(leases);
+        for lease in &leases {
+            info!(message_id = lease.id, delivery = lease.delivery, "Retrying message");
+            ServerStats::incr(&self.stats.retried_messages);
+            ServerStats::incr(&self.tally.retried);
+        }
+        self.resend(leases)
+    }
+
+    /// ACK 받지 못한 메시지를 batch 크기씩 묶어 다시 전송 - 클라이언트 스트림이 닫혔으면 false
+    ///
+    /// 호출하는 쪽이 `state.change()`를 잡고 있음. `--shuffle-window`면 잡아 둔 프레임과 함께 무작위 순서로
+    /// 모두 내보냄 - 다음 프레임을 기다리며 재전송을 잡아 두면 그동안 또 타임아웃됨.
+    fn resend(&self, leases: Vec<Lease<BusMessage>>) -> bool {
+        if leases.is_empty() {
+            return true;
+        }
+        for lease in &leases {
+            self.schedule(lease.id);
+        }
+        let messages: Vec<DataMessage> = leases
+            .into_iter()
+            .map(|lease| self.data_message(lease.item, lease.delivery))
+            .collect();
+        for chunk in messages.chunks(self.batch_size()) {
+            let span = info_span!("retry_message", message_id = chunk[0].id, messages = chunk.len());
+            if !span.in_scope(|| self.send(chunk.to_vec())) {
+                return false;
+            }
+            for message in chunk {
+                self.delivery_times.sent(message.id, message.delivery_attempt);
+                self.audit.message_sent(message.id, message.delivery_attempt);
+            }
+        }
+        self.release_shuffled()
+    }
+
+    /// 기한이 지난 메시지를 빼고 나머지를 반환 - 재전송 횟수가 남았어도 다시 보내지 않음
+    fn drop_expired(&self, leases: Vec<Lease<BusMessage>>) -> Vec<Lease<BusMessage>> {
+        let (expired, live): (Vec<_>, Vec<_>) = leases.into_iter().partition(|lease| self.past_deadline(&lease.item));
+        for lease in expired {
+            // 다시 보낸 적이 있는 in-flight 메시지(go-back-N)는 타이머가 걸려 있음
+            if let Some(timers) = &self.timers {
+                timers.clear(lease.id);
+            }
+            self.expire(lease.id);
+        }
+        live
+    }
+
+    fn past_deadline(&self, message: &BusMessage) -> bool {
+        self.retry.is_expired(message.timestamp, self.clock.unix_millis())
+    }
+
+    /// 기한이 지난 메시지를 pending에서 뺌 (`expired_to_dlq`면 dead letter로) - 실시간 데이터는 늦게 받아도 쓸모가 없음
+    fn expire(&self, id: u64) {
+        self.pending.remove(id, self.retry.expired_to_dlq);
+        self.delivery_times.forget(id);
+        warn!(message_id = id, dead_letter = self.retry.expired_to_dlq, "Message expired before it was acknowledged");
+        ServerStats::incr(&self.stats.expired_messages);
+        ServerStats::incr(&self.tally.expired);
+        self.stats.pending_messages.fetch_sub(1, Ordering::Relaxed);
+        self.window.notify_one();
+    }
+
+    /// 전달을 pending 큐에 in-flight로 기록
+    fn track(&self, delivery: Delivery) -> DataMessage {
+        let message_id = delivery.message.id;
+        if delivery.attempt > 1 {
+            info!(message_id, delivery = delivery.attempt, "Broker redelivered message");
+            ServerStats::incr(&self.stats.retried_messages);
+            ServerStats::incr(&self.tally.retried);
+        }
+        // 이미 in-flight인 메시지(브로커 재전송)는 가시성 타임아웃만 다시 시작
+        // 전달 횟수는 브로커가 아니라 pending 큐 기준 - 재연결 후 재전송까지 이어서 셈
+        let delivery_attempt = match self.pending.lease(message_id, delivery.message.clone()) {
+            // 서버 전체 pending 수 (모든 스트림 합계)
+            Ok(1) => {
+                ServerStats::incr(&self.stats.pending_messages);
+                1
+            }
+            Ok(attempt) => attempt,
+            Err(e) => {
+                warn!(message_id, error = %e, "Failed to track message");
+                delivery.attempt
+            }
+        };
+        self.highest_sent.fetch_max(message_id, Ordering::Relaxed);
+        self.schedule(message_id);
+        self.data_message(delivery.message, delivery_attempt)
+    }
+
+    /// in-flight가 된 메시지의 가시성 타임아웃에 맞춰 ACK 타이머를 (다시) 걺
+    fn schedule(&self, id: u64) {
+        if let Some(timers) = &self.timers
+            && let Some(after) = self.pending.visible_in(id)
+        {
+            timers.set(id, after);
+        }
+    }
+
+    /// 클라이언트가 중복 전달을 구분하도록 세션 epoch와 이 id의 전달 횟수를, 손상을 찾도록 checksum을 함께 보냄
+    fn data_message(&self, message: BusMessage, delivery_attempt: u32) -> DataMessage {
+        let timestamp = message.timestamp / 1000;
+        DataMessage {
+            checksum: if self.handshake.supports(hello::CHECKSUM) {
+                checksum(message.id, timestamp, &message.payload)
+            } else {
+                0
+            },
+            id: message.id,
+            timestamp,
+            payload: message.payload,
+            needs_ack: true,
+            epoch: self.epoch,
+            delivery_attempt,
+            priority: (message.id % u64::from(self.priority_levels)) as u32,
+            // 채널 번호는 합치는 쪽(`channels::mux`)이 붙임
+            channel: 0,
+            deadline_unix_ms: self.retry.deadline_ms(message.timestamp).unwrap_or_default(),
+        }
+    }
+
+    /// 한 프레임에 묶을 메시지 수 - batch를 알리지 않은 클라이언트에는 1
+    fn batch_size(&self) -> usize {
+        if self.handshake.supports(hello::BATCH) {
+            self.batch_size
+        } else {
+            1
+        }
+    }
+
+    /// outbox에 넣음 - batch 모드면 `DataBatch` 한 프레임(가장 높은 우선순위로), 아니면 메시지마다 한 프레임
+    fn send(&self, messages: Vec<DataMessage>) -> bool {
+        use streaming::stream_message::MessageType;
+
+        let frames = if self.batch_size() > 1 {
+            let priority = messages.iter().map(|message| message.priority).max().unwrap_or_default();
+            let batch_id = self.batches.record(messages.iter().map(|message| message.id).collect());
+            vec![(priority, MessageType::Batch(DataBatch { batch_id, messages, channel: 0 }))]
+        } else {
+            messages.into_iter().map(|message| (message.priority, MessageType::Data(message))).collect()
+        };
+        frames.into_iter().all(|(priority, frame)| {
+            if self.send_drops.as_ref().is_some_and(SendDropper::drops) {
+                info!("Simulated send failure");
+                return true;
+            }
+            let stream_msg = StreamMessage {
+                message_type: Some(frame),
+            };
+            match &self.shuffler {
+                Some(shuffler) => match shuffler.hold(priority, stream_msg) {
+                    Some((priority, released)) => self.outbox.push(priority, Ok(released)),
+                    None => true,
+                },
+                None => self.outbox.push(priority, Ok(stream_msg)),
+            }
+        })
+    }
+
+    /// `--shuffle-window`로 잡아 둔 프레임을 모두 내보냄 - 클라이언트 스트림이 닫혔으면 false
+    fn release_shuffled(&self) -> bool {
+        let Some(shuffler) = &self.shuffler else {
+            return true;
+        };
+        shuffler
+            .flush()
+            .into_iter()
+            .all(|(priority, frame)| self.outbox.push(priority, Ok(frame)))
+    }
+}
+
+#[tonic::async_trait]
+impl StreamingService for StreamingServer {
+    type BidirectionalStreamStream = ReceiverStream<Result<StreamMessage, Status>>;
+
+    async fn bidirectional_stream(
+        &self,
+        request: Request<Streaming<StreamMessage>>,
+    ) -> Result<Response<Self::BidirectionalStreamStream>, Status> {
+        // 클라이언트가 보낸 traceparent를 이어받아 스트림 전체를 하나의 trace로 묶음
+        let peer = request
+            .remote_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
+        let stream_span = info_span!("bidirectional_stream", peer = %peer);
+        playground_telemetry::set_remote_parent(&stream_span, request.metadata());
+
+        let client = ClientInfo::from_metadata(request.metadata());
+        let client_id = client.id.clone();
+        let in_stream = request.into_inner();
+        let out_stream = stream_span
+            .in_scope(|| self.open_client_stream(client, in_stream))
+            .ok_or_else(|| {
+                Status::already_exists(format!("client '{}' already has an open stream", client_id.unwrap_or_default()))
+            })?;
+        Ok(Response::new(out_stream))
+    }
+
+    async fn client_stream(
+        &self,
+        request: Request<Streaming<DataMessage>>,
+    ) -> Result<Response<UploadSummary>, Status> {
+        let peer = request
+            .remote_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
+        let upload_span = info_span!("client_stream", peer = %peer);
+        playground_telemetry::set_remote_parent(&upload_span, request.metadata());
+
+        let summary = self.receive_upload(request.into_inner()).instrument(upload_span).await?;
+        Ok(Response::new(summary))
+    }
+}
@@ -0,0 +1,76 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+
+use crate::streaming::StreamMessage;
+
+/// 순서 뒤섞기 - 보내는 데이터 프레임을 `window`개까지 잡아 두고 무작위 순서로 내보냄
+///
+/// 프록시는 TCP 스트림 하나 안에서 순서를 바꿀 수 없으므로 클라이언트의 재정렬 처리를 시험할 때 사용.
+/// 잡아 둔 시간도 ACK 타임아웃에 포함되므로 window가 크면 재전송이 늘어남. `seed`가 있으면 스트림마다
+/// `seed + stream_id`로 RNG를 만들어 같은 실행을 반복해도 같은 순서가 나옴.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Shuffle {
+    /// 잡아 둘 프레임 수 - 0이면 뒤섞지 않음
+    pub window: usize,
+    pub seed: Option<u64>,
+}
+
+impl Shuffle {
+    pub fn new(window: usize, seed: Option<u64>) -> Self {
+        Self { window, seed }
+    }
+
+    /// 스트림 하나의 버퍼 - window가 0이면 None
+    pub(crate) fn for_stream(&self, stream_id: u64) -> Option<Shuffler> {
+        (self.window > 0).then(|| Shuffler {
+            window: self.window,
+            inner: Arc::new(Mutex::new(Held {
+                rng: self
+                    .seed
+                    .map_or_else(StdRng::from_entropy, |seed| StdRng::seed_from_u64(seed.wrapping_add(stream_id))),
+                frames: Vec::with_capacity(self.window + 1),
+            })),
+        })
+    }
+}
+
+/// (우선순위, 프레임)
+type Frame = (u32, StreamMessage);
+
+#[derive(Clone)]
+pub(crate) struct Shuffler {
+    window: usize,
+    inner: Arc<Mutex<Held>>,
+}
+
+struct Held {
+    rng: StdRng,
+    frames: Vec<Frame>,
+}
+
+impl Shuffler {
+    /// 프레임을 잡아 둠 - window를 넘으면 잡아 둔 것 중 무작위로 하나를 내보냄
+    pub(crate) fn hold(&self, priority: u32, frame: StreamMessage) -> Option<Frame> {
+        let mut held = self.inner.lock().unwrap();
+        held.frames.push((priority, frame));
+        let len = held.frames.len();
+        if len <= self.window {
+            return None;
+        }
+        let index = held.rng.gen_range(0..len);
+        Some(held.frames.swap_remove(index))
+    }
+
+    /// 잡아 둔 프레임을 모두 무작위 순서로 내보냄 - 더 보낼 새 메시지가 없거나 ACK를 기다려야 할 때
+    pub(crate) fn flush(&self) -> Vec<Frame> {
+        let mut held = self.inner.lock().unwrap();
+        let mut released = Vec::with_capacity(held.frames.len());
+        while !held.frames.is_empty() {
+            let len = held.frames.len();
+            let index = held.rng.gen_range(0..len);
+            released.push(held.frames.swap_remove(index));
+        }
+        released
+    }
+}
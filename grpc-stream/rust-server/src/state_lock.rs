@@ -0,0 +1,21 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// pending 큐, ACK 기록, 전송 위치를 한 시점에 읽기 위한 서버 전체 잠금
+///
+/// 상태를 바꾸는 작업(전송, 재전송, ACK 처리, `DebugService` 변경)은 `change`를 잡고 서로 동시에 진행하고,
+/// `DebugService::snapshot`은 `freeze`로 진행 중인 작업이 끝나길 기다렸다가 모두 멈춘 채 읽음.
+/// 잡은 채로 await하거나 다시 잡지 않음 (기다리는 `freeze`가 있으면 두 번째 `change`가 막힘).
+#[derive(Clone, Default)]
+pub struct StateLock {
+    inner: Arc<RwLock<()>>,
+}
+
+impl StateLock {
+    pub(crate) fn change(&self) -> RwLockReadGuard<'_, ()> {
+        self.inner.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn freeze(&self) -> RwLockWriteGuard<'_, ()> {
+        self.inner.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::streaming::StreamSummary;
+
+/// 스트림 하나의 전송 집계 - 닫기 직전에 `StreamSummary`로 클라이언트에 보냄
+///
+/// `ServerStats`는 서버 전체 합계라 스트림마다 따로 셈. 같은 자리에서 함께 올림.
+#[derive(Debug, Default)]
+pub struct StreamTally {
+    pub sent: AtomicU64,
+    pub acked: AtomicU64,
+    pub retried: AtomicU64,
+    pub dead_lettered: AtomicU64,
+    pub expired: AtomicU64,
+}
+
+impl StreamTally {
+    pub fn summary(&self) -> StreamSummary {
+        StreamSummary {
+            sent: self.sent.load(Ordering::Relaxed),
+            acked: self.acked.load(Ordering::Relaxed),
+            retried: self.retried.load(Ordering::Relaxed),
+            dead_lettered: self.dead_lettered.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            channel: 0,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::future::poll_fn;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio_util::time::delay_queue::{DelayQueue, Key};
+
+enum Request {
+    Set(u64, Duration),
+    Clear(u64),
+}
+
+/// 재전송 타이머 요청 - pending 큐에 메시지를 in-flight로 올리거나 내리는 쪽이 보냄
+///
+/// 타이머는 메시지 id마다 하나. 같은 id로 다시 `set`하면 앞선 타이머를 대신함.
+#[derive(Debug, Clone)]
+pub struct RetryTimers {
+    tx: mpsc::UnboundedSender<Request>,
+}
+
+impl RetryTimers {
+    pub fn new() -> (Self, TimerWheel) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let wheel = TimerWheel {
+            requests: rx,
+            queue: DelayQueue::new(),
+            keys: HashMap::new(),
+        };
+        (Self { tx }, wheel)
+    }
+
+    /// `after` 뒤에 `id`의 ACK 대기가 끝남
+    pub fn set(&self, id: u64, after: Duration) {
+        let _ = self.tx.send(Request::Set(id, after));
+    }
+
+    /// ACK 받았거나 pending에서 빠져 더 기다릴 필요가 없음
+    pub fn clear(&self, id: u64) {
+        let _ = self.tx.send(Request::Clear(id));
+    }
+}
+
+/// 메시지 id로 찾는 `DelayQueue` - 타이머 하나를 걸고 풀고 꺼내는 비용이 in-flight 메시지 수와 무관
+pub struct TimerWheel {
+    requests: mpsc::UnboundedReceiver<Request>,
+    queue: DelayQueue<u64>,
+    keys: HashMap<u64, Key>,
+}
+
+impl TimerWheel {
+    /// ACK 대기가 끝난 다음 id - 그때까지 들어온 요청을 먼저 반영
+    ///
+    /// 모든 `RetryTimers`가 사라지고 남은 타이머도 없으면 None.
+    pub async fn next_expired(&mut self) -> Option<u64> {
+        loop {
+            tokio::select! {
+                biased;
+                request = self.requests.recv() => match request {
+                    Some(request) => self.apply(request),
+                    None if self.queue.is_empty() => return None,
+                    None => {
+                        let expired = poll_fn(|cx| self.queue.poll_expired(cx)).await?;
+                        return Some(self.expire(expired.into_inner()));
+                    }
+                },
+                Some(expired) = poll_fn(|cx| self.queue.poll_expired(cx)) => {
+                    return Some(self.expire(expired.into_inner()));
+                }
+            }
+        }
+    }
+
+    /// 기다리지 않고 이미 만료된 다음 id - 같은 순간에 끝난 타이머를 한꺼번에 모을 때 씀
+    pub fn try_expired(&mut self) -> Option<u64> {
+        while let Ok(request) = self.requests.try_recv() {
+            self.apply(request);
+        }
+        match self.queue.poll_expired(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(Some(expired)) => Some(self.expire(expired.into_inner())),
+            _ => None,
+        }
+    }
+
+    /// 걸려 있는 타이머 수
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn apply(&mut self, request: Request) {
+        match request {
+            Request::Set(id, after) => match self.keys.get(&id) {
+                Some(key) => self.queue.reset(key, after),
+                None => {
+                    let key = self.queue.insert(id, after);
+                    self.keys.insert(id, key);
+                }
+            },
+            Request::Clear(id) => {
+                if let Some(key) = self.keys.remove(&id) {
+                    self.queue.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn expire(&mut self, id: u64) -> u64 {
+        self.keys.remove(&id);
+        id
+    }
+}
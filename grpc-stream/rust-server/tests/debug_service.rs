@@ -0,0 +1,118 @@
+//! `DebugService`로 실행 중인 스트림의 pending 큐를 조회하고 바꿈 - tokio 일시정지 시간
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use grpc_stream_ack_server::debug::proto::debug_service_server::DebugService as _;
+use grpc_stream_ack_server::debug::proto::{
+    ListPendingRequest, PendingEntry, PendingMessage, PendingMessageRef, SessionSnapshot, SnapshotRequest,
+};
+use grpc_stream_ack_server::debug::DebugService;
+use grpc_stream_ack_server::server::StreamingServer;
+use grpc_stream_ack_server::streaming::{stream_message::MessageType, AckMessage, StreamMessage};
+use playground_clock::TokioClock;
+use playground_stats::ServerStats;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{Code, Request, Status};
+
+/// (id, delivery_attempt)
+fn delivery(message: Option<Result<StreamMessage, Status>>) -> (u64, u32) {
+    match message.unwrap().unwrap().message_type {
+        Some(MessageType::Data(data)) => (data.id, data.delivery_attempt),
+        other => panic!("expected data frame, got {:?}", other),
+    }
+}
+
+/// 세션이 하나뿐인 서버의 snapshot
+async fn only_session(debug: &DebugService) -> SessionSnapshot {
+    let mut sessions = debug.snapshot(Request::new(SnapshotRequest::default())).await.unwrap().into_inner().sessions;
+    assert_eq!(sessions.len(), 1);
+    sessions.remove(0)
+}
+
+fn target(id: u64) -> Request<PendingMessageRef> {
+    Request::new(PendingMessageRef { client: String::new(), id })
+}
+
+#[tokio::test(start_paused = true)]
+async fn list_force_retry_and_drop_pending_messages_mid_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new()));
+    let debug = server.debug_service();
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    assert_eq!(delivery(out.next().await), (1, 1));
+    let listed = debug.list_pending(Request::new(ListPendingRequest::default())).await.unwrap().into_inner();
+    assert_eq!(
+        listed.messages,
+        vec![PendingMessage {
+            client: "stream-1".to_string(),
+            id: 1,
+            delivery_attempt: 1,
+            in_flight: true,
+            retry_in_ms: 2000,
+            stream_open: true,
+        }]
+    );
+
+    // ACK 타임아웃(2초)을 기다리지 않고 바로 재전송
+    assert!(!debug.force_retry(target(1)).await.unwrap().into_inner().dead_letter);
+    assert_eq!(delivery(out.next().await), (1, 2));
+    assert_eq!(start.elapsed().as_secs(), 0);
+    let ack = StreamMessage { message_type: Some(MessageType::Ack(AckMessage { ack_id: 1, timestamp: 0, ..Default::default() })) };
+    client_tx.send(Ok(ack)).await.unwrap();
+
+    // 메시지 2는 ACK 없이 빼면 재전송하지 않고 스트림이 완료됨
+    assert_eq!(delivery(out.next().await), (2, 1));
+    debug.drop_pending(target(2)).await.unwrap();
+    assert!(out.next().await.is_none());
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+
+    assert_eq!(debug.force_retry(target(2)).await.unwrap_err().code(), Code::NotFound);
+}
+
+#[tokio::test(start_paused = true)]
+async fn snapshot_reads_pending_acked_and_sent_ids_together() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(TokioClock::new()));
+    let debug = server.debug_service();
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    assert_eq!(delivery(out.next().await), (1, 1));
+    let ack = StreamMessage { message_type: Some(MessageType::Ack(AckMessage { ack_id: 1, timestamp: 0, ..Default::default() })) };
+    client_tx.send(Ok(ack)).await.unwrap();
+    assert_eq!(delivery(out.next().await), (2, 1));
+    assert_eq!(
+        only_session(&debug).await,
+        SessionSnapshot {
+            client: "stream-1".to_string(),
+            epoch: 1,
+            stream_open: true,
+            highest_sent_id: 2,
+            next_id: 3,
+            pending: vec![PendingEntry { id: 2, retries: 0, in_flight: true }],
+            acked_through: 1,
+            acked_above: vec![],
+        }
+    );
+
+    // 2는 ACK 타임아웃(2초) 뒤 재전송 - 3을 보낸 뒤
+    assert_eq!(delivery(out.next().await), (3, 1));
+    assert_eq!(delivery(out.next().await), (2, 2));
+    let second = only_session(&debug).await;
+    assert_eq!(second.highest_sent_id, 3);
+    assert_eq!(second.next_id, 4);
+    assert_eq!(
+        second.pending,
+        vec![PendingEntry { id: 2, retries: 1, in_flight: true }, PendingEntry { id: 3, retries: 0, in_flight: true }]
+    );
+    // ACK한 id는 pending에 없음
+    assert!(second.pending.iter().all(|entry| entry.id > second.acked_through && !second.acked_above.contains(&entry.id)));
+}
@@ -0,0 +1,961 @@
+//! tokio 일시정지 시간으로 재전송 타이밍을 검증 - 수 초짜리 대기가 즉시 끝남
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_stream_ack_server::checksum::verify;
+use grpc_stream_ack_server::client::{ClientInfo, ClientKey};
+use grpc_stream_ack_server::fair_share::FairShare;
+use grpc_stream_ack_server::hello::{self, PROTOCOL_VERSION};
+use grpc_stream_ack_server::report::{DeliveryReport, ReportDir};
+use grpc_stream_ack_server::retry::{BackoffKind, RetryPolicy};
+use grpc_stream_ack_server::send_drop::SendDrops;
+use grpc_stream_ack_server::server::StreamingServer;
+use grpc_stream_ack_server::shuffle::Shuffle;
+use grpc_stream_ack_server::streaming::{
+    stream_message::MessageType, AckMessage, AckUpToMessage, BatchAckMessage, DataMessage, HelloMessage, NackMessage, PauseMessage,
+    ResumeMessage, StreamMessage,
+};
+use prost::Message;
+use playground_audit::{AuditConfig, AuditEvent, AuditLog, Outcome};
+use playground_clock::{Clock, TokioClock};
+use playground_stats::ServerStats;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::Status;
+
+type ClientTx = mpsc::Sender<Result<StreamMessage, Status>>;
+
+fn open(total_messages: u64, stats: Arc<ServerStats>) -> (ClientTx, ReceiverStream<Result<StreamMessage, Status>>) {
+    let server = StreamingServer::new(total_messages, stats, Arc::new(TokioClock::new()));
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let out = server.open_stream(ReceiverStream::new(client_rx));
+    (client_tx, out)
+}
+
+fn data_id(message: Result<StreamMessage, Status>) -> u64 {
+    match message.unwrap().message_type {
+        Some(MessageType::Data(data)) => data.id,
+        other => panic!("expected data frame, got {:?}", other),
+    }
+}
+
+/// (id, epoch, delivery_attempt)
+fn delivery(message: Result<StreamMessage, Status>) -> (u64, u64, u32) {
+    match message.unwrap().message_type {
+        Some(MessageType::Data(data)) => (data.id, data.epoch, data.delivery_attempt),
+        other => panic!("expected data frame, got {:?}", other),
+    }
+}
+
+async fn ack(client_tx: &ClientTx, id: u64) {
+    let ack = StreamMessage {
+        message_type: Some(MessageType::Ack(AckMessage { ack_id: id, timestamp: 0, ..Default::default() })),
+    };
+    client_tx.send(Ok(ack)).await.unwrap();
+}
+
+async fn nack(client_tx: &ClientTx, id: u64) {
+    let nack = StreamMessage {
+        message_type: Some(MessageType::Nack(NackMessage { nack_id: id, reason: "corrupt payload".to_string(), ..Default::default() })),
+    };
+    client_tx.send(Ok(nack)).await.unwrap();
+}
+
+async fn pause(client_tx: &ClientTx, retries: bool) {
+    let pause = StreamMessage {
+        message_type: Some(MessageType::Pause(PauseMessage { pause_retries: retries, timestamp: 0 })),
+    };
+    client_tx.send(Ok(pause)).await.unwrap();
+}
+
+async fn resume(client_tx: &ClientTx) {
+    let resume = StreamMessage {
+        message_type: Some(MessageType::Resume(ResumeMessage { timestamp: 0 })),
+    };
+    client_tx.send(Ok(resume)).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn closes_once_everything_is_acked() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(3, stats.clone());
+    let start = Instant::now();
+
+    let mut ids = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        ids.push(id);
+        ack(&client_tx, id).await;
+    }
+
+    assert_eq!(ids, vec![1, 2, 3]);
+    // t=3 전송 종료 - 마지막 ACK는 이미 받았으므로 바로 닫힘
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cumulative_ack_clears_every_pending_message_up_to_id() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        ack_timeout: Duration::from_secs(3),
+        ..RetryPolicy::default()
+    });
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    let mut ids = Vec::new();
+    while let Some(message) = out.next().await {
+        ids.push(data_id(message));
+        if ids.len() == 3 {
+            let ack = StreamMessage {
+                message_type: Some(MessageType::AckUpTo(AckUpToMessage { ack_id: 3, timestamp: 0, ..Default::default() })),
+            };
+            client_tx.send(Ok(ack)).await.unwrap();
+        }
+    }
+
+    // 프레임 하나로 세 메시지 모두 ACK - 메시지 1의 ACK 대기(t=3)가 끝나기 전에 도착해 재전송 없음
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!(stats.acked_messages.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn max_in_flight_holds_back_new_messages_until_acked() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(TokioClock::new())).with_max_in_flight(1);
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    let mut sent_at = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        sent_at.push((id, start.elapsed().as_millis()));
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        ack(&client_tx, id).await;
+    }
+
+    // 1초 간격이 아니라 앞 메시지의 ACK(1.5초 뒤)를 받고 나서야 다음 메시지
+    assert_eq!(sent_at, vec![(1, 0), (2, 1500), (3, 3000)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unacked_message_is_retried_until_max_retries() {
+    let stats = Arc::new(ServerStats::default());
+    let (_client_tx, mut out) = open(1, stats.clone());
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(60);
+
+    let mut deliveries = Vec::new();
+    while let Ok(Some(message)) = tokio::time::timeout_at(deadline, out.next()).await {
+        deliveries.push((delivery(message), start.elapsed().as_secs()));
+    }
+
+    // 최초 전송 + ACK 대기(2초)가 끝날 때마다 바로 재전송, 최대 3회
+    assert_eq!(deliveries, vec![((1, 1, 1), 0), ((1, 1, 2), 2), ((1, 1, 3), 4), ((1, 1, 4), 6)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn frames_a_slow_reader_leaves_in_the_outbox_record_blocked_time() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(TokioClock::new()))
+        .with_payload_bytes(64 * 1024)
+        .with_retry_policy(RetryPolicy {
+            ack_timeout: Duration::from_secs(10),
+            ..RetryPolicy::default()
+        });
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 채널에 한 프레임만 들어가므로 메시지 2(t=1), 3(t=2)은 t=3.5에 읽기 시작할 때까지 outbox에서 기다림
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    while let Some(message) = out.next().await {
+        let data = match message.unwrap().message_type {
+            Some(MessageType::Data(data)) => data,
+            other => panic!("expected data frame, got {:?}", other),
+        };
+        assert_eq!(data.payload.len(), 64 * 1024);
+        assert!(verify(&data));
+        ack(&client_tx, data.id).await;
+    }
+
+    let blocked = stats.delivery.send_blocked.snapshot();
+    assert_eq!(blocked.count, 3);
+    assert_eq!(blocked.sum, 4.0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_sends_are_retried_as_if_the_ack_was_lost() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(1, stats.clone(), Arc::new(TokioClock::new()))
+        .with_send_drops(SendDrops::new(1.0, Some(7)));
+    let (_client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 모든 전송이 빠짐 - 보낸 것으로 기록되어 ACK 대기가 끝날 때마다 재전송하다 dead letter
+    assert!(out.next().await.is_none());
+    assert_eq!(stats.messages_sent.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn send_drop_seed_repeats_the_same_deliveries() {
+    async fn run() -> (Vec<(u64, u32)>, u64) {
+        let stats = Arc::new(ServerStats::default());
+        let server = StreamingServer::new(20, stats.clone(), Arc::new(TokioClock::new()))
+            .with_send_drops(SendDrops::new(0.5, Some(42)));
+        let (client_tx, client_rx) = mpsc::channel(16);
+        let mut out = server.open_stream(ReceiverStream::new(client_rx));
+        let mut deliveries = Vec::new();
+        while let Some(message) = out.next().await {
+            let (id, _, attempt) = delivery(message);
+            deliveries.push((id, attempt));
+            ack(&client_tx, id).await;
+        }
+        (deliveries, stats.retried_messages.load(Ordering::Relaxed))
+    }
+
+    let (first, retried) = run().await;
+    assert!(retried > 0);
+    assert_eq!(run().await, (first, retried));
+}
+
+#[tokio::test(start_paused = true)]
+async fn shuffle_window_reorders_new_messages_and_repeats_with_a_seed() {
+    async fn run() -> Vec<u64> {
+        let stats = Arc::new(ServerStats::default());
+        // 잡아 둔 동안 타임아웃되지 않도록 ACK 대기를 길게
+        let server = StreamingServer::new(10, stats.clone(), Arc::new(TokioClock::new()))
+            .with_retry_policy(RetryPolicy { ack_timeout: Duration::from_secs(60), ..RetryPolicy::default() })
+            .with_shuffle(Shuffle::new(3, Some(42)));
+        let (client_tx, client_rx) = mpsc::channel(16);
+        let mut out = server.open_stream(ReceiverStream::new(client_rx));
+        let mut ids = Vec::new();
+        while let Some(message) = out.next().await {
+            let (id, _, attempt) = delivery(message);
+            assert_eq!(attempt, 1);
+            ids.push(id);
+            ack(&client_tx, id).await;
+        }
+        assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 0);
+        ids
+    }
+
+    let first = run().await;
+    let mut sorted = first.clone();
+    sorted.sort_unstable();
+    // 새 메시지를 다 보내면 잡아 둔 프레임도 모두 나감
+    assert_eq!(sorted, (1..=10).collect::<Vec<_>>());
+    assert_ne!(first, sorted);
+    assert_eq!(run().await, first);
+}
+
+#[tokio::test(start_paused = true)]
+async fn exponential_backoff_spaces_retries_up_to_max_backoff() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(1, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        backoff: BackoffKind::Exponential,
+        max_backoff: Duration::from_secs(6),
+        ..RetryPolicy::default()
+    });
+    let (_client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(60);
+
+    let mut deliveries = Vec::new();
+    while let Ok(Some(message)) = tokio::time::timeout_at(deadline, out.next()).await {
+        deliveries.push((data_id(message), start.elapsed().as_secs()));
+    }
+
+    // ACK 대기 2초 → 4초 → 6초(상한)
+    assert_eq!(deliveries, vec![(1, 0), (1, 2), (1, 6), (1, 12)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_jitter_moves_retries_off_whole_seconds() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(5, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        jitter: 0.5,
+        ..RetryPolicy::default()
+    });
+    let (_client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(60);
+
+    let mut retried_at = Vec::new();
+    let mut seen = Vec::new();
+    while let Ok(Some(message)) = tokio::time::timeout_at(deadline, out.next()).await {
+        let id = data_id(message);
+        if seen.contains(&id) {
+            retried_at.push(start.elapsed().as_millis());
+        }
+        seen.push(id);
+    }
+
+    // jitter 없이는 매초 보낸 메시지가 2초 뒤에 재전송되므로 정수 초에만 나감
+    assert_eq!(retried_at.len(), 15);
+    assert!(retried_at.iter().any(|millis| millis % 2000 != 0), "{:?}", retried_at);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 15);
+}
+
+#[tokio::test(start_paused = true)]
+async fn nack_redelivers_immediately_until_max_retries() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(1, stats.clone());
+    let start = Instant::now();
+
+    // 매번 NACK - 타임아웃(2초)을 기다리지 않고 바로 다시 오고, 한도를 넘으면 dead letter
+    let mut deliveries = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        deliveries.push((id, start.elapsed().as_secs()));
+        nack(&client_tx, id).await;
+    }
+
+    assert_eq!(deliveries, vec![(1, 0), (1, 0), (1, 0), (1, 0)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn acks_for_ids_not_pending_are_counted_by_kind() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        max_retries: 0,
+        ..RetryPolicy::default()
+    });
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 메시지 1은 ACK하지 않아 t=2에 재전송 없이 dead letter
+    assert_eq!(data_id(out.next().await.unwrap()), 1);
+    assert_eq!(data_id(out.next().await.unwrap()), 2);
+    ack(&client_tx, 2).await;
+    ack(&client_tx, 2).await;
+    ack(&client_tx, 7).await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    ack(&client_tx, 1).await;
+    drop(client_tx);
+    stats.all_streams_finished().await;
+
+    assert_eq!(stats.acked_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.duplicate_acks.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.unknown_acks.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.late_acks.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn lost_ack_is_recovered_by_retry() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(2, stats.clone());
+    let start = Instant::now();
+
+    let mut ids = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        // 메시지 1의 첫 ACK는 유실
+        let first_delivery_of_1 = id == 1 && !ids.contains(&1);
+        if !first_delivery_of_1 {
+            ack(&client_tx, id).await;
+        }
+        ids.push(id);
+    }
+
+    // t=2 메시지 1의 ACK 대기가 끝나 재전송, 그 ACK로 pending이 비어 바로 닫힘
+    assert_eq!(ids, vec![1, 2, 1]);
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.acked_messages.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ack_latency_and_retries_are_recorded_per_message() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(2, stats.clone());
+
+    let mut seen = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        match (id, seen.contains(&id)) {
+            // 메시지 1은 0.5초 늦게 ACK
+            (1, _) => {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                ack(&client_tx, id).await;
+            }
+            // 메시지 2의 첫 ACK는 유실 - 재전송분은 바로 ACK
+            (2, false) => {}
+            _ => ack(&client_tx, id).await,
+        }
+        seen.push(id);
+    }
+
+    let latency = stats.delivery.ack_latency.snapshot();
+    assert_eq!((latency.count, latency.sum), (2, 0.5));
+    let retries = stats.delivery.retries.snapshot();
+    assert_eq!(&retries.buckets[..2], &[(0.0, 1), (1.0, 2)]);
+    assert_eq!(retries.count, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn audit_log_records_retry_and_completion() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("ack-server-audit");
+    let _ = std::fs::remove_dir_all(&dir);
+    let clock = Arc::new(TokioClock::new());
+    let audit = AuditLog::open(AuditConfig::new(&dir, "ack-server", "test"), clock.clone()).unwrap();
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), clock).with_audit(audit.clone());
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    let mut ids = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        // 메시지 1의 첫 ACK는 유실
+        if id != 1 || ids.contains(&1) {
+            ack(&client_tx, id).await;
+        }
+        ids.push(id);
+    }
+    // 클라이언트가 요청 스트림을 닫아야 ACK 핸들러가 끝나고 stream_closed가 기록됨
+    drop(client_tx);
+    while stats.active_streams.load(Ordering::Relaxed) > 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let events: Vec<_> = playground_audit::read(&audit.path().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|record| record.event)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            AuditEvent::StreamOpened { stream: 1 },
+            AuditEvent::MessageSent { stream: 1, id: 1, attempt: 1 },
+            AuditEvent::MessageSent { stream: 1, id: 2, attempt: 1 },
+            AuditEvent::MessageAcked { stream: 1, id: 2 },
+            AuditEvent::MessageSent { stream: 1, id: 1, attempt: 2 },
+            AuditEvent::MessageAcked { stream: 1, id: 1 },
+            AuditEvent::StreamClosed { stream: 1, outcome: Outcome::Completed },
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn delivery_report_is_written_per_client_when_the_stream_ends() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("ack-server-reports");
+    let _ = std::fs::remove_dir_all(&dir);
+    let reports = ReportDir::create(&dir).unwrap();
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new())).with_report_dir(reports.clone());
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server
+        .open_client_stream(ClientInfo::new("chaos/run 1"), ReceiverStream::new(client_rx))
+        .unwrap();
+
+    let mut ids = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        // 메시지 1의 첫 ACK는 유실
+        if id != 1 || ids.contains(&1) {
+            ack(&client_tx, id).await;
+        }
+        ids.push(id);
+    }
+    drop(client_tx);
+    stats.all_streams_finished().await;
+
+    // 파일 이름에 쓸 수 없는 문자는 `_`로
+    let path = dir.join("chaos_run_1-epoch1.json");
+    assert_eq!(reports.file_for(&ClientKey::Client("chaos/run 1".to_string()), 1), path);
+    let report: DeliveryReport = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(report.client, "chaos/run 1");
+    assert_eq!(report.outcome, Outcome::Completed);
+    assert_eq!((report.sent, report.acked, report.retried, report.abandoned), (2, 2, 1, 0));
+    assert_eq!(report.ended_at_ms - report.started_at_ms, 2000);
+    let acks: Vec<(u64, u32)> = report.acks.iter().map(|ack| (ack.id, ack.retries)).collect();
+    assert_eq!(acks, vec![(2, 0), (1, 1)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn upload_counts_messages_and_bytes_until_interrupted() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(3, stats.clone(), Arc::new(TokioClock::new()));
+    let message = |id| DataMessage { id, timestamp: 0, payload: "abc".to_string(), needs_ack: false, ..Default::default() };
+
+    let summary = server
+        .receive_upload(tokio_stream::iter([Ok(message(1)), Ok(message(2))]))
+        .await
+        .unwrap();
+    assert_eq!((summary.messages, summary.bytes), (2, 2 * message(1).encoded_len() as u64));
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+
+    // 업로드 도중 클라이언트 cancel() - 받은 상태를 그대로 돌려줌
+    let interrupted = tokio_stream::iter([Ok(message(1)), Err(Status::cancelled("client cancel"))]);
+    let status = server.receive_upload(interrupted).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Cancelled);
+    assert_eq!(stats.cancellations().get("intentional_cancellation"), Some(&1));
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn each_client_id_has_its_own_pending_queue() {
+    let server = StreamingServer::new(2, Arc::new(ServerStats::default()), Arc::new(TokioClock::new()));
+    let (a_tx, a_rx) = mpsc::channel(16);
+    let (_b_tx, b_rx) = mpsc::channel(16);
+    let mut a = server.open_client_stream(ClientInfo::new("a"), ReceiverStream::new(a_rx)).unwrap();
+    let mut b = server.open_client_stream(ClientInfo::new("b"), ReceiverStream::new(b_rx)).unwrap();
+
+    // 두 클라이언트 모두 id 1을 받지만 a의 ACK는 b의 pending에 영향을 주지 않음
+    assert_eq!(data_id(a.next().await.unwrap()), 1);
+    assert_eq!(data_id(b.next().await.unwrap()), 1);
+    ack(&a_tx, 1).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(server.pending_for("a"), Some(0));
+    assert_eq!(server.pending_for("b"), Some(1));
+
+    // 같은 클라이언트의 두 번째 스트림은 거절
+    let (_dup_tx, dup_rx) = mpsc::channel(16);
+    assert!(server.open_client_stream(ClientInfo::new("a"), ReceiverStream::new(dup_rx)).is_none());
+
+    while let Some(message) = a.next().await {
+        ack(&a_tx, data_id(message)).await;
+    }
+    drop(a_tx);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(server.pending_for("a"), None);
+    let (_again_tx, again_rx) = mpsc::channel(16);
+    assert!(server.open_client_stream(ClientInfo::new("a"), ReceiverStream::new(again_rx)).is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn reconnect_with_last_acked_id_replays_only_later_messages() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(4, stats.clone(), Arc::new(TokioClock::new()));
+
+    // 1만 ACK하고 2를 받은 뒤 끊김 - 그 사이 3도 pending에 들어감
+    let (first_tx, first_rx) = mpsc::channel(16);
+    let mut first = server.open_client_stream(ClientInfo::new("a"), ReceiverStream::new(first_rx)).unwrap();
+    assert_eq!(delivery(first.next().await.unwrap()), (1, 1, 1));
+    ack(&first_tx, 1).await;
+    assert_eq!(data_id(first.next().await.unwrap()), 2);
+    drop((first, first_tx));
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(server.pending_for("a"), Some(2));
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+
+    let (tx, rx) = mpsc::channel(16);
+    let mut out = server
+        .open_client_stream(ClientInfo::new("a").with_last_acked(1), ReceiverStream::new(rx))
+        .unwrap();
+    let mut received = Vec::new();
+    while let Some(message) = out.next().await {
+        let (id, epoch, attempt) = delivery(message);
+        ack(&tx, id).await;
+        received.push((id, epoch, attempt));
+    }
+
+    // 재연결 전에 한 번씩 보낸 2, 3은 두 번째 전달
+    assert_eq!(received, vec![(2, 2, 2), (3, 2, 2), (4, 2, 1)]);
+    drop(tx);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(server.pending_for("a"), None);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn message_lost_inside_batch_retries_whole_batch() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(6, stats.clone(), Arc::new(TokioClock::new())).with_batch_size(3);
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    let mut batches = Vec::new();
+    while let Some(message) = out.next().await {
+        let batch = match message.unwrap().message_type {
+            Some(MessageType::Batch(batch)) => batch,
+            other => panic!("expected batch frame, got {:?}", other),
+        };
+        let ids: Vec<u64> = batch.messages.iter().map(|message| message.id).collect();
+        // 두 번째 batch의 메시지 하나를 잃어버린 것처럼 batch ACK를 보내지 않음
+        if batch.batch_id != 2 {
+            let ack = StreamMessage {
+                message_type: Some(MessageType::BatchAck(BatchAckMessage { batch_id: batch.batch_id, timestamp: 0, ..Default::default() })),
+            };
+            client_tx.send(Ok(ack)).await.unwrap();
+        }
+        batches.push((batch.batch_id, ids));
+    }
+
+    assert_eq!(batches, vec![(1, vec![1, 2, 3]), (2, vec![4, 5, 6]), (3, vec![4, 5, 6])]);
+    // 잃어버린 메시지 하나 때문에 batch 전체가 재전송됨
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.acked_messages.load(Ordering::Relaxed), 6);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn client_hello_without_batch_gets_one_message_per_frame() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new()))
+        .with_batch_size(2)
+        .with_hello_timeout(Duration::from_millis(500));
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let client_hello = StreamMessage {
+        message_type: Some(MessageType::Hello(HelloMessage {
+            protocol_version: PROTOCOL_VERSION + 1,
+            features: vec![hello::ACK_UP_TO.to_string()],
+        })),
+    };
+    client_tx.send(Ok(client_hello)).await.unwrap();
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    // 서버 Hello가 먼저 - 클라이언트가 더 새 버전이면 서버 버전으로 맞춤
+    match out.next().await.unwrap().unwrap().message_type {
+        Some(MessageType::Hello(server_hello)) => {
+            assert_eq!(server_hello.protocol_version, PROTOCOL_VERSION);
+            assert!(server_hello.features.iter().any(|feature| feature == hello::BATCH));
+        }
+        other => panic!("expected hello frame, got {:?}", other),
+    }
+    // batch와 checksum을 알리지 않았으므로 하나씩, checksum 없이 보냄 - Hello를 받았으니 기다리지 않음
+    let mut frames = Vec::new();
+    while let Some(message) = out.next().await {
+        let data = match message.unwrap().message_type {
+            Some(MessageType::Data(data)) => data,
+            other => panic!("expected data frame, got {:?}", other),
+        };
+        frames.push((data.id, data.checksum, start.elapsed().as_secs()));
+        ack(&client_tx, data.id).await;
+    }
+
+    // Hello를 기다리지 않고 t=0부터 SEND_INTERVAL마다 보내고, 마지막 전송 뒤 한 번 더 쉬고 닫힘
+    assert_eq!(frames, vec![(1, 0, 0), (2, 0, 1)]);
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_summary_is_the_last_frame_for_clients_that_ask_for_it() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        max_retries: 1,
+        ..RetryPolicy::default()
+    });
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let client_hello = StreamMessage {
+        message_type: Some(MessageType::Hello(HelloMessage {
+            protocol_version: PROTOCOL_VERSION,
+            features: vec![hello::SUMMARY.to_string()],
+        })),
+    };
+    client_tx.send(Ok(client_hello)).await.unwrap();
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 메시지 1은 ACK하지 않아 한 번 재전송한 뒤 dead letter
+    let mut summary = None;
+    while let Some(message) = out.next().await {
+        assert!(summary.is_none(), "frame after the summary");
+        match message.unwrap().message_type {
+            Some(MessageType::Data(data)) if data.id == 2 => ack(&client_tx, 2).await,
+            Some(MessageType::Summary(frame)) => summary = Some(frame),
+            _ => {}
+        }
+    }
+
+    let summary = summary.expect("summary frame");
+    assert_eq!(
+        (summary.sent, summary.acked, summary.retried, summary.dead_lettered, summary.expired),
+        (2, 1, 1, 1, 0)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn client_without_hello_gets_configured_batches_after_the_timeout() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new()))
+        .with_batch_size(2)
+        .with_hello_timeout(Duration::from_millis(500));
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    // 이전 버전 클라이언트 - 서버 Hello 없이 Hello를 기다린 뒤 설정대로 batch
+    let batch = match out.next().await.unwrap().unwrap().message_type {
+        Some(MessageType::Batch(batch)) => batch,
+        other => panic!("expected batch frame, got {:?}", other),
+    };
+    assert_eq!(start.elapsed(), Duration::from_millis(500));
+    assert_eq!(batch.messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![1, 2]);
+    assert!(batch.messages.iter().all(verify));
+    let ack = StreamMessage {
+        message_type: Some(MessageType::BatchAck(BatchAckMessage { batch_id: batch.batch_id, timestamp: 0, ..Default::default() })),
+    };
+    client_tx.send(Ok(ack)).await.unwrap();
+    assert!(out.next().await.is_none());
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn high_priority_retry_overtakes_queued_low_priority_messages() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(4, stats, Arc::new(TokioClock::new())).with_priority_levels(2);
+    let (_client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 클라이언트가 3.5초 동안 읽지 못함 - 그동안 2~4와 t=2(1), t=3(2)의 재전송이 outbox에 쌓임
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    let mut frames = Vec::new();
+    while let Ok(Some(message)) = tokio::time::timeout(Duration::from_millis(100), out.next()).await {
+        let data = match message.unwrap().message_type {
+            Some(MessageType::Data(data)) => data,
+            other => panic!("expected data frame, got {:?}", other),
+        };
+        frames.push((data.id, data.priority, data.delivery_attempt));
+    }
+
+    // 이미 채널에 있던 1 다음, 우선순위 1(홀수 id)이 재전송까지 포함해 먼저 나감 (t=2는 1의 재전송이 3보다 먼저)
+    assert_eq!(frames, vec![(1, 1, 1), (1, 1, 2), (3, 1, 1), (2, 0, 1), (4, 0, 1), (2, 0, 2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn message_past_ttl_expires_instead_of_being_retried() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(1, stats.clone(), Arc::new(TokioClock::new())).with_retry_policy(RetryPolicy {
+        message_ttl: Some(Duration::from_secs(3)),
+        ..RetryPolicy::default()
+    });
+    let (_client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+
+    let mut deliveries = Vec::new();
+    while let Some(message) = out.next().await {
+        deliveries.push((data_id(message), start.elapsed().as_secs()));
+    }
+
+    // t=2에는 아직 TTL 안이라 재전송, t=4에는 재전송 대신 만료 - pending이 비었으므로 스트림 종료
+    assert_eq!(deliveries, vec![(1, 0), (1, 2)]);
+    assert_eq!(start.elapsed(), Duration::from_secs(4));
+    assert_eq!(stats.expired_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn nacked_message_past_its_deadline_is_not_resent() {
+    let stats = Arc::new(ServerStats::default());
+    let clock = Arc::new(TokioClock::new());
+    let server = StreamingServer::new(1, stats.clone(), clock.clone()).with_retry_policy(RetryPolicy {
+        message_ttl: Some(Duration::from_secs(3)),
+        ..RetryPolicy::default()
+    });
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+    let start = Instant::now();
+    // t=0에 발행 - 기한은 초 단위로 자르지 않은 발행 시각 기준
+    let published_ms = clock.unix_millis();
+
+    let deadline = |message: Result<StreamMessage, Status>| match message.unwrap().message_type {
+        Some(MessageType::Data(data)) => {
+            assert_eq!(data.timestamp, published_ms / 1000);
+            (data.delivery_attempt, data.deadline_unix_ms - published_ms)
+        }
+        other => panic!("expected data frame, got {:?}", other),
+    };
+    // 재전송해도 기한은 처음 발행 시각 + TTL 그대로
+    assert_eq!(deadline(out.next().await.unwrap()), (1, 3000));
+    assert_eq!(deadline(out.next().await.unwrap()), (2, 3000));
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+    // 재전송 횟수가 남았어도 기한이 지난 NACK은 다시 보내지 않고 만료
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    nack(&client_tx, 1).await;
+    assert!(out.next().await.is_none());
+    assert!(start.elapsed() < Duration::from_secs(4));
+    assert_eq!(stats.expired_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.pending_messages.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ordered_redelivery_resends_later_messages_after_the_retry() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(4, stats.clone(), Arc::new(TokioClock::new()))
+        .with_retry_policy(RetryPolicy {
+            ack_timeout: Duration::from_millis(2500),
+            ..RetryPolicy::default()
+        })
+        .with_ordered_redelivery();
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // 다음 차례가 아닌 메시지는 버리고 누적 ACK만 보내는 클라이언트 - 메시지 1의 첫 전달은 유실
+    let mut frames = Vec::new();
+    let mut processed = Vec::new();
+    while let Some(message) = out.next().await {
+        let (id, _, attempt) = delivery(message);
+        frames.push((id, attempt));
+        if (id, attempt) == (1, 1) || id != processed.len() as u64 + 1 {
+            continue;
+        }
+        processed.push(id);
+        let ack = StreamMessage {
+            message_type: Some(MessageType::AckUpTo(AckUpToMessage { ack_id: id, timestamp: 0, ..Default::default() })),
+        };
+        client_tx.send(Ok(ack)).await.unwrap();
+    }
+
+    // t=2.5 메시지 1의 재전송 뒤로 이미 보낸 2, 3도 다시 보내고, 새 메시지 4는 그 다음
+    assert_eq!(frames, vec![(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2), (4, 1)]);
+    assert_eq!(processed, vec![1, 2, 3, 4]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 3);
+    assert_eq!(stats.acked_messages.load(Ordering::Relaxed), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn pause_holds_new_messages_until_resume() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(3, stats.clone());
+    let start = Instant::now();
+
+    assert_eq!(data_id(out.next().await.unwrap()), 1);
+    ack(&client_tx, 1).await;
+    pause(&client_tx, false).await;
+    // t=1에 보낼 메시지 2를 Resume까지 미룸
+    assert!(tokio::time::timeout(Duration::from_secs(5), out.next()).await.is_err());
+
+    resume(&client_tx).await;
+    let mut sent_at = Vec::new();
+    while let Some(message) = out.next().await {
+        let id = data_id(message);
+        sent_at.push((id, start.elapsed().as_secs()));
+        ack(&client_tx, id).await;
+    }
+
+    // t=5 재개하자마자 2, 그 뒤로는 다시 1초 간격
+    assert_eq!(sent_at, vec![(2, 5), (3, 6)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn pause_with_retries_holds_retries_until_resume() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(1, stats.clone());
+    let start = Instant::now();
+
+    // ACK하지 않고 재전송까지 멈춤 - t=2 ACK 대기가 끝나도 재전송하지 않음
+    assert_eq!(delivery(out.next().await.unwrap()), (1, 1, 1));
+    pause(&client_tx, true).await;
+    assert!(tokio::time::timeout(Duration::from_secs(5), out.next()).await.is_err());
+
+    resume(&client_tx).await;
+    assert_eq!(delivery(out.next().await.unwrap()), (1, 1, 2));
+    assert_eq!(start.elapsed(), Duration::from_secs(5));
+    ack(&client_tx, 1).await;
+
+    assert!(out.next().await.is_none());
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn data_messages_carry_a_checksum_that_catches_corruption() {
+    let stats = Arc::new(ServerStats::default());
+    let (client_tx, mut out) = open(1, stats.clone());
+
+    let mut data = match out.next().await.unwrap().unwrap().message_type {
+        Some(MessageType::Data(data)) => data,
+        other => panic!("expected data frame, got {:?}", other),
+    };
+    assert_ne!(data.checksum, 0);
+    assert!(verify(&data));
+
+    // 프록시가 payload 한 바이트를 바꾼 것처럼 - 검사에 걸리고 NACK하면 바로 다시 옴
+    data.payload.replace_range(0..1, "#");
+    assert!(!verify(&data));
+    nack(&client_tx, data.id).await;
+    let (id, _, attempt) = delivery(out.next().await.unwrap());
+    assert_eq!((id, attempt), (1, 2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn channels_number_and_retry_their_messages_independently() {
+    let stats = Arc::new(ServerStats::default());
+    let server = StreamingServer::new(2, stats.clone(), Arc::new(TokioClock::new())).with_channels(2);
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let mut out = server.open_stream(ReceiverStream::new(client_rx));
+
+    // (channel, id, delivery_attempt)
+    let mut received = Vec::new();
+    while let Some(message) = out.next().await {
+        let data = match message.unwrap().message_type {
+            Some(MessageType::Data(data)) => data,
+            other => panic!("expected data frame, got {:?}", other),
+        };
+        received.push((data.channel, data.id, data.delivery_attempt));
+        // 채널 1의 메시지 1만 한 번 잃어버린 것처럼 - 채널 0은 재전송 없이 끝나야 함
+        if (data.channel, data.id, data.delivery_attempt) == (1, 1, 1) {
+            continue;
+        }
+        let ack = StreamMessage {
+            message_type: Some(MessageType::Ack(AckMessage { ack_id: data.id, timestamp: 0, channel: data.channel })),
+        };
+        client_tx.send(Ok(ack)).await.unwrap();
+    }
+
+    received.sort_unstable();
+    assert_eq!(received, vec![(0, 1, 1), (0, 2, 1), (1, 1, 1), (1, 1, 2), (1, 2, 1)]);
+    assert_eq!(stats.retried_messages.load(Ordering::Relaxed), 1);
+    // 채널마다 스트림 하나로 셈
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fair_share_hands_out_send_slots_by_stream_weight() {
+    let stats = Arc::new(ServerStats::default());
+    // 슬롯은 2초마다 하나 - 두 스트림 모두 1초 간격보다 느리게 받으므로 항상 차례를 기다림
+    let server = StreamingServer::new(6, stats.clone(), Arc::new(TokioClock::new()))
+        .with_fair_share(FairShare::new(0.5, stats.clone()));
+    let (heavy_tx, heavy_rx) = mpsc::channel(16);
+    let (light_tx, light_rx) = mpsc::channel(16);
+    let heavy = server
+        .open_client_stream(ClientInfo::new("heavy").with_weight(3), ReceiverStream::new(heavy_rx))
+        .unwrap();
+    let light = server
+        .open_client_stream(ClientInfo::new("light").with_weight(1), ReceiverStream::new(light_rx))
+        .unwrap();
+
+    let mut arrivals = heavy.map(|message| ("heavy", message)).merge(light.map(|message| ("light", message)));
+    let mut order = Vec::new();
+    while let Some((name, message)) = arrivals.next().await {
+        let id = data_id(message);
+        order.push(name);
+        ack(if name == "heavy" { &heavy_tx } else { &light_tx }, id).await;
+    }
+
+    // 3:1 - heavy가 6개를 받는 동안 light는 2개 (먼저 연 heavy가 첫 슬롯을 받음)
+    let heavy_done = order.iter().rposition(|&name| name == "heavy").unwrap();
+    assert_eq!(order[..heavy_done].iter().filter(|&&name| name == "light").count(), 2);
+    assert_eq!(order.len(), 12);
+    assert_eq!(stats.fair_share.wait.snapshot().count, 12);
+    assert!(stats.fair_share.index() > 0.0);
+}
@@ -0,0 +1,261 @@
+//! 실험마다 남기는 JSONL 감사 로그
+//!
+//! 두 gRPC 서버가 연결/메시지/취소 이벤트를 실행(run)별 파일에 한 줄씩 추가한다.
+//! 파일이 `max_bytes`를 넘으면 `{name}.1.jsonl`, `{name}.2.jsonl` ... 로 밀어내며 회전하고,
+//! `read`로 회전된 파일까지 시간 순서대로 다시 읽을 수 있다.
+//!
+//! `PLAYGROUND_AUDIT_DIR`이 설정되었거나 서버의 `--event-log`로 파일을 지정한 경우에만 기록하고,
+//! 없으면 모든 호출이 아무 일도 하지 않는다.
+
+mod writer;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use playground_clock::SharedClock;
+use serde::{Deserialize, Serialize};
+
+use writer::RotatingWriter;
+
+pub const AUDIT_DIR_ENV: &str = "PLAYGROUND_AUDIT_DIR";
+/// 여러 컴포넌트의 로그를 같은 실행으로 묶을 때 지정 (없으면 시작 시각 밀리초)
+pub const RUN_ID_ENV: &str = "PLAYGROUND_RUN_ID";
+
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// 모든 메시지 전송(및 ACK) 완료
+    Completed,
+    /// 클라이언트가 명시적으로 취소
+    Cancelled,
+    /// 네트워크 단절 등으로 중간에 끊김
+    Disconnected,
+    /// 서버 종료로 남은 메시지를 보낸 뒤 닫음
+    ServerShutdown,
+    /// 클라이언트가 정한 마감 시간(`grpc-timeout`)이 지남
+    DeadlineExceeded,
+    /// 서버(관리자)가 취소
+    ServerCancelled,
+    /// 클라이언트가 요청 스트림을 닫아(half-close) 남은 메시지를 보낸 뒤 닫음
+    HalfClosed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    StreamOpened { stream: u64 },
+    /// `attempt`는 1부터 (2 이상이면 재전송)
+    MessageSent { stream: u64, id: u64, attempt: u32 },
+    MessageAcked { stream: u64, id: u64 },
+    StreamCancelled { stream: u64, reason: String },
+    StreamClosed { stream: u64, outcome: Outcome },
+}
+
+/// JSONL 한 줄
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub ts_ms: u64,
+    pub server: String,
+    pub run: String,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    pub dir: PathBuf,
+    /// 파일 이름 앞부분 (`{server}-{run}.jsonl`)
+    pub server: String,
+    pub run: String,
+    pub max_bytes: u64,
+    pub max_files: usize,
+    /// 지정하면 `dir`/`server`/`run`으로 만든 이름 대신 이 파일에 기록
+    pub file: Option<PathBuf>,
+}
+
+impl AuditConfig {
+    pub fn new(dir: impl Into<PathBuf>, server: &str, run: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            server: server.to_string(),
+            run: run.to_string(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            file: None,
+        }
+    }
+
+    /// 정해진 경로에 기록 - 여러 실행의 로그를 같은 이름으로 남겨 비교할 때
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// 현재 기록 중인 파일 경로
+    pub fn path(&self) -> PathBuf {
+        match &self.file {
+            Some(file) => file.clone(),
+            None => self.dir.join(format!("{}-{}.jsonl", self.server, self.run)),
+        }
+    }
+}
+
+struct Inner {
+    server: String,
+    run: String,
+    clock: SharedClock,
+    writer: Mutex<RotatingWriter>,
+}
+
+/// 서버 하나의 감사 로그 - 복제해서 스트림 태스크들과 공유
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    inner: Option<Arc<Inner>>,
+    next_stream: Arc<AtomicU64>,
+}
+
+impl AuditLog {
+    /// 기록하지 않는 로그 (서버 기본값)
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn open(config: AuditConfig, clock: SharedClock) -> io::Result<Self> {
+        let writer = RotatingWriter::open(config.path(), config.max_bytes, config.max_files)?;
+        Ok(Self {
+            inner: Some(Arc::new(Inner {
+                server: config.server,
+                run: config.run,
+                clock,
+                writer: Mutex::new(writer),
+            })),
+            next_stream: Arc::default(),
+        })
+    }
+
+    /// `PLAYGROUND_AUDIT_DIR`이 있으면 그 아래에 `{server}-{run}.jsonl`로 기록
+    pub fn from_env(server: &str, clock: SharedClock) -> io::Result<Self> {
+        let Ok(dir) = std::env::var(AUDIT_DIR_ENV) else {
+            return Ok(Self::disabled());
+        };
+        let run = run_id(&clock);
+        Self::open(AuditConfig::new(dir, server, &run), clock)
+    }
+
+    /// `--event-log` - `PLAYGROUND_AUDIT_DIR` 대신 `path`에 기록 (`run`은 `from_env`와 같은 규칙)
+    pub fn open_file(path: impl Into<PathBuf>, server: &str, clock: SharedClock) -> io::Result<Self> {
+        let path = path.into();
+        let run = run_id(&clock);
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self::open(AuditConfig::new(dir, server, &run).with_file(path), clock)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// 현재 기록 중인 파일 경로
+    pub fn path(&self) -> Option<PathBuf> {
+        self.inner
+            .as_ref()
+            .map(|inner| inner.writer.lock().unwrap().path().to_path_buf())
+    }
+
+    /// 새 스트림 번호를 배정하고 `stream_opened` 기록
+    pub fn stream(&self) -> StreamAudit {
+        let stream = self.next_stream.fetch_add(1, Ordering::Relaxed) + 1;
+        let audit = StreamAudit {
+            log: self.clone(),
+            stream,
+        };
+        audit.log.record(AuditEvent::StreamOpened { stream });
+        audit
+    }
+
+    /// 이벤트 한 줄 추가 - 기록 실패는 실험을 멈추지 않고 경고만 남김
+    pub fn record(&self, event: AuditEvent) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let record = AuditRecord {
+            ts_ms: inner.clock.unix_millis(),
+            server: inner.server.clone(),
+            run: inner.run.clone(),
+            event,
+        };
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("⚠️ [AUDIT] Failed to encode record: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = inner.writer.lock().unwrap().append(&line) {
+            eprintln!("⚠️ [AUDIT] Failed to write record: {}", e);
+        }
+    }
+}
+
+/// `PLAYGROUND_RUN_ID`, 없으면 시작 시각 밀리초
+fn run_id(clock: &SharedClock) -> String {
+    std::env::var(RUN_ID_ENV).unwrap_or_else(|_| clock.unix_millis().to_string())
+}
+
+/// 스트림 하나에 묶인 기록 핸들
+#[derive(Clone)]
+pub struct StreamAudit {
+    log: AuditLog,
+    stream: u64,
+}
+
+impl StreamAudit {
+    pub fn id(&self) -> u64 {
+        self.stream
+    }
+
+    pub fn message_sent(&self, id: u64, attempt: u32) {
+        self.log.record(AuditEvent::MessageSent {
+            stream: self.stream,
+            id,
+            attempt,
+        });
+    }
+
+    pub fn message_acked(&self, id: u64) {
+        self.log.record(AuditEvent::MessageAcked { stream: self.stream, id });
+    }
+
+    pub fn cancelled(&self, reason: &str) {
+        self.log.record(AuditEvent::StreamCancelled {
+            stream: self.stream,
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn closed(&self, outcome: Outcome) {
+        self.log.record(AuditEvent::StreamClosed {
+            stream: self.stream,
+            outcome,
+        });
+    }
+}
+
+/// `path`(현재 파일)와 회전된 파일들을 오래된 것부터 읽음
+pub fn read(path: &Path) -> io::Result<Vec<AuditRecord>> {
+    let mut records = Vec::new();
+    for file in writer::files_oldest_first(path) {
+        let content = std::fs::read_to_string(&file)?;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let record = serde_json::from_str(line).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file.display(), e))
+            })?;
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
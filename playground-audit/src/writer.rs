@@ -0,0 +1,92 @@
+//! 크기 기준으로 회전하는 추가 전용 파일
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub struct RotatingWriter {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    /// 현재 파일을 제외하고 남길 회전 파일 수
+    max_files: usize,
+}
+
+/// `run.jsonl`의 n번째 회전 파일 `run.n.jsonl`
+fn rotated(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}.jsonl", stem, index))
+}
+
+/// 존재하는 회전 파일과 현재 파일을 오래된 것부터 나열
+pub fn files_oldest_first(path: &Path) -> Vec<PathBuf> {
+    let mut rotated_files: Vec<_> = (1..)
+        .map(|index| rotated(path, index))
+        .take_while(|file| file.exists())
+        .collect();
+    rotated_files.reverse();
+    if path.exists() {
+        rotated_files.push(path.to_path_buf());
+    }
+    rotated_files
+}
+
+impl RotatingWriter {
+    pub fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 한 줄 추가 후 바로 flush (프로세스가 죽어도 그 시점까지는 남도록)
+    pub fn append(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()?;
+        self.written += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            // 회전 파일을 남기지 않으면 현재 파일을 비우고 다시 씀
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let oldest = rotated(&self.path, self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let from = rotated(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(&self.path, 1))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use playground_audit::{read, AuditConfig, AuditEvent, AuditLog, Outcome};
+use playground_clock::SystemClock;
+
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn records_round_trip_in_order() {
+    let dir = fresh_dir("audit-round-trip");
+    let log = AuditLog::open(AuditConfig::new(&dir, "test-server", "run1"), Arc::new(SystemClock)).unwrap();
+
+    let stream = log.stream();
+    stream.message_sent(1, 1);
+    stream.message_acked(1);
+    stream.message_sent(2, 1);
+    stream.message_sent(2, 2);
+    stream.cancelled("gRPC standard cancellation");
+    stream.closed(Outcome::Cancelled);
+
+    let path = log.path().unwrap();
+    assert_eq!(path, dir.join("test-server-run1.jsonl"));
+
+    let records = read(&path).unwrap();
+    assert!(records.iter().all(|record| record.server == "test-server" && record.run == "run1"));
+    let events: Vec<_> = records.into_iter().map(|record| record.event).collect();
+    assert_eq!(
+        events,
+        vec![
+            AuditEvent::StreamOpened { stream: 1 },
+            AuditEvent::MessageSent { stream: 1, id: 1, attempt: 1 },
+            AuditEvent::MessageAcked { stream: 1, id: 1 },
+            AuditEvent::MessageSent { stream: 1, id: 2, attempt: 1 },
+            AuditEvent::MessageSent { stream: 1, id: 2, attempt: 2 },
+            AuditEvent::StreamCancelled {
+                stream: 1,
+                reason: "gRPC standard cancellation".to_string()
+            },
+            AuditEvent::StreamClosed {
+                stream: 1,
+                outcome: Outcome::Cancelled
+            },
+        ]
+    );
+}
+
+#[test]
+fn each_line_is_flat_json() {
+    let dir = fresh_dir("audit-flat");
+    let log = AuditLog::open(AuditConfig::new(&dir, "s", "r"), Arc::new(SystemClock)).unwrap();
+    log.stream().message_sent(7, 1);
+
+    let content = std::fs::read_to_string(log.path().unwrap()).unwrap();
+    let last: serde_json::Value = serde_json::from_str(content.lines().last().unwrap()).unwrap();
+    assert_eq!(last["event"], "message_sent");
+    assert_eq!(last["id"], 7);
+    assert_eq!(last["stream"], 1);
+}
+
+#[test]
+fn rotation_keeps_max_files_and_read_spans_them() {
+    let dir = fresh_dir("audit-rotation");
+    let mut config = AuditConfig::new(&dir, "rot", "run");
+    config.max_bytes = 300;
+    config.max_files = 2;
+    let log = AuditLog::open(config, Arc::new(SystemClock)).unwrap();
+
+    let stream = log.stream();
+    for id in 1..=40 {
+        stream.message_sent(id, 1);
+    }
+
+    assert!(dir.join("rot-run.1.jsonl").exists());
+    assert!(dir.join("rot-run.2.jsonl").exists());
+    assert!(!dir.join("rot-run.3.jsonl").exists());
+    for file in std::fs::read_dir(&dir).unwrap() {
+        assert!(file.unwrap().metadata().unwrap().len() <= 300);
+    }
+
+    // 오래된 파일은 버려졌지만 남은 기록은 순서대로 이어져야 함
+    let ids: Vec<_> = read(&dir.join("rot-run.jsonl"))
+        .unwrap()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            AuditEvent::MessageSent { id, .. } => Some(id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(*ids.last().unwrap(), 40);
+    assert!(ids.windows(2).all(|pair| pair[1] == pair[0] + 1));
+}
+
+#[test]
+fn disabled_log_still_numbers_streams() {
+    let log = AuditLog::disabled();
+    assert!(!log.is_enabled());
+    assert_eq!(log.stream().id(), 1);
+    assert_eq!(log.stream().id(), 2);
+    assert!(log.path().is_none());
+}
+
+#[test]
+fn open_file_writes_to_the_given_path() {
+    let dir = fresh_dir("audit-event-log");
+    let path = dir.join("events.jsonl");
+    let log = AuditLog::open_file(&path, "test-server", Arc::new(SystemClock)).unwrap();
+
+    let stream = log.stream();
+    stream.closed(Outcome::Completed);
+
+    assert_eq!(log.path().unwrap(), path);
+    let events: Vec<_> = read(&path).unwrap().into_iter().map(|record| record.event).collect();
+    assert_eq!(
+        events,
+        vec![
+            AuditEvent::StreamOpened { stream: 1 },
+            AuditEvent::StreamClosed { stream: 1, outcome: Outcome::Completed },
+        ]
+    );
+}
@@ -0,0 +1,186 @@
+//! 스트리밍 서버 공용 bearer 토큰 인증
+//!
+//! `--auth-token`으로 허용 토큰 목록을 주거나 `--auth-hmac-secret`으로 서명 토큰을 검증한다.
+//! 옵션이 없으면 기존처럼 인증 없이 동작. 실패는 항상 UNAUTHENTICATED라
+//! 클라이언트가 취소(CANCELLED)나 끊김(UNAVAILABLE)과 구분할 수 있다.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use clap::Args;
+use ring::hmac;
+use tonic::metadata::{Ascii, MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use tracing::warn;
+
+/// 토큰을 싣는 요청 메타데이터 키
+pub const AUTHORIZATION_METADATA: &str = "authorization";
+
+/// 서버 CLI에 `#[command(flatten)]`으로 붙이는 인증 옵션
+#[derive(Args, Debug, Clone, Default)]
+pub struct AuthArgs {
+    /// Accepted bearer token; repeat or comma-separate for several (enables auth)
+    #[arg(long = "auth-token", env = "GRPC_AUTH_TOKENS", value_delimiter = ',', conflicts_with = "auth_hmac_secret")]
+    pub auth_tokens: Vec<String>,
+
+    /// Secret for HMAC-signed bearer tokens `<subject>.<hex HMAC-SHA256(subject)>` (enables auth)
+    #[arg(long, env = "GRPC_AUTH_HMAC_SECRET")]
+    pub auth_hmac_secret: Option<String>,
+}
+
+impl AuthArgs {
+    /// 로그에 남길 모드 이름
+    pub fn mode(&self) -> &'static str {
+        match (&self.auth_hmac_secret, self.auth_tokens.is_empty()) {
+            (Some(_), _) => "hmac",
+            (None, false) => "tokens",
+            (None, true) => "none",
+        }
+    }
+
+    pub fn authenticator(&self) -> Authenticator {
+        match &self.auth_hmac_secret {
+            Some(secret) => Authenticator::hmac(secret.as_bytes()),
+            None if !self.auth_tokens.is_empty() => Authenticator::tokens(self.auth_tokens.iter().cloned()),
+            None => Authenticator::disabled(),
+        }
+    }
+}
+
+/// 인증 실패 사유 - `Status::unauthenticated`로 변환됨
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    Missing,
+    Malformed,
+    Invalid,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuthError::Missing => "missing bearer token",
+            AuthError::Malformed => "malformed authorization header",
+            AuthError::Invalid => "invalid bearer token",
+        })
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<AuthError> for Status {
+    fn from(error: AuthError) -> Self {
+        Status::unauthenticated(error.to_string())
+    }
+}
+
+/// 요청 메타데이터에 실을 수 없는 값 - `Status::invalid_argument`로 변환됨
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMetadata(pub String);
+
+impl fmt::Display for InvalidMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid metadata value: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidMetadata {}
+
+impl From<InvalidMetadata> for Status {
+    fn from(error: InvalidMetadata) -> Self {
+        Status::invalid_argument(error.to_string())
+    }
+}
+
+/// 클라이언트가 요청 메타데이터에 싣는 값 (`Bearer <token>`, 클라이언트 id 등)
+pub fn metadata_value(value: &str) -> Result<MetadataValue<Ascii>, InvalidMetadata> {
+    value.parse().map_err(|_| InvalidMetadata(value.to_string()))
+}
+
+/// `authorization: Bearer <token>` 검증 - tonic 서비스에 interceptor로 붙임
+#[derive(Clone, Default)]
+pub struct Authenticator {
+    verifier: Option<Arc<Verifier>>,
+}
+
+enum Verifier {
+    Tokens(HashSet<String>),
+    Hmac(hmac::Key),
+}
+
+impl Authenticator {
+    /// 모든 요청을 통과시킴
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// 목록에 있는 토큰만 허용
+    pub fn tokens(tokens: impl IntoIterator<Item = String>) -> Self {
+        Self::with_verifier(Verifier::Tokens(tokens.into_iter().collect()))
+    }
+
+    /// `secret`으로 서명된 `sign_token` 형식 토큰만 허용
+    pub fn hmac(secret: &[u8]) -> Self {
+        Self::with_verifier(Verifier::Hmac(hmac::Key::new(hmac::HMAC_SHA256, secret)))
+    }
+
+    fn with_verifier(verifier: Verifier) -> Self {
+        Self { verifier: Some(Arc::new(verifier)) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.verifier.is_some()
+    }
+
+    /// 메타데이터의 토큰을 검증 - HMAC 토큰이면 서명된 subject를 돌려줌
+    pub fn authenticate(&self, metadata: &MetadataMap) -> Result<Option<String>, AuthError> {
+        let Some(verifier) = &self.verifier else {
+            return Ok(None);
+        };
+        let header = metadata
+            .get(AUTHORIZATION_METADATA)
+            .ok_or(AuthError::Missing)?;
+        let token = header
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or(AuthError::Malformed)?;
+
+        match verifier.as_ref() {
+            Verifier::Tokens(tokens) if tokens.contains(token) => Ok(None),
+            Verifier::Hmac(key) => verify_hmac(key, token)
+                .map(|subject| Some(subject.to_string()))
+                .ok_or(AuthError::Invalid),
+            Verifier::Tokens(_) => Err(AuthError::Invalid),
+        }
+    }
+}
+
+impl Interceptor for Authenticator {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        match self.authenticate(request.metadata()) {
+            Ok(_) => Ok(request),
+            Err(error) => {
+                warn!(reason = %error, "Rejected unauthenticated request");
+                Err(error.into())
+            }
+        }
+    }
+}
+
+/// `subject`에 대한 HMAC 토큰 `<subject>.<hex HMAC-SHA256(secret, subject)>`
+pub fn sign_token(secret: &[u8], subject: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    format!("{}.{}", subject, hex::encode(hmac::sign(&key, subject.as_bytes())))
+}
+
+/// 서명이 맞으면 subject (서명 비교는 상수 시간)
+fn verify_hmac<'a>(key: &hmac::Key, token: &'a str) -> Option<&'a str> {
+    let (subject, signature) = token.rsplit_once('.')?;
+    let signature = hex::decode(signature).ok()?;
+    hmac::verify(key, subject.as_bytes(), &signature).ok()?;
+    Some(subject)
+}
@@ -0,0 +1,64 @@
+use playground_auth::{metadata_value, sign_token, AuthError, Authenticator, InvalidMetadata, AUTHORIZATION_METADATA};
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::{Code, Request};
+
+fn bearer(token: &str) -> MetadataMap {
+    let mut metadata = MetadataMap::new();
+    metadata.insert(AUTHORIZATION_METADATA, format!("Bearer {}", token).parse().unwrap());
+    metadata
+}
+
+#[test]
+fn disabled_accepts_requests_without_token() {
+    let auth = Authenticator::disabled();
+    assert!(!auth.is_enabled());
+    assert_eq!(auth.authenticate(&MetadataMap::new()).unwrap(), None);
+}
+
+#[test]
+fn static_tokens_accept_only_listed_tokens() {
+    let auth = Authenticator::tokens(["alpha".to_string(), "beta".to_string()]);
+    assert!(auth.authenticate(&bearer("beta")).is_ok());
+
+    assert_eq!(auth.authenticate(&MetadataMap::new()), Err(AuthError::Missing));
+    assert_eq!(auth.authenticate(&bearer("gamma")), Err(AuthError::Invalid));
+
+    let mut basic = MetadataMap::new();
+    basic.insert(AUTHORIZATION_METADATA, "Basic alpha".parse().unwrap());
+    assert_eq!(auth.authenticate(&basic), Err(AuthError::Malformed));
+}
+
+#[test]
+fn hmac_tokens_return_signed_subject() {
+    let auth = Authenticator::hmac(b"secret");
+    let token = sign_token(b"secret", "client-a");
+    assert_eq!(auth.authenticate(&bearer(&token)).unwrap().as_deref(), Some("client-a"));
+
+    // 다른 비밀키로 서명했거나 subject를 바꾼 토큰은 거부
+    let forged = sign_token(b"other", "client-a");
+    let (_, signature) = token.rsplit_once('.').unwrap();
+    for token in [forged, format!("client-b.{}", signature), "client-a".to_string()] {
+        assert_eq!(auth.authenticate(&bearer(&token)), Err(AuthError::Invalid));
+    }
+}
+
+#[test]
+fn interceptor_rejects_with_unauthenticated() {
+    let mut auth = Authenticator::tokens(["alpha".to_string()]);
+    let status = auth.call(Request::new(())).unwrap_err();
+    assert_eq!((status.code(), status.message()), (Code::Unauthenticated, "missing bearer token"));
+
+    let mut request = Request::new(());
+    *request.metadata_mut() = bearer("alpha");
+    assert!(auth.call(request).is_ok());
+}
+
+#[test]
+fn metadata_value_rejects_control_characters_as_invalid_argument() {
+    assert_eq!(metadata_value("Bearer alpha").unwrap(), "Bearer alpha");
+    assert_eq!(metadata_value("alpha\nbeta"), Err(InvalidMetadata("alpha\nbeta".to_string())));
+
+    let status: tonic::Status = metadata_value("alpha\nbeta").unwrap_err().into();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use rskafka::chrono::DateTime;
+use rskafka::client::error::{Error as KafkaError, ProtocolError};
+use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::record::{Record, RecordAndOffset};
+
+use crate::{BusError, BusMessage, Delivery, MessageBus, Subscription};
+
+const TOPIC_PREFIX: &str = "playground-";
+const FETCH_MAX_BYTES: i32 = 1_000_000;
+const FETCH_WAIT_MS: i32 = 500;
+const CREATE_TOPIC_TIMEOUT_MS: i32 = 5_000;
+
+fn kafka_error(e: impl std::fmt::Display) -> BusError {
+    BusError::Backend(e.to_string())
+}
+
+/// 토픽별 커밋 위치
+///
+/// rskafka에는 consumer group이 없으므로 커밋 오프셋은 프로세스 안에서 관리한다.
+/// 재구독하면 커밋 위치부터 다시 읽으므로, ack 되지 않은 메시지는 재연결 시점에만 재전달된다.
+#[derive(Default)]
+struct TopicOffsets {
+    committed: i64,
+    acked: BTreeSet<i64>,
+    offset_of: HashMap<u64, i64>,
+    deliveries: HashMap<i64, u32>,
+}
+
+type Offsets = Arc<Mutex<HashMap<String, TopicOffsets>>>;
+
+/// 단일 파티션 Kafka 토픽 기반 버스
+pub struct KafkaBus {
+    client: Client,
+    partitions: tokio::sync::Mutex<HashMap<String, Arc<PartitionClient>>>,
+    offsets: Offsets,
+}
+
+impl KafkaBus {
+    pub async fn connect(url: &str) -> Result<Self, BusError> {
+        let bootstrap = url.trim_start_matches("kafka://").to_string();
+        let client = ClientBuilder::new(vec![bootstrap]).build().await.map_err(kafka_error)?;
+        Ok(Self {
+            client,
+            partitions: tokio::sync::Mutex::default(),
+            offsets: Arc::default(),
+        })
+    }
+
+    /// 토픽이 없으면 만들고 파티션 0 클라이언트 반환
+    async fn partition(&self, topic: &str) -> Result<Arc<PartitionClient>, BusError> {
+        let mut partitions = self.partitions.lock().await;
+        if let Some(partition) = partitions.get(topic) {
+            return Ok(partition.clone());
+        }
+
+        let name = format!("{}{}", TOPIC_PREFIX, topic);
+        let controller = self.client.controller_client().map_err(kafka_error)?;
+        match controller.create_topic(name.as_str(), 1, 1, CREATE_TOPIC_TIMEOUT_MS).await {
+            Ok(()) => {}
+            Err(KafkaError::ServerError {
+                protocol_error: ProtocolError::TopicAlreadyExists,
+                ..
+            }) => {}
+            Err(e) => return Err(kafka_error(e)),
+        }
+
+        let partition = Arc::new(
+            self.client
+                .partition_client(name, 0, UnknownTopicHandling::Retry)
+                .await
+                .map_err(kafka_error)?,
+        );
+        partitions.insert(topic.to_string(), partition.clone());
+        Ok(partition)
+    }
+}
+
+#[async_trait]
+impl MessageBus for KafkaBus {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    /// 살아있는 구독에는 재전송하지 않음 - 재구독 시 커밋 위치부터 다시 읽을 뿐
+    fn redelivers(&self) -> bool {
+        false
+    }
+
+    fn settle_time(&self) -> Duration {
+        Duration::from_millis(FETCH_WAIT_MS as u64)
+    }
+
+    async fn publish(&self, topic: &str, message: BusMessage) -> Result<(), BusError> {
+        // rskafka의 chrono는 clock feature 없이 빌드되므로 현재 시각을 직접 변환
+        let now_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let record = Record {
+            key: Some(message.id.to_be_bytes().to_vec()),
+            value: Some(serde_json::to_vec(&message)?),
+            headers: BTreeMap::new(),
+            timestamp: DateTime::from_timestamp_millis(now_millis).unwrap_or_default(),
+        };
+        self.partition(topic)
+            .await?
+            .produce(vec![record], Compression::NoCompression)
+            .await
+            .map_err(kafka_error)?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: &str) -> Result<Box<dyn Subscription>, BusError> {
+        let partition = self.partition(topic).await?;
+        let next_offset = self
+            .offsets
+            .lock()
+            .unwrap()
+            .get(topic)
+            .map_or(0, |offsets| offsets.committed);
+
+        Ok(Box::new(KafkaSubscription {
+            topic: topic.to_string(),
+            partition,
+            next_offset,
+            buffered: VecDeque::new(),
+            offsets: self.offsets.clone(),
+        }))
+    }
+
+    async fn ack(&self, topic: &str, id: u64) -> Result<(), BusError> {
+        let mut offsets = self.offsets.lock().unwrap();
+        let offsets = offsets.entry(topic.to_string()).or_default();
+        if let Some(offset) = offsets.offset_of.get(&id).copied() {
+            offsets.acked.insert(offset);
+            // 연속으로 ack 된 구간까지만 커밋 (Kafka consumer와 같은 방식)
+            while offsets.acked.remove(&offsets.committed) {
+                offsets.committed += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct KafkaSubscription {
+    topic: String,
+    partition: Arc<PartitionClient>,
+    next_offset: i64,
+    buffered: VecDeque<RecordAndOffset>,
+    offsets: Offsets,
+}
+
+#[async_trait]
+impl Subscription for KafkaSubscription {
+    async fn next(&mut self) -> Result<Delivery, BusError> {
+        loop {
+            if let Some(record) = self.buffered.pop_front() {
+                let value = record.record.value.unwrap_or_default();
+                let message: BusMessage = serde_json::from_slice(&value)?;
+
+                let mut offsets = self.offsets.lock().unwrap();
+                let offsets = offsets.entry(self.topic.clone()).or_default();
+                offsets.offset_of.insert(message.id, record.offset);
+                let attempt = offsets.deliveries.entry(record.offset).or_insert(0);
+                *attempt += 1;
+
+                return Ok(Delivery {
+                    message,
+                    attempt: *attempt,
+                });
+            }
+
+            let (records, _high_watermark) = self
+                .partition
+                .fetch_records(self.next_offset, 1..FETCH_MAX_BYTES, FETCH_WAIT_MS)
+                .await
+                .map_err(kafka_error)?;
+            if let Some(last) = records.last() {
+                self.next_offset = last.offset + 1;
+            }
+            self.buffered.extend(records);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! 스트리밍 서버의 메시지 소스/싱크 추상화
+//!
+//! 서버는 보낼 메시지를 버스에 발행하고, 구독에서 꺼낸 전달을 클라이언트로 보낸 뒤 ack 한다.
+//! 기본 in-memory 버스는 기존 동작 그대로(재전송은 애플리케이션 몫)이고,
+//! NATS JetStream / Kafka 백엔드로 바꾸면 브로커 수준 재전송과 직접 구현한 재전송을 비교할 수 있다.
+//!
+//! 백엔드는 `PLAYGROUND_BUS_URL`로 선택 (`memory`, `nats://host:4222`, `kafka://host:9092`).
+//! 브로커 백엔드는 각각 `nats`, `kafka` feature로 빌드해야 한다.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+mod memory;
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+pub use memory::InMemoryBus;
+
+pub const BUS_URL_ENV: &str = "PLAYGROUND_BUS_URL";
+
+/// 버스에 실리는 메시지 (gRPC 프로토콜과 무관한 최소 형태)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusMessage {
+    pub id: u64,
+    /// 발행 시각 (unix ms) - 클라이언트에 보내는 `DataMessage.timestamp`는 초 단위라 서버가 바꿔서 보냄
+    pub timestamp: u64,
+    pub payload: String,
+}
+
+/// 구독에서 꺼낸 메시지 한 건
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub message: BusMessage,
+    /// 브로커 기준 몇 번째 전달인지 (1부터, 2 이상이면 브로커 재전송)
+    pub attempt: u32,
+}
+
+#[derive(Debug)]
+pub enum BusError {
+    /// 지원하지 않는 URL 이거나 해당 feature 없이 빌드됨
+    Unsupported(String),
+    /// 브로커 통신 오류
+    Backend(String),
+    /// 메시지 인코딩/디코딩 실패
+    Codec(serde_json::Error),
+}
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusError::Unsupported(message) => write!(f, "unsupported bus: {}", message),
+            BusError::Backend(message) => write!(f, "bus backend error: {}", message),
+            BusError::Codec(e) => write!(f, "bus message codec error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+impl From<serde_json::Error> for BusError {
+    fn from(e: serde_json::Error) -> Self {
+        BusError::Codec(e)
+    }
+}
+
+#[async_trait]
+pub trait MessageBus: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// ack 되지 않은 메시지를 브로커가 스스로 다시 전달하는지
+    ///
+    /// true 이면 서버는 자체 재전송을 끄고 브로커 재전송을 그대로 전달한다.
+    fn redelivers(&self) -> bool;
+
+    /// 남은 재전송이 도착할 때까지 기다려볼 시간 (스트림 마무리 시 사용)
+    fn settle_time(&self) -> Duration;
+
+    async fn publish(&self, topic: &str, message: BusMessage) -> Result<(), BusError>;
+
+    async fn subscribe(&self, topic: &str) -> Result<Box<dyn Subscription>, BusError>;
+
+    /// 클라이언트까지 처리된 메시지 확인 - 이후 재전송 대상에서 빠짐
+    async fn ack(&self, topic: &str, id: u64) -> Result<(), BusError>;
+}
+
+#[async_trait]
+pub trait Subscription: Send {
+    /// 다음 전달을 기다림
+    async fn next(&mut self) -> Result<Delivery, BusError>;
+}
+
+pub type SharedBus = Arc<dyn MessageBus>;
+
+/// URL 스킴으로 백엔드 선택
+pub async fn connect(url: &str) -> Result<SharedBus, BusError> {
+    let scheme = url.split_once("://").map_or(url, |(scheme, _)| scheme);
+    match scheme {
+        "memory" => Ok(Arc::new(InMemoryBus::default())),
+        "nats" => connect_nats(url).await,
+        "kafka" => connect_kafka(url).await,
+        other => Err(BusError::Unsupported(format!("unknown scheme '{}'", other))),
+    }
+}
+
+/// `PLAYGROUND_BUS_URL`로 백엔드 선택 (없으면 in-memory)
+pub async fn from_env() -> Result<SharedBus, BusError> {
+    match std::env::var(BUS_URL_ENV) {
+        Ok(url) => connect(&url).await,
+        Err(_) => Ok(Arc::new(InMemoryBus::default())),
+    }
+}
+
+#[cfg(feature = "nats")]
+async fn connect_nats(url: &str) -> Result<SharedBus, BusError> {
+    Ok(Arc::new(nats::NatsBus::connect(url).await?))
+}
+
+#[cfg(not(feature = "nats"))]
+async fn connect_nats(url: &str) -> Result<SharedBus, BusError> {
+    Err(BusError::Unsupported(format!("{} (rebuild with --features nats)", url)))
+}
+
+#[cfg(feature = "kafka")]
+async fn connect_kafka(url: &str) -> Result<SharedBus, BusError> {
+    Ok(Arc::new(kafka::KafkaBus::connect(url).await?))
+}
+
+#[cfg(not(feature = "kafka"))]
+async fn connect_kafka(url: &str) -> Result<SharedBus, BusError> {
+    Err(BusError::Unsupported(format!("{} (rebuild with --features kafka)", url)))
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::{BusError, BusMessage, Delivery, MessageBus, Subscription};
+
+type SharedReceiver = Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<BusMessage>>>;
+
+struct Topic {
+    tx: mpsc::UnboundedSender<BusMessage>,
+    rx: SharedReceiver,
+}
+
+/// 프로세스 내부 큐 - 한 번 꺼낸 메시지는 다시 전달되지 않음 (재전송은 애플리케이션 몫)
+#[derive(Default)]
+pub struct InMemoryBus {
+    topics: Mutex<HashMap<String, Topic>>,
+}
+
+impl InMemoryBus {
+    fn with_topic<T>(&self, topic: &str, f: impl FnOnce(&Topic) -> T) -> T {
+        let mut topics = self.topics.lock().unwrap();
+        let topic = topics.entry(topic.to_string()).or_insert_with(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            Topic {
+                tx,
+                rx: Arc::new(tokio::sync::Mutex::new(rx)),
+            }
+        });
+        f(topic)
+    }
+}
+
+#[async_trait]
+impl MessageBus for InMemoryBus {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn redelivers(&self) -> bool {
+        false
+    }
+
+    fn settle_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    async fn publish(&self, topic: &str, message: BusMessage) -> Result<(), BusError> {
+        // 토픽이 수신 측을 들고 있으므로 전송은 실패하지 않음
+        self.with_topic(topic, |topic| topic.tx.send(message))
+            .map_err(|e| BusError::Backend(e.to_string()))
+    }
+
+    async fn subscribe(&self, topic: &str) -> Result<Box<dyn Subscription>, BusError> {
+        let rx = self.with_topic(topic, |topic| topic.rx.clone());
+        Ok(Box::new(MemorySubscription { rx }))
+    }
+
+    async fn ack(&self, _topic: &str, _id: u64) -> Result<(), BusError> {
+        Ok(())
+    }
+}
+
+struct MemorySubscription {
+    rx: SharedReceiver,
+}
+
+#[async_trait]
+impl Subscription for MemorySubscription {
+    async fn next(&mut self) -> Result<Delivery, BusError> {
+        match self.rx.lock().await.recv().await {
+            Some(message) => Ok(Delivery { message, attempt: 1 }),
+            None => Err(BusError::Backend("in-memory topic closed".to_string())),
+        }
+    }
+}
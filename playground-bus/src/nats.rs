@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_nats::jetstream::{self, consumer, stream};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+
+use crate::{BusError, BusMessage, Delivery, MessageBus, Subscription};
+
+const STREAM_NAME: &str = "PLAYGROUND";
+const SUBJECT_PREFIX: &str = "playground";
+/// 이 시간 안에 ack가 없으면 JetStream이 재전송 (ACK 서버의 ACK 타임아웃과 같은 값)
+const ACK_WAIT: Duration = Duration::from_secs(2);
+/// 최초 전달 + 재전송 3회 (ACK 서버의 MAX_RETRIES와 같은 한도)
+const MAX_DELIVER: i64 = 4;
+
+type Unacked = Arc<Mutex<HashMap<(String, u64), jetstream::Message>>>;
+
+fn nats_error(e: impl std::fmt::Display) -> BusError {
+    BusError::Backend(e.to_string())
+}
+
+/// JetStream 기반 버스 - 토픽마다 durable pull consumer를 두고 브로커가 재전송
+pub struct NatsBus {
+    jetstream: jetstream::Context,
+    unacked: Unacked,
+}
+
+impl NatsBus {
+    pub async fn connect(url: &str) -> Result<Self, BusError> {
+        let client = async_nats::connect(url).await.map_err(nats_error)?;
+        let jetstream = jetstream::new(client);
+        jetstream
+            .get_or_create_stream(stream::Config {
+                name: STREAM_NAME.to_string(),
+                subjects: vec![format!("{}.>", SUBJECT_PREFIX)],
+                ..Default::default()
+            })
+            .await
+            .map_err(nats_error)?;
+
+        Ok(Self {
+            jetstream,
+            unacked: Arc::default(),
+        })
+    }
+}
+
+fn subject(topic: &str) -> String {
+    format!("{}.{}", SUBJECT_PREFIX, topic)
+}
+
+/// durable 이름에는 '.' 등을 쓸 수 없음
+fn durable_name(topic: &str) -> String {
+    topic
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[async_trait]
+impl MessageBus for NatsBus {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    fn redelivers(&self) -> bool {
+        true
+    }
+
+    fn settle_time(&self) -> Duration {
+        ACK_WAIT + Duration::from_secs(1)
+    }
+
+    async fn publish(&self, topic: &str, message: BusMessage) -> Result<(), BusError> {
+        let payload = serde_json::to_vec(&message)?;
+        self.jetstream
+            .publish(subject(topic), payload.into())
+            .await
+            .map_err(nats_error)?
+            .await
+            .map_err(nats_error)?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: &str) -> Result<Box<dyn Subscription>, BusError> {
+        let stream = self.jetstream.get_stream(STREAM_NAME).await.map_err(nats_error)?;
+        let name = durable_name(topic);
+        let consumer: consumer::PullConsumer = stream
+            .get_or_create_consumer(
+                &name,
+                consumer::pull::Config {
+                    durable_name: Some(name.clone()),
+                    filter_subject: subject(topic),
+                    ack_wait: ACK_WAIT,
+                    max_deliver: MAX_DELIVER,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(nats_error)?;
+        let messages = consumer.messages().await.map_err(nats_error)?;
+
+        Ok(Box::new(NatsSubscription {
+            topic: topic.to_string(),
+            messages,
+            unacked: self.unacked.clone(),
+        }))
+    }
+
+    async fn ack(&self, topic: &str, id: u64) -> Result<(), BusError> {
+        let message = self.unacked.lock().unwrap().remove(&(topic.to_string(), id));
+        match message {
+            Some(message) => message.ack().await.map_err(nats_error),
+            None => Ok(()),
+        }
+    }
+}
+
+struct NatsSubscription {
+    topic: String,
+    messages: consumer::pull::Stream,
+    unacked: Unacked,
+}
+
+#[async_trait]
+impl Subscription for NatsSubscription {
+    async fn next(&mut self) -> Result<Delivery, BusError> {
+        let message = match self.messages.next().await {
+            Some(message) => message.map_err(nats_error)?,
+            None => return Err(BusError::Backend("JetStream consumer closed".to_string())),
+        };
+
+        let attempt = message.info().map_err(nats_error)?.delivered as u32;
+        let bus_message: BusMessage = serde_json::from_slice(&message.payload)?;
+        // 재전송된 경우 최신 전달로 ack 하도록 교체
+        self.unacked
+            .lock()
+            .unwrap()
+            .insert((self.topic.clone(), bus_message.id), message);
+
+        Ok(Delivery {
+            message: bus_message,
+            attempt,
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use playground_bus::{connect, BusError, BusMessage, InMemoryBus, MessageBus};
+
+fn message(id: u64) -> BusMessage {
+    BusMessage {
+        id,
+        timestamp: 0,
+        payload: format!("Message {}", id),
+    }
+}
+
+#[tokio::test]
+async fn memory_bus_delivers_in_publish_order_once() {
+    let bus = InMemoryBus::default();
+    let mut subscription = bus.subscribe("stream-1").await.unwrap();
+
+    for id in 1..=3 {
+        bus.publish("stream-1", message(id)).await.unwrap();
+    }
+
+    for id in 1..=3 {
+        let delivery = subscription.next().await.unwrap();
+        assert_eq!(delivery.message, message(id));
+        assert_eq!(delivery.attempt, 1);
+    }
+    assert!(!bus.redelivers());
+}
+
+#[tokio::test]
+async fn memory_bus_keeps_topics_separate() {
+    let bus = InMemoryBus::default();
+    bus.publish("a", message(1)).await.unwrap();
+    bus.publish("b", message(2)).await.unwrap();
+
+    let mut b = bus.subscribe("b").await.unwrap();
+    assert_eq!(b.next().await.unwrap().message.id, 2);
+}
+
+#[tokio::test]
+async fn connect_picks_backend_by_scheme() {
+    assert_eq!(connect("memory").await.unwrap().name(), "memory");
+    assert!(matches!(
+        connect("amqp://localhost").await,
+        Err(BusError::Unsupported(_))
+    ));
+}
@@ -0,0 +1,130 @@
+//! 프록시를 거쳐 스트림을 끝까지 받는 재연결 클라이언트
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Channel, Endpoint};
+use tonic::Status;
+
+use crate::Target;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_millis(200);
+/// 깨진 HTTP/2 프레임에 걸려 멈춘 연결을 끊어내기 위한 keepalive
+/// (block 구간보다 길게 잡아 단순 정지는 오류로 보지 않음)
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(1);
+const KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Serialize)]
+pub struct Attempt {
+    /// 수신 순서대로 기록한 메시지 id
+    pub delivered: Vec<u64>,
+    /// "OK", gRPC 상태 코드 이름, 또는 "Timeout"
+    pub status: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ClientRun {
+    pub attempts: Vec<Attempt>,
+}
+
+/// 스트림이 정상 종료되거나 `deadline`에 도달할 때까지 재연결하며 수신
+pub async fn run_client(target: Target, addr: SocketAddr, deadline: Instant) -> ClientRun {
+    let deadline = tokio::time::Instant::from_std(deadline);
+    let mut run = ClientRun::default();
+    // 재연결해도 취소 서버가 같은 생성기를 이어가도록 시도마다 같은 세션 id 사용
+    let session = format!("chaos-{}-{}", std::process::id(), NEXT_SESSION.fetch_add(1, Ordering::Relaxed));
+
+    loop {
+        let mut delivered = Vec::new();
+        let status = match tokio::time::timeout_at(deadline, attempt(target, addr, &session, &mut delivered)).await {
+            Ok(Ok(())) => "OK".to_string(),
+            Ok(Err(status)) => format!("{:?}", status.code()),
+            Err(_) => "Timeout".to_string(),
+        };
+        let finished = status == "OK" || status == "Timeout";
+        run.attempts.push(Attempt { delivered, status });
+
+        if finished || tokio::time::Instant::now() + RECONNECT_DELAY >= deadline {
+            return run;
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn attempt(target: Target, addr: SocketAddr, session: &str, delivered: &mut Vec<u64>) -> Result<(), Status> {
+    let channel = connect(addr).await?;
+    match target {
+        Target::CancelServer => cancel_attempt(channel, session, delivered).await,
+        Target::AckServer => ack_attempt(channel, delivered).await,
+    }
+}
+
+async fn connect(addr: SocketAddr) -> Result<Channel, Status> {
+    Endpoint::try_from(format!("http://{}", addr))
+        .map_err(|e| Status::internal(e.to_string()))?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .http2_keep_alive_interval(KEEPALIVE_INTERVAL)
+        .keep_alive_timeout(KEEPALIVE_TIMEOUT)
+        .keep_alive_while_idle(true)
+        .connect()
+        .await
+        .map_err(|e| Status::unavailable(e.to_string()))
+}
+
+/// Python 클라이언트처럼 요청 스트림은 바로 닫고 응답만 수신
+async fn cancel_attempt(channel: Channel, session: &str, delivered: &mut Vec<u64>) -> Result<(), Status> {
+    use cancel_server::session::SESSION_METADATA;
+    use cancel_server::streaming::streaming_service_client::StreamingServiceClient;
+
+    let mut request = tonic::Request::new(tokio_stream::empty());
+    let session = session.parse().map_err(|_| Status::internal("invalid session id"))?;
+    request.metadata_mut().insert(SESSION_METADATA, session);
+
+    let mut client = StreamingServiceClient::new(channel);
+    let mut stream = client
+        .bidirectional_stream(request)
+        .await?
+        .into_inner();
+
+    while let Some(message) = stream.message().await? {
+        delivered.push(message.id);
+    }
+    Ok(())
+}
+
+/// 받은 데이터 메시지마다 즉시 ACK
+async fn ack_attempt(channel: Channel, delivered: &mut Vec<u64>) -> Result<(), Status> {
+    use ack_server::streaming::stream_message::MessageType;
+    use ack_server::streaming::streaming_service_client::StreamingServiceClient;
+    use ack_server::streaming::{AckMessage, StreamMessage};
+
+    let (ack_tx, ack_rx) = mpsc::channel(16);
+    let mut client = StreamingServiceClient::new(channel);
+    let mut stream = client
+        .bidirectional_stream(ReceiverStream::new(ack_rx))
+        .await?
+        .into_inner();
+
+    while let Some(message) = stream.message().await? {
+        if let Some(MessageType::Data(data)) = message.message_type {
+            delivered.push(data.id);
+            let ack = StreamMessage {
+                message_type: Some(MessageType::Ack(AckMessage {
+                    ack_id: data.id,
+                    timestamp: 0,
+                    channel: data.channel,
+                })),
+            };
+            // 서버가 먼저 끊은 경우 ACK 전송 실패는 무시하고 스트림 결과를 따름
+            let _ = ack_tx.send(ack).await;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! 시나리오마다 임시 포트에 띄우는 서버/프록시
+
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use playground_clock::{SharedClock, SystemClock};
+use playground_stats::ServerStats;
+use rust_proxy::fault::FaultSchedule;
+use rust_proxy::status::ProxyStatus;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_util::sync::CancellationToken;
+use tonic::transport::Server;
+
+use crate::{Target, MESSAGES};
+
+/// 취소 서버의 메시지 간격 (초 단위가 최소)
+const CANCEL_INTERVAL_SECS: u64 = 1;
+
+/// drop 시 백그라운드 태스크를 중단
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+pub struct ServerHandle {
+    pub addr: SocketAddr,
+    pub stats: Arc<ServerStats>,
+    _task: AbortOnDrop,
+}
+
+impl ServerHandle {
+    pub fn active_streams(&self) -> u64 {
+        self.stats.active_streams.load(Ordering::Relaxed)
+    }
+
+    /// 서버의 모든 스트림 태스크가 끝날 때까지 대기 - 제한 시간 안에 끝나면 true
+    pub async fn wait_drained(&self, grace: Duration) -> bool {
+        let deadline = Instant::now() + grace;
+        while self.active_streams() > 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        true
+    }
+}
+
+pub async fn start_server(target: Target) -> std::io::Result<ServerHandle> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let stats = Arc::new(ServerStats::default());
+    let clock: SharedClock = Arc::new(SystemClock);
+
+    let router = match target {
+        Target::CancelServer => Server::builder().add_service(
+            cancel_server::streaming::streaming_service_server::StreamingServiceServer::new(
+                cancel_server::server::StreamingServer::new(CANCEL_INTERVAL_SECS, MESSAGES, stats.clone(), clock),
+            ),
+        ),
+        Target::AckServer => Server::builder().add_service(
+            ack_server::streaming::streaming_service_server::StreamingServiceServer::new(
+                ack_server::server::StreamingServer::new(MESSAGES, stats.clone(), clock),
+            ),
+        ),
+    };
+
+    let task = tokio::spawn(async move {
+        if let Err(e) = router.serve_with_incoming(TcpListenerStream::new(listener)).await {
+            eprintln!("❌ [CHAOS] Server error: {}", e);
+        }
+    });
+
+    Ok(ServerHandle {
+        addr,
+        stats,
+        _task: AbortOnDrop(task),
+    })
+}
+
+pub struct ProxyHandle {
+    pub addr: SocketAddr,
+    pub status: Arc<ProxyStatus>,
+    _task: AbortOnDrop,
+}
+
+pub async fn start_proxy(upstream: SocketAddr, schedule: FaultSchedule) -> std::io::Result<ProxyHandle> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let status = Arc::new(ProxyStatus::default());
+
+    let proxy_status = status.clone();
+    let task = tokio::spawn(async move {
+        // 시나리오가 끝나면 태스크째 중단하므로 종료 토큰은 쓰지 않음
+        let shutdown = CancellationToken::new();
+        if let Err(e) = rust_proxy::proxy::run(listener, upstream.to_string(), schedule, proxy_status, shutdown).await {
+            eprintln!("❌ [CHAOS] Proxy error: {}", e);
+        }
+    });
+
+    Ok(ProxyHandle {
+        addr,
+        status,
+        _task: AbortOnDrop(task),
+    })
+}
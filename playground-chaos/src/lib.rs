@@ -0,0 +1,287 @@
+//! 프록시 장애 매트릭스로 두 gRPC 서버를 검증하는 카오스 테스트 스위트
+//!
+//! 시나리오마다 서버와 프록시를 임시 포트에 새로 띄우고, 프록시를 거친 클라이언트가
+//! 재연결을 반복하며 스트림을 끝까지 받는다. 결과는 불변식 검사와 함께
+//! 호환성 매트릭스(JSON + Markdown)로 남긴다.
+
+pub mod clients;
+pub mod harness;
+pub mod report;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rust_proxy::fault::{BlockMode, Fault, FaultSchedule, ScheduleOrigin, Side};
+use serde::Serialize;
+
+use clients::ClientRun;
+
+/// 시나리오당 서버가 보내는 메시지 수
+pub const MESSAGES: u64 = 6;
+/// 장애 구간 시작 시각 (시나리오 시작 기준)
+const FAULT_START: Duration = Duration::from_millis(2500);
+/// 클라이언트가 스트림을 끝까지 받아야 하는 제한 시간
+const CLIENT_DEADLINE: Duration = Duration::from_secs(45);
+/// 클라이언트 종료 후 서버 태스크가 정리되기를 기다리는 시간
+const DRAIN_GRACE: Duration = Duration::from_secs(10);
+
+/// 전송 계층 오류로 볼 수 있는 상태 코드
+const TRANSPORT_CODES: &[&str] = &["Unavailable", "Unknown", "Internal", "Cancelled"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Target {
+    /// grpc-stream-cancel 서버 (at-most-once)
+    CancelServer,
+    /// grpc-stream ACK 서버 (at-least-once)
+    AckServer,
+}
+
+impl Target {
+    pub const ALL: [Target; 2] = [Target::CancelServer, Target::AckServer];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::CancelServer => "cancel-server",
+            Target::AckServer => "ack-server",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaultCase {
+    None,
+    Block,
+    Reset,
+    Latency,
+    Loss,
+}
+
+impl FaultCase {
+    pub const ALL: [FaultCase; 5] = [
+        FaultCase::None,
+        FaultCase::Block,
+        FaultCase::Reset,
+        FaultCase::Latency,
+        FaultCase::Loss,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FaultCase::None => "none",
+            FaultCase::Block => "block",
+            FaultCase::Reset => "reset",
+            FaultCase::Latency => "latency",
+            FaultCase::Loss => "loss",
+        }
+    }
+
+    /// 재연결해도 장애가 다시 시작되지 않도록 시나리오 시작 시각을 공유하는 스케줄
+    pub fn schedule(&self, origin: Instant) -> FaultSchedule {
+        let schedule = FaultSchedule::new(ScheduleOrigin::Shared(origin));
+        match self {
+            FaultCase::None => schedule,
+            FaultCase::Block => schedule.with(Fault::Block(BlockMode::Stall), FAULT_START, Duration::from_secs(2)),
+            FaultCase::Reset => schedule.with(Fault::Reset(Side::Both), FAULT_START, Duration::from_millis(300)),
+            FaultCase::Latency => schedule.with(
+                Fault::Latency {
+                    delay: Duration::from_millis(300),
+                    jitter: Duration::ZERO,
+                },
+                FAULT_START,
+                Duration::from_secs(3),
+            ),
+            FaultCase::Loss => schedule.with(Fault::Loss(0.3), FAULT_START, Duration::from_secs(2)),
+        }
+    }
+
+    /// 이 장애에서 클라이언트가 볼 수 있는 오류 코드와, 오류가 반드시 발생해야 하는지 여부
+    fn expected_errors(&self) -> (&'static [&'static str], bool) {
+        match self {
+            // 연결은 유지되고 느려지기만 해야 함
+            FaultCase::None | FaultCase::Block | FaultCase::Latency => (&[], false),
+            FaultCase::Reset => (TRANSPORT_CODES, true),
+            // 어떤 바이트가 버려지느냐에 따라 연결이 살아남을 수도 있음
+            FaultCase::Loss => (TRANSPORT_CODES, false),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Invariant {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl Invariant {
+    fn new(name: &'static str, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioResult {
+    pub target: Target,
+    pub fault: FaultCase,
+    pub elapsed_ms: u64,
+    pub run: ClientRun,
+    pub invariants: Vec<Invariant>,
+}
+
+impl ScenarioResult {
+    pub fn passed(&self) -> bool {
+        self.invariants.iter().all(|invariant| invariant.passed)
+    }
+
+    /// 시도별 최종 상태를 `Unknown → OK` 형태로 요약
+    pub fn status_summary(&self) -> String {
+        self.run
+            .attempts
+            .iter()
+            .map(|attempt| attempt.status.as_str())
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+/// 서버 하나와 장애 하나의 조합을 실행하고 불변식 검사
+pub async fn run_scenario(target: Target, fault: FaultCase) -> std::io::Result<ScenarioResult> {
+    let started = Instant::now();
+    let server = harness::start_server(target).await?;
+    let proxy = harness::start_proxy(server.addr, fault.schedule(started)).await?;
+
+    let run = clients::run_client(target, proxy.addr, started + CLIENT_DEADLINE).await;
+    let drained = server.wait_drained(DRAIN_GRACE).await;
+
+    let invariants = vec![
+        check_completed(&run),
+        match target {
+            Target::CancelServer => check_at_most_once(&run),
+            Target::AckServer => check_at_least_once(&run),
+        },
+        Invariant::new(
+            "tasks_terminated",
+            drained,
+            if drained {
+                "no active streams left on the server".to_string()
+            } else {
+                format!("{} stream(s) still active after {:?}", server.active_streams(), DRAIN_GRACE)
+            },
+        ),
+        check_status_codes(fault, &run),
+    ];
+
+    Ok(ScenarioResult {
+        target,
+        fault,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        run,
+        invariants,
+    })
+}
+
+/// 전체 매트릭스를 동시에 실행 (시나리오마다 포트가 달라 서로 간섭하지 않음)
+pub async fn run_matrix() -> std::io::Result<Vec<ScenarioResult>> {
+    let mut tasks = Vec::new();
+    for target in Target::ALL {
+        for fault in FaultCase::ALL {
+            tasks.push(tokio::spawn(run_scenario(target, fault)));
+        }
+    }
+
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.expect("scenario task panicked")?);
+    }
+    Ok(results)
+}
+
+fn check_completed(run: &ClientRun) -> Invariant {
+    let last = run.attempts.last().map(|attempt| attempt.status.as_str()).unwrap_or("-");
+    Invariant::new(
+        "stream_completed",
+        last == "OK",
+        format!("{} attempt(s), last status {}", run.attempts.len(), last),
+    )
+}
+
+/// 취소 서버: 재연결을 거쳐도 같은 메시지가 두 번 오면 안 됨 (유실은 허용)
+fn check_at_most_once(run: &ClientRun) -> Invariant {
+    let mut seen = HashMap::new();
+    for id in run.attempts.iter().flat_map(|attempt| &attempt.delivered) {
+        *seen.entry(*id).or_insert(0u32) += 1;
+    }
+    let mut duplicates: Vec<_> = seen.into_iter().filter(|(_, count)| *count > 1).collect();
+    duplicates.sort();
+
+    Invariant::new(
+        "at_most_once",
+        duplicates.is_empty(),
+        if duplicates.is_empty() {
+            "no duplicate ids across attempts".to_string()
+        } else {
+            format!("duplicate (id, count): {:?}", duplicates)
+        },
+    )
+}
+
+/// ACK 서버: 마지막 스트림에서 모든 id를 받아야 하고, 중복은 재전송 한도 이내여야 함
+fn check_at_least_once(run: &ClientRun) -> Invariant {
+    let max_deliveries = 1 + ack_server::retry::MAX_RETRIES;
+    let mut problems = Vec::new();
+
+    for (index, attempt) in run.attempts.iter().enumerate() {
+        let mut seen = HashMap::new();
+        for id in &attempt.delivered {
+            *seen.entry(*id).or_insert(0u32) += 1;
+        }
+        if attempt.status == "OK" {
+            let missing: Vec<_> = (1..=MESSAGES).filter(|id| !seen.contains_key(id)).collect();
+            if !missing.is_empty() {
+                problems.push(format!("attempt {} missing ids {:?}", index + 1, missing));
+            }
+        }
+        let mut excess: Vec<_> = seen.into_iter().filter(|(_, count)| *count > max_deliveries).collect();
+        excess.sort();
+        if !excess.is_empty() {
+            problems.push(format!("attempt {} over {} deliveries: {:?}", index + 1, max_deliveries, excess));
+        }
+    }
+
+    Invariant::new(
+        "at_least_once",
+        problems.is_empty(),
+        if problems.is_empty() {
+            "every id delivered, duplicates within retry budget".to_string()
+        } else {
+            problems.join("; ")
+        },
+    )
+}
+
+fn check_status_codes(fault: FaultCase, run: &ClientRun) -> Invariant {
+    let (allowed, required) = fault.expected_errors();
+    let errors: Vec<_> = run
+        .attempts
+        .iter()
+        .map(|attempt| attempt.status.as_str())
+        .filter(|status| *status != "OK")
+        .collect();
+    let unexpected: Vec<_> = errors.iter().filter(|status| !allowed.contains(status)).collect();
+
+    let passed = unexpected.is_empty() && (!required || !errors.is_empty());
+    let detail = if !unexpected.is_empty() {
+        format!("unexpected status codes {:?} (allowed {:?})", unexpected, allowed)
+    } else if required && errors.is_empty() {
+        "fault did not surface any error to the client".to_string()
+    } else {
+        format!("observed {:?}", errors)
+    };
+    Invariant::new("status_codes", passed, detail)
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use playground_chaos::report;
+
+#[derive(Parser, Debug)]
+#[command(name = "playground-chaos")]
+#[command(about = "Run the proxy fault matrix against both gRPC servers")]
+struct Args {
+    /// Directory for chaos-matrix.json / chaos-matrix.md
+    #[arg(long, default_value = "chaos-report")]
+    out_dir: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    println!("🌪️ [CHAOS] Running fault matrix (this takes ~20 seconds)");
+    let results = playground_chaos::run_matrix().await?;
+    report::write_artifacts(&results, &args.out_dir)?;
+
+    println!();
+    println!("{}", report::markdown(&results));
+    println!("📁 [CHAOS] Matrix written to {}", args.out_dir.display());
+
+    if results.iter().any(|result| !result.passed()) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
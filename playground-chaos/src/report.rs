@@ -0,0 +1,61 @@
+//! 호환성 매트릭스 산출물 (JSON + Markdown)
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::{FaultCase, ScenarioResult, Target};
+
+pub const JSON_FILE: &str = "chaos-matrix.json";
+pub const MARKDOWN_FILE: &str = "chaos-matrix.md";
+
+/// 서버 × 장애 표와 실패한 불변식 목록
+pub fn markdown(results: &[ScenarioResult]) -> String {
+    let mut out = String::from("# Chaos compatibility matrix\n\n| server |");
+    for fault in FaultCase::ALL {
+        let _ = write!(out, " {} |", fault.name());
+    }
+    out.push_str("\n|---|");
+    out.push_str(&"---|".repeat(FaultCase::ALL.len()));
+    out.push('\n');
+
+    for target in Target::ALL {
+        let _ = write!(out, "| {} |", target.name());
+        for fault in FaultCase::ALL {
+            match results.iter().find(|r| r.target == target && r.fault == fault) {
+                Some(result) => {
+                    let mark = if result.passed() { "✅" } else { "❌" };
+                    let _ = write!(out, " {} {} |", mark, result.status_summary());
+                }
+                None => out.push_str(" - |"),
+            }
+        }
+        out.push('\n');
+    }
+
+    let failures: Vec<_> = results.iter().filter(|r| !r.passed()).collect();
+    if !failures.is_empty() {
+        out.push_str("\n## Violations\n\n");
+        for result in failures {
+            for invariant in result.invariants.iter().filter(|i| !i.passed) {
+                let _ = writeln!(
+                    out,
+                    "- {} / {}: `{}` {}",
+                    result.target.name(),
+                    result.fault.name(),
+                    invariant.name,
+                    invariant.detail
+                );
+            }
+        }
+    }
+    out
+}
+
+/// `dir`에 JSON/Markdown 매트릭스 저장
+pub fn write_artifacts(results: &[ScenarioResult], dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let json = serde_json::to_string_pretty(results)?;
+    std::fs::write(dir.join(JSON_FILE), json)?;
+    std::fs::write(dir.join(MARKDOWN_FILE), markdown(results))?;
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use std::path::Path;
+
+use playground_chaos::report;
+
+/// 전체 매트릭스를 실행하고 모든 불변식이 지켜지는지 확인
+#[tokio::test]
+async fn fault_matrix_holds_invariants() {
+    let results = playground_chaos::run_matrix().await.expect("scenario setup failed");
+
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("chaos-report");
+    report::write_artifacts(&results, &out_dir).expect("failed to write matrix");
+
+    assert!(
+        results.iter().all(|result| result.passed()),
+        "invariant violations:\n{}",
+        report::markdown(&results)
+    );
+}
@@ -0,0 +1,64 @@
+//! 서버가 사용하는 시계 추상화
+//!
+//! 타이머(`tokio::time::sleep`, `interval`)는 이미 tokio의 일시정지 시간을 따르지만,
+//! 메시지 타임스탬프와 재전송 판단에 쓰는 벽시계(`SystemTime`)는 따르지 않는다.
+//! 서버는 벽시계를 항상 `Clock`을 통해 읽어서 테스트에서 가상 시간으로 구동할 수 있게 한다.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub trait Clock: Send + Sync + 'static {
+    /// UNIX epoch 기준 현재 시각
+    fn unix_time(&self) -> Duration;
+
+    fn unix_secs(&self) -> u64 {
+        self.unix_time().as_secs()
+    }
+
+    fn unix_millis(&self) -> u64 {
+        self.unix_time().as_millis() as u64
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// 실제 시스템 시계 (프로덕션 기본값)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn unix_time(&self) -> Duration {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+    }
+}
+
+/// tokio 시간을 따라가는 시계
+///
+/// 생성 시점의 벽시계에 `tokio::time::Instant` 경과 시간을 더하므로,
+/// `#[tokio::test(start_paused = true)]`에서 `advance()`/자동 진행에 맞춰 움직인다.
+#[derive(Debug, Clone, Copy)]
+pub struct TokioClock {
+    base: Duration,
+    start: tokio::time::Instant,
+}
+
+impl TokioClock {
+    pub fn new() -> Self {
+        Self {
+            base: SystemClock.unix_time(),
+            start: tokio::time::Instant::now(),
+        }
+    }
+}
+
+impl Default for TokioClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TokioClock {
+    fn unix_time(&self) -> Duration {
+        self.base + self.start.elapsed()
+    }
+}
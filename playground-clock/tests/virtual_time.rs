@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use playground_clock::{Clock, SharedClock, SystemClock, TokioClock};
+
+#[tokio::test(start_paused = true)]
+async fn tokio_clock_moves_only_with_tokio_time() {
+    let clock = TokioClock::new();
+    let start = clock.unix_time();
+
+    // 멈춘 시간에서는 실제로 시간이 흘러도 그대로
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(clock.unix_time(), start);
+
+    tokio::time::advance(Duration::from_millis(1500)).await;
+    assert_eq!(clock.unix_time() - start, Duration::from_millis(1500));
+
+    // 자동 진행 - 긴 타이머도 즉시 끝나고 시계는 그만큼 앞으로 감
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(clock.unix_millis() - start.as_millis() as u64, 31_500);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tokio_clock_starts_at_wall_clock_time() {
+    let clock: SharedClock = Arc::new(TokioClock::new());
+    let wall = SystemClock.unix_time();
+    assert!(wall.abs_diff(clock.unix_time()) < Duration::from_secs(1));
+    assert_eq!(clock.unix_secs(), clock.unix_time().as_secs());
+}
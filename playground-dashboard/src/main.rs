@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, response::Html, routing::get, Json, Router};
+use clap::Parser;
+use playground_stats::proto::{stats_service_client::StatsServiceClient, StatsSnapshot, WatchStatsRequest};
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Parser, Debug)]
+#[command(name = "playground-dashboard")]
+#[command(about = "Live web dashboard for the gRPC streaming playground")]
+struct Args {
+    /// Dashboard HTTP listen address
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    listen: SocketAddr,
+
+    /// Streaming server exposing StatsService (repeatable)
+    #[arg(long = "server", default_value = "http://[::1]:50051")]
+    servers: Vec<String>,
+
+    /// Proxy status endpoint
+    #[arg(long, default_value = "http://[::1]:8081/status")]
+    proxy_status: String,
+
+    /// r0-execute `--progress-file` to follow
+    #[arg(long)]
+    executor_progress: Option<PathBuf>,
+}
+
+#[derive(Default, Clone, Serialize)]
+struct ServerView {
+    connected: bool,
+    snapshot: Option<StatsSnapshot>,
+    error: Option<String>,
+}
+
+#[derive(Default, Clone, Serialize)]
+struct DashboardState {
+    servers: BTreeMap<String, ServerView>,
+    proxy: Option<serde_json::Value>,
+    proxy_error: Option<String>,
+    executor: Option<serde_json::Value>,
+}
+
+type SharedState = Arc<RwLock<DashboardState>>;
+
+/// 서버의 WatchStats 스트림을 구독 - 끊기면 재연결
+async fn watch_server(addr: String, state: SharedState) {
+    loop {
+        let result = async {
+            let mut client = StatsServiceClient::connect(addr.clone()).await?;
+            let mut stream = client
+                .watch_stats(WatchStatsRequest {
+                    interval_ms: POLL_INTERVAL.as_millis() as u64,
+                })
+                .await?
+                .into_inner();
+
+            while let Some(snapshot) = stream.message().await? {
+                let mut state = state.write().await;
+                let view = state.servers.entry(addr.clone()).or_default();
+                view.connected = true;
+                view.snapshot = Some(snapshot);
+                view.error = None;
+            }
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        }
+        .await;
+
+        {
+            let mut state = state.write().await;
+            let view = state.servers.entry(addr.clone()).or_default();
+            view.connected = false;
+            view.error = result.err().map(|e| e.to_string());
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn poll_proxy(url: String, state: SharedState) {
+    let client = reqwest::Client::new();
+    loop {
+        let result = async {
+            client
+                .get(&url)
+                .timeout(POLL_INTERVAL)
+                .send()
+                .await?
+                .json::<serde_json::Value>()
+                .await
+        }
+        .await;
+
+        {
+            let mut state = state.write().await;
+            match result {
+                Ok(status) => {
+                    state.proxy = Some(status);
+                    state.proxy_error = None;
+                }
+                Err(e) => {
+                    state.proxy = None;
+                    state.proxy_error = Some(e.to_string());
+                }
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn poll_executor(path: PathBuf, state: SharedState) {
+    loop {
+        let progress = tokio::fs::read(&path)
+            .await
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        state.write().await.executor = progress;
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(include_str!("index.html"))
+}
+
+async fn api_state(State(state): State<SharedState>) -> Json<DashboardState> {
+    Json(state.read().await.clone())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let state = SharedState::default();
+
+    for server in &args.servers {
+        tokio::spawn(watch_server(server.clone(), state.clone()));
+    }
+    tokio::spawn(poll_proxy(args.proxy_status.clone(), state.clone()));
+    if let Some(path) = args.executor_progress.clone() {
+        tokio::spawn(poll_executor(path, state.clone()));
+    }
+
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/api/state", get(api_state))
+        .with_state(state);
+
+    println!("📊 [DASHBOARD] Listening on http://{}", args.listen);
+    println!("📊 [DASHBOARD] Servers: {:?}", args.servers);
+    println!("📊 [DASHBOARD] Proxy status: {}", args.proxy_status);
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! 컴포넌트 빌드/실행/종료
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use tokio::process::{Child, Command};
+
+use crate::wiring::{Component, Role};
+
+/// SIGTERM 후 drain을 기다리는 시간 (playground-shutdown 기본 drain 10초보다 약간 길게)
+const STOP_GRACE: Duration = Duration::from_secs(12);
+
+/// Rust 컴포넌트의 크레이트 경로와 바이너리 이름 (저장소 루트 기준)
+fn rust_binary(role: Role) -> Option<(&'static str, &'static str)> {
+    match role {
+        Role::CancelServer => Some(("grpc-stream-cancel/rust-server", "grpc-stream-server")),
+        Role::AckServer => Some(("grpc-stream/rust-server", "grpc-stream-ack-server")),
+        Role::Proxy => Some(("grpc-stream-cancel/rust-proxy", "rust-proxy")),
+        Role::CancelClient | Role::AckClient => None,
+    }
+}
+
+fn client_dir(role: Role) -> &'static str {
+    match role {
+        Role::AckClient => "grpc-stream/python-client",
+        _ => "grpc-stream-cancel/python-client",
+    }
+}
+
+/// 필요한 Rust 컴포넌트를 release로 빌드
+///
+/// `cargo run`으로 띄우면 종료 신호가 cargo에만 가므로 빌드된 바이너리를 직접 실행한다.
+pub async fn build(root: &Path, components: &[Component]) -> io::Result<()> {
+    for (dir, _) in components.iter().filter_map(|component| rust_binary(component.role)) {
+        let status = Command::new("cargo")
+            .args(["build", "--release", "--quiet", "--manifest-path"])
+            .arg(root.join(dir).join("Cargo.toml"))
+            .status()
+            .await?;
+        if !status.success() {
+            return Err(io::Error::other(format!("cargo build failed for {}", dir)));
+        }
+    }
+    Ok(())
+}
+
+fn program(root: &Path, component: &Component, python: &str) -> (PathBuf, Vec<String>, PathBuf) {
+    match rust_binary(component.role) {
+        Some((dir, binary)) => (
+            root.join(dir).join("target/release").join(binary),
+            component.args.clone(),
+            root.join(dir),
+        ),
+        None => {
+            let mut args = vec!["client.py".to_string()];
+            args.extend(component.args.iter().cloned());
+            (PathBuf::from(python), args, root.join(client_dir(component.role)))
+        }
+    }
+}
+
+pub fn spawn(root: &Path, component: &Component, python: &str) -> io::Result<Child> {
+    let (program, args, dir) = program(root, component, python);
+    Command::new(&program)
+        .args(&args)
+        .current_dir(dir)
+        .envs(component.env.iter().map(|(key, value)| (*key, value.as_str())))
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to start {}: {}", program.display(), e)))
+}
+
+/// SIGTERM으로 정상 종료를 요청하고, 시간 안에 끝나지 않으면 강제 종료
+pub async fn stop(child: &mut Child) -> io::Result<ExitStatus> {
+    if let Some(status) = child.try_wait()? {
+        return Ok(status);
+    }
+    terminate(child);
+    match tokio::time::timeout(STOP_GRACE, child.wait()).await {
+        Ok(status) => status,
+        Err(_) => {
+            child.start_kill()?;
+            child.wait().await
+        }
+    }
+}
+
+#[cfg(unix)]
+fn terminate(child: &Child) {
+    if let Some(pid) = child.id() {
+        // SAFETY: 아직 회수되지 않은 자식 프로세스의 pid에 신호만 보냄
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) {
+    let _ = child.start_kill();
+}
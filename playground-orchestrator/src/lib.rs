@@ -0,0 +1,9 @@
+//! 컨테이너 없이 클라이언트 → 프록시 → 서버를 임시 포트로 연결해 시나리오를 실행하는 오케스트레이터
+//!
+//! 포트를 고정하지 않고 매번 새로 배정해 각 컴포넌트의 환경변수로 다음 홉을 알려주므로,
+//! 같은 머신에서 여러 실험을 동시에 돌릴 수 있다.
+
+pub mod launch;
+pub mod ports;
+pub mod probe;
+pub mod wiring;
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use playground_orchestrator::wiring::{Scenario, Wiring};
+use playground_orchestrator::{launch, ports, probe};
+
+#[derive(Parser, Debug)]
+#[command(name = "playground-orchestrator")]
+#[command(about = "Run a client → proxy → server scenario on freshly allocated ports")]
+struct Args {
+    #[arg(long, value_enum, default_value = "disconnect")]
+    scenario: Scenario,
+
+    /// Repository root
+    #[arg(long, default_value = "..")]
+    root: PathBuf,
+
+    /// Python interpreter for the clients
+    #[arg(long, default_value = "python")]
+    python: String,
+
+    /// How long each component may take to start accepting connections
+    #[arg(long, default_value_t = 30)]
+    startup_timeout_secs: u64,
+
+    /// Use the existing release binaries instead of running `cargo build` first
+    #[arg(long)]
+    skip_build: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let startup_timeout = Duration::from_secs(args.startup_timeout_secs);
+    // 컴포넌트는 각자 크레이트 디렉터리에서 실행되므로 상대 경로가 틀어지지 않게 절대 경로로
+    let root = args.root.canonicalize()?;
+
+    let ports = ports::allocate(args.scenario.ports_needed())?;
+    let wiring = Wiring::plan(args.scenario, &ports);
+
+    println!("🔌 [ORCHESTRATOR] Scenario '{}' wiring:", wiring.scenario.name());
+    for component in &wiring.components {
+        let listen = component.listen.map_or("-".to_string(), |addr| addr.to_string());
+        let next_hop = component.next_hop.map_or("-".to_string(), |addr| addr.to_string());
+        println!("   {:<14} listen {:<16} → {}", component.name(), listen, next_hop);
+    }
+
+    if !args.skip_build {
+        println!("🔨 [ORCHESTRATOR] Building components...");
+        launch::build(&root, &wiring.components).await?;
+    }
+
+    // 서버부터 띄우고, 각 홉이 연결을 받기 시작한 것을 확인한 뒤 다음 홉을 띄움
+    let mut services = Vec::new();
+    for component in wiring.services() {
+        let mut child = launch::spawn(&root, component, &args.python)?;
+        let addr = component.listen.expect("services always listen");
+        let ready = async {
+            if component.role.has_health_check() {
+                probe::wait_serving(addr, startup_timeout).await
+            } else {
+                probe::wait_reachable(addr, startup_timeout).await
+            }
+        };
+        tokio::select! {
+            ready = ready => ready?,
+            status = child.wait() => {
+                return Err(format!("{} exited during startup ({})", component.name(), status?).into());
+            }
+        }
+        println!("✅ [ORCHESTRATOR] {} is accepting connections on {}", component.name(), addr);
+        services.push((component.name(), child));
+    }
+
+    // 클라이언트가 붙을 입구까지 전체 경로가 열렸는지 마지막으로 확인
+    probe::wait_reachable(wiring.entry(), startup_timeout).await?;
+    println!("🚀 [ORCHESTRATOR] Path verified, starting {}", wiring.client().name());
+
+    let client_status = launch::spawn(&root, wiring.client(), &args.python)?.wait().await?;
+    println!("🏁 [ORCHESTRATOR] {} exited ({})", wiring.client().name(), client_status);
+
+    // 프록시 → 서버 순서로 종료 (시작의 역순)
+    for (name, mut child) in services.into_iter().rev() {
+        let status = launch::stop(&mut child).await?;
+        println!("🧹 [ORCHESTRATOR] {} stopped ({})", name, status);
+    }
+
+    std::process::exit(client_status.code().unwrap_or(1));
+}
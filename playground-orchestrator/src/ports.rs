@@ -0,0 +1,15 @@
+//! 임시 포트 배정
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+
+/// OS에서 서로 다른 임시 포트 `count`개를 받아옴
+///
+/// 리스너를 모두 연 상태에서 번호를 읽어야 서로 겹치지 않는다. 반환 시점에 리스너는 닫히므로
+/// 컴포넌트가 bind 하기 전에 다른 프로세스가 가져갈 수 있는데, 이 경우는 연결 확인 단계에서 드러난다.
+pub fn allocate(count: usize) -> io::Result<Vec<SocketAddr>> {
+    let listeners = (0..count)
+        .map(|_| TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))
+        .collect::<io::Result<Vec<_>>>()?;
+    listeners.iter().map(TcpListener::local_addr).collect()
+}
@@ -0,0 +1,68 @@
+//! 컴포넌트가 연결을 받기 시작했는지 확인 (TCP 연결 또는 gRPC 헬스 체크)
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+use tonic::transport::Endpoint;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+const PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `addr`에 TCP 연결이 될 때까지 재시도 - `timeout` 안에 안 되면 `TimedOut`
+pub async fn wait_reachable(addr: SocketAddr, timeout: Duration) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match TcpStream::connect(addr).await {
+            Ok(_) => return Ok(()),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{} not reachable after {:?}: {}", addr, timeout, e),
+                ));
+            }
+            Err(_) => tokio::time::sleep(PROBE_INTERVAL).await,
+        }
+    }
+}
+
+/// gRPC 헬스 체크가 SERVING을 돌려줄 때까지 재시도 - `timeout` 안에 안 되면 `TimedOut`
+pub async fn wait_serving(addr: SocketAddr, timeout: Duration) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let status = check_health(addr).await;
+        match status {
+            Ok(ServingStatus::Serving) => return Ok(()),
+            _ if Instant::now() >= deadline => {
+                let last = match status {
+                    Ok(status) => status.as_str_name().to_string(),
+                    Err(e) => e,
+                };
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{} not serving after {:?}: {}", addr, timeout, last),
+                ));
+            }
+            _ => tokio::time::sleep(PROBE_INTERVAL).await,
+        }
+    }
+}
+
+/// 서버 전체 상태(서비스 이름 "") 한 번 조회
+async fn check_health(addr: SocketAddr) -> Result<ServingStatus, String> {
+    let channel = Endpoint::from_shared(format!("http://{}", addr))
+        .map_err(|e| e.to_string())?
+        .connect()
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut client = HealthClient::new(channel);
+    let response = client
+        .check(HealthCheckRequest { service: String::new() })
+        .await
+        .map_err(|status| status.to_string())?;
+    Ok(response.into_inner().status())
+}
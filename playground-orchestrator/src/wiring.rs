@@ -0,0 +1,175 @@
+//! 시나리오별 컴포넌트 구성과 홉 연결
+
+use std::net::SocketAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Scenario {
+    /// 클라이언트 → 취소 서버, 스트림 중간에 클라이언트가 취소
+    Cancel,
+    /// 클라이언트 → 프록시 → 취소 서버, 프록시가 네트워크 단절 흉내
+    Disconnect,
+    /// 클라이언트 → ACK 서버
+    Ack,
+    /// 클라이언트 → 프록시 → ACK 서버
+    AckProxy,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 4] = [Scenario::Cancel, Scenario::Disconnect, Scenario::Ack, Scenario::AckProxy];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::Cancel => "cancel",
+            Scenario::Disconnect => "disconnect",
+            Scenario::Ack => "ack",
+            Scenario::AckProxy => "ack-proxy",
+        }
+    }
+
+    fn uses_proxy(&self) -> bool {
+        matches!(self, Scenario::Disconnect | Scenario::AckProxy)
+    }
+
+    /// 서버 1개 + 프록시(수신/상태) 2개
+    pub fn ports_needed(&self) -> usize {
+        if self.uses_proxy() {
+            3
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    CancelServer,
+    AckServer,
+    Proxy,
+    CancelClient,
+    AckClient,
+}
+
+impl Role {
+    pub fn is_client(&self) -> bool {
+        matches!(self, Role::CancelClient | Role::AckClient)
+    }
+
+    /// 표준 gRPC 헬스 체크 서비스를 제공하는지 (프록시는 TCP만 전달)
+    pub fn has_health_check(&self) -> bool {
+        matches!(self, Role::CancelServer | Role::AckServer)
+    }
+}
+
+/// 실행할 프로세스 하나와 그 주소 설정
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub role: Role,
+    /// 이 컴포넌트가 연결을 받는 주소 (클라이언트는 없음)
+    pub listen: Option<SocketAddr>,
+    /// 연결할 다음 홉 (서버는 없음)
+    pub next_hop: Option<SocketAddr>,
+    /// 컴포넌트 설정을 덮어쓰는 환경변수
+    pub env: Vec<(&'static str, String)>,
+    pub args: Vec<String>,
+}
+
+impl Component {
+    pub fn name(&self) -> &'static str {
+        match self.role {
+            Role::CancelServer => "cancel-server",
+            Role::AckServer => "ack-server",
+            Role::Proxy => "proxy",
+            Role::CancelClient => "cancel-client",
+            Role::AckClient => "ack-client",
+        }
+    }
+}
+
+/// 시나리오의 전체 연결 구성 - 서버부터 클라이언트 순서 (시작 순서와 같음)
+#[derive(Debug, Clone)]
+pub struct Wiring {
+    pub scenario: Scenario,
+    pub components: Vec<Component>,
+}
+
+impl Wiring {
+    /// `ports`로 각 컴포넌트의 수신 주소를 정하고 다음 홉을 앞 컴포넌트의 주소로 설정
+    ///
+    /// `ports`는 최소 `scenario.ports_needed()`개여야 한다.
+    pub fn plan(scenario: Scenario, ports: &[SocketAddr]) -> Self {
+        assert!(
+            ports.len() >= scenario.ports_needed(),
+            "scenario {} needs {} ports",
+            scenario.name(),
+            scenario.ports_needed()
+        );
+
+        let server_addr = ports[0];
+        let mut components = vec![match scenario {
+            Scenario::Cancel | Scenario::Disconnect => Component {
+                role: Role::CancelServer,
+                listen: Some(server_addr),
+                next_hop: None,
+                env: vec![("GRPC_LISTEN_ADDRESS", server_addr.to_string())],
+                // 메시지 간격 1초 (test_*.sh와 같음)
+                args: vec!["--message-interval".to_string(), "1".to_string()],
+            },
+            Scenario::Ack | Scenario::AckProxy => Component {
+                role: Role::AckServer,
+                listen: Some(server_addr),
+                next_hop: None,
+                env: vec![("GRPC_LISTEN_ADDRESS", server_addr.to_string())],
+                // 메시지 5개 (run.sh 기본값과 같음)
+                args: vec!["5".to_string()],
+            },
+        }];
+
+        let mut entry = server_addr;
+        if scenario.uses_proxy() {
+            let (proxy_addr, status_addr) = (ports[1], ports[2]);
+            components.push(Component {
+                role: Role::Proxy,
+                listen: Some(proxy_addr),
+                next_hop: Some(entry),
+                env: vec![
+                    ("PROXY_LISTEN_ADDRESS", proxy_addr.to_string()),
+                    ("PROXY_UPSTREAM_ADDRESS", entry.to_string()),
+                    ("PROXY_STATUS_ADDRESS", status_addr.to_string()),
+                ],
+                args: Vec::new(),
+            });
+            entry = proxy_addr;
+        }
+
+        let (role, args) = match scenario {
+            Scenario::Cancel => (Role::CancelClient, vec!["--mode", "auto_cancel", "--delay", "3.0"]),
+            Scenario::Disconnect => (Role::CancelClient, vec!["--mode", "simple"]),
+            Scenario::Ack | Scenario::AckProxy => (Role::AckClient, Vec::new()),
+        };
+        components.push(Component {
+            role,
+            listen: None,
+            next_hop: Some(entry),
+            env: vec![("GRPC_SERVER_ADDRESS", entry.to_string())],
+            args: args.into_iter().map(String::from).collect(),
+        });
+
+        Self { scenario, components }
+    }
+
+    /// 클라이언트가 처음 연결하는 주소
+    pub fn entry(&self) -> SocketAddr {
+        self.client()
+            .next_hop
+            .expect("client always has a next hop")
+    }
+
+    pub fn client(&self) -> &Component {
+        self.components.last().expect("wiring always has a client")
+    }
+
+    /// 클라이언트보다 먼저 띄워야 하는 컴포넌트 (서버 → 프록시 순서)
+    pub fn services(&self) -> &[Component] {
+        &self.components[..self.components.len() - 1]
+    }
+}
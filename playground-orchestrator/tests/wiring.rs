@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use playground_orchestrator::wiring::{Role, Scenario, Wiring};
+use playground_orchestrator::{ports, probe};
+
+fn env<'a>(wiring: &'a Wiring, role: Role, key: &str) -> &'a str {
+    let component = wiring
+        .components
+        .iter()
+        .find(|component| component.role == role)
+        .expect("component in wiring");
+    component
+        .env
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.as_str())
+        .expect("env var set")
+}
+
+#[test]
+fn allocated_ports_are_distinct() {
+    let addrs = ports::allocate(8).unwrap();
+    let unique: HashSet<_> = addrs.iter().map(|addr| addr.port()).collect();
+    assert_eq!(unique.len(), 8);
+    assert!(addrs.iter().all(|addr| addr.port() != 0));
+}
+
+/// 클라이언트 → 프록시 → 서버로 각 홉이 다음 컴포넌트의 수신 주소를 가리켜야 함
+#[test]
+fn proxy_scenarios_chain_client_proxy_server() {
+    for scenario in [Scenario::Disconnect, Scenario::AckProxy] {
+        let addrs = ports::allocate(scenario.ports_needed()).unwrap();
+        let wiring = Wiring::plan(scenario, &addrs);
+        let (server, proxy) = match scenario {
+            Scenario::Disconnect => (Role::CancelServer, Role::Proxy),
+            _ => (Role::AckServer, Role::Proxy),
+        };
+        let client = wiring.client().role;
+
+        let server_listen = env(&wiring, server, "GRPC_LISTEN_ADDRESS");
+        assert_eq!(env(&wiring, proxy, "PROXY_UPSTREAM_ADDRESS"), server_listen);
+        assert_eq!(
+            env(&wiring, client, "GRPC_SERVER_ADDRESS"),
+            env(&wiring, proxy, "PROXY_LISTEN_ADDRESS")
+        );
+        assert_eq!(wiring.entry().to_string(), env(&wiring, proxy, "PROXY_LISTEN_ADDRESS"));
+        assert_ne!(env(&wiring, proxy, "PROXY_STATUS_ADDRESS"), server_listen);
+    }
+}
+
+#[test]
+fn direct_scenarios_connect_client_to_server() {
+    for scenario in [Scenario::Cancel, Scenario::Ack] {
+        let addrs = ports::allocate(scenario.ports_needed()).unwrap();
+        let wiring = Wiring::plan(scenario, &addrs);
+
+        assert_eq!(wiring.services().len(), 1);
+        assert!(wiring.client().role.is_client());
+        assert_eq!(wiring.entry(), addrs[0]);
+    }
+}
+
+/// 같은 시나리오를 두 번 배치해도 포트가 겹치지 않아 동시에 실행 가능
+#[test]
+fn parallel_runs_do_not_share_ports() {
+    let first = ports::allocate(Scenario::Disconnect.ports_needed()).unwrap();
+    let second = ports::allocate(Scenario::Disconnect.ports_needed()).unwrap();
+    let first = Wiring::plan(Scenario::Disconnect, &first);
+    let second = Wiring::plan(Scenario::Disconnect, &second);
+
+    let listens = |wiring: &Wiring| -> HashSet<_> {
+        wiring.components.iter().filter_map(|component| component.listen).collect()
+    };
+    assert!(listens(&first).is_disjoint(&listens(&second)));
+}
+
+#[tokio::test]
+async fn probe_waits_for_listener() {
+    let addr = ports::allocate(1).unwrap()[0];
+
+    let server = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        let _ = listener.accept().await;
+    });
+
+    probe::wait_reachable(addr, Duration::from_secs(5)).await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn probe_times_out_when_nothing_listens() {
+    let addr = ports::allocate(1).unwrap()[0];
+    let error = probe::wait_reachable(addr, Duration::from_millis(300)).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+}
+
+#[tokio::test]
+async fn health_probe_waits_for_serving_status() {
+    use tonic_health::ServingStatus;
+
+    let addr = ports::allocate(1).unwrap()[0];
+    let (mut health, service) = tonic_health::server::health_reporter();
+    health.set_service_status("", ServingStatus::NotServing).await;
+    tokio::spawn(tonic::transport::Server::builder().add_service(service).serve(addr));
+
+    // 연결은 되지만 NOT_SERVING이면 준비되지 않은 것
+    let error = probe::wait_serving(addr, Duration::from_millis(500)).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    assert!(error.to_string().contains("NOT_SERVING"), "{}", error);
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        health.set_service_status("", ServingStatus::Serving).await;
+    });
+    probe::wait_serving(addr, Duration::from_secs(5)).await.unwrap();
+}
@@ -0,0 +1,165 @@
+//! SIGINT/SIGTERM 기반 프로세스 공용 종료 처리
+//!
+//! 종료 단계는 실행 중 → 연결 수락 중단 → 진행 중 작업 drain → flush → 종료 순서로만 진행된다.
+//! 첫 신호에서 `token()`이 취소되고, 두 번째 신호는 drain을 기다리지 않고 즉시 종료한다.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// 바이너리들이 drain 단계에서 기본으로 기다리는 시간
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 두 번째 신호로 강제 종료할 때의 종료 코드 (128 + SIGINT)
+const FORCED_EXIT_CODE: i32 = 130;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Running,
+    /// 새 연결/작업 수락 중단 (token 취소 시점)
+    StopAccepting,
+    /// 진행 중인 스트림/작업 마무리 대기
+    Drain,
+    /// span, 진행 상황 파일 등 버퍼 내보내기
+    Flush,
+    Exit,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::Running => "running",
+            Phase::StopAccepting => "stop accepting",
+            Phase::Drain => "drain",
+            Phase::Flush => "flush",
+            Phase::Exit => "exit",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone)]
+pub struct Shutdown {
+    token: CancellationToken,
+    phase: Arc<Mutex<Phase>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            phase: Arc::new(Mutex::new(Phase::Running)),
+        }
+    }
+}
+
+impl Shutdown {
+    /// 신호 처리 없이 생성 (테스트나 `trigger`로 직접 종료하는 경우)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// SIGINT/SIGTERM을 감시하는 인스턴스 생성 (tokio 런타임 안에서 호출해야 함)
+    pub fn install() -> Self {
+        let shutdown = Self::new();
+        tokio::spawn(listen(shutdown.clone()));
+        shutdown
+    }
+
+    /// 종료가 시작되면 취소되는 토큰
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub async fn requested(&self) {
+        self.token.cancelled().await
+    }
+
+    /// 종료 시작 - 수락 중단 단계로 넘어가고 토큰 취소
+    pub fn trigger(&self, reason: &str) {
+        if self.token.is_cancelled() {
+            return;
+        }
+        println!("🛑 [SHUTDOWN] {} received", reason);
+        self.advance(Phase::StopAccepting);
+        self.token.cancel();
+    }
+
+    pub fn phase(&self) -> Phase {
+        *self.phase.lock().unwrap()
+    }
+
+    /// 다음 단계로 이동 - 이미 지난 단계로는 돌아가지 않음
+    pub fn advance(&self, phase: Phase) {
+        let mut current = self.phase.lock().unwrap();
+        if phase > *current {
+            *current = phase;
+            println!("🛑 [SHUTDOWN] Phase: {}", phase);
+        }
+    }
+
+    /// `work`를 실행하다 종료가 시작되면 drain 단계로 넘어가 최대 `timeout`만큼 더 기다림
+    ///
+    /// `work`는 토큰을 보고 스스로 수락을 멈추고 끝나야 한다.
+    /// 시간 안에 끝나지 않으면 `None`을 반환하고 `work`는 버려진다.
+    pub async fn drain<F: Future>(&self, work: F, timeout: Duration) -> Option<F::Output> {
+        tokio::pin!(work);
+        tokio::select! {
+            // 신호와 동시에 끝난 경우에도 drain 단계를 거치도록 신호를 먼저 확인
+            biased;
+            _ = self.requested() => {}
+            output = &mut work => return Some(output),
+        }
+
+        self.advance(Phase::Drain);
+        match tokio::time::timeout(timeout, work).await {
+            Ok(output) => Some(output),
+            Err(_) => {
+                println!("⏰ [SHUTDOWN] Drain timed out after {:?}, abandoning in-flight work", timeout);
+                None
+            }
+        }
+    }
+}
+
+async fn listen(shutdown: Shutdown) {
+    let Some(signal) = wait_for_signal().await else {
+        return;
+    };
+    shutdown.trigger(signal);
+
+    if wait_for_signal().await.is_some() {
+        eprintln!("🛑 [SHUTDOWN] Second signal received, exiting immediately");
+        std::process::exit(FORCED_EXIT_CODE);
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> Option<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            eprintln!("[SHUTDOWN] Failed to install SIGTERM handler: {}", e);
+            return tokio::signal::ctrl_c().await.ok().map(|_| "SIGINT");
+        }
+    };
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result.ok().map(|_| "SIGINT"),
+        _ = terminate.recv() => Some("SIGTERM"),
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> Option<&'static str> {
+    tokio::signal::ctrl_c().await.ok().map(|_| "Ctrl-C")
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use playground_shutdown::{Phase, Shutdown};
+
+#[tokio::test(start_paused = true)]
+async fn work_finishing_before_shutdown_stays_running() {
+    let shutdown = Shutdown::new();
+
+    let output = shutdown
+        .drain(async { 42 }, Duration::from_secs(1))
+        .await;
+
+    assert_eq!(output, Some(42));
+    assert_eq!(shutdown.phase(), Phase::Running);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drain_waits_for_work_that_stops_on_the_token() {
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let work = async move {
+        token.cancelled().await;
+        // 진행 중인 작업 마무리에 시간이 걸리는 상황
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        "drained"
+    };
+
+    let trigger = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        trigger.trigger("test");
+    });
+
+    let output = shutdown.drain(work, Duration::from_secs(5)).await;
+
+    assert_eq!(output, Some("drained"));
+    assert_eq!(shutdown.phase(), Phase::Drain);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drain_gives_up_after_timeout() {
+    let shutdown = Shutdown::new();
+    shutdown.trigger("test");
+
+    let output = shutdown
+        .drain(std::future::pending::<()>(), Duration::from_secs(5))
+        .await;
+
+    assert_eq!(output, None);
+    assert_eq!(shutdown.phase(), Phase::Drain);
+}
+
+#[test]
+fn phases_only_move_forward() {
+    let shutdown = Shutdown::new();
+
+    shutdown.advance(Phase::Flush);
+    shutdown.advance(Phase::Drain);
+
+    assert_eq!(shutdown.phase(), Phase::Flush);
+}
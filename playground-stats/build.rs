@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("stats_descriptor.bin"))
+        .type_attribute(".", "#[derive(serde::Serialize)]")
+        .compile_protos(&["proto/stats.proto"], &["proto"])?;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Prometheus 히스토그램 - 고정 버킷 경계마다 관측 수와 합계를 셈
+
+use std::sync::Mutex;
+
+/// `le` 경계가 오름차순인 히스토그램 (마지막 `+Inf` 버킷은 `count`)
+pub struct Histogram {
+    bounds: &'static [f64],
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// 경계별 관측 수 (누적 아님)
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// `render`용 누적 값
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    /// (경계, 그 이하 관측 수)
+    pub buckets: Vec<(f64, u64)>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            inner: Mutex::new(Inner {
+                buckets: vec![0; bounds.len()],
+                ..Inner::default()
+            }),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(bucket) = self.bounds.iter().position(|&bound| value <= bound) {
+            inner.buckets[bucket] += 1;
+        }
+        inner.sum += value;
+        inner.count += 1;
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let inner = self.inner.lock().unwrap();
+        let mut cumulative = 0;
+        let buckets = self
+            .bounds
+            .iter()
+            .zip(&inner.buckets)
+            .map(|(&bound, &count)| {
+                cumulative += count;
+                (bound, cumulative)
+            })
+            .collect();
+        HistogramSnapshot {
+            buckets,
+            sum: inner.sum,
+            count: inner.count,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! 스트리밍 서버 공용 통계 카운터와 `StatsService` 구현
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+pub mod histogram;
+pub mod metrics;
+pub mod summary;
+pub mod upload;
+
+pub mod proto {
+    tonic::include_proto!("playground.stats");
+
+    /// 리플렉션 서비스에 등록할 `stats.proto` descriptor set
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("stats_descriptor");
+}
+
+use histogram::Histogram;
+use proto::stats_service_server::{StatsService as StatsServiceTrait, StatsServiceServer};
+use proto::{StatsSnapshot, WatchStatsRequest};
+
+/// ACK 지연 버킷 경계 (초) - 재전송 간격(초 단위)까지 덮음
+const ACK_LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// 프레임이 스트림 채널에 자리가 날 때까지 기다린 시간 버킷 경계 (초) - 평소엔 0에 가깝고 HTTP/2 윈도가 차면 길어짐
+const SEND_BLOCKED_BUCKETS: &[f64] = &[0.0001, 0.001, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0];
+
+/// 공유 전송 슬롯을 기다린 시간 버킷 경계 (초) - 슬롯 간격의 몇 배를 기다렸는지가 보이도록
+const FAIR_SHARE_WAIT_BUCKETS: &[f64] = &[0.01, 0.1, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+
+/// 메시지당 재전송 횟수 버킷 경계
+const RETRY_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 3.0, 5.0, 10.0];
+
+/// `all_streams_finished`가 카운터를 다시 확인하는 간격
+const FINISHED_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 서버 전체 통계 - 해당 서버에 없는 항목은 0으로 남음
+#[derive(Default)]
+pub struct ServerStats {
+    pub active_streams: AtomicU64,
+    pub messages_generated: AtomicU64,
+    pub messages_sent: AtomicU64,
+    pub pending_messages: AtomicU64,
+    pub acked_messages: AtomicU64,
+    pub retried_messages: AtomicU64,
+    pub completed_streams: AtomicU64,
+    pub cancelled_streams: AtomicU64,
+    /// 응답 큐에 쌓여 클라이언트가 아직 꺼내 가지 않은 메시지 (모든 스트림 합계)
+    pub queued_messages: AtomicU64,
+    /// 응답 큐가 가득 차서 overflow 정책으로 버린 메시지
+    pub dropped_messages: AtomicU64,
+    /// `--broadcast`에서 수신자가 채널 용량보다 뒤처져 건너뛴 메시지 (모든 스트림 합계)
+    pub lagged_messages: AtomicU64,
+    /// ACK 없이 `--message-ttl`을 넘겨 재전송하지 않고 뺀 메시지
+    pub expired_messages: AtomicU64,
+    /// 이미 ACK한 메시지를 다시 ACK한 횟수
+    pub duplicate_acks: AtomicU64,
+    /// ACK 없이 pending에서 빠진(만료, dead letter) 메시지에 뒤늦게 온 ACK
+    pub late_acks: AtomicU64,
+    /// 보낸 적 없는 id에 대한 ACK - 클라이언트 쪽 기록이 어긋났다는 신호
+    pub unknown_acks: AtomicU64,
+    /// ACK 받은 메시지의 전달 분포 (ACK 서버만 기록)
+    pub delivery: DeliveryHistograms,
+    /// 스트림들이 나눠 쓰는 전송 슬롯의 분배 (ACK 서버 `--fair-share-rate`만 기록)
+    pub fair_share: FairShareStats,
+    /// 취소 사유 종류별 스트림 수 - 합계는 `cancelled_streams`와 같음
+    cancellations: Mutex<BTreeMap<String, u64>>,
+}
+
+/// 메시지 단위 전달 분포
+pub struct DeliveryHistograms {
+    /// 마지막으로 보낸 뒤 ACK가 올 때까지 (초)
+    pub ack_latency: Histogram,
+    /// ACK 받을 때까지 다시 보낸 횟수
+    pub retries: Histogram,
+    /// 프레임마다 outbox에 들어가서 스트림 채널로 넘어갈 때까지 (초) - 클라이언트가 못 따라오면 길어짐
+    pub send_blocked: Histogram,
+}
+
+impl Default for DeliveryHistograms {
+    fn default() -> Self {
+        Self {
+            ack_latency: Histogram::new(ACK_LATENCY_BUCKETS),
+            retries: Histogram::new(RETRY_BUCKETS),
+            send_blocked: Histogram::new(SEND_BLOCKED_BUCKETS),
+        }
+    }
+}
+
+/// 공유 전송 슬롯 분배 - 가중치만큼 나눠 받는지, 굶는 스트림이 있는지
+pub struct FairShareStats {
+    /// 스트림이 슬롯을 요청해서 받을 때까지 (초)
+    pub wait: Histogram,
+    /// 최근 분배의 Jain 공정성 지수 (`f64` 비트) - 1이면 가중치대로, 1/n이면 한 스트림이 독차지
+    index: AtomicU64,
+}
+
+impl Default for FairShareStats {
+    fn default() -> Self {
+        Self {
+            wait: Histogram::new(FAIR_SHARE_WAIT_BUCKETS),
+            index: AtomicU64::new(0),
+        }
+    }
+}
+
+impl FairShareStats {
+    /// 스케줄러를 쓰지 않거나 아직 분배하지 않았으면 0
+    pub fn index(&self) -> f64 {
+        f64::from_bits(self.index.load(Ordering::Relaxed))
+    }
+
+    pub fn set_index(&self, index: f64) {
+        self.index.store(index.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl ServerStats {
+    pub fn incr(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 완료되지 못하고 끝난 스트림 기록 - `reason`은 값 종류가 적은 분류 이름
+    pub fn stream_cancelled(&self, reason: &str) {
+        Self::incr(&self.cancelled_streams);
+        *self.cancellations.lock().unwrap().entry(reason.to_string()).or_default() += 1;
+    }
+
+    /// 취소 사유 종류별 스트림 수
+    pub fn cancellations(&self) -> BTreeMap<String, u64> {
+        self.cancellations.lock().unwrap().clone()
+    }
+
+    /// 스트림이 하나 이상 끝났고 열린 스트림이 없을 때까지 대기 (`--exit-when-done`)
+    pub async fn all_streams_finished(&self) {
+        loop {
+            let finished = self.completed_streams.load(Ordering::Relaxed) + self.cancelled_streams.load(Ordering::Relaxed);
+            if finished > 0 && self.active_streams.load(Ordering::Relaxed) == 0 {
+                return;
+            }
+            tokio::time::sleep(FINISHED_POLL_INTERVAL).await;
+        }
+    }
+
+    /// 스트림 시작 시 호출 - 반환된 가드가 drop 되면 active_streams 감소
+    pub fn stream_opened(self: &Arc<Self>) -> ActiveStreamGuard {
+        self.active_streams.fetch_add(1, Ordering::Relaxed);
+        ActiveStreamGuard { stats: self.clone() }
+    }
+
+    pub fn snapshot(&self, server: &str) -> StatsSnapshot {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        StatsSnapshot {
+            server: server.to_string(),
+            timestamp,
+            active_streams: self.active_streams.load(Ordering::Relaxed),
+            messages_generated: self.messages_generated.load(Ordering::Relaxed),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            pending_messages: self.pending_messages.load(Ordering::Relaxed),
+            acked_messages: self.acked_messages.load(Ordering::Relaxed),
+            retried_messages: self.retried_messages.load(Ordering::Relaxed),
+            completed_streams: self.completed_streams.load(Ordering::Relaxed),
+            cancelled_streams: self.cancelled_streams.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub struct ActiveStreamGuard {
+    stats: Arc<ServerStats>,
+}
+
+impl Drop for ActiveStreamGuard {
+    fn drop(&mut self) {
+        self.stats.active_streams.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub struct StatsService {
+    server: String,
+    stats: Arc<ServerStats>,
+}
+
+impl StatsService {
+    pub fn new(server: impl Into<String>, stats: Arc<ServerStats>) -> StatsServiceServer<Self> {
+        StatsServiceServer::new(Self {
+            server: server.into(),
+            stats,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl StatsServiceTrait for StatsService {
+    type WatchStatsStream = ReceiverStream<Result<StatsSnapshot, Status>>;
+
+    async fn watch_stats(
+        &self,
+        request: Request<WatchStatsRequest>,
+    ) -> Result<Response<Self::WatchStatsStream>, Status> {
+        let interval_ms = match request.into_inner().interval_ms {
+            0 => 1000,
+            ms => ms,
+        };
+        let (tx, rx) = mpsc::channel(4);
+        let server = self.server.clone();
+        let stats = self.stats.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+            loop {
+                ticker.tick().await;
+                // 구독자가 연결을 끊으면 종료
+                if tx.send(Ok(stats.snapshot(&server))).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
@@ -0,0 +1,113 @@
+//! `GET /metrics` - `ServerStats`를 Prometheus 텍스트 형식으로 노출하는 HTTP 사이드카
+
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use tokio_util::sync::CancellationToken;
+
+use crate::histogram::Histogram;
+use crate::ServerStats;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+struct Metrics {
+    server: String,
+    stats: Arc<ServerStats>,
+}
+
+/// Prometheus 텍스트 형식으로 변환 - 모든 항목에 `server` 레이블을 붙임
+pub fn render(server: &str, stats: &ServerStats) -> String {
+    let server = escape(server);
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: &AtomicU64| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(out, "{}{{server=\"{}\"}} {}", name, server, value.load(Ordering::Relaxed));
+    };
+
+    metric("playground_active_streams", "gauge", "Streams currently open.", &stats.active_streams);
+    metric("playground_messages_generated_total", "counter", "Messages generated.", &stats.messages_generated);
+    metric("playground_messages_sent_total", "counter", "Messages handed to client streams, including retries.", &stats.messages_sent);
+    metric("playground_messages_retried_total", "counter", "Messages sent again (server retry, broker redelivery or resume replay).", &stats.retried_messages);
+    metric("playground_messages_acked_total", "counter", "Messages acknowledged by clients.", &stats.acked_messages);
+    metric("playground_pending_messages", "gauge", "Messages sent but not yet acknowledged.", &stats.pending_messages);
+    metric("playground_queued_messages", "gauge", "Messages buffered in response queues, not yet taken by clients.", &stats.queued_messages);
+    metric("playground_messages_dropped_total", "counter", "Messages discarded because a response queue was full (overflow policy).", &stats.dropped_messages);
+    metric("playground_messages_lagged_total", "counter", "Broadcast messages skipped because a client's receiver fell behind.", &stats.lagged_messages);
+    metric("playground_messages_expired_total", "counter", "Unacknowledged messages removed instead of retried because they outlived their TTL.", &stats.expired_messages);
+    metric("playground_acks_duplicate_total", "counter", "ACKs for messages that were already acknowledged.", &stats.duplicate_acks);
+    metric("playground_acks_late_total", "counter", "ACKs for messages that expired or failed before the ACK arrived.", &stats.late_acks);
+    metric("playground_acks_unknown_total", "counter", "ACKs for message IDs that were never sent.", &stats.unknown_acks);
+    metric("playground_streams_completed_total", "counter", "Streams that delivered every message.", &stats.completed_streams);
+
+    let _ = writeln!(out, "# HELP playground_streams_cancelled_total Streams that ended before completing, by reason.");
+    let _ = writeln!(out, "# TYPE playground_streams_cancelled_total counter");
+    for (reason, count) in stats.cancellations() {
+        let _ = writeln!(
+            out,
+            "playground_streams_cancelled_total{{server=\"{}\",reason=\"{}\"}} {}",
+            server,
+            escape(&reason),
+            count
+        );
+    }
+
+    let _ = writeln!(out, "# HELP playground_fairness_index Jain's fairness index of recent shared send slots per unit of stream weight (0 without --fair-share-rate).");
+    let _ = writeln!(out, "# TYPE playground_fairness_index gauge");
+    let _ = writeln!(out, "playground_fairness_index{{server=\"{}\"}} {}", server, stats.fair_share.index());
+
+    histogram(&mut out, &server, "playground_ack_latency_seconds", "Time from the last send of a message to its ACK.", &stats.delivery.ack_latency);
+    histogram(&mut out, &server, "playground_message_retries", "Times a message was sent again before it was acknowledged.", &stats.delivery.retries);
+    histogram(&mut out, &server, "playground_send_blocked_seconds", "Time a frame waited for room in the stream's send channel (flow control backpressure).", &stats.delivery.send_blocked);
+    histogram(&mut out, &server, "playground_fair_share_wait_seconds", "Time a stream waited for a shared send slot (--fair-share-rate).", &stats.fair_share.wait);
+    out
+}
+
+/// `_bucket`(누적, `+Inf` 포함), `_sum`, `_count` 줄을 씀 - `server`는 이미 이스케이프된 값
+fn histogram(out: &mut String, server: &str, name: &str, help: &str, histogram: &Histogram) {
+    let snapshot = histogram.snapshot();
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    for (bound, count) in snapshot.buckets {
+        let _ = writeln!(out, "{}_bucket{{server=\"{}\",le=\"{}\"}} {}", name, server, bound, count);
+    }
+    let _ = writeln!(out, "{}_bucket{{server=\"{}\",le=\"+Inf\"}} {}", name, server, snapshot.count);
+    let _ = writeln!(out, "{}_sum{{server=\"{}\"}} {}", name, server, snapshot.sum);
+    let _ = writeln!(out, "{}_count{{server=\"{}\"}} {}", name, server, snapshot.count);
+}
+
+/// 레이블 값 이스케이프 (역슬래시, 따옴표, 줄바꿈)
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+async fn get_metrics(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&metrics.server, &metrics.stats))
+}
+
+/// `GET /metrics` HTTP 엔드포인트 실행 (`shutdown` 취소 시 종료)
+pub async fn serve(
+    addr: SocketAddr,
+    server: impl Into<String>,
+    stats: Arc<ServerStats>,
+    shutdown: CancellationToken,
+) -> std::io::Result<()> {
+    let metrics = Arc::new(Metrics {
+        server: server.into(),
+        stats,
+    });
+    let app = Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(metrics);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await
+}
@@ -0,0 +1,122 @@
+//! `--stats-interval` - 주기마다 `ServerStats`를 로그 한 줄로 요약
+//!
+//! 메시지마다 남는 로그 대신 soak 테스트에서 볼 수 있도록 활성 스트림, 초당 전송 수, 큐 점유, 지난 보고
+//! 이후의 취소를 `Stats summary` 이벤트 하나로 남김. `RUST_LOG=warn,playground_stats=info`로 요약만 볼 수 있음.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::ServerStats;
+
+/// 보고 한 번의 값 - 전송 수와 취소는 지난 보고 이후의 증가분
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub active_streams: u64,
+    pub messages_per_sec: f64,
+    /// 응답 큐에 쌓인 메시지 (모든 스트림 합계)
+    pub queued_messages: u64,
+    /// 보냈지만 아직 ack 받지 못한 메시지
+    pub pending_messages: u64,
+    pub cancelled_streams: u64,
+    /// 지난 보고 이후 늘어난 취소 사유별 스트림 수 (0인 사유는 빠짐)
+    pub cancellations: Cancellations,
+}
+
+/// `network_disconnection=2,intentional_cancellation=1` 형식으로 출력되는 사유별 취소 수
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cancellations(pub BTreeMap<String, u64>);
+
+impl fmt::Display for Cancellations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (reason, count)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}={}", reason, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// 직전 보고 시점의 누적값 - 다음 보고의 증가분 기준
+pub struct Summary {
+    stats: Arc<ServerStats>,
+    messages_sent: u64,
+    cancellations: BTreeMap<String, u64>,
+    reported_at: Instant,
+}
+
+impl Summary {
+    pub fn new(stats: Arc<ServerStats>) -> Self {
+        Self {
+            messages_sent: stats.messages_sent.load(Ordering::Relaxed),
+            cancellations: stats.cancellations(),
+            reported_at: Instant::now(),
+            stats,
+        }
+    }
+
+    /// 지금까지의 증가분으로 보고를 만들고 기준을 현재로 옮김
+    pub fn report(&mut self) -> Report {
+        let now = Instant::now();
+        let messages_sent = self.stats.messages_sent.load(Ordering::Relaxed);
+        let cancellations = self.stats.cancellations();
+        let elapsed = now.duration_since(self.reported_at).as_secs_f64();
+
+        let delta: BTreeMap<String, u64> = cancellations
+            .iter()
+            .map(|(reason, &count)| (reason.clone(), count - self.cancellations.get(reason).copied().unwrap_or(0)))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        let sent = messages_sent - self.messages_sent;
+        let report = Report {
+            active_streams: self.stats.active_streams.load(Ordering::Relaxed),
+            messages_per_sec: if elapsed > 0.0 { sent as f64 / elapsed } else { 0.0 },
+            queued_messages: self.stats.queued_messages.load(Ordering::Relaxed),
+            pending_messages: self.stats.pending_messages.load(Ordering::Relaxed),
+            cancelled_streams: delta.values().sum(),
+            cancellations: Cancellations(delta),
+        };
+
+        self.messages_sent = messages_sent;
+        self.cancellations = cancellations;
+        self.reported_at = now;
+        report
+    }
+}
+
+/// `interval`마다 요약을 로그로 남김 - `shutdown`이 취소되면 마지막 구간을 한 번 더 남기고 끝남
+pub async fn log_every(stats: Arc<ServerStats>, interval: Duration, shutdown: CancellationToken) {
+    let mut summary = Summary::new(stats);
+    let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        let last = tokio::select! {
+            _ = ticker.tick() => false,
+            _ = shutdown.cancelled() => true,
+        };
+        log(&summary.report());
+        if last {
+            return;
+        }
+    }
+}
+
+fn log(report: &Report) {
+    info!(
+        active_streams = report.active_streams,
+        msgs_per_sec = format_args!("{:.1}", report.messages_per_sec),
+        queued = report.queued_messages,
+        pending = report.pending_messages,
+        cancelled = report.cancelled_streams,
+        cancellations = %report.cancellations,
+        "Stats summary"
+    );
+}
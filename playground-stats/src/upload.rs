@@ -0,0 +1,66 @@
+//! 클라이언트 스트리밍 업로드(`ClientStream`) 집계 - 두 스트리밍 서버가 같이 씀
+//!
+//! 서버마다 `DataMessage`/`UploadSummary` 타입은 다르지만 세는 방법은 같으므로 메시지는 `prost::Message`로
+//! 받고 결과는 `UploadTally`로 돌려줌.
+
+use std::sync::Arc;
+
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+use tonic::Status;
+use tracing::{field, info, info_span, warn, Instrument};
+
+use crate::ServerStats;
+
+/// 업로드 한 번에서 받은 양 - 각 서버의 `UploadSummary`와 같은 필드
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadTally {
+    pub messages: u64,
+    /// 받은 메시지의 인코딩 크기 합
+    pub bytes: u64,
+    /// 업로드 시작부터 클라이언트가 스트림을 닫을 때까지 (ms)
+    pub duration_ms: u64,
+}
+
+/// 클라이언트가 스트림을 닫을 때까지 받은 메시지 수와 바이트를 집계
+///
+/// 도중에 취소되거나 끊기면 그때까지 받은 양을 기록하고 받은 상태를 그대로 돌려줌.
+/// `upload` span(stream_id, cancel_reason 필드)에 기록됨.
+pub async fn receive<S, M>(stats: &Arc<ServerStats>, stream_id: u64, mut in_stream: S) -> Result<UploadTally, Status>
+where
+    S: Stream<Item = Result<M, Status>> + Unpin,
+    M: prost::Message,
+{
+    let upload_span = info_span!("upload", stream_id, cancel_reason = field::Empty);
+    async move {
+        let _stream_guard = stats.stream_opened();
+        let start = Instant::now();
+        let mut tally = UploadTally::default();
+        while let Some(message) = in_stream.next().await {
+            let status = match message {
+                Ok(message) => {
+                    tally.messages += 1;
+                    tally.bytes += message.encoded_len() as u64;
+                    continue;
+                }
+                Err(status) => status,
+            };
+            let kind = match status.code() {
+                tonic::Code::Cancelled => "intentional_cancellation",
+                tonic::Code::DeadlineExceeded => "deadline_exceeded",
+                _ => "network_disconnection",
+            };
+            tracing::Span::current().record("cancel_reason", status.message());
+            warn!(code = ?status.code(), messages = tally.messages, bytes = tally.bytes, "Upload interrupted");
+            stats.stream_cancelled(kind);
+            return Err(status);
+        }
+
+        tally.duration_ms = start.elapsed().as_millis() as u64;
+        ServerStats::incr(&stats.completed_streams);
+        info!(messages = tally.messages, bytes = tally.bytes, duration_ms = tally.duration_ms, "Upload completed");
+        Ok(tally)
+    }
+    .instrument(upload_span)
+    .await
+}
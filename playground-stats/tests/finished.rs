@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use playground_stats::ServerStats;
+
+#[tokio::test(start_paused = true)]
+async fn all_streams_finished_waits_for_the_last_open_stream() {
+    let stats = Arc::new(ServerStats::default());
+    let first = stats.stream_opened();
+    let second = stats.stream_opened();
+    let waiter = tokio::spawn({
+        let stats = stats.clone();
+        async move { stats.all_streams_finished().await }
+    });
+
+    drop(first);
+    ServerStats::incr(&stats.completed_streams);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(!waiter.is_finished());
+
+    drop(second);
+    stats.stream_cancelled("intentional_cancellation");
+    tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+}
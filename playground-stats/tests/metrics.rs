@@ -0,0 +1,82 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use playground_stats::{metrics, ServerStats};
+
+#[test]
+fn render_exposes_counters_and_cancellations_by_reason() {
+    let stats = Arc::new(ServerStats::default());
+    let _stream = stats.stream_opened();
+    stats.messages_sent.store(7, Ordering::Relaxed);
+    stats.queued_messages.store(3, Ordering::Relaxed);
+    stats.expired_messages.store(2, Ordering::Relaxed);
+    stats.duplicate_acks.store(4, Ordering::Relaxed);
+    stats.stream_cancelled("network_disconnection");
+    stats.stream_cancelled("network_disconnection");
+    stats.stream_cancelled("intentional_cancellation");
+
+    let text = metrics::render("cancel\"server", &stats);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(lines.contains(&"# TYPE playground_messages_sent_total counter"));
+    assert!(lines.contains(&r#"playground_active_streams{server="cancel\"server"} 1"#));
+    assert!(lines.contains(&r#"playground_messages_sent_total{server="cancel\"server"} 7"#));
+    assert!(lines.contains(&r#"playground_queued_messages{server="cancel\"server"} 3"#));
+    assert!(lines.contains(&r#"playground_messages_expired_total{server="cancel\"server"} 2"#));
+    assert!(lines.contains(&r#"playground_acks_duplicate_total{server="cancel\"server"} 4"#));
+    assert!(lines.contains(&r#"playground_acks_unknown_total{server="cancel\"server"} 0"#));
+    assert!(lines.contains(
+        &r#"playground_streams_cancelled_total{server="cancel\"server",reason="network_disconnection"} 2"#
+    ));
+    assert!(lines.contains(
+        &r#"playground_streams_cancelled_total{server="cancel\"server",reason="intentional_cancellation"} 1"#
+    ));
+    assert_eq!(stats.cancelled_streams.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn render_exposes_delivery_histograms_with_cumulative_buckets() {
+    let stats = ServerStats::default();
+    stats.delivery.ack_latency.observe(0.003);
+    stats.delivery.ack_latency.observe(2.0);
+    stats.delivery.retries.observe(0.0);
+    stats.delivery.retries.observe(2.0);
+    stats.delivery.retries.observe(12.0);
+    stats.delivery.send_blocked.observe(0.0);
+    stats.delivery.send_blocked.observe(0.75);
+
+    let text = metrics::render("ack", &stats);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(lines.contains(&"# TYPE playground_ack_latency_seconds histogram"));
+    assert!(lines.contains(&r#"playground_ack_latency_seconds_bucket{server="ack",le="0.001"} 0"#));
+    assert!(lines.contains(&r#"playground_ack_latency_seconds_bucket{server="ack",le="0.005"} 1"#));
+    assert!(lines.contains(&r#"playground_ack_latency_seconds_bucket{server="ack",le="2.5"} 2"#));
+    assert!(lines.contains(&r#"playground_ack_latency_seconds_sum{server="ack"} 2.003"#));
+    assert!(lines.contains(&r#"playground_ack_latency_seconds_count{server="ack"} 2"#));
+    // 가장 큰 경계(10)를 넘은 관측은 +Inf 버킷에만 들어감
+    assert!(lines.contains(&r#"playground_message_retries_bucket{server="ack",le="0"} 1"#));
+    assert!(lines.contains(&r#"playground_message_retries_bucket{server="ack",le="10"} 2"#));
+    assert!(lines.contains(&r#"playground_message_retries_bucket{server="ack",le="+Inf"} 3"#));
+
+    assert!(lines.contains(&r#"playground_send_blocked_seconds_bucket{server="ack",le="0.0001"} 1"#));
+    assert!(lines.contains(&r#"playground_send_blocked_seconds_bucket{server="ack",le="1"} 2"#));
+    assert!(lines.contains(&r#"playground_send_blocked_seconds_sum{server="ack"} 0.75"#));
+}
+
+#[test]
+fn render_exposes_fair_share_index_and_waits() {
+    let stats = ServerStats::default();
+    let text = metrics::render("ack", &stats);
+    assert!(text.lines().any(|line| line == r#"playground_fairness_index{server="ack"} 0"#));
+
+    stats.fair_share.set_index(0.75);
+    stats.fair_share.wait.observe(2.0);
+    let text = metrics::render("ack", &stats);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(lines.contains(&"# TYPE playground_fairness_index gauge"));
+    assert!(lines.contains(&r#"playground_fairness_index{server="ack"} 0.75"#));
+    assert!(lines.contains(&r#"playground_fair_share_wait_seconds_bucket{server="ack",le="1"} 0"#));
+    assert!(lines.contains(&r#"playground_fair_share_wait_seconds_bucket{server="ack",le="2"} 1"#));
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use playground_stats::summary::{Cancellations, Summary};
+use playground_stats::ServerStats;
+
+#[tokio::test(start_paused = true)]
+async fn report_covers_only_the_interval_since_the_last_one() {
+    let stats = Arc::new(ServerStats::default());
+    stats.messages_sent.store(100, Ordering::Relaxed);
+    stats.stream_cancelled("network_disconnection");
+    let mut summary = Summary::new(stats.clone());
+
+    let _stream = stats.stream_opened();
+    stats.messages_sent.fetch_add(50, Ordering::Relaxed);
+    stats.queued_messages.store(4, Ordering::Relaxed);
+    stats.stream_cancelled("intentional_cancellation");
+    stats.stream_cancelled("intentional_cancellation");
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    let report = summary.report();
+    assert_eq!(report.active_streams, 1);
+    assert_eq!(report.messages_per_sec, 5.0);
+    assert_eq!(report.queued_messages, 4);
+    assert_eq!(report.cancelled_streams, 2);
+    assert_eq!(report.cancellations.to_string(), "intentional_cancellation=2");
+
+    // 아무 일도 없었던 다음 구간은 0
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let report = summary.report();
+    assert_eq!(report.messages_per_sec, 0.0);
+    assert_eq!(report.cancelled_streams, 0);
+    assert_eq!(report.cancellations, Cancellations(BTreeMap::new()));
+    assert_eq!(report.cancellations.to_string(), "");
+}
@@ -0,0 +1,32 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use playground_stats::upload::{receive, UploadTally};
+use playground_stats::ServerStats;
+use prost::Message;
+use tonic::Status;
+
+#[tokio::test]
+async fn receive_counts_messages_and_encoded_bytes() {
+    let stats = Arc::new(ServerStats::default());
+    let messages = ["a".to_string(), "bcd".to_string()];
+    let bytes = messages.iter().map(|m| m.encoded_len() as u64).sum();
+
+    let tally = receive(&stats, 1, tokio_stream::iter(messages.map(Ok))).await.unwrap();
+
+    assert_eq!(tally, UploadTally { messages: 2, bytes, duration_ms: tally.duration_ms });
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 1);
+    assert_eq!(stats.active_streams.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test]
+async fn receive_returns_the_interruption_and_records_its_kind() {
+    let stats = Arc::new(ServerStats::default());
+    let stream = tokio_stream::iter([Ok("a".to_string()), Err(Status::cancelled("client went away"))]);
+
+    let status = receive(&stats, 1, stream).await.unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::Cancelled);
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 0);
+    assert_eq!(stats.cancellations().get("intentional_cancellation"), Some(&1));
+}
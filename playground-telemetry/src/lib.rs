@@ -0,0 +1,228 @@
+//! 플레이그라운드 공용 OpenTelemetry 설정
+//!
+//! OTLP endpoint가 주어지거나 `OTEL_EXPORTER_OTLP_ENDPOINT` 환경변수가 설정된 경우에만 OTLP(gRPC) exporter를 켠다.
+//! 설정이 없으면 span은 모두 no-op이므로 기존 데모는 collector 없이 그대로 동작한다.
+//! `init_with_logs`는 여기에 더해 로그를 stdout으로 출력한다 (`RUST_LOG`로 레벨 조정, 기본 info).
+//! `console` feature의 `init_with_console`은 tokio-console이 붙을 수 있는 계층도 함께 설치한다.
+
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use std::fmt;
+use std::str::FromStr;
+use tonic::metadata::{KeyRef, MetadataKey, MetadataMap, MetadataValue};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// stdout 로그 출력 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// 사람이 읽는 한 줄 형식 (span 필드 포함)
+    #[default]
+    Pretty,
+    /// 한 줄에 JSON 객체 하나 - 현재 span과 상위 span 필드 포함
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("unknown log format '{}' (expected json or pretty)", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// 프로세스 종료 시 남은 span을 flush 하기 위한 가드
+pub struct TelemetryGuard {
+    provider: Option<TracerProvider>,
+}
+
+impl TelemetryGuard {
+    pub fn is_enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("[TELEMETRY] Failed to flush spans: {}", e);
+            }
+        }
+    }
+}
+
+/// tracing subscriber + OTLP exporter 초기화 (tokio 런타임 안에서 호출해야 함)
+pub fn init(service_name: &'static str) -> TelemetryGuard {
+    install(service_name, None, env_endpoint(), None)
+}
+
+/// `init`과 같고, 추가로 tracing 이벤트를 `format` 형식으로 stdout에 출력
+pub fn init_with_logs(service_name: &'static str, format: LogFormat) -> TelemetryGuard {
+    install(service_name, Some(format), env_endpoint(), None)
+}
+
+/// `init_with_logs`와 같지만 OTLP endpoint를 직접 지정 (`None`이면 export 없이 로그만 출력)
+pub fn init_with_endpoint(
+    service_name: &'static str,
+    format: LogFormat,
+    endpoint: Option<&str>,
+) -> TelemetryGuard {
+    install(service_name, Some(format), endpoint.map(str::to_string), None)
+}
+
+/// `init_with_endpoint`에 더해 tokio-console 서버(`TOKIO_CONSOLE_BIND`, 기본 127.0.0.1:6669)를 띄움
+#[cfg(feature = "console")]
+pub fn init_with_console(
+    service_name: &'static str,
+    format: LogFormat,
+    endpoint: Option<&str>,
+) -> TelemetryGuard {
+    let console = console_subscriber::ConsoleLayer::builder().with_default_env().spawn();
+    install(service_name, Some(format), endpoint.map(str::to_string), Some(console.boxed()))
+}
+
+fn env_endpoint() -> Option<String> {
+    std::env::var(OTLP_ENDPOINT_ENV).ok().filter(|endpoint| !endpoint.is_empty())
+}
+
+/// registry에 바로 얹는 계층 - 로그 계층, 그리고 `console`처럼 필터 없이 모든 이벤트를 봐야 하는 추가 계층
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+fn log_layer(format: LogFormat) -> BoxedLayer {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    match format {
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().with_filter(filter).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_filter(filter)
+            .boxed(),
+    }
+}
+
+fn install(
+    service_name: &'static str,
+    log_format: Option<LogFormat>,
+    endpoint: Option<String>,
+    extra: Option<BoxedLayer>,
+) -> TelemetryGuard {
+    // 프록시를 포함한 모든 홉이 W3C traceparent 헤더를 사용
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let logs = log_format.map(log_layer);
+    // 로그 계층은 자기 필터만 적용하므로 함께 묶어도 서로의 이벤트를 거르지 않음
+    let layers: Option<Vec<BoxedLayer>> = match (logs, extra) {
+        (None, None) => None,
+        (logs, extra) => Some(logs.into_iter().chain(extra).collect()),
+    };
+
+    let Some(endpoint) = endpoint else {
+        if let Some(layers) = layers {
+            if let Err(e) = tracing_subscriber::registry().with(layers).try_init() {
+                eprintln!("[TELEMETRY] Failed to install tracing subscriber: {}", e);
+            }
+        }
+        return TelemetryGuard { provider: None };
+    };
+
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("[TELEMETRY] Failed to build OTLP exporter: {}", e);
+            return TelemetryGuard { provider: None };
+        }
+    };
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", service_name)]))
+        .build();
+    let tracer = provider.tracer(service_name);
+    global::set_tracer_provider(provider.clone());
+
+    if let Err(e) = tracing_subscriber::registry()
+        .with(layers)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+    {
+        eprintln!("[TELEMETRY] Failed to install tracing subscriber: {}", e);
+    }
+
+    TelemetryGuard {
+        provider: Some(provider),
+    }
+}
+
+struct MetadataExtractor<'a>(&'a MetadataMap);
+
+impl Extractor for MetadataExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0
+            .keys()
+            .map(|key| match key {
+                KeyRef::Ascii(key) => key.as_str(),
+                KeyRef::Binary(key) => key.as_str(),
+            })
+            .collect()
+    }
+}
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::from_bytes(key.as_bytes()),
+            MetadataValue::try_from(value.as_str()),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+/// 수신한 gRPC 메타데이터의 traceparent를 span의 부모로 연결
+pub fn set_remote_parent(span: &tracing::Span, metadata: &MetadataMap) {
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&MetadataExtractor(metadata))
+    });
+    span.set_parent(parent);
+}
+
+/// span의 컨텍스트를 송신할 gRPC 메타데이터에 주입
+pub fn inject_context(span: &tracing::Span, metadata: &mut MetadataMap) {
+    let context = span.context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(metadata))
+    });
+}
@@ -0,0 +1,10 @@
+use playground_telemetry::LogFormat;
+
+#[test]
+fn log_format_round_trips_through_cli_values() {
+    for format in [LogFormat::Pretty, LogFormat::Json] {
+        assert_eq!(format.to_string().parse::<LogFormat>(), Ok(format));
+    }
+    assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+    assert!("xml".parse::<LogFormat>().is_err());
+}
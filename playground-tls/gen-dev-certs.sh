@@ -0,0 +1,26 @@
+#!/bin/bash
+# 로컬 실험용 CA / 서버 / 클라이언트 인증서 생성 (운영용 아님)
+# 사용법: ./gen-dev-certs.sh [출력 디렉터리, 기본 ./certs]
+set -euo pipefail
+
+OUT=${1:-certs}
+mkdir -p "$OUT"
+cd "$OUT"
+
+openssl req -x509 -newkey rsa:2048 -nodes -days 365 \
+    -keyout ca.key -out ca.pem -subj "/CN=ray-playground dev CA"
+
+issue() {
+    local name=$1 usage=$2 san=$3
+    openssl req -newkey rsa:2048 -nodes -keyout "$name.key" -out "$name.csr" -subj "/CN=$name"
+    printf 'subjectAltName=%s\nextendedKeyUsage=%s\n' "$san" "$usage" > "$name.ext"
+    openssl x509 -req -in "$name.csr" -CA ca.pem -CAkey ca.key -CAcreateserial \
+        -days 365 -extfile "$name.ext" -out "$name.pem"
+    rm "$name.csr" "$name.ext"
+}
+
+# 서버는 프록시를 거쳐도 같은 호스트 이름/IP로 접속하므로 포트와 무관
+issue server serverAuth "DNS:localhost,IP:::1,IP:127.0.0.1"
+issue client clientAuth "DNS:playground-client"
+
+echo "Wrote $(pwd)/{ca,server,client}.{pem,key}"
@@ -0,0 +1,55 @@
+//! 스트리밍 서버 공용 TLS 옵션
+//!
+//! `--tls-cert`/`--tls-key`를 주면 TLS, `--tls-client-ca`까지 주면 mTLS로 동작한다.
+//! 옵션이 없으면 기존처럼 평문 HTTP/2.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+/// 서버 CLI에 `#[command(flatten)]`으로 붙이는 TLS 옵션
+#[derive(Args, Debug, Clone, Default)]
+pub struct TlsArgs {
+    /// PEM certificate chain served to clients (enables TLS)
+    #[arg(long, env = "GRPC_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, env = "GRPC_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM CA bundle that client certificates must chain to (enables mTLS)
+    #[arg(long, env = "GRPC_TLS_CLIENT_CA", requires = "tls_cert")]
+    pub tls_client_ca: Option<PathBuf>,
+}
+
+impl TlsArgs {
+    /// 로그에 남길 모드 이름
+    pub fn mode(&self) -> &'static str {
+        match (&self.tls_cert, &self.tls_client_ca) {
+            (None, _) => "plaintext",
+            (Some(_), None) => "tls",
+            (Some(_), Some(_)) => "mtls",
+        }
+    }
+
+    /// 인증서 파일을 읽어 tonic 서버 설정을 만듦 (TLS 옵션이 없으면 None)
+    pub fn server_config(&self) -> io::Result<Option<ServerTlsConfig>> {
+        let (Some(cert), Some(key)) = (&self.tls_cert, &self.tls_key) else {
+            return Ok(None);
+        };
+        let mut config = ServerTlsConfig::new().identity(Identity::from_pem(read_pem(cert)?, read_pem(key)?));
+        if let Some(ca) = &self.tls_client_ca {
+            config = config.client_ca_root(Certificate::from_pem(read_pem(ca)?));
+        }
+        Ok(Some(config))
+    }
+}
+
+/// 파일 이름을 오류 메시지에 포함해서 읽음 - 잘못된 경로를 바로 알 수 있게
+fn read_pem(path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("failed to read {}: {}", path.display(), e)))
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if r0_execute::validate_elf(data).is_ok() {
+        assert!(data.starts_with(r0_execute::V2_ELF_MAGIC));
+    }
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Input files come from the market and are fully untrusted.
+fuzz_target!(|data: &[u8]| {
+    let _ = r0_execute::decode_guest_input(data);
+});
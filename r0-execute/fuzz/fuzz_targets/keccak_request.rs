@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// keccak-prover decodes request bytes straight off the network.
+fuzz_target!(|data: &[u8]| {
+    let _ = r0_execute::keccak::decode_request(data);
+});
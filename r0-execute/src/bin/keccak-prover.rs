@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use playground_shutdown::{Phase, Shutdown, DEFAULT_DRAIN_TIMEOUT};
+use r0_execute::keccak::proto::keccak_proving_service_server::{KeccakProvingService, KeccakProvingServiceServer};
+use r0_execute::keccak::proto::{KeccakProof, KeccakRequest};
+use r0_execute::keccak::{connect, decode_request, DEFAULT_PROVER_ADDRESS, MAX_MESSAGE_BYTES};
+use risc0_zkvm::{get_prover_server, ProverOpts};
+use std::{fs, path::PathBuf, time::Instant};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+use tracing::{info_span, Instrument};
+
+#[derive(Parser, Debug)]
+#[command(name = "keccak-prover")]
+#[command(about = "Prove keccak coprocessor requests over a gRPC stream")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the KeccakProvingService
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = DEFAULT_PROVER_ADDRESS)]
+        listen: String,
+    },
+    /// Send saved keccak_*.bin requests to a running prover and save the receipts
+    Submit {
+        /// Address of the prover
+        #[arg(long, default_value = DEFAULT_PROVER_ADDRESS)]
+        prover: String,
+
+        /// Directory for keccak_*.receipt files (default: next to each request)
+        #[arg(short = 'd', long)]
+        output_dir: Option<PathBuf>,
+
+        /// keccak_*.bin files written by r0-execute
+        #[arg(required = true)]
+        requests: Vec<PathBuf>,
+    },
+}
+
+struct KeccakProver;
+
+#[tonic::async_trait]
+impl KeccakProvingService for KeccakProver {
+    type ProveStreamStream = ReceiverStream<Result<KeccakProof, Status>>;
+
+    async fn prove_stream(
+        &self,
+        request: Request<Streaming<KeccakRequest>>,
+    ) -> Result<Response<Self::ProveStreamStream>, Status> {
+        let mut requests = request.into_inner();
+        let (tx, rx) = mpsc::channel(4);
+
+        // Requests are proved one at a time per stream, in arrival order
+        tokio::spawn(async move {
+            while let Some(message) = requests.next().await {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(status) => {
+                        println!("Request stream failed: {}", status);
+                        break;
+                    }
+                };
+                let id = message.id;
+                let proof = prove(message).instrument(info_span!("prove_keccak", id)).await;
+                if proof.error.is_empty() {
+                    println!("Proved keccak request {} in {}ms ({} bytes)", id, proof.prove_time_ms, proof.receipt.len());
+                } else {
+                    eprintln!("Failed to prove keccak request {}: {}", id, proof.error);
+                }
+                if tx.send(Ok(proof)).await.is_err() {
+                    println!("Client went away, dropping remaining requests");
+                    break;
+                }
+            }
+        }.instrument(info_span!("prove_stream")));
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+async fn prove(message: KeccakRequest) -> KeccakProof {
+    let id = message.id;
+    let start_time = Instant::now();
+
+    // The prover is not Send and proving is CPU bound, so it runs on a blocking thread
+    let result = tokio::task::spawn_blocking(move || -> Result<(Vec<u8>, Vec<u8>)> {
+        let request = decode_request(&message.request)?;
+        let prover = get_prover_server(&ProverOpts::default())?;
+        let receipt = prover.prove_keccak(&request).context("Keccak proving failed")?;
+        let receipt = bincode::serialize(&receipt).context("Failed to serialize keccak receipt")?;
+        Ok((request.claim_digest.as_bytes().to_vec(), receipt))
+    })
+    .await
+    .context("Failed to join prover task")
+    .and_then(|result| result);
+
+    let prove_time_ms = start_time.elapsed().as_millis() as u64;
+    match result {
+        Ok((claim_digest, receipt)) => KeccakProof {
+            id,
+            claim_digest,
+            receipt,
+            error: String::new(),
+            prove_time_ms,
+        },
+        Err(e) => KeccakProof {
+            id,
+            error: format!("{:#}", e),
+            prove_time_ms,
+            ..Default::default()
+        },
+    }
+}
+
+async fn serve(listen: &str, shutdown: &Shutdown) -> Result<()> {
+    let addr = listen.parse().with_context(|| format!("Invalid listen address: {}", listen))?;
+
+    println!("Keccak prover listening on {}", addr);
+    let service = KeccakProvingServiceServer::new(KeccakProver)
+        .max_decoding_message_size(MAX_MESSAGE_BYTES)
+        .max_encoding_message_size(MAX_MESSAGE_BYTES);
+    let server = Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(addr, shutdown.token().cancelled_owned());
+
+    // Stop accepting streams on SIGINT/SIGTERM and give running proofs a grace period
+    if let Some(result) = shutdown.drain(server, DEFAULT_DRAIN_TIMEOUT).await {
+        result.context("Keccak prover failed")?;
+    }
+    Ok(())
+}
+
+async fn submit(prover: &str, output_dir: Option<PathBuf>, mut paths: Vec<PathBuf>) -> Result<()> {
+    paths.sort();
+    let mut requests = Vec::with_capacity(paths.len());
+    for (index, path) in paths.iter().enumerate() {
+        let request = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        // Reject files that are not keccak requests before sending anything
+        decode_request(&request).with_context(|| format!("{} is not a keccak request", path.display()))?;
+        requests.push(KeccakRequest {
+            id: index as u64 + 1,
+            request,
+        });
+    }
+    if let Some(dir) = &output_dir {
+        fs::create_dir_all(dir).context("Failed to create output directory")?;
+    }
+
+    let mut client = connect(prover).await?;
+    println!("Submitting {} keccak requests to {}", requests.len(), prover);
+    let mut proofs = client
+        .prove_stream(tokio_stream::iter(requests))
+        .await
+        .context("Failed to open prove stream")?
+        .into_inner();
+
+    let mut failed = 0;
+    while let Some(proof) = proofs.next().await {
+        let proof = proof.context("Prove stream failed")?;
+        let Some(path) = (proof.id as usize).checked_sub(1).and_then(|index| paths.get(index)) else {
+            eprintln!("Prover returned unknown request id {}", proof.id);
+            continue;
+        };
+        if !proof.error.is_empty() {
+            failed += 1;
+            eprintln!("Failed to prove {}: {}", path.display(), proof.error);
+            continue;
+        }
+
+        let receipt_path = match &output_dir {
+            Some(dir) => dir.join(path.with_extension("receipt").file_name().unwrap_or_default()),
+            None => path.with_extension("receipt"),
+        };
+        fs::write(&receipt_path, &proof.receipt)
+            .with_context(|| format!("Failed to write {}", receipt_path.display()))?;
+        println!("Saved receipt for {} to: {} ({} bytes, {}ms)",
+            path.display(), receipt_path.display(), proof.receipt.len(), proof.prove_time_ms);
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} keccak requests failed", failed, paths.len());
+    }
+    println!("All {} keccak requests proved", paths.len());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let telemetry = playground_telemetry::init("keccak-prover");
+    let shutdown = Shutdown::install();
+
+    let result = match args.command {
+        Command::Serve { listen } => serve(&listen, &shutdown).await,
+        Command::Submit { prover, output_dir, requests } => {
+            let job = submit(&prover, output_dir, requests);
+            shutdown
+                .drain(job, DEFAULT_DRAIN_TIMEOUT)
+                .await
+                .unwrap_or_else(|| Err(anyhow::anyhow!("Submission interrupted by shutdown signal")))
+        }
+    };
+
+    shutdown.advance(Phase::Flush);
+    drop(telemetry);
+    shutdown.advance(Phase::Exit);
+    result
+}
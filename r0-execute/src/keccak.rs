@@ -0,0 +1,94 @@
+//! Keccak proof requests on the wire: shared by the coprocessor sink in `r0-execute`
+//! and the `keccak-prover` service.
+
+use anyhow::{Context, Result};
+use bincode::Options;
+use risc0_zkvm::{Digest, ProveKeccakRequest};
+use serde::{Deserialize, Serialize};
+use tonic::transport::Channel;
+
+use self::proto::keccak_proving_service_client::KeccakProvingServiceClient;
+
+pub mod proto {
+    tonic::include_proto!("keccak");
+}
+
+/// Default address of the `keccak-prover` service.
+pub const DEFAULT_PROVER_ADDRESS: &str = "[::1]:50061";
+
+/// Upper bound for one encoded request (and for gRPC messages carrying requests or receipts).
+pub const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+pub type KeccakState = [u64; 25];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableKeccakRequest {
+    /// The digest of the claim that this keccak input is expected to produce.
+    pub claim_digest: Digest,
+
+    /// The requested size of the keccak proof, in powers of 2.
+    pub po2: usize,
+
+    /// The control root which identifies a particular keccak circuit revision.
+    pub control_root: Digest,
+
+    /// Input transcript to provide to the keccak circuit.
+    pub input: Vec<KeccakState>,
+}
+
+impl From<&ProveKeccakRequest> for SerializableKeccakRequest {
+    fn from(req: &ProveKeccakRequest) -> Self {
+        SerializableKeccakRequest {
+            claim_digest: req.claim_digest,
+            po2: req.po2,
+            control_root: req.control_root,
+            input: req.input.clone(),
+        }
+    }
+}
+
+impl From<SerializableKeccakRequest> for ProveKeccakRequest {
+    fn from(req: SerializableKeccakRequest) -> Self {
+        ProveKeccakRequest {
+            claim_digest: req.claim_digest,
+            po2: req.po2,
+            control_root: req.control_root,
+            input: req.input,
+        }
+    }
+}
+
+/// Encode a request the same way the `keccak_*.bin` files are written.
+pub fn encode_request(request: &ProveKeccakRequest) -> Result<Vec<u8>> {
+    bincode::serialize(&SerializableKeccakRequest::from(request)).context("Failed to encode keccak request")
+}
+
+/// Decode a `keccak_*.bin` file or a `KeccakRequest.request` payload.
+///
+/// The bytes may come from the network, so length prefixes are bounded by
+/// `MAX_MESSAGE_BYTES` instead of being trusted for allocation.
+pub fn decode_request(bytes: &[u8]) -> Result<ProveKeccakRequest> {
+    // Same layout as `bincode::serialize`, plus a size limit
+    let request: SerializableKeccakRequest = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(MAX_MESSAGE_BYTES as u64)
+        .deserialize(bytes)
+        .context("Failed to decode keccak request")?;
+    Ok(request.into())
+}
+
+/// Connect to a `keccak-prover`; a bare `host:port` is treated as plain http.
+pub async fn connect(address: &str) -> Result<KeccakProvingServiceClient<Channel>> {
+    let url = if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    };
+    let client = KeccakProvingServiceClient::connect(url.clone())
+        .await
+        .with_context(|| format!("Failed to connect to keccak prover at {}", url))?;
+    Ok(client
+        .max_decoding_message_size(MAX_MESSAGE_BYTES)
+        .max_encoding_message_size(MAX_MESSAGE_BYTES))
+}
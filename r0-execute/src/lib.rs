@@ -0,0 +1,27 @@
+pub mod keccak;
+pub mod progress;
+
+use anyhow::{Context, Result};
+use boundless_market::input::GuestEnv;
+
+pub const V2_ELF_MAGIC: &[u8] = b"R0BF";
+
+/// Check that the bytes start with the R0 program binary magic.
+///
+/// Only the header is inspected here; the zkVM does the full parse when the
+/// executor is created.
+pub fn validate_elf(elf_data: &[u8]) -> Result<()> {
+    if elf_data.len() < V2_ELF_MAGIC.len() {
+        anyhow::bail!("ELF data too short: {} bytes", elf_data.len());
+    }
+    if !elf_data.starts_with(V2_ELF_MAGIC) {
+        anyhow::bail!("missing R0BF magic header");
+    }
+    Ok(())
+}
+
+/// Decode an encoded `GuestEnv` and return the guest stdin bytes.
+pub fn decode_guest_input(input_data: &[u8]) -> Result<Vec<u8>> {
+    let env = GuestEnv::decode(input_data).context("Failed to decode guest input")?;
+    Ok(env.stdin)
+}
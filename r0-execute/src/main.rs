@@ -1,12 +1,16 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path};
+use std::{fs, path::{Path, PathBuf}, sync::Arc};
 use tokio::sync::mpsc;
-use risc0_zkvm::{CoprocessorCallback, Digest, ExecutorEnv, ExecutorImpl, NullSegmentRef, ProveKeccakRequest, Segment};
-use boundless_market::input::GuestEnv;
-
-const V2_ELF_MAGIC: &[u8] = b"R0BF";
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use risc0_zkvm::{CoprocessorCallback, ExecutorEnv, ExecutorImpl, NullSegmentRef, ProveKeccakRequest, Segment};
+use r0_execute::keccak::proto::KeccakRequest;
+use r0_execute::keccak::{connect, encode_request, SerializableKeccakRequest};
+use r0_execute::progress::ProgressReporter;
+use r0_execute::{decode_guest_input, validate_elf};
+use playground_shutdown::{Phase, Shutdown, DEFAULT_DRAIN_TIMEOUT};
+use tracing::{info_span, Instrument};
 
 #[derive(Parser, Debug)]
 #[command(name = "r0-execute")]
@@ -35,34 +39,14 @@ struct Args {
     /// Segment limit in powers of 2 (default: 21)
     #[arg(long, default_value = "21")]
     segment_limit_po2: u32,
-}
-
-pub type KeccakState = [u64; 25];
-
-#[derive(Serialize)]
-struct SerializableKeccakRequest {
-    /// The digest of the claim that this keccak input is expected to produce.
-    pub claim_digest: Digest,
 
-    /// The requested size of the keccak proof, in powers of 2.
-    pub po2: usize,
-
-    /// The control root which identifies a particular keccak circuit revision.
-    pub control_root: Digest,
-
-    /// Input transcript to provide to the keccak circuit.
-    pub input: Vec<KeccakState>,
-}
+    /// Write job progress as JSON to this file while executing (read by the dashboard)
+    #[arg(long)]
+    progress_file: Option<PathBuf>,
 
-impl From<&ProveKeccakRequest> for SerializableKeccakRequest {
-    fn from(req: &ProveKeccakRequest) -> Self {
-        SerializableKeccakRequest {
-            claim_digest: req.claim_digest,
-            po2: req.po2,
-            control_root: req.control_root,
-            input: req.input.clone(),
-        }
-    }
+    /// Also stream keccak requests to a keccak-prover at this address and save the receipts
+    #[arg(long)]
+    keccak_prover: Option<String>,
 }
 
 struct Coprocessor {
@@ -90,6 +74,8 @@ struct LocalExecutionResult {
     total_cycles: u64,
     segment_count: usize,
     keccak_count: usize,
+    #[serde(default)]
+    keccak_proved: usize,
     execution_time_ms: u128,
     error: Option<String>,
 }
@@ -99,6 +85,7 @@ struct ExecutionResult {
     total_cycles: u64,
     segment_count: usize,
     keccak_count: usize,
+    keccak_proved: usize,
 }
 
 struct LocalExecutor;
@@ -115,9 +102,8 @@ impl LocalExecutor {
             .with_context(|| format!("Failed to read ELF file from: {}", elf_path))?;
             
         // Validate that it's actually an ELF file
-        if elf_data.len() < 4 || &elf_data[0..4] != V2_ELF_MAGIC {
-            return Err(anyhow::anyhow!("File {} is not a valid R0 ELF file", elf_path));
-        }
+        validate_elf(&elf_data)
+            .with_context(|| format!("File {} is not a valid R0 ELF file", elf_path))?;
         
         println!("Successfully read ELF file: {} bytes", elf_data.len());
         Ok(elf_data)
@@ -133,7 +119,8 @@ impl LocalExecutor {
         Ok(input_data)
     }
 
-    async fn execute_locally(&self, elf_path: &str, input_path: &str, output_dir: &str, dry_run: bool, json_output: bool, segment_limit_po2: u32) -> Result<LocalExecutionResult> {
+    #[tracing::instrument(name = "submit", skip(self, output_dir, progress))]
+    async fn execute_locally(&self, elf_path: &str, input_path: &str, output_dir: &str, dry_run: bool, json_output: bool, segment_limit_po2: u32, keccak_prover: Option<String>, progress: Arc<ProgressReporter>) -> Result<LocalExecutionResult> {
         println!("Executing locally...");
         
         let start_time = std::time::Instant::now();
@@ -146,7 +133,10 @@ impl LocalExecutor {
         println!("Input data size: {} bytes", input_data.len());
         
         // Execute with zkVM
-        let result = self.execute_with_zkvm(&elf_data, &input_data, output_dir, dry_run, json_output, segment_limit_po2).await?;
+        progress.publish("executing");
+        let result = self.execute_with_zkvm(&elf_data, &input_data, output_dir, dry_run, json_output, segment_limit_po2, keccak_prover, progress.clone()).await;
+        progress.publish(if result.is_ok() { "completed" } else { "failed" });
+        let result = result?;
         
         let execution_time = start_time.elapsed().as_millis();
         
@@ -155,23 +145,25 @@ impl LocalExecutor {
             total_cycles: result.total_cycles,
             segment_count: result.segment_count,
             keccak_count: result.keccak_count,
+            keccak_proved: result.keccak_proved,
             execution_time_ms: execution_time,
             error: None,
         })
     }
 
 
-    async fn execute_with_zkvm(&self, elf_data: &[u8], input_data: &[u8], output_dir: &str, dry_run: bool, json_output: bool, segment_limit_po2: u32) -> Result<ExecutionResult> {
+    async fn execute_with_zkvm(&self, elf_data: &[u8], input_data: &[u8], output_dir: &str, dry_run: bool, json_output: bool, segment_limit_po2: u32, keccak_prover: Option<String>, progress: Arc<ProgressReporter>) -> Result<ExecutionResult> {
         let (segment_tx, mut segment_rx) = mpsc::channel::<Segment>(100);
         let (keccak_tx, mut keccak_rx) = mpsc::channel::<ProveKeccakRequest>(100);
         
         // Clone elf data
         let elf_data = elf_data.to_vec();
         // Decode input data
-        let decoded_input_data = GuestEnv::decode(input_data)?.stdin;
+        let decoded_input_data = decode_guest_input(input_data)?;
 
         // Spawn segment writer task
         let segment_output_dir = output_dir.to_string();
+        let segment_progress = progress.clone();
         let segment_writer = tokio::spawn(async move {
             if !dry_run {
                 // Create output directory if it doesn't exist
@@ -184,6 +176,7 @@ impl LocalExecutor {
             let mut segment_count = 0;
             while let Some(segment) = segment_rx.recv().await {
                 segment_count += 1;
+                let _span = info_span!("persist_segment", index = segment.index).entered();
                 println!("Processing segment {}: index={}", segment_count, segment.index);
                 
                 if json_output {
@@ -223,15 +216,43 @@ impl LocalExecutor {
                         eprintln!("Failed to serialize segment {}", segment.index);
                     }
                 }
+                segment_progress.segment_done();
             }
             segment_count
-        });
+        }.instrument(info_span!("persist")));
+
+        // Optionally stream keccak requests to a remote prover and save the receipts it returns
+        let (prover_tx, prover_task) = match keccak_prover {
+            Some(address) => {
+                let (prover_tx, prover_rx) = mpsc::channel::<KeccakRequest>(100);
+                let receipt_output_dir = output_dir.to_string();
+                let task = tokio::spawn(
+                    stream_to_prover(address, prover_rx, receipt_output_dir, dry_run)
+                        .instrument(info_span!("remote_prove")),
+                );
+                (Some(prover_tx), Some(task))
+            }
+            None => (None, None),
+        };
 
         let keccak_output_dir = output_dir.to_string();
+        let keccak_progress = progress;
         let keccak_writer = tokio::spawn(async move {
             let mut keccak_count = 0;
             while let Some(request) = keccak_rx.recv().await {
                 keccak_count += 1;
+                if let Some(prover_tx) = &prover_tx {
+                    match encode_request(&request) {
+                        Ok(bytes) => {
+                            let remote_request = KeccakRequest { id: keccak_count as u64, request: bytes };
+                            if prover_tx.send(remote_request).await.is_err() {
+                                eprintln!("Keccak prover stream closed, request {} not sent", keccak_count);
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to encode Keccak proof request {}: {}", keccak_count, e),
+                    }
+                }
+                let _span = info_span!("persist_keccak", index = keccak_count).entered();
                 println!("Received Keccak proof request: {}", keccak_count);
 
                 let serializable_request = SerializableKeccakRequest::from(&request);
@@ -273,15 +294,20 @@ impl LocalExecutor {
                         eprintln!("Failed to serialize Keccak proof request {}", keccak_count);
                     }
                 }
+                keccak_progress.keccak_done();
             }
             keccak_count
-        });
+        }.instrument(info_span!("persist")));
         
         // Execute in blocking task (similar to reference code)
         let exec_limit = 100_000 * 1024 * 1024;
         let coproc = Coprocessor::new(keccak_tx);
 
+        // spawn_blocking 스레드에서도 submit span 아래에 execute span이 붙도록 전달
+        let execute_span = info_span!("execute", segment_limit_po2);
         let exec_task = tokio::task::spawn_blocking(move || -> Result<(u64, u64)> {
+            let _span = execute_span.entered();
+
             // Build execution environment
             let env = ExecutorEnv::builder()
                 .write_slice(&decoded_input_data)
@@ -319,6 +345,13 @@ impl LocalExecutor {
         let keccak_count = keccak_writer.await
             .context("Failed to join keccak writer")?;
 
+        let keccak_proved = match prover_task {
+            Some(task) => task.await
+                .context("Failed to join keccak prover stream")?
+                .context("Remote keccak proving failed")?,
+            None => 0,
+        };
+
         println!("Execution completed: {} cycles (user: {}), {} segments", 
                 total_cycles, user_cycles, segment_count);
 
@@ -327,10 +360,12 @@ impl LocalExecutor {
             total_cycles: total_cycles,
             segment_count,
             keccak_count,
+            keccak_proved,
         })
     }
 
 
+    #[tracing::instrument(name = "persist_result", skip(self, result))]
     async fn save_results(&self, result: &LocalExecutionResult, output_dir: &str, dry_run: bool) -> Result<()> {
         if dry_run {
             // Dry run mode: skip saving
@@ -357,9 +392,50 @@ impl LocalExecutor {
 }
 
 
+/// Stream keccak requests to a `keccak-prover` and save each returned receipt as
+/// `keccak_XXXX.receipt`. Returns the number of proved requests.
+async fn stream_to_prover(address: String, requests: mpsc::Receiver<KeccakRequest>, output_dir: String, dry_run: bool) -> Result<usize> {
+    let mut client = connect(&address).await?;
+    if !dry_run {
+        fs::create_dir_all(&output_dir).context("Failed to create output directory")?;
+    }
+    println!("Streaming Keccak proof requests to prover at {}", address);
+
+    let mut proofs = client
+        .prove_stream(ReceiverStream::new(requests))
+        .await
+        .context("Failed to open keccak prove stream")?
+        .into_inner();
+
+    let mut proved = 0;
+    while let Some(proof) = proofs.next().await {
+        let proof = proof.context("Keccak prove stream failed")?;
+        if !proof.error.is_empty() {
+            eprintln!("Remote prover failed Keccak proof request {}: {}", proof.id, proof.error);
+            continue;
+        }
+        proved += 1;
+
+        if dry_run {
+            // Dry run mode: only log the size
+            println!("Keccak receipt {} would be saved with size: {} bytes ({}ms)", proof.id, proof.receipt.len(), proof.prove_time_ms);
+            continue;
+        }
+        let receipt_path = Path::new(&output_dir).join(format!("keccak_{:04}.receipt", proof.id));
+        if let Err(e) = fs::write(&receipt_path, &proof.receipt) {
+            eprintln!("Failed to save Keccak receipt {}: {}", proof.id, e);
+        } else {
+            println!("Saved Keccak receipt {} to: {} ({} bytes, {}ms)", proof.id, receipt_path.display(), proof.receipt.len(), proof.prove_time_ms);
+        }
+    }
+    Ok(proved)
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    let telemetry = playground_telemetry::init("r0-execute");
+    let shutdown = Shutdown::install();
 
     println!("ELF path: {}", args.elf_path);
     println!("Input path: {}", args.input_path);
@@ -369,14 +445,31 @@ async fn main() -> Result<()> {
     let executor = LocalExecutor::new();
 
     // Execute locally using file paths
-    let result = executor.execute_locally(&args.elf_path, &args.input_path, &args.output_dir, args.dry_run, args.json_output, args.segment_limit_po2).await
-        .context("Failed to execute locally")?;
+    let progress = Arc::new(ProgressReporter::new(args.progress_file.clone()));
+    let job = executor.execute_locally(&args.elf_path, &args.input_path, &args.output_dir, args.dry_run, args.json_output, args.segment_limit_po2, args.keccak_prover.clone(), progress.clone());
+
+    // On SIGINT/SIGTERM give the running job a grace period to finish, then record
+    // the interruption and flush spans instead of dying mid-write
+    let Some(result) = shutdown.drain(job, DEFAULT_DRAIN_TIMEOUT).await else {
+        progress.publish("interrupted");
+        shutdown.advance(Phase::Flush);
+        drop(telemetry);
+        shutdown.advance(Phase::Exit);
+        eprintln!("Execution interrupted by shutdown signal");
+        // The zkVM executor runs on a blocking thread that cannot be cancelled, and dropping
+        // the runtime would join it, so exit directly
+        std::process::exit(130);
+    };
+    let result = result.context("Failed to execute locally")?;
 
     println!("Execution completed:");
     println!("  - User cycles: {}", result.user_cycles);
     println!("  - Total cycles: {}", result.total_cycles);
     println!("  - Segment count: {}", result.segment_count);
     println!("  - Keccak count: {}", result.keccak_count);
+    if args.keccak_prover.is_some() {
+        println!("  - Keccak proved: {}", result.keccak_proved);
+    }
     println!("  - Execution time: {}ms", result.execution_time_ms);
 
     // Save results to local storage
@@ -389,5 +482,9 @@ async fn main() -> Result<()> {
         println!("Local execution completed successfully!");
     }
 
+    shutdown.advance(Phase::Flush);
+    drop(telemetry);
+    shutdown.advance(Phase::Exit);
+
     Ok(())
 }
@@ -0,0 +1,65 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+#[derive(Debug, Serialize)]
+pub struct ExecutionProgress {
+    pub state: &'static str,
+    pub segments: usize,
+    pub keccak_requests: usize,
+    pub elapsed_ms: u128,
+}
+
+/// Publishes job progress as a small JSON file so external tools (the playground
+/// dashboard) can follow a long execution without parsing stdout.
+pub struct ProgressReporter {
+    path: Option<PathBuf>,
+    started: Instant,
+    segments: AtomicUsize,
+    keccak_requests: AtomicUsize,
+}
+
+impl ProgressReporter {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            started: Instant::now(),
+            segments: AtomicUsize::new(0),
+            keccak_requests: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn segment_done(&self) {
+        self.segments.fetch_add(1, Ordering::Relaxed);
+        self.publish("executing");
+    }
+
+    pub fn keccak_done(&self) {
+        self.keccak_requests.fetch_add(1, Ordering::Relaxed);
+        self.publish("executing");
+    }
+
+    pub fn publish(&self, state: &'static str) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let progress = ExecutionProgress {
+            state,
+            segments: self.segments.load(Ordering::Relaxed),
+            keccak_requests: self.keccak_requests.load(Ordering::Relaxed),
+            elapsed_ms: self.started.elapsed().as_millis(),
+        };
+
+        // Write to a temp file and rename so readers never see a partial document
+        let tmp_path = path.with_extension("json.tmp");
+        let result = serde_json::to_vec(&progress)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&tmp_path, json))
+            .and_then(|_| std::fs::rename(&tmp_path, path));
+        if let Err(e) = result {
+            eprintln!("Failed to write progress file {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! 지연 분포 요약
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencySummary {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut millis: Vec<u64> = samples.iter().map(|sample| sample.as_millis() as u64).collect();
+        millis.sort_unstable();
+        Self {
+            count: millis.len(),
+            p50_ms: percentile(&millis, 50),
+            p95_ms: percentile(&millis, 95),
+            p99_ms: percentile(&millis, 99),
+            max_ms: millis.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// 정렬된 표본에서 nearest-rank 백분위수 (표본이 없으면 0)
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,164 @@
+//! 여러 스트림을 동시에 열어 서버의 동시성 동작을 측정하는 부하 생성기
+//!
+//! 스트림을 램프업 구간에 고르게 나눠 열고, 스트림마다 설정한 방식으로 ACK 하며
+//! 메시지 도착 시각을 기록한다. 일부 스트림은 설정한 확률로 도중에 취소한다.
+//! 결과는 스트림별/전체 지연 분포, 처리량, 상태별 오류 수로 요약한다.
+
+pub mod latency;
+pub mod stream;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use latency::LatencySummary;
+use stream::StreamSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Target {
+    /// grpc-stream-cancel 서버 (ACK 없음)
+    CancelServer,
+    /// grpc-stream ACK 서버
+    AckServer,
+}
+
+impl Target {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::CancelServer => "cancel-server",
+            Target::AckServer => "ack-server",
+        }
+    }
+}
+
+/// 받은 데이터 메시지에 ACK 하는 방식 (ACK 서버에만 적용)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AckMode {
+    Immediate,
+    /// 받은 뒤 일정 시간 후 ACK
+    Delayed(Duration),
+    /// 주어진 확률로 ACK를 보내지 않음 (서버 재전송 유도)
+    Lossy(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    pub target: Target,
+    /// `http://host:port`
+    pub addr: String,
+    pub streams: usize,
+    /// 이 시간에 걸쳐 스트림을 고르게 나눠 염 (0이면 한꺼번에)
+    pub ramp: Duration,
+    pub ack: AckMode,
+    /// 서버가 스트림마다 새 메시지를 보내는 간격 (지연 계산 기준)
+    pub interval: Duration,
+    /// 손실 모드 난수 시드 (스트림마다 `seed + index`)
+    pub seed: u64,
+    /// 스트림을 도중에 취소할 확률 (스트림마다 같은 난수 생성기로 결정)
+    pub cancel_probability: f64,
+    /// 취소할 스트림은 연 뒤 이만큼 지나 호출을 버림
+    pub cancel_after: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoadReport {
+    pub target: Target,
+    pub streams: usize,
+    pub ramp_ms: u64,
+    pub ack: AckMode,
+    pub cancel_probability: f64,
+    pub elapsed_ms: u64,
+    pub completed: usize,
+    /// 클라이언트가 일부러 취소한 스트림 수 (실패로 보지 않음)
+    pub cancelled: usize,
+    /// 오류로 끝난 스트림 수 (gRPC 상태 코드 이름별)
+    pub errors: BTreeMap<String, usize>,
+    /// 모든 스트림이 받은 데이터 메시지 수 (재전송 포함)
+    pub messages: usize,
+    /// 전체 실행 시간 기준 초당 수신 메시지 수
+    pub throughput_per_sec: f64,
+    /// 모든 스트림의 표본을 합친 분포
+    pub delivery: LatencySummary,
+    pub redelivery: LatencySummary,
+    pub inter_message: LatencySummary,
+    pub per_stream: Vec<StreamSummary>,
+}
+
+impl LoadReport {
+    pub fn failed(&self) -> usize {
+        self.errors.values().sum()
+    }
+}
+
+/// `index`번째 스트림을 여는 시각 (실행 시작 기준)
+pub fn ramp_offset(index: usize, streams: usize, ramp: Duration) -> Duration {
+    if streams <= 1 {
+        return Duration::ZERO;
+    }
+    ramp.mul_f64(index as f64 / (streams - 1) as f64)
+}
+
+/// 설정대로 스트림을 열고 모두 끝날 때까지 기다린 뒤 요약
+pub async fn run(config: LoadConfig) -> LoadReport {
+    let started = tokio::time::Instant::now();
+
+    let mut tasks = Vec::with_capacity(config.streams);
+    for index in 0..config.streams {
+        let offset = ramp_offset(index, config.streams, config.ramp);
+        let addr = config.addr.clone();
+        let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(index as u64));
+        // 취소 확률이 0이면 난수를 뽑지 않아 손실 모드 결과가 그대로 유지됨
+        let cancel_after = (config.cancel_probability > 0.0 && rng.gen_bool(config.cancel_probability))
+            .then_some(config.cancel_after);
+        let (target, ack) = (config.target, config.ack);
+        tasks.push(tokio::spawn(async move {
+            tokio::time::sleep_until(started + offset).await;
+            let record = stream::run_stream(target, &addr, ack, rng, cancel_after).await;
+            (offset, record)
+        }));
+    }
+
+    let mut per_stream = Vec::with_capacity(config.streams);
+    let mut delivery = Vec::new();
+    let mut redelivery = Vec::new();
+    let mut inter_message = Vec::new();
+    for (index, task) in tasks.into_iter().enumerate() {
+        let (offset, record) = task.await.expect("stream task panicked");
+        delivery.extend(record.delivery_lag(config.interval));
+        redelivery.extend(record.redelivery_gaps());
+        inter_message.extend(record.inter_arrival_gaps());
+        per_stream.push(record.summarize(index, offset, config.interval));
+    }
+
+    let elapsed = started.elapsed();
+    let mut errors = BTreeMap::new();
+    for summary in &per_stream {
+        if summary.status != "OK" && summary.status != stream::CLIENT_CANCELLED {
+            *errors.entry(summary.status.clone()).or_insert(0) += 1;
+        }
+    }
+    let messages = per_stream.iter().map(|summary| summary.received).sum();
+
+    LoadReport {
+        target: config.target,
+        streams: config.streams,
+        ramp_ms: config.ramp.as_millis() as u64,
+        ack: config.ack,
+        cancel_probability: config.cancel_probability,
+        elapsed_ms: elapsed.as_millis() as u64,
+        completed: per_stream.iter().filter(|summary| summary.status == "OK").count(),
+        cancelled: per_stream.iter().filter(|summary| summary.status == stream::CLIENT_CANCELLED).count(),
+        errors,
+        messages,
+        throughput_per_sec: messages as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        delivery: LatencySummary::from_samples(&delivery),
+        redelivery: LatencySummary::from_samples(&redelivery),
+        inter_message: LatencySummary::from_samples(&inter_message),
+        per_stream,
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+use stream_loadgen::latency::LatencySummary;
+use stream_loadgen::{AckMode, LoadConfig, Target};
+
+/// 출력에 보여줄 가장 느린 스트림 수
+const SLOWEST_SHOWN: usize = 5;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TargetArg {
+    Ack,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AckArg {
+    Immediate,
+    Delayed,
+    Lossy,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "stream-loadgen")]
+#[command(about = "Open many concurrent bidirectional streams and report throughput, latency and errors")]
+struct Args {
+    /// Server to load
+    #[arg(long, value_enum, default_value = "ack")]
+    target: TargetArg,
+
+    #[arg(long, default_value = "http://[::1]:50051")]
+    addr: String,
+
+    /// Number of concurrent streams
+    #[arg(long, default_value_t = 50)]
+    streams: usize,
+
+    /// Spread stream starts evenly over this many seconds
+    #[arg(long, default_value_t = 0.0)]
+    ramp_secs: f64,
+
+    /// ACK behavior (ack server only)
+    #[arg(long, value_enum, default_value = "immediate")]
+    ack: AckArg,
+
+    /// Delay before each ACK in `delayed` mode
+    #[arg(long, default_value_t = 500)]
+    ack_delay_ms: u64,
+
+    /// Probability of dropping an ACK in `lossy` mode
+    #[arg(long, default_value_t = 0.1)]
+    loss: f64,
+
+    /// Server send interval per stream, used as the latency baseline
+    #[arg(long, default_value_t = 1000)]
+    interval_ms: u64,
+
+    /// RNG seed for `lossy` mode and --cancel-prob
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Probability that a stream is cancelled by the client partway through
+    #[arg(long, default_value_t = 0.0)]
+    cancel_prob: f64,
+
+    /// Cancelled streams drop the call this many milliseconds after opening
+    #[arg(long, default_value_t = 2000)]
+    cancel_after_ms: u64,
+
+    /// Write the full per-stream report as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
+}
+
+fn describe(summary: &LatencySummary) -> String {
+    format!(
+        "n={} p50={}ms p95={}ms p99={}ms max={}ms",
+        summary.count, summary.p50_ms, summary.p95_ms, summary.p99_ms, summary.max_ms
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    if !(0.0..=1.0).contains(&args.loss) {
+        return Err(format!("--loss must be between 0 and 1, got {}", args.loss).into());
+    }
+    if !(0.0..=1.0).contains(&args.cancel_prob) {
+        return Err(format!("--cancel-prob must be between 0 and 1, got {}", args.cancel_prob).into());
+    }
+
+    let config = LoadConfig {
+        target: match args.target {
+            TargetArg::Ack => Target::AckServer,
+            TargetArg::Cancel => Target::CancelServer,
+        },
+        addr: args.addr,
+        streams: args.streams,
+        ramp: Duration::from_secs_f64(args.ramp_secs.max(0.0)),
+        ack: match args.ack {
+            AckArg::Immediate => AckMode::Immediate,
+            AckArg::Delayed => AckMode::Delayed(Duration::from_millis(args.ack_delay_ms)),
+            AckArg::Lossy => AckMode::Lossy(args.loss),
+        },
+        interval: Duration::from_millis(args.interval_ms),
+        seed: args.seed,
+        cancel_probability: args.cancel_prob,
+        cancel_after: Duration::from_millis(args.cancel_after_ms),
+    };
+
+    println!(
+        "📈 [LOADGEN] Opening {} streams to {} ({}) over {:?}, ACK mode {:?}, cancel probability {}",
+        config.streams,
+        config.addr,
+        config.target.name(),
+        config.ramp,
+        config.ack,
+        config.cancel_probability
+    );
+    let report = stream_loadgen::run(config).await;
+
+    println!(
+        "📈 [LOADGEN] {}/{} streams completed, {} cancelled by the client, {} failed in {}ms",
+        report.completed,
+        report.streams,
+        report.cancelled,
+        report.failed(),
+        report.elapsed_ms
+    );
+    println!(
+        "📈 [LOADGEN] Throughput:      {} messages, {:.1} msg/s",
+        report.messages, report.throughput_per_sec
+    );
+    println!("📈 [LOADGEN] Delivery lag:    {}", describe(&report.delivery));
+    println!("📈 [LOADGEN] Redelivery gap:  {}", describe(&report.redelivery));
+    println!("📈 [LOADGEN] Inter-message:   {}", describe(&report.inter_message));
+    for (status, count) in &report.errors {
+        println!("❌ [LOADGEN] {} stream(s) ended with {}", count, status);
+    }
+
+    let mut slowest: Vec<_> = report.per_stream.iter().collect();
+    slowest.sort_by_key(|summary| std::cmp::Reverse(summary.delivery.p99_ms));
+    for summary in slowest.iter().take(SLOWEST_SHOWN) {
+        println!(
+            "   stream {:>4} [{}] received={} duplicates={} acks_dropped={} lag {}",
+            summary.index,
+            summary.status,
+            summary.received,
+            summary.duplicates,
+            summary.acks_dropped,
+            describe(&summary.delivery)
+        );
+    }
+
+    if let Some(path) = args.json {
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+        println!("📁 [LOADGEN] Report written to {}", path.display());
+    }
+
+    if report.failed() > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
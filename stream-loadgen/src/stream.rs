@@ -0,0 +1,250 @@
+//! 스트림 하나를 열고 끝날 때까지 수신하며 도착 시각을 기록
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Channel, Endpoint};
+use tonic::Status;
+
+use crate::latency::LatencySummary;
+use crate::{AckMode, Target};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 클라이언트가 `cancel_after`에 호출을 버린 스트림의 상태
+pub const CLIENT_CANCELLED: &str = "ClientCancelled";
+
+/// 메시지 하나의 도착 기록
+#[derive(Debug, Clone, Copy)]
+pub struct Arrival {
+    pub id: u64,
+    /// 스트림을 연 시점부터의 경과 시간
+    pub at: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct StreamRecord {
+    pub arrivals: Vec<Arrival>,
+    /// 손실 모드에서 일부러 보내지 않은 ACK 수
+    pub acks_dropped: u64,
+    /// "OK", gRPC 상태 코드 이름, 또는 `CLIENT_CANCELLED`
+    pub status: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamSummary {
+    pub index: usize,
+    pub status: String,
+    /// 실행 시작부터 이 스트림을 연 시각 (램프업)
+    pub started_ms: u64,
+    pub elapsed_ms: u64,
+    pub received: usize,
+    pub unique: usize,
+    pub duplicates: usize,
+    pub acks_dropped: u64,
+    /// 새 메시지가 스트림 자체 송신 일정보다 늦게 도착한 정도
+    pub delivery: LatencySummary,
+    /// 같은 id가 다시 도착하기까지 걸린 시간 (ACK 서버 재전송)
+    pub redelivery: LatencySummary,
+    /// 연속한 두 도착 사이의 간격 (재전송 포함)
+    pub inter_message: LatencySummary,
+}
+
+impl StreamRecord {
+    /// 처음 도착한 메시지들의 일정 대비 지연
+    ///
+    /// 서버는 스트림마다 `interval` 간격으로 새 메시지를 보내므로, k번째 새 메시지는
+    /// 첫 메시지 도착 후 `k * interval`에 와야 한다. 그보다 늦은 만큼을 지연으로 본다.
+    /// (취소 서버는 메시지 생성기를 연결 간에 공유하므로 id가 아니라 도착 순서를 기준으로 함)
+    pub fn delivery_lag(&self, interval: Duration) -> Vec<Duration> {
+        let mut seen = std::collections::HashSet::new();
+        let firsts: Vec<_> = self.arrivals.iter().filter(|arrival| seen.insert(arrival.id)).collect();
+        let Some(anchor) = firsts.first().map(|arrival| arrival.at) else {
+            return Vec::new();
+        };
+
+        firsts
+            .iter()
+            .enumerate()
+            .map(|(k, arrival)| arrival.at.saturating_sub(anchor + interval * k as u32))
+            .collect()
+    }
+
+    /// 재전송된 id마다 직전 도착으로부터의 간격
+    pub fn redelivery_gaps(&self) -> Vec<Duration> {
+        let mut last_seen = std::collections::HashMap::new();
+        let mut gaps = Vec::new();
+        for arrival in &self.arrivals {
+            if let Some(previous) = last_seen.insert(arrival.id, arrival.at) {
+                gaps.push(arrival.at - previous);
+            }
+        }
+        gaps
+    }
+
+    /// 바로 앞 도착으로부터의 간격 - id와 관계없이 도착 순서대로
+    pub fn inter_arrival_gaps(&self) -> Vec<Duration> {
+        self.arrivals
+            .windows(2)
+            .map(|pair| pair[1].at.saturating_sub(pair[0].at))
+            .collect()
+    }
+
+    pub fn summarize(&self, index: usize, started: Duration, interval: Duration) -> StreamSummary {
+        let unique = self
+            .arrivals
+            .iter()
+            .map(|arrival| arrival.id)
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        StreamSummary {
+            index,
+            status: self.status.clone(),
+            started_ms: started.as_millis() as u64,
+            elapsed_ms: self.elapsed.as_millis() as u64,
+            received: self.arrivals.len(),
+            unique,
+            duplicates: self.arrivals.len() - unique,
+            acks_dropped: self.acks_dropped,
+            delivery: LatencySummary::from_samples(&self.delivery_lag(interval)),
+            redelivery: LatencySummary::from_samples(&self.redelivery_gaps()),
+            inter_message: LatencySummary::from_samples(&self.inter_arrival_gaps()),
+        }
+    }
+}
+
+/// 스트림을 하나 열고 서버가 끝낼 때까지 수신 - `cancel_after`가 있으면 그때 호출을 버림
+pub async fn run_stream(
+    target: Target,
+    addr: &str,
+    ack: AckMode,
+    rng: StdRng,
+    cancel_after: Option<Duration>,
+) -> StreamRecord {
+    let started = Instant::now();
+    let mut record = StreamRecord::default();
+
+    let receiving = receive(target, addr, ack, rng, started, &mut record);
+    // 시간이 지나면 수신 future를 통째로 버림 - 스트림과 연결도 함께 닫힘
+    let result = match cancel_after {
+        Some(delay) => tokio::time::timeout(delay, receiving).await.ok(),
+        None => Some(receiving.await),
+    };
+
+    record.status = match result {
+        Some(Ok(())) => "OK".to_string(),
+        Some(Err(status)) => format!("{:?}", status.code()),
+        None => CLIENT_CANCELLED.to_string(),
+    };
+    record.elapsed = started.elapsed();
+    record
+}
+
+async fn receive(
+    target: Target,
+    addr: &str,
+    ack: AckMode,
+    rng: StdRng,
+    started: Instant,
+    record: &mut StreamRecord,
+) -> Result<(), Status> {
+    let channel = connect(addr).await?;
+    match target {
+        Target::CancelServer => cancel_stream(channel, started, record).await,
+        Target::AckServer => ack_stream(channel, ack, rng, started, record).await,
+    }
+}
+
+/// 스트림마다 별도 HTTP/2 연결 사용 (실제 클라이언트 여러 개와 같은 조건)
+async fn connect(addr: &str) -> Result<Channel, Status> {
+    Endpoint::try_from(addr.to_string())
+        .map_err(|e| Status::invalid_argument(e.to_string()))?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .connect()
+        .await
+        .map_err(|e| Status::unavailable(e.to_string()))
+}
+
+/// 취소 서버는 클라이언트 메시지를 쓰지 않으므로 요청 스트림은 바로 닫음
+async fn cancel_stream(channel: Channel, started: Instant, record: &mut StreamRecord) -> Result<(), Status> {
+    use cancel_server::streaming::streaming_service_client::StreamingServiceClient;
+
+    let mut client = StreamingServiceClient::new(channel);
+    let mut stream = client
+        .bidirectional_stream(tokio_stream::empty())
+        .await?
+        .into_inner();
+
+    while let Some(message) = stream.message().await? {
+        record.arrivals.push(Arrival {
+            id: message.id,
+            at: started.elapsed(),
+        });
+    }
+    Ok(())
+}
+
+async fn ack_stream(
+    channel: Channel,
+    ack: AckMode,
+    mut rng: StdRng,
+    started: Instant,
+    record: &mut StreamRecord,
+) -> Result<(), Status> {
+    use ack_server::streaming::stream_message::MessageType;
+    use ack_server::streaming::streaming_service_client::StreamingServiceClient;
+    use ack_server::streaming::{AckMessage, StreamMessage};
+
+    let (ack_tx, ack_rx) = mpsc::channel(64);
+    let mut client = StreamingServiceClient::new(channel);
+    let mut stream = client
+        .bidirectional_stream(ReceiverStream::new(ack_rx))
+        .await?
+        .into_inner();
+
+    while let Some(message) = stream.message().await? {
+        let Some(MessageType::Data(data)) = message.message_type else {
+            continue;
+        };
+        record.arrivals.push(Arrival {
+            id: data.id,
+            at: started.elapsed(),
+        });
+
+        let reply = StreamMessage {
+            message_type: Some(MessageType::Ack(AckMessage {
+                ack_id: data.id,
+                timestamp: 0,
+                channel: data.channel,
+            })),
+        };
+        // 서버가 먼저 끊은 경우 ACK 전송 실패는 무시하고 스트림 결과를 따름
+        match ack {
+            AckMode::Immediate => {
+                let _ = ack_tx.send(reply).await;
+            }
+            AckMode::Delayed(delay) => {
+                // 수신은 막지 않고 ACK만 늦게 보냄 (지연이 같으므로 순서는 유지됨)
+                let ack_tx = ack_tx.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = ack_tx.send(reply).await;
+                });
+            }
+            AckMode::Lossy(loss) => {
+                if rng.gen_bool(loss) {
+                    record.acks_dropped += 1;
+                } else {
+                    let _ = ack_tx.send(reply).await;
+                }
+            }
+        }
+    }
+    Ok(())
+}
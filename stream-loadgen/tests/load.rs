@@ -0,0 +1,123 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ack_server::server::StreamingServer;
+use ack_server::streaming::streaming_service_server::StreamingServiceServer;
+use playground_clock::SystemClock;
+use playground_stats::ServerStats;
+use stream_loadgen::latency::LatencySummary;
+use stream_loadgen::stream::{Arrival, StreamRecord};
+use stream_loadgen::{ramp_offset, AckMode, LoadConfig, Target};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+const MESSAGES: u64 = 3;
+
+#[test]
+fn nearest_rank_percentiles() {
+    let samples: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+    let summary = LatencySummary::from_samples(&samples);
+    assert_eq!(summary.count, 100);
+    assert_eq!(summary.p50_ms, 50);
+    assert_eq!(summary.p95_ms, 95);
+    assert_eq!(summary.p99_ms, 99);
+    assert_eq!(summary.max_ms, 100);
+    assert_eq!(LatencySummary::from_samples(&[]), LatencySummary::default());
+}
+
+#[test]
+fn ramp_spreads_starts_evenly() {
+    let ramp = Duration::from_secs(4);
+    assert_eq!(ramp_offset(0, 5, ramp), Duration::ZERO);
+    assert_eq!(ramp_offset(2, 5, ramp), Duration::from_secs(2));
+    assert_eq!(ramp_offset(4, 5, ramp), ramp);
+    assert_eq!(ramp_offset(0, 1, ramp), Duration::ZERO);
+}
+
+/// 새 메시지는 일정 대비 지연만, 같은 id는 재전송 간격으로 집계
+#[test]
+fn lag_is_measured_against_the_send_schedule() {
+    let at = |id, ms| Arrival {
+        id,
+        at: Duration::from_millis(ms),
+    };
+    let record = StreamRecord {
+        arrivals: vec![at(1, 100), at(2, 1100), at(3, 2400), at(1, 2600)],
+        ..Default::default()
+    };
+
+    let lag = record.delivery_lag(Duration::from_secs(1));
+    assert_eq!(lag, vec![Duration::ZERO, Duration::ZERO, Duration::from_millis(300)]);
+    assert_eq!(record.redelivery_gaps(), vec![Duration::from_millis(2500)]);
+    assert_eq!(
+        record.inter_arrival_gaps(),
+        [1000, 1300, 200].map(Duration::from_millis).to_vec()
+    );
+}
+
+/// ACK 서버를 임시 포트에 띄움
+async fn serve_ack_server(stats: Arc<ServerStats>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = StreamingServer::new(MESSAGES, stats, Arc::new(SystemClock));
+    tokio::spawn(
+        Server::builder()
+            .add_service(StreamingServiceServer::new(server))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    format!("http://{}", addr)
+}
+
+fn config(addr: String, streams: usize) -> LoadConfig {
+    LoadConfig {
+        target: Target::AckServer,
+        addr,
+        streams,
+        ramp: Duration::from_millis(200),
+        ack: AckMode::Immediate,
+        interval: Duration::from_secs(1),
+        seed: 0,
+        cancel_probability: 0.0,
+        cancel_after: Duration::from_secs(2),
+    }
+}
+
+/// 여러 스트림이 모두 끝까지 받는지 확인
+#[tokio::test]
+async fn concurrent_streams_complete_against_ack_server() {
+    let stats = Arc::new(ServerStats::default());
+    let addr = serve_ack_server(stats.clone()).await;
+
+    let report = stream_loadgen::run(config(addr, 8)).await;
+
+    assert_eq!(report.completed, 8, "{:#?}", report.per_stream);
+    for summary in &report.per_stream {
+        assert_eq!(summary.unique, MESSAGES as usize);
+    }
+    assert_eq!(report.delivery.count, 8 * MESSAGES as usize);
+    assert_eq!(report.messages, 8 * MESSAGES as usize);
+    assert_eq!(report.inter_message.count, 8 * (MESSAGES as usize - 1));
+    assert!(report.throughput_per_sec > 0.0);
+    assert!(report.errors.is_empty());
+    assert_eq!(stats.completed_streams.load(Ordering::Relaxed), 8);
+}
+
+/// 취소 확률이 1이면 모든 스트림을 도중에 버리고, 실패로 세지 않음
+#[tokio::test]
+async fn cancelled_streams_are_counted_apart_from_errors() {
+    let addr = serve_ack_server(Arc::new(ServerStats::default())).await;
+
+    let report = stream_loadgen::run(LoadConfig {
+        cancel_probability: 1.0,
+        cancel_after: Duration::from_millis(1500),
+        ..config(addr, 4)
+    })
+    .await;
+
+    assert_eq!((report.completed, report.cancelled, report.failed()), (0, 4, 0), "{:#?}", report.per_stream);
+    for summary in &report.per_stream {
+        assert!(summary.unique < MESSAGES as usize);
+    }
+}